@@ -5,11 +5,12 @@ use aries_vcx_ledger::ledger::indy_vdr_ledger::{DefaultIndyLedgerRead, DefaultIn
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 
 use crate::handlers::{
-    connection::ServiceConnections, credential_definition::ServiceCredentialDefinitions,
-    did_exchange::DidcommHandlerDidExchange, holder::ServiceCredentialsHolder,
-    issuer::ServiceCredentialsIssuer, out_of_band::ServiceOutOfBand, prover::ServiceProver,
-    revocation_registry::ServiceRevocationRegistries, schema::ServiceSchemas,
-    verifier::ServiceVerifier,
+    basic_message::ServiceBasicMessage, connection::ServiceConnections,
+    credential_definition::ServiceCredentialDefinitions, did_exchange::DidcommHandlerDidExchange,
+    did_rotate::ServiceDidRotate, holder::ServiceCredentialsHolder,
+    issuer::ServiceCredentialsIssuer, mediation::ServiceMediation, out_of_band::ServiceOutOfBand,
+    prover::ServiceProver, revocation_registry::ServiceRevocationRegistries,
+    schema::ServiceSchemas, verifier::ServiceVerifier,
 };
 
 pub struct Agent<W> {
@@ -19,6 +20,7 @@ pub struct Agent<W> {
     pub(super) anoncreds: Anoncreds,
     pub(super) wallet: Arc<W>,
     pub(super) connections: Arc<ServiceConnections<W>>,
+    pub(super) mediation: Arc<ServiceMediation<W>>,
     pub(super) schemas: Arc<ServiceSchemas<W>>,
     pub(super) cred_defs: Arc<ServiceCredentialDefinitions<W>>,
     pub(super) rev_regs: Arc<ServiceRevocationRegistries<W>>,
@@ -28,6 +30,8 @@ pub struct Agent<W> {
     pub(super) prover: Arc<ServiceProver<W>>,
     pub(super) out_of_band: Arc<ServiceOutOfBand<W>>,
     pub(super) did_exchange: Arc<DidcommHandlerDidExchange<W>>,
+    pub(super) basic_message: Arc<ServiceBasicMessage<W>>,
+    pub(super) did_rotate: Arc<ServiceDidRotate<W>>,
 }
 
 // Note: We do this manually, otherwise compiler is requesting us to implement Clone for generic
@@ -42,6 +46,7 @@ impl<W> Clone for Agent<W> {
             anoncreds: self.anoncreds,
             wallet: self.wallet.clone(),
             connections: self.connections.clone(),
+            mediation: self.mediation.clone(),
             schemas: self.schemas.clone(),
             cred_defs: self.cred_defs.clone(),
             rev_regs: self.rev_regs.clone(),
@@ -51,6 +56,8 @@ impl<W> Clone for Agent<W> {
             prover: self.prover.clone(),
             out_of_band: self.out_of_band.clone(),
             did_exchange: self.did_exchange.clone(),
+            basic_message: self.basic_message.clone(),
+            did_rotate: self.did_rotate.clone(),
         }
     }
 }
@@ -80,6 +87,10 @@ impl<T: BaseWallet> Agent<T> {
         self.connections.clone()
     }
 
+    pub fn mediation(&self) -> Arc<ServiceMediation<T>> {
+        self.mediation.clone()
+    }
+
     pub fn out_of_band(&self) -> Arc<ServiceOutOfBand<T>> {
         self.out_of_band.clone()
     }
@@ -88,6 +99,14 @@ impl<T: BaseWallet> Agent<T> {
         self.did_exchange.clone()
     }
 
+    pub fn basic_message(&self) -> Arc<ServiceBasicMessage<T>> {
+        self.basic_message.clone()
+    }
+
+    pub fn did_rotate(&self) -> Arc<ServiceDidRotate<T>> {
+        self.did_rotate.clone()
+    }
+
     pub fn schemas(&self) -> Arc<ServiceSchemas<T>> {
         self.schemas.clone()
     }
@@ -33,9 +33,11 @@ use crate::{
     agent::agent_struct::Agent,
     error::AgentResult,
     handlers::{
-        connection::ServiceConnections, credential_definition::ServiceCredentialDefinitions,
-        did_exchange::DidcommHandlerDidExchange, holder::ServiceCredentialsHolder,
-        issuer::ServiceCredentialsIssuer, out_of_band::ServiceOutOfBand, prover::ServiceProver,
+        basic_message::ServiceBasicMessage, connection::ServiceConnections,
+        credential_definition::ServiceCredentialDefinitions,
+        did_exchange::DidcommHandlerDidExchange, did_rotate::ServiceDidRotate,
+        holder::ServiceCredentialsHolder, issuer::ServiceCredentialsIssuer,
+        mediation::ServiceMediation, out_of_band::ServiceOutOfBand, prover::ServiceProver,
         revocation_registry::ServiceRevocationRegistries, schema::ServiceSchemas,
         verifier::ServiceVerifier,
     },
@@ -144,13 +146,32 @@ impl<W: BaseWallet> Agent<W> {
             wallet.clone(),
             service_endpoint.clone(),
         ));
-        let did_exchange = Arc::new(DidcommHandlerDidExchange::new(
+        let mediation = Arc::new(ServiceMediation::new(
             wallet.clone(),
-            did_resolver_registry,
             service_endpoint.clone(),
+            connections.clone(),
+        ));
+        let did_exchange = Arc::new(DidcommHandlerDidExchange::new(
+            wallet.clone(),
+            did_resolver_registry.clone(),
+            mediation.clone(),
             issuer_did.to_string(),
         ));
-        let out_of_band = Arc::new(ServiceOutOfBand::new(wallet.clone(), service_endpoint));
+        let basic_message = Arc::new(ServiceBasicMessage::new(
+            wallet.clone(),
+            connections.clone(),
+            did_exchange.clone(),
+        ));
+        let did_rotate = Arc::new(ServiceDidRotate::new(
+            wallet.clone(),
+            did_resolver_registry,
+            did_exchange.clone(),
+        ));
+        let out_of_band = Arc::new(ServiceOutOfBand::new(
+            wallet.clone(),
+            mediation.clone(),
+            did_exchange.clone(),
+        ));
         let schemas = Arc::new(ServiceSchemas::new(
             ledger_read.clone(),
             ledger_write.clone(),
@@ -180,6 +201,7 @@ impl<W: BaseWallet> Agent<W> {
             ledger_read.clone(),
             anoncreds,
             wallet.clone(),
+            service_endpoint.clone(),
             connections.clone(),
         ));
         let verifier = Arc::new(ServiceVerifier::new(
@@ -192,6 +214,7 @@ impl<W: BaseWallet> Agent<W> {
             ledger_read.clone(),
             anoncreds,
             wallet.clone(),
+            service_endpoint,
             connections.clone(),
         ));
 
@@ -201,7 +224,10 @@ impl<W: BaseWallet> Agent<W> {
             anoncreds,
             wallet,
             connections,
+            mediation,
             did_exchange,
+            basic_message,
+            did_rotate,
             out_of_band,
             schemas,
             cred_defs,
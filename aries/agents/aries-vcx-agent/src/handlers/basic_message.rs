@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use aries_vcx::{
+    handlers::basic_message::{
+        build_basic_message, delete_chat_history, get_chat_history, receive_basic_message,
+        send_basic_message, ChatMessage,
+    },
+    messages::{
+        decorators::localization::Locale, msg_fields::protocols::basic_message::BasicMessage,
+    },
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+
+use super::{connection::ServiceConnections, did_exchange::DidcommHandlerDidExchange};
+use crate::{error::*, http::VcxHttpClient};
+
+pub struct ServiceBasicMessage<T> {
+    wallet: Arc<T>,
+    connections: Arc<ServiceConnections<T>>,
+    did_exchange: Arc<DidcommHandlerDidExchange<T>>,
+}
+
+impl<T: BaseWallet> ServiceBasicMessage<T> {
+    pub fn new(
+        wallet: Arc<T>,
+        connections: Arc<ServiceConnections<T>>,
+        did_exchange: Arc<DidcommHandlerDidExchange<T>>,
+    ) -> Self {
+        Self {
+            wallet,
+            connections,
+            did_exchange,
+        }
+    }
+
+    /// Sends a basic message over the connection identified by `connection_id`, which may have
+    /// been established either through the connection protocol or through DID exchange.
+    pub async fn send_message(
+        &self,
+        connection_id: &str,
+        content: String,
+        locale: Option<Locale>,
+    ) -> AgentResult<ChatMessage> {
+        let message = build_basic_message(content, locale);
+        let wallet = self.wallet.as_ref();
+        let chat_message = if self.connections.exists_by_id(connection_id) {
+            let connection = self.connections.get_by_id(connection_id)?;
+            send_basic_message(wallet, &connection, &VcxHttpClient, connection_id, message).await?
+        } else if self.did_exchange.exists_by_id(connection_id) {
+            let connection = self.did_exchange.get_by_id(connection_id)?;
+            send_basic_message(wallet, &connection, &VcxHttpClient, connection_id, message).await?
+        } else {
+            return Err(AgentError::from_msg(
+                AgentErrorKind::NotFound,
+                &format!("No connection found with id {connection_id}"),
+            ));
+        };
+        Ok(chat_message)
+    }
+
+    pub async fn handle_message(
+        &self,
+        connection_id: &str,
+        message: BasicMessage,
+    ) -> AgentResult<ChatMessage> {
+        info!(
+            "Received basic message over connection {}: {}",
+            connection_id, message.content.content
+        );
+        Ok(receive_basic_message(self.wallet.as_ref(), connection_id, message).await?)
+    }
+
+    pub async fn get_history(&self, connection_id: &str) -> AgentResult<Vec<ChatMessage>> {
+        Ok(get_chat_history(self.wallet.as_ref(), connection_id).await?)
+    }
+
+    pub async fn delete_history(&self, connection_id: &str) -> AgentResult<()> {
+        Ok(delete_chat_history(self.wallet.as_ref(), connection_id).await?)
+    }
+}
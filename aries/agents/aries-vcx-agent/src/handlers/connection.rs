@@ -3,6 +3,7 @@ use std::sync::{Arc, Mutex};
 use aries_vcx::{
     handlers::util::AnyInvitation,
     messages::{
+        decorators::transport::ReturnRoute,
         msg_fields::protocols::{
             connection::{request::Request, response::Response},
             notification::ack::Ack,
@@ -66,6 +67,30 @@ impl<T: BaseWallet> ServiceConnections<T> {
         Ok(())
     }
 
+    /// Sends the message asking the counterparty to reply over the same connection, returning
+    /// the reply if there is one.
+    pub async fn send_message_with_return_route(
+        &self,
+        connection_id: &str,
+        message: &AriesMessage,
+    ) -> AgentResult<Option<AriesMessage>> {
+        let connection = self.get_by_id(connection_id)?;
+        info!(
+            "Sending message with return route to connection identified by id {}. Plaintext \
+             message payload: {}",
+            connection_id, message
+        );
+        let reply = connection
+            .send_message_with_return_route(
+                self.wallet.as_ref(),
+                message,
+                ReturnRoute::All,
+                &VcxHttpClient,
+            )
+            .await?;
+        Ok(reply)
+    }
+
     pub async fn create_invitation(
         &self,
         pw_info: Option<PairwiseInfo>,
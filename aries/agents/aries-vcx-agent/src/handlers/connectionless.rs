@@ -0,0 +1,98 @@
+use aries_vcx::{
+    did_parser_nom::Did,
+    handlers::out_of_band::connectionless::{
+        build_service_decorator, create_ephemeral_did, decorate_with_service,
+        send_connectionless_message, ConnectionlessService,
+    },
+    messages::{
+        decorators::service::Service,
+        msg_fields::protocols::out_of_band::invitation::Invitation as OobInvitation, AriesMessage,
+    },
+};
+use aries_vcx_ledger::ledger::indy_vdr_ledger::DefaultIndyLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use public_key::Key;
+use url::Url;
+
+use crate::{error::*, http::VcxHttpClient};
+
+/// The party messages of a protocol instance are exchanged with.
+#[derive(Clone)]
+pub(crate) enum Counterparty {
+    /// A party we have an established connection with, identified by the id of the connection
+    Connection(String),
+    /// A party we have no connection with, replied to at its service
+    Connectionless(ConnectionlessExchange),
+}
+
+impl Counterparty {
+    pub fn connection_id(&self) -> AgentResult<String> {
+        match self {
+            Counterparty::Connection(connection_id) => Ok(connection_id.to_owned()),
+            Counterparty::Connectionless(_) => Err(AgentError::from_msg(
+                AgentErrorKind::InvalidState,
+                "Protocol instance is not associated with any connection",
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ConnectionlessExchange {
+    pub ephemeral_did: Did,
+    pub ephemeral_key: Key,
+    pub their_service: ConnectionlessService,
+}
+
+impl ConnectionlessExchange {
+    /// Starts a connectionless exchange with the sender of a request, replying to the `~service`
+    /// decorator of the request, or, if it is missing, to the service of the invitation the
+    /// request was attached to.
+    pub async fn create(
+        wallet: &impl BaseWallet,
+        ledger_read: &DefaultIndyLedgerRead,
+        service_decorator: Option<&Service>,
+        invitation: Option<&OobInvitation>,
+    ) -> AgentResult<Self> {
+        let their_service = match (service_decorator, invitation) {
+            (Some(service), _) => ConnectionlessService::from_service_decorator(service)?,
+            (None, Some(invitation)) => {
+                ConnectionlessService::from_invitation(ledger_read, invitation).await?
+            }
+            (None, None) => {
+                return Err(AgentError::from_msg(
+                    AgentErrorKind::InvalidArguments,
+                    "Connectionless request is missing both ~service decorator and invitation",
+                ))
+            }
+        };
+        let (ephemeral_did, ephemeral_key) = create_ephemeral_did(wallet).await?;
+        Ok(Self {
+            ephemeral_did,
+            ephemeral_key,
+            their_service,
+        })
+    }
+
+    /// Sends `message` to their service. Messages which may carry the `~service` decorator are
+    /// decorated with our `service_endpoint`, so that the counterparty is able to reply.
+    pub async fn send_message(
+        &self,
+        wallet: &impl BaseWallet,
+        service_endpoint: &Url,
+        message: AriesMessage,
+    ) -> AgentResult<()> {
+        let our_service =
+            build_service_decorator(&self.ephemeral_key, service_endpoint.to_owned(), vec![]);
+        let message = decorate_with_service(message.clone(), our_service).unwrap_or(message);
+        send_connectionless_message(
+            wallet,
+            &VcxHttpClient,
+            &self.ephemeral_key,
+            &self.their_service,
+            &message,
+        )
+        .await?;
+        Ok(())
+    }
+}
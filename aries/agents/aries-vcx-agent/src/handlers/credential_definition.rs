@@ -1,11 +1,15 @@
 use std::sync::{Arc, Mutex};
 
 use anoncreds_types::data_types::identifiers::schema_id::SchemaId;
-use aries_vcx::{common::primitives::credential_definition::CredentialDef, did_parser_nom::Did};
+use aries_vcx::{
+    common::primitives::credential_definition::{is_cred_def_retired, CredentialDef},
+    did_parser_nom::Did,
+};
 use aries_vcx_anoncreds::anoncreds::anoncreds::Anoncreds;
 use aries_vcx_ledger::ledger::indy_vdr_ledger::{DefaultIndyLedgerRead, DefaultIndyLedgerWrite};
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 
+use super::revocation_registry::ServiceRevocationRegistries;
 use crate::{
     error::*,
     storage::{agent_storage_inmem::AgentStorageInMem, AgentStorage},
@@ -68,6 +72,39 @@ impl<T: BaseWallet> ServiceCredentialDefinitions<T> {
         Ok(())
     }
 
+    /// Rotates the credential definition to a new one under `new_tag`, retiring the old one. The
+    /// revocation registry of a revocable credential definition is replaced by a new one, stored
+    /// in `rev_regs`. Nothing is stored unless the whole rotation succeeds. Returns the id of the
+    /// new (not yet published) credential definition.
+    pub async fn rotate_cred_def(
+        &self,
+        cred_def_id: &str,
+        new_tag: String,
+        rev_regs: &ServiceRevocationRegistries<T>,
+    ) -> AgentResult<String> {
+        let cred_def = self.cred_defs.get(cred_def_id)?;
+        let rev_reg = rev_regs.get_by_cred_def_id(cred_def_id)?;
+        let (rotated, rotated_rev_reg) = cred_def
+            .rotate(
+                self.wallet.as_ref(),
+                self.ledger_read.as_ref(),
+                &self.anoncreds,
+                new_tag,
+                rev_reg.as_ref(),
+            )
+            .await?;
+        if let Some(rotated_rev_reg) = rotated_rev_reg {
+            rev_regs.insert(rotated_rev_reg)?;
+        }
+        self.cred_defs
+            .insert(&rotated.get_cred_def_id().to_string(), rotated)
+    }
+
+    pub async fn is_retired(&self, cred_def_id: &str) -> AgentResult<bool> {
+        let cred_def = self.cred_defs.get(cred_def_id)?;
+        Ok(is_cred_def_retired(self.wallet.as_ref(), cred_def.get_cred_def_id()).await?)
+    }
+
     pub fn cred_def_json(&self, thread_id: &str) -> AgentResult<String> {
         self.cred_defs
             .get(thread_id)?
@@ -87,4 +124,15 @@ impl<T: BaseWallet> ServiceCredentialDefinitions<T> {
         };
         self.cred_defs.find_by(f)
     }
+
+    /// Like [Self::find_by_schema_id], but skips credential definitions which were retired.
+    pub async fn find_active_by_schema_id(&self, schema_id: &str) -> AgentResult<Vec<String>> {
+        let mut active = Vec::new();
+        for id in self.find_by_schema_id(schema_id)? {
+            if !self.is_retired(&id).await? {
+                active.push(id);
+            }
+        }
+        Ok(active)
+    }
 }
@@ -16,10 +16,7 @@ use aries_vcx::{
     },
     protocols::did_exchange::{
         resolve_enc_key_from_did_doc, resolve_enc_key_from_invitation,
-        state_machine::{
-            generic::{GenericDidExchange, ThinState},
-            helpers::create_peer_did_4,
-        },
+        state_machine::generic::{GenericDidExchange, ThinState},
     },
     transport::Transport,
     utils::encryption_envelope::EncryptionEnvelope,
@@ -28,8 +25,8 @@ use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use did_resolver_registry::ResolverRegistry;
 use did_resolver_sov::did_resolver::did_doc::schema::did_doc::DidDocument;
 use public_key::{Key, KeyType};
-use url::Url;
 
+use super::mediation::ServiceMediation;
 use crate::{
     http::VcxHttpClient,
     storage::{agent_storage_inmem::AgentStorageInMem, AgentStorage},
@@ -40,8 +37,10 @@ use crate::{
 pub struct DidcommHandlerDidExchange<T> {
     wallet: Arc<T>,
     resolver_registry: Arc<ResolverRegistry>,
-    service_endpoint: Url,
+    mediation: Arc<ServiceMediation<T>>,
     did_exchange: Arc<AgentStorageInMem<(GenericDidExchange, Option<AriesMessage>)>>,
+    // Maps thread ids of requested exchanges to ids of the out-of-band invitations they accept
+    invitations: AgentStorageInMem<String>,
     public_did: String,
 }
 
@@ -49,14 +48,15 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
     pub fn new(
         wallet: Arc<T>,
         resolver_registry: Arc<ResolverRegistry>,
-        service_endpoint: Url,
+        mediation: Arc<ServiceMediation<T>>,
         public_did: String,
     ) -> Self {
         Self {
             wallet,
-            service_endpoint,
+            mediation,
             resolver_registry,
             did_exchange: Arc::new(AgentStorageInMem::new("did-exchange")),
+            invitations: AgentStorageInMem::new("did-exchange-invitations"),
             public_did,
         }
     }
@@ -68,14 +68,13 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
         version: DidExchangeTypeV1,
     ) -> AgentResult<(String, Option<String>, String)> {
         // todo: type the return type
-        let (our_peer_did, _our_verkey) =
-            create_peer_did_4(self.wallet.as_ref(), self.service_endpoint.clone(), vec![]).await?;
+        let (our_peer_did, _our_verkey) = self.mediation.create_peer_did_4().await?;
         let our_did = our_peer_did.did().to_string();
 
         let their_did: Did = their_did.parse()?;
         let (requester, request) = GenericDidExchange::construct_request(
             &self.resolver_registry,
-            invitation_id,
+            invitation_id.clone(),
             &their_did,
             &our_peer_did,
             "".to_owned(),
@@ -115,6 +114,9 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
         //       of protocol then we need to update storage to enable identification by
         //       multiple IDs (both thid, pthid (or arbitrary other))
         self.did_exchange.insert(&thid, (requester.clone(), None))?;
+        if let Some(invitation_id) = invitation_id {
+            self.invitations.insert(&thid, invitation_id)?;
+        }
         VcxHttpClient
             .send_message(encryption_envelope.0, service.service_endpoint())
             .await?;
@@ -153,8 +155,7 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
             }
         };
 
-        let (our_peer_did, _our_verkey) =
-            create_peer_did_4(self.wallet.as_ref(), self.service_endpoint.clone(), vec![]).await?;
+        let (our_peer_did, _our_verkey) = self.mediation.create_peer_did_4().await?;
 
         let pthid = thread
             .ok_or_else(|| {
@@ -245,6 +246,9 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
         )
         .await?;
         self.did_exchange.insert(&thid, (requester.clone(), None))?;
+        if let Some(invitation_id) = invitation_id {
+            self.invitations.insert(&thid, invitation_id)?;
+        }
         VcxHttpClient
             .send_message(encryption_envelope.0, service.service_endpoint())
             .await?;
@@ -269,8 +273,46 @@ impl<T: BaseWallet> DidcommHandlerDidExchange<T> {
         self.did_exchange.contains_key(thread_id)
     }
 
-    pub fn invitation_id(&self, _thread_id: &str) -> AgentResult<String> {
-        unimplemented!()
+    pub(in crate::handlers) fn get_by_id(
+        &self,
+        thread_id: &str,
+    ) -> AgentResult<GenericDidExchange> {
+        let (did_exchange, _) = self.did_exchange.get(thread_id)?;
+        Ok(did_exchange)
+    }
+
+    pub(in crate::handlers) fn update(
+        &self,
+        thread_id: &str,
+        did_exchange: GenericDidExchange,
+    ) -> AgentResult<String> {
+        self.did_exchange.insert(thread_id, (did_exchange, None))
+    }
+
+    /// Removes the exchange, e.g. once the connection has been hung up.
+    pub(in crate::handlers) fn remove(&self, thread_id: &str) -> AgentResult<()> {
+        self.did_exchange.remove(thread_id)?;
+        self.invitations.remove(thread_id)?;
+        Ok(())
+    }
+
+    /// Returns the id of the out-of-band invitation the exchange identified by `thread_id` was
+    /// requested for.
+    pub fn invitation_id(&self, thread_id: &str) -> AgentResult<String> {
+        self.invitations.get(thread_id)
+    }
+
+    /// Returns the thread ids of the exchanges requested for out-of-band invitations, along
+    /// with the ids of the invitations.
+    pub(in crate::handlers) fn invitation_ids(&self) -> AgentResult<Vec<(String, String)>> {
+        self.invitations
+            .find_by(|(thread_id, _)| Some(thread_id.to_owned()))?
+            .into_iter()
+            .map(|thread_id| {
+                let invitation_id = self.invitations.get(&thread_id)?;
+                Ok((thread_id, invitation_id))
+            })
+            .collect()
     }
 
     pub fn public_did(&self) -> &str {
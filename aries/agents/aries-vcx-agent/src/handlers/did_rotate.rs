@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use aries_vcx::{
+    errors::error::AriesVcxError,
+    messages::msg_fields::protocols::did_rotate::{
+        ack::DidRotateAck, hangup::Hangup, problem_report::DidRotateProblemReport, rotate::Rotate,
+    },
+    protocols::{
+        did_exchange::{
+            state_machine::helpers::create_peer_did_4,
+            transition::transition_result::TransitionResult,
+        },
+        did_rotate::{
+            build_hangup, observer::DidRotateObserver, rotator::DidRotator, states::RotatePrepared,
+        },
+    },
+    transport::MessageConnection,
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_resolver_registry::ResolverRegistry;
+use url::Url;
+
+use super::did_exchange::DidcommHandlerDidExchange;
+use crate::{
+    error::*,
+    http::VcxHttpClient,
+    storage::{agent_storage_inmem::AgentStorageInMem, AgentStorage},
+};
+
+pub struct ServiceDidRotate<T> {
+    wallet: Arc<T>,
+    resolver_registry: Arc<ResolverRegistry>,
+    did_exchange: Arc<DidcommHandlerDidExchange<T>>,
+    rotations: AgentStorageInMem<(String, DidRotator<RotatePrepared>)>,
+}
+
+impl<T: BaseWallet> ServiceDidRotate<T> {
+    pub fn new(
+        wallet: Arc<T>,
+        resolver_registry: Arc<ResolverRegistry>,
+        did_exchange: Arc<DidcommHandlerDidExchange<T>>,
+    ) -> Self {
+        Self {
+            wallet,
+            resolver_registry,
+            did_exchange,
+            rotations: AgentStorageInMem::new("did-rotate"),
+        }
+    }
+
+    /// Rotates our DID on the DID exchange connection identified by `connection_id` to a new
+    /// peer DID, reachable at `service_endpoint` through `routing_keys` (e.g. those of a new
+    /// mediator). The connection keeps using our current DID until the rotation is acknowledged.
+    pub async fn rotate(
+        &self,
+        connection_id: &str,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+    ) -> AgentResult<String> {
+        let wallet = self.wallet.as_ref();
+        let connection = self.did_exchange.get_by_id(connection_id)?;
+        let (new_peer_did, _) = create_peer_did_4(wallet, service_endpoint, routing_keys).await?;
+        let rotator =
+            DidRotator::prepare_rotation(wallet, connection.our_did_document(), &new_peer_did)
+                .await?;
+        connection
+            .send_message(wallet, &rotator.get_rotate().clone().into(), &VcxHttpClient)
+            .await?;
+        let thread_id = rotator.get_thread_id().to_owned();
+        self.rotations
+            .insert(&thread_id, (connection_id.to_owned(), rotator))
+    }
+
+    /// Switches the connection over to our new DID, completing the rotation. Returns the id of
+    /// the connection.
+    pub fn handle_ack(&self, ack: DidRotateAck) -> AgentResult<String> {
+        let thread_id = ack.decorators.thread.thid.clone();
+        let (connection_id, rotator) = self.rotations.get(&thread_id)?;
+        let rotator = rotator.receive_ack(ack).map_err(AriesVcxError::from)?;
+        let mut connection = self.did_exchange.get_by_id(&connection_id)?;
+        connection.rotate_our_did_document(rotator.new_did_document().clone())?;
+        self.did_exchange.update(&connection_id, connection)?;
+        self.rotations.remove(&thread_id)?;
+        Ok(connection_id)
+    }
+
+    pub fn handle_problem_report(&self, problem_report: DidRotateProblemReport) -> AgentResult<()> {
+        let thread_id = problem_report
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .ok_or_else(|| {
+                AgentError::from_msg(
+                    AgentErrorKind::InvalidState,
+                    "Problem report did not contain a thread",
+                )
+            })?;
+        let (connection_id, rotator) = self.rotations.get(&thread_id)?;
+        let rotator = rotator
+            .receive_problem_report(problem_report)
+            .map_err(AriesVcxError::from)?;
+        self.rotations.remove(&thread_id)?;
+        warn!(
+            "Rotation of our DID on connection {} was rejected: {}",
+            connection_id,
+            rotator.get_failure_message()
+        );
+        Ok(())
+    }
+
+    /// Verifies the rotation of their DID on the connection identified by `connection_id` and
+    /// acknowledges it over their new DID. If the rotation can not be accepted, a problem report
+    /// is sent back and the connection keeps using their current DID.
+    pub async fn handle_rotate(&self, connection_id: &str, rotate: Rotate) -> AgentResult<()> {
+        let wallet = self.wallet.as_ref();
+        let mut connection = self.did_exchange.get_by_id(connection_id)?;
+        let observer = DidRotateObserver::receive_rotate(rotate);
+        match observer
+            .accept_rotation(wallet, &self.resolver_registry, connection.their_did_doc())
+            .await
+        {
+            Ok(TransitionResult { state, output }) => {
+                connection.rotate_their_did_document(state.new_did_document().clone())?;
+                connection
+                    .send_message(wallet, &output.into(), &VcxHttpClient)
+                    .await?;
+                self.did_exchange.update(connection_id, connection)?;
+                Ok(())
+            }
+            Err(TransitionResult { state, output }) => {
+                connection
+                    .send_message(wallet, &output.into(), &VcxHttpClient)
+                    .await?;
+                Err(AgentError::from_msg(
+                    AgentErrorKind::InvalidState,
+                    &format!(
+                        "Rejected rotation of DID on connection {connection_id}: {}",
+                        state.get_failure_message()
+                    ),
+                ))
+            }
+        }
+    }
+
+    /// Tells the other party of the connection identified by `connection_id` that we are ending
+    /// the relationship, and removes the connection.
+    pub async fn hangup(&self, connection_id: &str) -> AgentResult<()> {
+        let connection = self.did_exchange.get_by_id(connection_id)?;
+        connection
+            .send_message(self.wallet.as_ref(), &build_hangup().into(), &VcxHttpClient)
+            .await?;
+        self.remove_connection(connection_id)
+    }
+
+    /// Removes the connection hung up by the other party, so that nothing more is sent over it.
+    pub fn handle_hangup(&self, connection_id: &str, _hangup: Hangup) -> AgentResult<()> {
+        info!(
+            "Connection {} was hung up by the other party",
+            connection_id
+        );
+        self.remove_connection(connection_id)
+    }
+
+    fn remove_connection(&self, connection_id: &str) -> AgentResult<()> {
+        let rotations = self.rotations.find_by(|(thread_id, rotation)| {
+            let rotation = rotation.lock().ok()?;
+            (rotation.0 == connection_id).then(|| thread_id.to_owned())
+        })?;
+        for thread_id in rotations {
+            self.rotations.remove(&thread_id)?;
+        }
+        self.did_exchange.remove(connection_id)
+    }
+}
@@ -4,36 +4,42 @@ use aries_vcx::{
     did_parser_nom::Did,
     handlers::issuance::holder::Holder,
     messages::{
-        msg_fields::protocols::cred_issuance::v1::{
-            issue_credential::IssueCredentialV1, offer_credential::OfferCredentialV1,
-            propose_credential::ProposeCredentialV1,
+        msg_fields::protocols::{
+            cred_issuance::v1::{
+                issue_credential::IssueCredentialV1, offer_credential::OfferCredentialV1,
+                propose_credential::ProposeCredentialV1,
+            },
+            out_of_band::invitation::Invitation as OobInvitation,
         },
         AriesMessage,
     },
-    protocols::{issuance::holder::state_machine::HolderState, SendClosure},
+    protocols::issuance::holder::state_machine::HolderState,
 };
 use aries_vcx_anoncreds::anoncreds::anoncreds::Anoncreds;
 use aries_vcx_ledger::ledger::indy_vdr_ledger::DefaultIndyLedgerRead;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use url::Url;
 
 use crate::{
     error::*,
-    handlers::connection::ServiceConnections,
-    http::VcxHttpClient,
+    handlers::{
+        connection::ServiceConnections,
+        connectionless::{ConnectionlessExchange, Counterparty},
+    },
     storage::{agent_storage_inmem::AgentStorageInMem, AgentStorage},
 };
 
 #[derive(Clone)]
 struct HolderWrapper {
     holder: Holder,
-    connection_id: String,
+    counterparty: Counterparty,
 }
 
 impl HolderWrapper {
     pub fn new(holder: Holder, connection_id: &str) -> Self {
         Self {
             holder,
-            connection_id: connection_id.to_string(),
+            counterparty: Counterparty::Connection(connection_id.to_string()),
         }
     }
 }
@@ -42,6 +48,7 @@ pub struct ServiceCredentialsHolder<T> {
     ledger_read: Arc<DefaultIndyLedgerRead>,
     anoncreds: Anoncreds,
     wallet: Arc<T>,
+    service_endpoint: Url,
     creds_holder: AgentStorageInMem<HolderWrapper>,
     service_connections: Arc<ServiceConnections<T>>,
 }
@@ -51,6 +58,7 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
         ledger_read: Arc<DefaultIndyLedgerRead>,
         anoncreds: Anoncreds,
         wallet: Arc<T>,
+        service_endpoint: Url,
         service_connections: Arc<ServiceConnections<T>>,
     ) -> Self {
         Self {
@@ -59,6 +67,7 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
             ledger_read,
             anoncreds,
             wallet,
+            service_endpoint,
         }
     }
 
@@ -68,8 +77,8 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
     }
 
     pub fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
-        let HolderWrapper { connection_id, .. } = self.creds_holder.get(thread_id)?;
-        Ok(connection_id)
+        let HolderWrapper { counterparty, .. } = self.creds_holder.get(thread_id)?;
+        counterparty.connection_id()
     }
 
     pub async fn send_credential_proposal(
@@ -103,30 +112,74 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
         )
     }
 
+    /// Creates a holder from a credential offer received without a connection, either attached
+    /// to `invitation` or decorated with `~service`. The credential request will be sent straight
+    /// to the service of the issuer.
+    pub async fn create_from_connectionless_offer(
+        &self,
+        offer: OfferCredentialV1,
+        invitation: Option<&OobInvitation>,
+    ) -> AgentResult<String> {
+        let exchange = ConnectionlessExchange::create(
+            self.wallet.as_ref(),
+            self.ledger_read.as_ref(),
+            offer.decorators.service.as_ref(),
+            invitation,
+        )
+        .await?;
+        let holder = Holder::create_from_offer("foobar", offer)?;
+        self.creds_holder.insert(
+            &holder.get_thread_id()?,
+            HolderWrapper {
+                holder,
+                counterparty: Counterparty::Connectionless(exchange),
+            },
+        )
+    }
+
     pub async fn send_credential_request(&self, thread_id: &str) -> AgentResult<String> {
-        let connection_id = self.get_connection_id(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        let HolderWrapper {
+            mut holder,
+            counterparty,
+        } = self.creds_holder.get(thread_id)?;
         // todo: technically doesn't need to be DID at all, and definitely need not to be pairwise
         // DID
-        let pw_did_as_entropy = connection.pairwise_info().pw_did.to_string();
+        let pw_did_as_entropy = match &counterparty {
+            Counterparty::Connection(connection_id) => Did::parse(
+                self.service_connections
+                    .get_by_id(connection_id)?
+                    .pairwise_info()
+                    .pw_did
+                    .to_string(),
+            )?,
+            Counterparty::Connectionless(exchange) => exchange.ephemeral_did.clone(),
+        };
 
-        let mut holder = self.get_holder(thread_id)?;
         let message = holder
             .prepare_credential_request(
                 self.wallet.as_ref(),
                 self.ledger_read.as_ref(),
                 &self.anoncreds,
-                Did::parse(pw_did_as_entropy)?,
+                pw_did_as_entropy,
             )
             .await?;
-
-        self.service_connections
-            .send_message(&connection_id, &message)
-            .await?;
+        // The issuer may reply with the credential over the same connection. The request is sent
+        // regardless, so the holder is kept waiting for the credential if the reply fails.
+        if let Some(reply) = self.send_message(&counterparty, message).await? {
+            if let Err(err) = self
+                .process_credential_reply(&mut holder, &counterparty, reply)
+                .await
+            {
+                warn!("Failed to process reply to credential request: {}", err);
+            }
+        }
 
         self.creds_holder.insert(
             &holder.get_thread_id()?,
-            HolderWrapper::new(holder, &connection_id),
+            HolderWrapper {
+                holder,
+                counterparty,
+            },
         )
     }
 
@@ -135,9 +188,10 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
         thread_id: &str,
         msg_issue_credential: IssueCredentialV1,
     ) -> AgentResult<String> {
-        let mut holder = self.get_holder(thread_id)?;
-        let connection_id = self.get_connection_id(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
+        let HolderWrapper {
+            mut holder,
+            counterparty,
+        } = self.creds_holder.get(thread_id)?;
 
         holder
             .process_credential(
@@ -147,25 +201,62 @@ impl<T: BaseWallet> ServiceCredentialsHolder<T> {
                 msg_issue_credential.clone(),
             )
             .await?;
-        match holder.get_final_message()? {
-            None => {}
-            Some(msg_response) => {
-                let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-                    Box::pin(async move {
-                        connection
-                            .send_message(self.wallet.as_ref(), &msg, &VcxHttpClient)
-                            .await
-                    })
-                });
-                send_closure(msg_response).await?;
-            }
+        if let Some(msg_response) = holder.get_final_message()? {
+            self.send_message(&counterparty, msg_response).await?;
         }
         self.creds_holder.insert(
             &holder.get_thread_id()?,
-            HolderWrapper::new(holder, &connection_id),
+            HolderWrapper {
+                holder,
+                counterparty,
+            },
         )
     }
 
+    /// Processes the reply of the issuer to the credential request, acknowledging the credential
+    /// if one was issued.
+    async fn process_credential_reply(
+        &self,
+        holder: &mut Holder,
+        counterparty: &Counterparty,
+        reply: AriesMessage,
+    ) -> AgentResult<()> {
+        holder
+            .process_aries_msg(
+                self.wallet.as_ref(),
+                self.ledger_read.as_ref(),
+                &self.anoncreds,
+                reply,
+            )
+            .await?;
+        if let Some(msg_response) = holder.get_final_message()? {
+            self.send_message(counterparty, msg_response).await?;
+        }
+        Ok(())
+    }
+
+    /// Sends the message to the counterparty, returning the reply received over the same
+    /// connection, if any.
+    async fn send_message(
+        &self,
+        counterparty: &Counterparty,
+        message: AriesMessage,
+    ) -> AgentResult<Option<AriesMessage>> {
+        match counterparty {
+            Counterparty::Connection(connection_id) => {
+                self.service_connections
+                    .send_message_with_return_route(connection_id, &message)
+                    .await
+            }
+            Counterparty::Connectionless(exchange) => {
+                exchange
+                    .send_message(self.wallet.as_ref(), &self.service_endpoint, message)
+                    .await?;
+                Ok(None)
+            }
+        }
+    }
+
     pub fn get_state(&self, thread_id: &str) -> AgentResult<HolderState> {
         Ok(self.get_holder(thread_id)?.get_state())
     }
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use aries_vcx::{
+    handlers::mediation::recipient::{MediatedMessage, MediationGrant, MediationRecipient},
+    protocols::{
+        connection::GenericConnection, did_exchange::state_machine::helpers::create_peer_did_4,
+    },
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_peer::peer_did::{numalgos::numalgo4::Numalgo4, PeerDid};
+use public_key::Key;
+use tokio::sync::Mutex;
+use url::Url;
+
+use super::connection::ServiceConnections;
+use crate::{error::*, http::VcxHttpClient};
+
+const PICKUP_BATCH_SIZE: u32 = 10;
+
+pub struct ServiceMediation<T> {
+    wallet: Arc<T>,
+    service_endpoint: Url,
+    connections: Arc<ServiceConnections<T>>,
+    recipient: Mutex<Option<MediationRecipient<GenericConnection>>>,
+}
+
+impl<T: BaseWallet> ServiceMediation<T> {
+    pub fn new(
+        wallet: Arc<T>,
+        service_endpoint: Url,
+        connections: Arc<ServiceConnections<T>>,
+    ) -> Self {
+        Self {
+            wallet,
+            service_endpoint,
+            connections,
+            recipient: Mutex::new(None),
+        }
+    }
+
+    /// Requests mediation by the counterparty of the connection identified by `connection_id`.
+    /// Once granted, the peer DIDs created by the agent, and the invitations built from them,
+    /// receive messages through the mediator.
+    pub async fn request_mediation(&self, connection_id: &str) -> AgentResult<MediationGrant> {
+        let connection = self.connections.get_by_id(connection_id)?;
+        let mut recipient = MediationRecipient::new(connection);
+        let grant = recipient
+            .request_mediation(self.wallet.as_ref(), &VcxHttpClient)
+            .await?
+            .clone();
+        *self.recipient.lock().await = Some(recipient);
+        Ok(grant)
+    }
+
+    pub async fn grant(&self) -> Option<MediationGrant> {
+        self.recipient
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|recipient| recipient.grant().cloned())
+    }
+
+    /// Creates a `did:peer:4` for a new connection. If mediation has been granted, its service
+    /// has the endpoint and routing keys of the mediator and its key is added to the keylist,
+    /// otherwise the agent's own endpoint is used.
+    pub async fn create_peer_did_4(&self) -> AgentResult<(PeerDid<Numalgo4>, Key)> {
+        let mut recipient = self.recipient.lock().await;
+        match recipient.as_mut() {
+            Some(recipient) if recipient.grant().is_some() => Ok(recipient
+                .create_peer_did_4(self.wallet.as_ref(), &VcxHttpClient)
+                .await?),
+            _ => Ok(
+                create_peer_did_4(self.wallet.as_ref(), self.service_endpoint.clone(), vec![])
+                    .await?,
+            ),
+        }
+    }
+
+    /// Picks up the messages queued by the mediator.
+    pub async fn pickup_messages(&self) -> AgentResult<Vec<MediatedMessage>> {
+        let recipient = self.recipient.lock().await;
+        let recipient = recipient.as_ref().ok_or_else(|| {
+            AgentError::from_msg(
+                AgentErrorKind::InvalidState,
+                "Mediation has not been requested",
+            )
+        })?;
+        let mut messages = Vec::new();
+        recipient
+            .pickup_all(
+                self.wallet.as_ref(),
+                &VcxHttpClient,
+                PICKUP_BATCH_SIZE,
+                |message| {
+                    messages.push(message);
+                    Ok(())
+                },
+            )
+            .await?;
+        Ok(messages)
+    }
+}
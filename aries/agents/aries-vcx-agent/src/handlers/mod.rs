@@ -1,8 +1,12 @@
+pub(crate) mod basic_message;
 pub(crate) mod connection;
+pub(crate) mod connectionless;
 pub(crate) mod credential_definition;
 pub(crate) mod did_exchange;
+pub(crate) mod did_rotate;
 pub(crate) mod holder;
 pub(crate) mod issuer;
+pub(crate) mod mediation;
 pub(crate) mod out_of_band;
 pub(crate) mod prover;
 pub(crate) mod revocation_registry;
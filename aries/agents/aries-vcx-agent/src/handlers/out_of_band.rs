@@ -5,41 +5,51 @@ use aries_vcx::{
         receiver::OutOfBandReceiver, sender::OutOfBandSender, GenericOutOfBand,
     },
     messages::{
-        msg_fields::protocols::out_of_band::invitation::{Invitation as OobInvitation, OobService},
+        msg_fields::protocols::out_of_band::{
+            invitation::{Invitation as OobInvitation, OobService},
+            reuse::HandshakeReuse,
+            reuse_accepted::HandshakeReuseAccepted,
+        },
         msg_types::{
             protocols::did_exchange::{DidExchangeType, DidExchangeTypeV1},
             Protocol,
         },
         AriesMessage,
     },
-    protocols::did_exchange::state_machine::helpers::create_peer_did_4,
+    transport::MessageConnection,
 };
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use url::Url;
 
+use super::{did_exchange::DidcommHandlerDidExchange, mediation::ServiceMediation};
 use crate::{
+    error::*,
+    http::VcxHttpClient,
     storage::{agent_storage_inmem::AgentStorageInMem, AgentStorage},
-    AgentResult,
 };
 
 pub struct ServiceOutOfBand<T> {
     wallet: Arc<T>,
-    service_endpoint: Url,
+    mediation: Arc<ServiceMediation<T>>,
+    did_exchange: Arc<DidcommHandlerDidExchange<T>>,
     out_of_band: Arc<AgentStorageInMem<GenericOutOfBand>>,
 }
 
 impl<T: BaseWallet> ServiceOutOfBand<T> {
-    pub fn new(wallet: Arc<T>, service_endpoint: Url) -> Self {
+    pub fn new(
+        wallet: Arc<T>,
+        mediation: Arc<ServiceMediation<T>>,
+        did_exchange: Arc<DidcommHandlerDidExchange<T>>,
+    ) -> Self {
         Self {
             wallet,
-            service_endpoint,
+            mediation,
+            did_exchange,
             out_of_band: Arc::new(AgentStorageInMem::new("out-of-band")),
         }
     }
 
     pub async fn create_invitation(&self) -> AgentResult<AriesMessage> {
-        let (peer_did, _our_verkey) =
-            create_peer_did_4(self.wallet.as_ref(), self.service_endpoint.clone(), vec![]).await?;
+        let (peer_did, _our_verkey) = self.mediation.create_peer_did_4().await?;
 
         let sender = OutOfBandSender::create()
             .append_service(&OobService::Did(peer_did.to_string()))
@@ -73,4 +83,179 @@ impl<T: BaseWallet> ServiceOutOfBand<T> {
     pub fn exists_by_id(&self, thread_id: &str) -> bool {
         self.out_of_band.contains_key(thread_id)
     }
+
+    /// Returns the id of an existing DID exchange connection with the inviter of the received
+    /// invitation identified by `invitation_id`, if there is one.
+    pub fn find_reusable_connection(&self, invitation_id: &str) -> AgentResult<Option<String>> {
+        let receiver = self.get_receiver(invitation_id)?;
+        reusable_connection(
+            &self.out_of_band,
+            &receiver,
+            self.did_exchange.invitation_ids()?,
+        )
+    }
+
+    /// Sends a handshake reuse message, referencing the received invitation identified by
+    /// `invitation_id`, over the existing connection identified by `connection_id`. Returns the
+    /// thread id of the reuse.
+    pub async fn send_handshake_reuse(
+        &self,
+        invitation_id: &str,
+        connection_id: &str,
+    ) -> AgentResult<String> {
+        let reuse = self.get_receiver(invitation_id)?.build_handshake_reuse();
+        let connection = self.did_exchange.get_by_id(connection_id)?;
+        connection
+            .send_message(self.wallet.as_ref(), &reuse.clone().into(), &VcxHttpClient)
+            .await?;
+        Ok(reuse.decorators.thread.thid)
+    }
+
+    /// Accepts the reuse of the connection identified by `connection_id`, over which `reuse` was
+    /// received, in place of establishing a new connection from one of our invitations.
+    pub async fn handle_handshake_reuse(
+        &self,
+        connection_id: &str,
+        reuse: HandshakeReuse,
+    ) -> AgentResult<()> {
+        let invitation_id = reuse.decorators.thread.pthid.clone().ok_or_else(|| {
+            AgentError::from_msg(
+                AgentErrorKind::InvalidArguments,
+                "Handshake reuse message is missing the parent thread id",
+            )
+        })?;
+        let sender = match self.find_sender(&invitation_id)? {
+            Some(sender) => sender,
+            None => {
+                return Err(AgentError::from_msg(
+                    AgentErrorKind::NotFound,
+                    &format!("No invitation referenced by handshake reuse: {invitation_id}"),
+                ))
+            }
+        };
+        let reuse_accepted = sender.handle_handshake_reuse(&reuse)?;
+        let connection = self.did_exchange.get_by_id(connection_id)?;
+        connection
+            .send_message(self.wallet.as_ref(), &reuse_accepted.into(), &VcxHttpClient)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the id of the received invitation whose handshake reuse was accepted.
+    pub fn handle_handshake_reuse_accepted(
+        &self,
+        reuse_accepted: HandshakeReuseAccepted,
+    ) -> AgentResult<String> {
+        let invitation_id = reuse_accepted
+            .decorators
+            .thread
+            .pthid
+            .clone()
+            .unwrap_or_default();
+        self.get_receiver(&invitation_id)?
+            .handle_handshake_reuse_accepted(&reuse_accepted)?;
+        Ok(invitation_id)
+    }
+
+    fn get_receiver(&self, invitation_id: &str) -> AgentResult<OutOfBandReceiver> {
+        match self.out_of_band.get(invitation_id)? {
+            GenericOutOfBand::Receiver(receiver) => Ok(receiver),
+            GenericOutOfBand::Sender(_) => Err(AgentError::from_msg(
+                AgentErrorKind::InvalidState,
+                &format!("Invitation {invitation_id} was not received, but sent by us"),
+            )),
+        }
+    }
+
+    // The parent thread id of a handshake reuse is either the id of the invitation, or one of
+    // our public DIDs listed in its services
+    fn find_sender(&self, pthid: &str) -> AgentResult<Option<OutOfBandSender>> {
+        let ids = self.out_of_band.find_by(|(id, out_of_band)| {
+            let out_of_band = out_of_band.lock().ok()?;
+            match &*out_of_band {
+                GenericOutOfBand::Sender(sender)
+                    if id == pthid
+                        || sender
+                            .get_services()
+                            .contains(&OobService::Did(pthid.to_owned())) =>
+                {
+                    Some(id.to_owned())
+                }
+                _ => None,
+            }
+        })?;
+        match ids.first() {
+            Some(id) => match self.out_of_band.get(id)? {
+                GenericOutOfBand::Sender(sender) => Ok(Some(sender)),
+                GenericOutOfBand::Receiver(_) => Ok(None),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+/// Returns the first of `connections`, given as pairs of connection id and id of the invitation
+/// the connection was requested for, which was established with the inviter of `receiver`.
+fn reusable_connection(
+    out_of_band: &AgentStorageInMem<GenericOutOfBand>,
+    receiver: &OutOfBandReceiver,
+    connections: Vec<(String, String)>,
+) -> AgentResult<Option<String>> {
+    let mut invitations = Vec::new();
+    for (connection_id, invitation_id) in connections {
+        if invitation_id == receiver.get_id() || !out_of_band.contains_key(&invitation_id) {
+            continue;
+        }
+        if let GenericOutOfBand::Receiver(connection_receiver) = out_of_band.get(&invitation_id)? {
+            invitations.push((connection_id, connection_receiver.oob));
+        }
+    }
+    Ok(receiver
+        .find_reusable_connection(
+            invitations
+                .iter()
+                .map(|(connection_id, invitation)| (connection_id.as_str(), invitation)),
+        )
+        .map(ToOwned::to_owned))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn receive(out_of_band: &AgentStorageInMem<GenericOutOfBand>, did: &str) -> OutOfBandReceiver {
+        let sender = OutOfBandSender::create().append_service(&OobService::Did(did.to_owned()));
+        let receiver =
+            OutOfBandReceiver::create_from_a2a_msg(&sender.invitation_to_aries_message()).unwrap();
+        out_of_band
+            .insert(
+                &receiver.get_id(),
+                GenericOutOfBand::Receiver(receiver.clone()),
+            )
+            .unwrap();
+        receiver
+    }
+
+    #[test]
+    fn test_reuse_connection_of_same_inviter() {
+        let out_of_band = AgentStorageInMem::new("out-of-band");
+        let first = receive(&out_of_band, "did:sov:LjgpST2rjsoxYegQDRm7EL");
+        let connections = vec![("connection".to_owned(), first.get_id())];
+
+        let second = receive(&out_of_band, "did:sov:LjgpST2rjsoxYegQDRm7EL");
+        assert_eq!(
+            reusable_connection(&out_of_band, &second, connections.clone()).unwrap(),
+            Some("connection".to_owned())
+        );
+        // The connection requested for an invitation is not reused for the same invitation
+        assert_eq!(
+            reusable_connection(&out_of_band, &first, connections.clone()).unwrap(),
+            None
+        );
+        let other_inviter = receive(&out_of_band, "did:sov:Nh7sQdPWmUQELx1TKHCqeX");
+        assert_eq!(
+            reusable_connection(&out_of_band, &other_inviter, connections).unwrap(),
+            None
+        );
+    }
 }
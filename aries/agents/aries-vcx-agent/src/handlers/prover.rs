@@ -4,8 +4,9 @@ use anoncreds_types::data_types::messages::cred_selection::SelectedCredentials;
 use aries_vcx::{
     handlers::{proof_presentation::prover::Prover, util::PresentationProposalData},
     messages::{
-        msg_fields::protocols::present_proof::v1::{
-            ack::AckPresentationV1, request::RequestPresentationV1,
+        msg_fields::protocols::{
+            out_of_band::invitation::Invitation as OobInvitation,
+            present_proof::v1::{ack::AckPresentationV1, request::RequestPresentationV1},
         },
         AriesMessage,
     },
@@ -15,8 +16,12 @@ use aries_vcx_anoncreds::anoncreds::anoncreds::Anoncreds;
 use aries_vcx_ledger::ledger::indy_vdr_ledger::DefaultIndyLedgerRead;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use serde_json::Value;
+use url::Url;
 
-use super::connection::ServiceConnections;
+use super::{
+    connection::ServiceConnections,
+    connectionless::{ConnectionlessExchange, Counterparty},
+};
 use crate::{
     error::*,
     http::VcxHttpClient,
@@ -26,14 +31,14 @@ use crate::{
 #[derive(Clone)]
 struct ProverWrapper {
     prover: Prover,
-    connection_id: String,
+    counterparty: Counterparty,
 }
 
 impl ProverWrapper {
     pub fn new(prover: Prover, connection_id: &str) -> Self {
         Self {
             prover,
-            connection_id: connection_id.to_string(),
+            counterparty: Counterparty::Connection(connection_id.to_string()),
         }
     }
 }
@@ -42,6 +47,7 @@ pub struct ServiceProver<T> {
     ledger_read: Arc<DefaultIndyLedgerRead>,
     anoncreds: Anoncreds,
     wallet: Arc<T>,
+    service_endpoint: Url,
     provers: AgentStorageInMem<ProverWrapper>,
     service_connections: Arc<ServiceConnections<T>>,
 }
@@ -51,6 +57,7 @@ impl<T: BaseWallet> ServiceProver<T> {
         ledger_read: Arc<DefaultIndyLedgerRead>,
         anoncreds: Anoncreds,
         wallet: Arc<T>,
+        service_endpoint: Url,
         service_connections: Arc<ServiceConnections<T>>,
     ) -> Self {
         Self {
@@ -59,6 +66,7 @@ impl<T: BaseWallet> ServiceProver<T> {
             ledger_read,
             anoncreds,
             wallet,
+            service_endpoint,
         }
     }
 
@@ -68,8 +76,8 @@ impl<T: BaseWallet> ServiceProver<T> {
     }
 
     pub fn get_connection_id(&self, thread_id: &str) -> AgentResult<String> {
-        let ProverWrapper { connection_id, .. } = self.provers.get(thread_id)?;
-        Ok(connection_id)
+        let ProverWrapper { counterparty, .. } = self.provers.get(thread_id)?;
+        counterparty.connection_id()
     }
 
     async fn get_credentials_for_presentation(
@@ -107,6 +115,31 @@ impl<T: BaseWallet> ServiceProver<T> {
         )
     }
 
+    /// Creates a prover from a presentation request received without a connection, either
+    /// attached to `invitation` or decorated with `~service`. The presentation will be sent
+    /// straight to the service of the verifier.
+    pub async fn create_from_connectionless_request(
+        &self,
+        request: RequestPresentationV1,
+        invitation: Option<&OobInvitation>,
+    ) -> AgentResult<String> {
+        let exchange = ConnectionlessExchange::create(
+            self.wallet.as_ref(),
+            self.ledger_read.as_ref(),
+            request.decorators.service.as_ref(),
+            invitation,
+        )
+        .await?;
+        let prover = Prover::create_from_request("", request)?;
+        self.provers.insert(
+            &prover.get_thread_id()?,
+            ProverWrapper {
+                prover,
+                counterparty: Counterparty::Connectionless(exchange),
+            },
+        )
+    }
+
     pub async fn send_proof_proposal(
         &self,
         connection_id: &str,
@@ -147,9 +180,8 @@ impl<T: BaseWallet> ServiceProver<T> {
     ) -> AgentResult<()> {
         let ProverWrapper {
             mut prover,
-            connection_id,
+            counterparty,
         } = self.provers.get(thread_id)?;
-        let connection = self.service_connections.get_by_id(&connection_id)?;
         let credentials = self
             .get_credentials_for_presentation(&prover, tails_dir)
             .await?;
@@ -163,21 +195,34 @@ impl<T: BaseWallet> ServiceProver<T> {
             )
             .await?;
 
-        let wallet = &self.wallet;
-
-        let send_closure: SendClosure = Box::new(|msg: AriesMessage| {
-            Box::pin(async move {
-                connection
-                    .send_message(wallet.as_ref(), &msg, &VcxHttpClient)
-                    .await
-            })
-        });
-
         let message = prover.mark_presentation_sent()?;
-        send_closure(message).await?;
+        match &counterparty {
+            Counterparty::Connection(connection_id) => {
+                // The verifier may acknowledge the presentation over the same connection. The
+                // presentation is sent regardless, so the prover is kept waiting for the
+                // acknowledgement if the reply fails.
+                let reply = self
+                    .service_connections
+                    .send_message_with_return_route(connection_id, &message)
+                    .await?;
+                if let Some(reply) = reply {
+                    if let Err(err) = prover.process_aries_msg(reply).await {
+                        warn!("Failed to process reply to presentation: {}", err);
+                    }
+                }
+            }
+            Counterparty::Connectionless(exchange) => {
+                exchange
+                    .send_message(self.wallet.as_ref(), &self.service_endpoint, message)
+                    .await?;
+            }
+        }
         self.provers.insert(
             &prover.get_thread_id()?,
-            ProverWrapper::new(prover, &connection_id),
+            ProverWrapper {
+                prover,
+                counterparty,
+            },
         )?;
         Ok(())
     }
@@ -189,12 +234,15 @@ impl<T: BaseWallet> ServiceProver<T> {
     ) -> AgentResult<String> {
         let ProverWrapper {
             mut prover,
-            connection_id,
+            counterparty,
         } = self.provers.get(thread_id)?;
         prover.process_presentation_ack(ack)?;
         self.provers.insert(
             &prover.get_thread_id()?,
-            ProverWrapper::new(prover, &connection_id),
+            ProverWrapper {
+                prover,
+                counterparty,
+            },
         )
     }
 
@@ -46,6 +46,29 @@ impl<T: BaseWallet> ServiceRevocationRegistries<T> {
         Ok(rev_reg.get_rev_reg_def().value.tails_hash)
     }
 
+    /// Returns the revocation registry of the credential definition, if it has one. Fails if it
+    /// has several, as which one is meant would be ambiguous.
+    pub(in crate::handlers) fn get_by_cred_def_id(
+        &self,
+        cred_def_id: &str,
+    ) -> AgentResult<Option<RevocationRegistry>> {
+        match self.find_by_cred_def_id(cred_def_id)?.as_slice() {
+            [] => Ok(None),
+            [rev_reg_id] => Ok(Some(self.rev_regs.get(rev_reg_id)?)),
+            rev_reg_ids => Err(AgentError::from_msg(
+                AgentErrorKind::InvalidState,
+                &format!(
+                    "Credential definition {cred_def_id} has several revocation registries: \
+                     {rev_reg_ids:?}"
+                ),
+            )),
+        }
+    }
+
+    pub(in crate::handlers) fn insert(&self, rev_reg: RevocationRegistry) -> AgentResult<String> {
+        self.rev_regs.insert(&rev_reg.get_rev_reg_id(), rev_reg)
+    }
+
     pub fn get_tails_dir(&self, thread_id: &str) -> AgentResult<String> {
         let rev_reg = self.rev_regs.get(thread_id)?;
         Ok(rev_reg.get_tails_dir())
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use anoncreds_types::data_types::messages::pres_request::PresentationRequest;
 use aries_vcx::{
+    common::proofs::verifier::verification_report::VerificationReport,
     handlers::proof_presentation::verifier::Verifier,
     messages::{
         msg_fields::protocols::present_proof::v1::{
@@ -103,6 +104,11 @@ impl<T: BaseWallet> ServiceVerifier<T> {
         Ok(verifier.get_verification_status())
     }
 
+    pub fn get_verification_report(&self, thread_id: &str) -> AgentResult<VerificationReport> {
+        let VerifierWrapper { verifier, .. } = self.verifiers.get(thread_id)?;
+        Ok(verifier.get_verification_report()?)
+    }
+
     pub async fn verify_presentation(
         &self,
         thread_id: &str,
@@ -10,4 +10,13 @@ impl Transport for VcxHttpClient {
         shared::http_client::post_message(msg, service_endpoint).await?;
         Ok(())
     }
+
+    async fn send_message_and_receive(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        let response = shared::http_client::post_message(msg, service_endpoint).await?;
+        Ok((!response.is_empty()).then_some(response))
+    }
 }
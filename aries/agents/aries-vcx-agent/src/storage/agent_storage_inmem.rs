@@ -101,6 +101,12 @@ where
         store.contains_key(id)
     }
 
+    fn remove(&self, id: &str) -> AgentResult<bool> {
+        info!("Removing object {} from in-memory store {}", id, self.name);
+        let mut store = self.lock_store_write()?;
+        Ok(store.remove(id).is_some())
+    }
+
     fn find_by<F>(&self, closure: F) -> AgentResult<Vec<String>>
     where
         F: FnMut((&String, &Self::Value)) -> Option<String>,
@@ -7,6 +7,8 @@ pub trait AgentStorage<T> {
     fn get(&self, id: &str) -> AgentResult<T>;
     fn insert(&self, id: &str, obj: T) -> AgentResult<String>;
     fn contains_key(&self, id: &str) -> bool;
+    /// Removes the object, returning whether it was stored
+    fn remove(&self, id: &str) -> AgentResult<bool>;
     fn find_by<F>(&self, closure: F) -> AgentResult<Vec<String>>
     where
         F: FnMut((&String, &Self::Value)) -> Option<String>;
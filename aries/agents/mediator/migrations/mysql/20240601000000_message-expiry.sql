@@ -0,0 +1,5 @@
+-- Time messages were received at (unix timestamp, in seconds), for purging expired messages.
+ALTER TABLE messages
+    ADD COLUMN received_at BIGINT NOT NULL DEFAULT 0,
+    ADD INDEX(received_at);
+UPDATE messages SET received_at = UNIX_TIMESTAMP();
@@ -0,0 +1,49 @@
+-- PostgreSQL port of the mysql migration of the same name.
+-- Account and message ids are generated by the mediator, which also stores their readable form.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    -- metadata (total accounts registered until this account's creation)
+    seq_num BIGSERIAL,
+    -- uuid, generated by the mediator
+    account_id BYTEA NOT NULL UNIQUE,
+    -- for display purpose
+    account_name VARCHAR(36) NOT NULL,
+    -- mediator facing pubkey
+    -- UNIQUE constraint automatically creates index for fast unique checking; is reused for scanning
+    auth_pubkey VARCHAR(64) NOT NULL UNIQUE,
+    -- key used to sign messages to aries peer
+    our_signing_key VARCHAR(64) NOT NULL,
+    -- their complete did_doc, including routing keys etc (useful for wrapping messages in encryption envelope)
+    did_doc TEXT NOT NULL,
+    PRIMARY KEY (account_id)
+);
+
+
+CREATE TABLE IF NOT EXISTS recipients (
+    seq_num BIGSERIAL,
+    account_id BYTEA NOT NULL,
+    recipient_key VARCHAR(64) NOT NULL UNIQUE,
+    PRIMARY KEY (account_id, recipient_key),
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE
+);
+
+
+CREATE TABLE IF NOT EXISTS messages (
+    -- also gives the order messages were received in
+    seq_num BIGSERIAL,
+    account_id BYTEA NOT NULL,
+    -- Allow nullable so that recipient_key remove key-updates are possible
+    -- without also removing associated messages
+    recipient_key VARCHAR(64) NULL,
+    -- Readable message id (for identifying message over wire), generated by the mediator
+    message_id VARCHAR(36) NOT NULL UNIQUE,
+    message_data BYTEA NOT NULL,
+    PRIMARY KEY (account_id, message_id),
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (recipient_key) REFERENCES recipients(recipient_key)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS messages_account_recipient ON messages (account_id, recipient_key);
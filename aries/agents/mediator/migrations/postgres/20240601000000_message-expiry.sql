@@ -0,0 +1,5 @@
+-- Time messages were received at (unix timestamp, in seconds), for purging expired messages.
+ALTER TABLE messages ADD COLUMN IF NOT EXISTS received_at BIGINT NOT NULL DEFAULT 0;
+UPDATE messages SET received_at = EXTRACT(EPOCH FROM NOW())::BIGINT;
+
+CREATE INDEX IF NOT EXISTS messages_received_at ON messages (received_at);
@@ -0,0 +1,47 @@
+-- SQLite port of the mysql migration of the same name.
+-- SQLite has no UUID functions, so account and message ids are generated by the mediator,
+-- which also stores their readable form.
+
+CREATE TABLE IF NOT EXISTS accounts (
+    -- metadata (total accounts registered until this account's creation)
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    -- uuid, generated by the mediator
+    account_id BLOB NOT NULL UNIQUE,
+    -- for display purpose
+    account_name TEXT NOT NULL,
+    -- mediator facing pubkey
+    auth_pubkey TEXT NOT NULL UNIQUE,
+    -- key used to sign messages to aries peer
+    our_signing_key TEXT NOT NULL,
+    -- their complete did_doc, including routing keys etc (useful for wrapping messages in encryption envelope)
+    did_doc TEXT NOT NULL
+);
+
+
+CREATE TABLE IF NOT EXISTS recipients (
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id BLOB NOT NULL,
+    recipient_key TEXT NOT NULL UNIQUE,
+    UNIQUE (account_id, recipient_key),
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE
+);
+
+
+CREATE TABLE IF NOT EXISTS messages (
+    -- also gives the order messages were received in
+    seq_num INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id BLOB NOT NULL,
+    -- Allow nullable so that recipient_key remove key-updates are possible
+    -- without also removing associated messages
+    recipient_key TEXT NULL,
+    -- Readable message id (for identifying message over wire), generated by the mediator
+    message_id TEXT NOT NULL UNIQUE,
+    message_data BLOB NOT NULL,
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE,
+    FOREIGN KEY (recipient_key) REFERENCES recipients(recipient_key)
+        ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS messages_account_recipient ON messages (account_id, recipient_key);
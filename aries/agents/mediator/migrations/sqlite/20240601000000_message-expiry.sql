@@ -0,0 +1,6 @@
+-- Time messages were received at (unix timestamp, in seconds), for purging expired messages.
+-- SQLite can't add a column with a non-constant default, queued messages are stamped afterwards.
+ALTER TABLE messages ADD COLUMN received_at INTEGER NOT NULL DEFAULT 0;
+UPDATE messages SET received_at = CAST(strftime('%s', 'now') AS INTEGER);
+
+CREATE INDEX IF NOT EXISTS messages_received_at ON messages (received_at);
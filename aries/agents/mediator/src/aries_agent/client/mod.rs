@@ -96,7 +96,7 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     ) -> Result<(), String> {
         let their_vk = state.remote_vk().map_err(|e| e.to_string())?;
         let our_vk = &state.pairwise_info().pw_vk;
-        self.create_account(&their_vk, our_vk, state.their_did_doc())
+        self.create_account(&their_vk, our_vk, &state.their_did_doc().clone().into())
             .await?;
         Ok(())
     }
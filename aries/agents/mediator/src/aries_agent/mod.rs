@@ -1,9 +1,21 @@
-use std::{marker::PhantomData, sync::Arc};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, RwLock},
+};
 
 use aries_vcx::{
-    handlers::out_of_band::sender::OutOfBandSender,
+    did_doc::schema::{did_doc::DidDocument, service::typed::ServiceType},
+    handlers::{mediation::recipient::MediationGrant, out_of_band::sender::OutOfBandSender},
     messages::msg_fields::protocols::out_of_band::invitation::OobService,
-    utils::encryption_envelope::EncryptionEnvelope,
+    protocols::did_exchange::state_machine::{
+        generic::GenericDidExchange, helpers::create_peer_did_4,
+    },
+    utils::{
+        didcomm_utils::{
+            get_ed25519_recipient_keys, get_ed25519_routing_keys, resolve_ed25519_key_agreement,
+        },
+        encryption_envelope::EncryptionEnvelope,
+    },
 };
 use aries_vcx_wallet::{
     errors::error::VcxWalletError,
@@ -13,19 +25,40 @@ use aries_vcx_wallet::{
         structs_io::UnpackMessageOutput,
     },
 };
+use did_peer::{
+    peer_did::{numalgos::numalgo4::Numalgo4, PeerDid},
+    resolver::PeerDidResolver,
+};
+use did_resolver_registry::ResolverRegistry;
 use diddoc_legacy::aries::{diddoc::AriesDidDoc, service::AriesService};
 use messages::{
     msg_fields::protocols::{
         connection::{request::Request, response::Response, Connection},
+        did_exchange::v1_x::request::AnyRequest,
         out_of_band::invitation::Invitation as OOBInvitation,
     },
+    msg_types::{
+        protocols::{
+            connection::{ConnectionType, ConnectionTypeV1},
+            did_exchange::{DidExchangeType, DidExchangeTypeV1},
+        },
+        Protocol,
+    },
     AriesMessage,
 };
+use public_key::{Key, KeyType};
 use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
-    persistence::{get_persistence, AccountDetails, MediatorPersistence},
+    mediation::{
+        live_delivery::LiveDeliverySessions,
+        queue_limits::{QueueLimits, QueueOverflows},
+        upstream::build_mediation_grant,
+    },
+    persistence::{
+        get_persistence, AccountDetails, AccountDidDoc, DatabasePool, MediatorPersistence,
+    },
     utils::{prelude::*, structs::VerKey},
 };
 
@@ -37,7 +70,18 @@ pub mod utils;
 pub struct Agent<T: BaseWallet, P: MediatorPersistence> {
     wallet: Arc<T>,
     persistence: Arc<P>,
-    service: Option<AriesService>,
+    /// Service advertised in invitations, replaced when service keys are rotated
+    service: Arc<RwLock<Option<AriesService>>>,
+    /// Peer DID advertised in invitations for DID Exchange requesters
+    peer_did: Arc<RwLock<Option<PeerDid<Numalgo4>>>>,
+    /// Mediation granted to us by an upstream mediator, which mediation we grant routes through
+    upstream_grant: Arc<RwLock<Option<MediationGrant>>>,
+    resolver_registry: Arc<ResolverRegistry>,
+    live_delivery: LiveDeliverySessions,
+    queue_limits: QueueLimits,
+    queue_overflows: QueueOverflows,
+    /// Bearer token of the admin API, which is disabled if unset
+    admin_api_token: Option<String>,
 }
 
 pub type ArcAgent<T, P> = Arc<Agent<T, P>>;
@@ -47,31 +91,55 @@ pub struct AgentBuilder<T: BaseWallet> {
 }
 /// Constructors
 impl<T: BaseWallet> AgentBuilder<T> {
+    /// Agent persisting to the database given by the environment, see [get_persistence].
     pub async fn new_from_wallet_config(
         config: impl ManageWallet,
-    ) -> Result<Agent<impl BaseWallet, sqlx::MySqlPool>, VcxWalletError> {
-        let wallet = Arc::new(config.create_wallet().await?);
-
+    ) -> anyhow::Result<Agent<impl BaseWallet, DatabasePool>> {
         info!("Connecting to persistence layer");
-        let persistence = Arc::new(get_persistence().await);
+        let persistence = get_persistence().await?;
+        Ok(Self::new_from_wallet_config_and_persistence(config, persistence).await?)
+    }
+    pub async fn new_from_wallet_config_and_persistence<P: MediatorPersistence>(
+        config: impl ManageWallet,
+        persistence: P,
+    ) -> Result<Agent<impl BaseWallet, P>, VcxWalletError> {
+        let wallet = Arc::new(config.create_wallet().await?);
         Ok(Agent {
             wallet,
-            persistence,
-            service: None,
+            persistence: Arc::new(persistence),
+            service: Arc::new(RwLock::new(None)),
+            peer_did: Arc::new(RwLock::new(None)),
+            upstream_grant: Arc::new(RwLock::new(None)),
+            resolver_registry: Arc::new(
+                ResolverRegistry::new().register_resolver("peer".into(), PeerDidResolver::new()),
+            ),
+            live_delivery: LiveDeliverySessions::default(),
+            queue_limits: QueueLimits::default(),
+            queue_overflows: QueueOverflows::default(),
+            admin_api_token: None,
         })
     }
-    pub async fn new_demo_agent() -> Result<Agent<impl BaseWallet, sqlx::MySqlPool>, VcxWalletError>
-    {
-        let config = AskarWalletConfig::new(
-            "sqlite://:memory:",
-            KeyMethod::Unprotected,
-            "",
-            &Uuid::new_v4().to_string(),
-        );
-        Self::new_from_wallet_config(config).await
+    pub async fn new_demo_agent() -> anyhow::Result<Agent<impl BaseWallet, DatabasePool>> {
+        Self::new_from_wallet_config(demo_wallet_config()).await
+    }
+    /// Demo agent with the given persistence, e.g. an in-memory sqlite database.
+    pub async fn new_demo_agent_with_persistence<P: MediatorPersistence>(
+        persistence: P,
+    ) -> Result<Agent<impl BaseWallet, P>, VcxWalletError> {
+        Self::new_from_wallet_config_and_persistence(demo_wallet_config(), persistence).await
     }
 }
 
+/// Config of a dummy in-memory wallet
+fn demo_wallet_config() -> AskarWalletConfig {
+    AskarWalletConfig::new(
+        "sqlite://:memory:",
+        KeyMethod::Unprotected,
+        "",
+        &Uuid::new_v4().to_string(),
+    )
+}
+
 // Utils
 impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     pub fn get_wallet_ref(&self) -> Arc<T> {
@@ -80,11 +148,58 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     pub fn get_persistence_ref(&self) -> Arc<impl MediatorPersistence> {
         self.persistence.clone()
     }
-    pub fn get_service_ref(&self) -> Option<&AriesService> {
-        self.service.as_ref()
+    pub fn get_service(&self) -> Option<AriesService> {
+        self.service.read().unwrap().clone()
+    }
+    /// Peer DID resolving to the current service, which is the routing DID of mediated recipients
+    pub fn get_peer_did(&self) -> Option<String> {
+        self.peer_did
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|peer_did| peer_did.to_string())
+    }
+    pub fn set_upstream_grant(&self, upstream_grant: Option<MediationGrant>) {
+        *self.upstream_grant.write().unwrap() = upstream_grant;
     }
+    /// Endpoint and routing keys granted to mediated recipients, which route through the upstream
+    /// mediator if we are mediated ourselves
+    pub fn get_mediation_grant(&self) -> Option<MediationGrant> {
+        let service = self.get_service()?;
+        let service_key = service.recipient_keys.first()?.to_owned();
+        let outer_grant = self
+            .upstream_grant
+            .read()
+            .unwrap()
+            .clone()
+            .unwrap_or(MediationGrant {
+                endpoint: service.service_endpoint,
+                routing_keys: service.routing_keys,
+            });
+        Some(build_mediation_grant(service_key, outer_grant))
+    }
+    pub fn get_live_delivery_ref(&self) -> &LiveDeliverySessions {
+        &self.live_delivery
+    }
+    pub fn get_queue_limits_ref(&self) -> &QueueLimits {
+        &self.queue_limits
+    }
+    pub fn get_queue_overflows_ref(&self) -> &QueueOverflows {
+        &self.queue_overflows
+    }
+    pub fn set_queue_limits(&mut self, queue_limits: QueueLimits) {
+        self.queue_limits = queue_limits;
+    }
+    pub fn get_admin_api_token(&self) -> Option<&str> {
+        self.admin_api_token.as_deref()
+    }
+    pub fn set_admin_api_token(&mut self, admin_api_token: Option<String>) {
+        self.admin_api_token = admin_api_token;
+    }
+    /// Creates new service keys and peer DID, which invitations are issued with from now on.
+    /// Accounts keep the keys they were created with, but outstanding invitations are invalidated.
     pub async fn reset_service(
-        &mut self,
+        &self,
         routing_keys: Vec<String>,
         service_endpoint: url::Url,
     ) -> Result<(), VcxWalletError> {
@@ -94,10 +209,14 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             type_: "did-communication".to_owned(),
             priority: 0,
             recipient_keys: vec![did_data.verkey().base58()],
-            routing_keys,
-            service_endpoint,
+            routing_keys: routing_keys.clone(),
+            service_endpoint: service_endpoint.clone(),
         };
-        self.service = Some(service);
+        let (peer_did, _) = create_peer_did_4(self.wallet.as_ref(), service_endpoint, routing_keys)
+            .await
+            .map_err(|err| VcxWalletError::InvalidInput(err.to_string()))?;
+        *self.service.write().unwrap() = Some(service);
+        *self.peer_did.write().unwrap() = Some(peer_did);
         Ok(())
     }
 
@@ -109,9 +228,23 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         self.reset_service(routing_keys, service_endpoint).await
     }
     pub fn get_oob_invite(&self) -> Result<OOBInvitation, String> {
-        if let Some(service) = &self.service {
-            let invitation = OutOfBandSender::create()
-                .append_service(&OobService::AriesService(service.clone()))
+        if let Some(service) = self.get_service() {
+            // The inline service comes first, for requesters of the legacy connection protocol
+            let mut sender =
+                OutOfBandSender::create().append_service(&OobService::AriesService(service));
+            if let Some(peer_did) = self.get_peer_did() {
+                sender = sender.append_service(&OobService::Did(peer_did));
+            }
+            let invitation = sender
+                .append_handshake_protocol(Protocol::DidExchangeType(DidExchangeType::V1(
+                    DidExchangeTypeV1::new_v1_1(),
+                )))
+                .and_then(|sender| {
+                    sender.append_handshake_protocol(Protocol::ConnectionType(ConnectionType::V1(
+                        ConnectionTypeV1::new_v1_0(),
+                    )))
+                })
+                .map_err(string_from_std_error)?
                 .oob;
             Ok(invitation)
         } else {
@@ -123,7 +256,7 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             .wallet
             .unpack_message(didcomm_msg)
             .await
-            .expect("Valid didcomm?");
+            .map_err(|err| format!("Failed to unpack didcomm message: {err}"))?;
         info!("{:#?}", unpacked);
         Ok(unpacked)
     }
@@ -144,6 +277,54 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         .map_err(string_from_std_error)
     }
 
+    /// Packs a message for the holder of the account, with the key we use for this account
+    pub async fn pack_didcomm_for_account(
+        &self,
+        message: &[u8],
+        account_details: &AccountDetails,
+    ) -> Result<EncryptionEnvelope, String> {
+        match &account_details.their_did_doc {
+            AccountDidDoc::Legacy(their_diddoc) => {
+                self.pack_didcomm(message, &account_details.our_signing_key, their_diddoc)
+                    .await
+            }
+            AccountDidDoc::Peer(their_did_doc) => {
+                let our_vk = Key::from_base58(&account_details.our_signing_key, KeyType::Ed25519)
+                    .map_err(string_from_std_error)?;
+                self.pack_didcomm_for_peer(message, our_vk, their_did_doc)
+                    .await
+            }
+        }
+    }
+
+    async fn pack_didcomm_for_peer(
+        &self,
+        message: &[u8],
+        our_vk: Key,
+        their_did_doc: &DidDocument,
+    ) -> Result<EncryptionEnvelope, String> {
+        let service = their_did_doc
+            .get_service_of_type(&ServiceType::DIDCommV1)
+            .map_err(string_from_std_error)?;
+        let mut recipient_keys = get_ed25519_recipient_keys(their_did_doc, service.id())
+            .map_err(string_from_std_error)?;
+        if recipient_keys.is_empty() {
+            recipient_keys
+                .push(resolve_ed25519_key_agreement(their_did_doc).map_err(string_from_std_error)?);
+        }
+        let routing_keys =
+            get_ed25519_routing_keys(their_did_doc, service.id()).map_err(string_from_std_error)?;
+        EncryptionEnvelope::create_from_recipient_keys(
+            self.wallet.as_ref(),
+            message,
+            Some(our_vk),
+            recipient_keys,
+            routing_keys,
+        )
+        .await
+        .map_err(string_from_std_error)
+    }
+
     pub async fn auth_and_get_details(
         &self,
         sender_verkey: &Option<VerKey>,
@@ -177,14 +358,10 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             .create_and_store_my_did(None, None)
             .await
             .map_err(|e| e.to_string())?;
-        let old_vk = self
-            .service
-            .as_ref()
-            .unwrap()
-            .recipient_keys
-            .first()
-            .unwrap()
-            .to_owned();
+        let service = self
+            .get_service()
+            .ok_or("No service to respond to connection requests with")?;
+        let old_vk = service.recipient_keys.first().unwrap().to_owned();
 
         let response: Response = utils::build_response_content(
             self.wallet.as_ref(),
@@ -192,8 +369,8 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             old_vk.clone(),
             did_data.did().into(),
             did_data.verkey().base58(),
-            self.service.as_ref().unwrap().service_endpoint.clone(),
-            self.service.as_ref().unwrap().routing_keys.clone(),
+            service.service_endpoint.clone(),
+            service.routing_keys.clone(),
         )
         .await
         .map_err(|e| e.to_string())?;
@@ -211,8 +388,61 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         let auth_pubkey = their_keys
             .first()
             .ok_or("No recipient key for client :/ ?".to_owned())?;
-        self.create_account(auth_pubkey, &did_data.verkey().base58(), &their_diddoc)
+        self.create_account(
+            auth_pubkey,
+            &did_data.verkey().base58(),
+            &their_diddoc.into(),
+        )
+        .await?;
+        Ok(packed_response_envelope)
+    }
+
+    /// Responds to a DID Exchange request, received packed for `invitation_key`. An account is
+    /// created for the peer DID of the requester, later messages being authenticated against the
+    /// key agreement key of its DID document.
+    pub async fn handle_did_exchange_req(
+        &self,
+        request: AnyRequest,
+        invitation_key: &VerKey,
+    ) -> Result<EncryptionEnvelope, String> {
+        let service = self
+            .get_service()
+            .ok_or("No service to respond to DID Exchange requests with")?;
+        let invitation_key =
+            Key::from_base58(invitation_key, KeyType::Ed25519).map_err(string_from_std_error)?;
+        let (our_peer_did, our_vk) = create_peer_did_4(
+            self.wallet.as_ref(),
+            service.service_endpoint.clone(),
+            service.routing_keys.clone(),
+        )
+        .await
+        .map_err(string_from_std_error)?;
+        let (responder, response) = GenericDidExchange::handle_request(
+            self.wallet.as_ref(),
+            &self.resolver_registry,
+            request,
+            &our_peer_did,
+            invitation_key,
+        )
+        .await
+        .map_err(string_from_std_error)?;
+        let their_did_doc = responder.their_did_doc();
+        let their_vk =
+            resolve_ed25519_key_agreement(their_did_doc).map_err(string_from_std_error)?;
+        let aries_response: AriesMessage = response.into();
+        let packed_response_envelope = self
+            .pack_didcomm_for_peer(
+                json!(aries_response).to_string().as_bytes(),
+                our_vk.clone(),
+                their_did_doc,
+            )
             .await?;
+        self.create_account(
+            &their_vk.base58(),
+            &our_vk.base58(),
+            &their_did_doc.clone().into(),
+        )
+        .await?;
         Ok(packed_response_envelope)
     }
 
@@ -220,7 +450,7 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         &self,
         their_vk: &VerKey,
         our_vk: &VerKey,
-        did_doc: &AriesDidDoc,
+        did_doc: &AccountDidDoc,
     ) -> Result<(), String> {
         self.persistence
             .create_account(their_vk, our_vk, &json!(did_doc).to_string())
@@ -230,7 +460,7 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "sqlite"))]
 mod test {
     use aries_vcx::{
         protocols::oob::oob_invitation_to_legacy_did_doc,
@@ -242,12 +472,16 @@ mod test {
     use test_utils::mockdata::mock_ledger::MockLedger;
 
     use super::AgentBuilder;
+    use crate::persistence::get_db_pool_from_url;
 
     #[tokio::test]
     pub async fn test_pack_unpack() {
         let message: Value = serde_json::from_str("{}").unwrap();
         let message_bytes = serde_json::to_vec(&message).unwrap();
-        let mut agent = AgentBuilder::<AskarWallet>::new_demo_agent().await.unwrap();
+        let persistence = get_db_pool_from_url("sqlite::memory:").await.unwrap();
+        let mut agent = AgentBuilder::<AskarWallet>::new_demo_agent_with_persistence(persistence)
+            .await
+            .unwrap();
         agent
             .init_service(
                 vec![],
@@ -260,7 +494,7 @@ mod test {
             oob_invitation_to_legacy_did_doc(&mock_ledger, &agent.get_oob_invite().unwrap())
                 .await
                 .unwrap();
-        let our_service = agent.service.as_ref().unwrap();
+        let our_service = agent.get_service().unwrap();
         let our_vk = our_service.recipient_keys.first().unwrap();
         let EncryptionEnvelope(packed) = agent
             .pack_didcomm(&message_bytes, our_vk, &their_diddoc)
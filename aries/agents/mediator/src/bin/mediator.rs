@@ -1,12 +1,24 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context;
 use aries_vcx_wallet::wallet::askar::{
     askar_wallet_config::AskarWalletConfig, key_method::KeyMethod, AskarWallet,
 };
 use log::info;
-use mediator::aries_agent::AgentBuilder;
+use mediator::{
+    aries_agent::AgentBuilder,
+    mediation::{
+        queue_limits::{spawn_purge_task, QueueLimits},
+        upstream::{
+            connect_upstream_mediator, spawn_upstream_pickup_task, HttpTransport,
+            DEFAULT_PICKUP_INTERVAL,
+        },
+    },
+};
 use uuid::Uuid;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> anyhow::Result<()> {
     load_dot_env();
     setup_logging();
     info!("Starting up mediator! ⚙️⚙️");
@@ -19,29 +31,62 @@ async fn main() {
         "",
         &Uuid::new_v4().to_string(),
     );
-    let wallet_config_json = std::env::var("INDY_WALLET_CONFIG");
-    let wallet_config = wallet_config_json
-        .ok()
-        .map_or(default_wallet_config, |json| {
-            serde_json::from_str(&json).unwrap()
-        });
+    let wallet_config = match std::env::var("INDY_WALLET_CONFIG") {
+        Ok(json) => serde_json::from_str(&json).context("Invalid INDY_WALLET_CONFIG")?,
+        Err(_) => default_wallet_config,
+    };
     info!("Wallet Config: {:?}", wallet_config);
-    let mut agent = AgentBuilder::<AskarWallet>::new_from_wallet_config(wallet_config)
-        .await
-        .unwrap();
+    let mut agent = AgentBuilder::<AskarWallet>::new_from_wallet_config(wallet_config).await?;
     agent
         .init_service(
             vec![],
-            format!("http://{endpoint_root}/didcomm").parse().unwrap(),
+            format!("http://{endpoint_root}/didcomm")
+                .parse()
+                .context("Invalid ENDPOINT_ROOT")?,
         )
-        .await
-        .unwrap();
+        .await?;
+    let queue_limits = QueueLimits::from_env()?;
+    info!("Message queue limits: {:?}", queue_limits);
+    spawn_purge_task(agent.get_persistence_ref(), &queue_limits);
+    agent.set_queue_limits(queue_limits);
+    let admin_api_token = std::env::var("ADMIN_API_TOKEN").ok();
+    if admin_api_token.is_none() {
+        info!("ADMIN_API_TOKEN is not set, admin API is disabled");
+    }
+    agent.set_admin_api_token(admin_api_token);
+    if let Ok(invitation_url) = std::env::var("UPSTREAM_MEDIATOR_INVITATION_URL") {
+        info!("Connecting to upstream mediator: {}", invitation_url);
+        let invitation = reqwest::get(invitation_url)
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("Failed to fetch the invitation of the upstream mediator")?
+            .json()
+            .await
+            .context("Invalid invitation of the upstream mediator")?;
+        let transport = HttpTransport::default();
+        let recipient = connect_upstream_mediator(&agent, invitation, &transport)
+            .await
+            .map_err(anyhow::Error::msg)
+            .context("Failed to connect to the upstream mediator")?;
+        let pickup_interval = match std::env::var("UPSTREAM_PICKUP_INTERVAL_SECONDS") {
+            Ok(secs) => Duration::from_secs(
+                secs.parse()
+                    .context("Invalid UPSTREAM_PICKUP_INTERVAL_SECONDS")?,
+            ),
+            Err(_) => DEFAULT_PICKUP_INTERVAL,
+        };
+        spawn_upstream_pickup_task(
+            Arc::new(agent.clone()),
+            recipient,
+            transport,
+            pickup_interval,
+        );
+    }
     let app_router = mediator::http_routes::build_router(agent).await;
     info!("Starting server");
-    let listener = tokio::net::TcpListener::bind(&endpoint_root).await.unwrap();
-    axum::serve(listener, app_router.into_make_service())
-        .await
-        .unwrap();
+    let listener = tokio::net::TcpListener::bind(&endpoint_root).await?;
+    axum::serve(listener, app_router.into_make_service()).await?;
+    Ok(())
 }
 
 fn setup_logging() {
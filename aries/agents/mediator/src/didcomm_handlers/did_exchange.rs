@@ -0,0 +1,32 @@
+use aries_vcx_wallet::wallet::{base_wallet::BaseWallet, structs_io::UnpackMessageOutput};
+use messages::msg_fields::protocols::did_exchange::{
+    v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, v1_x::request::AnyRequest, DidExchange,
+};
+
+use super::{unhandled_aries_message, utils::prelude::*, ArcAgent};
+
+pub async fn handle_did_exchange<T: BaseWallet, P: MediatorPersistence>(
+    agent: ArcAgent<T, P>,
+    did_exchange: DidExchange,
+    unpacked: &UnpackMessageOutput,
+) -> Result<Option<EncryptionEnvelope>, String> {
+    let request = match did_exchange {
+        DidExchange::V1_0(DidExchangeV1_0::Request(request)) => AnyRequest::V1_0(request),
+        DidExchange::V1_1(DidExchangeV1_1::Request(request)) => AnyRequest::V1_1(request),
+        // The account was created when responding to the request, nothing is left to do
+        DidExchange::V1_0(DidExchangeV1_0::Complete(_))
+        | DidExchange::V1_1(DidExchangeV1_1::Complete(_)) => {
+            let account_details = agent.auth_and_get_details(&unpacked.sender_verkey).await?;
+            info!(
+                "DID Exchange completed for {:?}",
+                account_details.account_name
+            );
+            return Ok(None);
+        }
+        _ => return Err(unhandled_aries_message(did_exchange)),
+    };
+    agent
+        .handle_did_exchange_req(request, &unpacked.recipient_verkey)
+        .await
+        .map(Some)
+}
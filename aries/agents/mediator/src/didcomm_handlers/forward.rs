@@ -1,15 +1,174 @@
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use messages::msg_fields::protocols::{notification::ack::Ack, routing::Forward};
+use messages::{
+    msg_fields::protocols::{notification::ack::Ack, routing::Forward},
+    AriesMessage,
+};
+use serde_json::Value;
 
 use super::{utils::prelude::*, ArcAgent};
-use crate::mediation::forward::handle_forward;
+use crate::mediation::{
+    forward::{build_forward_ack, forward_recipient_keys, handle_forward},
+    pickup::build_delivery,
+    queue_limits::build_queue_full_problem_report,
+};
 
 pub async fn handle_routing_forward(
     agent: ArcAgent<impl BaseWallet, impl MediatorPersistence>,
     forward: Forward,
 ) -> Result<Ack, String> {
     info!("{:?}", forward);
-    let ack = handle_forward(agent.get_persistence_ref(), forward).await;
+    let mut result = Ok(());
+    for recipient_key in recipient_keys_by_account(&agent, &forward).await {
+        let delivered = deliver_to_recipient(&agent, &recipient_key, &forward.content.msg).await;
+        result = result.and(delivered);
+    }
+    result.map(|_| build_forward_ack(forward.id))
+}
+
+/// Returns a recipient key of each account the forwarded message is for. The recipient named by
+/// the forward is kept even if unknown, for the persistence layer to reject.
+async fn recipient_keys_by_account(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    forward: &Forward,
+) -> Vec<String> {
+    let persistence = agent.get_persistence_ref();
+    let mut accounts = Vec::new();
+    let mut recipient_keys = Vec::new();
+    for (index, recipient_key) in forward_recipient_keys(forward).into_iter().enumerate() {
+        match persistence.get_recipient_account(&recipient_key).await {
+            Ok(auth_pubkey) if !accounts.contains(&auth_pubkey) => {
+                accounts.push(auth_pubkey);
+                recipient_keys.push(recipient_key);
+            }
+            Ok(_) => (),
+            Err(_) if index == 0 => recipient_keys.push(recipient_key),
+            Err(_) => (),
+        }
+    }
+    recipient_keys
+}
+
+/// Queues the forwarded message, and pushes it to the recipient if it has a live session. The
+/// pushed message stays queued until acknowledged, see [crate::mediation::live_delivery].
+async fn deliver_to_recipient(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    recipient_key: &str,
+    message: &Value,
+) -> Result<(), String> {
+    let message_id = queue_forward(agent, recipient_key, message).await?;
+    if let Err(err) = push_live_delivery(agent, recipient_key, message_id, message).await {
+        info!("Live delivery failed, message stays queued: {}", err);
+    }
+    Ok(())
+}
+
+/// Pushes the queued message in a delivery over the live session of the recipient, if any.
+/// Returns whether the message was pushed.
+async fn push_live_delivery(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    recipient_key: &str,
+    message_id: String,
+    message: &Value,
+) -> Result<bool, String> {
+    let live_delivery = agent.get_live_delivery_ref();
+    if live_delivery.is_empty() {
+        return Ok(false);
+    }
+    let persistence = agent.get_persistence_ref();
+    let Ok(auth_pubkey) = persistence.get_recipient_account(recipient_key).await else {
+        return Ok(false);
+    };
+    let Some((live_session, version)) = live_delivery.get(&auth_pubkey) else {
+        return Ok(false);
+    };
+    let account_details = persistence
+        .get_account_details(&auth_pubkey)
+        .await
+        .map_err(string_from_std_error)?;
+    let message_data = serde_json::to_vec(message).map_err(string_from_std_error)?;
+    let delivery = build_delivery(
+        version,
+        Some(recipient_key.to_owned()),
+        vec![(message_id, message_data)],
+    );
+    let aries_message = AriesMessage::Pickup(delivery);
+    let aries_message_bytes = serde_json::to_vec(&aries_message).map_err(string_from_std_error)?;
+    let EncryptionEnvelope(packed_message_bytes) = agent
+        .pack_didcomm_for_account(&aries_message_bytes, &account_details)
+        .await?;
+    live_session.push(packed_message_bytes)?;
+    info!("Pushed forward over live session of {}", auth_pubkey);
+    Ok(true)
+}
+
+/// Queues the forwarded message, within the quota of its recipient if there is one. On overflow,
+/// the recipient is notified once, until its queue has room again.
+async fn queue_forward(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    recipient_key: &str,
+    message: &Value,
+) -> Result<String, String> {
+    let queue_limits = agent.get_queue_limits_ref();
+    let persistence = agent.get_persistence_ref();
+    if !queue_limits.has_quota() {
+        return handle_forward(persistence, recipient_key, message)
+            .await
+            .map_err(string_from_std_error);
+    }
+    let queued = persistence
+        .persist_forward_message_within_quota(
+            recipient_key,
+            &message.to_string(),
+            queue_limits.max_messages,
+            queue_limits.max_bytes,
+        )
+        .await
+        .map_err(string_from_std_error)?;
+    let auth_pubkey = persistence
+        .get_recipient_account(recipient_key)
+        .await
+        .map_err(string_from_std_error)?;
+    let queue_overflows = agent.get_queue_overflows_ref();
+    if let Some(message_id) = queued {
+        queue_overflows.clear(&auth_pubkey);
+        return Ok(message_id);
+    }
+    info!("Message queue of {} is full", auth_pubkey);
+    if queue_overflows.mark(&auth_pubkey) {
+        if let Err(err) = notify_queue_full(agent, &auth_pubkey, recipient_key).await {
+            info!(
+                "Failed to notify {} of full message queue: {}",
+                auth_pubkey, err
+            );
+        }
+    }
+    Err(format!(
+        "Message queue of recipient {} is full",
+        recipient_key
+    ))
+}
 
-    Ok(ack)
+/// Queues a problem report for the account, past its quota, telling its queue is full
+async fn notify_queue_full(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    auth_pubkey: &str,
+    recipient_key: &str,
+) -> Result<(), String> {
+    let persistence = agent.get_persistence_ref();
+    let account_details = persistence
+        .get_account_details(auth_pubkey)
+        .await
+        .map_err(string_from_std_error)?;
+    let problem_report = AriesMessage::ReportProblem(build_queue_full_problem_report());
+    let problem_report_bytes =
+        serde_json::to_vec(&problem_report).map_err(string_from_std_error)?;
+    let EncryptionEnvelope(packed_message_bytes) = agent
+        .pack_didcomm_for_account(&problem_report_bytes, &account_details)
+        .await?;
+    let packed_message = String::from_utf8(packed_message_bytes).map_err(string_from_std_error)?;
+    persistence
+        .persist_forward_message(recipient_key, &packed_message)
+        .await
+        .map(|_| ())
+        .map_err(string_from_std_error)
 }
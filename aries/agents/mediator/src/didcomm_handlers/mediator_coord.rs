@@ -1,6 +1,8 @@
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use messages::msg_fields::protocols::coordinate_mediation::{
-    CoordinateMediation, MediateGrant, MediateGrantContent, MediateGrantDecorators,
+    v1::{self, CoordinateMediationV1},
+    v2::{self, CoordinateMediationV2},
+    CoordinateMediation,
 };
 use uuid::Uuid;
 
@@ -11,36 +13,47 @@ pub async fn handle_mediation_coord(
     coord_msg: CoordinateMediation,
     auth_pubkey: &str,
 ) -> Result<CoordinateMediation, String> {
-    if let CoordinateMediation::MediateRequest(_mediate_request) = coord_msg {
-        let service = agent
-            .get_service_ref()
-            .ok_or("Mediation agent must have service defined.")?;
-        let mut routing_keys = Vec::new();
-        routing_keys.extend_from_slice(&service.routing_keys);
-        routing_keys.push(
-            service
-                .recipient_keys
-                .first()
-                .expect("Service must have recipient key")
-                .to_owned(),
-        );
-        let mediate_grant_content = MediateGrantContent {
-            endpoint: service.service_endpoint.to_string(),
-            routing_keys,
-        };
-        let mediate_grant = MediateGrant::builder()
-            .content(mediate_grant_content)
-            .decorators(MediateGrantDecorators::default())
-            .id(Uuid::new_v4().to_string())
-            .build();
-        let coord_response = CoordinateMediation::MediateGrant(mediate_grant);
-        return Ok(coord_response);
-    };
-    let coord_response = crate::mediation::coordination::handle_coord_authenticated(
-        agent.get_persistence_ref(),
-        coord_msg,
-        auth_pubkey,
-    )
-    .await;
-    Ok(coord_response)
+    match coord_msg {
+        CoordinateMediation::V1(CoordinateMediationV1::MediateRequest(_mediate_request)) => {
+            let grant = agent
+                .get_mediation_grant()
+                .ok_or("Mediation agent must have service defined.")?;
+            let mediate_grant_content = v1::MediateGrantContent {
+                endpoint: grant.endpoint.to_string(),
+                routing_keys: grant.routing_keys,
+            };
+            let mediate_grant = v1::MediateGrant::builder()
+                .content(mediate_grant_content)
+                .decorators(v1::MediateGrantDecorators::default())
+                .id(Uuid::new_v4().to_string())
+                .build();
+            Ok(CoordinateMediation::V1(
+                CoordinateMediationV1::MediateGrant(mediate_grant),
+            ))
+        }
+        // Our peer DID resolves to our endpoint and routing keys, it's the routing DID
+        CoordinateMediation::V2(CoordinateMediationV2::MediateRequest(_mediate_request)) => {
+            let routing_did = agent
+                .get_peer_did()
+                .ok_or("Mediation agent must have peer DID defined.")?;
+            let mediate_grant = v2::MediateGrant::builder()
+                .content(v2::MediateGrantContent {
+                    routing_did: vec![routing_did],
+                })
+                .decorators(v2::MediateGrantDecorators::default())
+                .id(Uuid::new_v4().to_string())
+                .build();
+            Ok(CoordinateMediation::V2(
+                CoordinateMediationV2::MediateGrant(mediate_grant),
+            ))
+        }
+        coord_msg => {
+            crate::mediation::coordination::handle_coord_authenticated(
+                agent.get_persistence_ref(),
+                coord_msg,
+                auth_pubkey,
+            )
+            .await
+        }
+    }
 }
@@ -1,20 +1,31 @@
 use std::fmt::Debug;
 
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use axum::{body::Bytes, extract::State, Json};
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{Message as WsMessage, WebSocket},
+        State,
+    },
+    Json,
+};
 use messages::AriesMessage;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use utils::prelude::*;
 
+use crate::mediation::live_delivery::LiveSession;
+
 mod connection;
+mod did_exchange;
 mod forward;
 mod mediator_coord;
 mod pickup;
 mod utils;
 
 use connection::handle_aries_connection;
-use forward::handle_routing_forward;
+use did_exchange::handle_did_exchange;
+pub use forward::handle_routing_forward;
 use mediator_coord::handle_mediation_coord;
 use pickup::handle_pickup_protocol;
 
@@ -31,8 +42,88 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
     State(agent): State<ArcAgent<T, P>>,
     didcomm_msg: Bytes,
 ) -> Result<Json<Value>, String> {
-    log::info!("processing message {:?}", &didcomm_msg);
-    let unpacked = agent.unpack_didcomm(&didcomm_msg).await.unwrap();
+    match handle_aries_message(&agent, &didcomm_msg, None).await? {
+        Some(EncryptionEnvelope(packed_message_bytes)) => {
+            let packed_json = serde_json::from_slice(&packed_message_bytes[..]).unwrap();
+            Ok(Json(packed_json))
+        }
+        None => Ok(Json(json!({}))),
+    }
+}
+
+/// Serves a persistent DIDComm connection: packed messages are read from the socket, responses
+/// are returned through it, as are deliveries pushed while live delivery is enabled.
+pub async fn handle_aries_websocket<T: BaseWallet, P: MediatorPersistence>(
+    agent: ArcAgent<T, P>,
+    mut socket: WebSocket,
+) {
+    let (live_session, mut pushed_messages) = LiveSession::new();
+    loop {
+        let outgoing = tokio::select! {
+            incoming = socket.recv() => match incoming {
+                Some(Ok(WsMessage::Text(didcomm_msg))) => {
+                    handle_websocket_message(&agent, didcomm_msg.as_bytes(), &live_session).await
+                }
+                Some(Ok(WsMessage::Binary(didcomm_msg))) => {
+                    handle_websocket_message(&agent, &didcomm_msg, &live_session).await
+                }
+                // Pings are answered by axum
+                Some(Ok(WsMessage::Ping(_) | WsMessage::Pong(_))) => None,
+                Some(Ok(WsMessage::Close(_))) | None => break,
+                Some(Err(err)) => {
+                    info!("Websocket connection failed: {}", err);
+                    break;
+                }
+            },
+            // Pushed messages stay queued until the recipient acknowledges them
+            Some(pushed_message) = pushed_messages.recv() => Some(pushed_message),
+        };
+        let Some(packed_message_bytes) = outgoing else {
+            continue;
+        };
+        let frame = match String::from_utf8(packed_message_bytes) {
+            Ok(frame) => WsMessage::Text(frame.into()),
+            Err(err) => {
+                info!(
+                    "Sending packed message as binary frame, as it isn't UTF-8: {}",
+                    err
+                );
+                WsMessage::Binary(err.into_bytes().into())
+            }
+        };
+        if let Err(err) = socket.send(frame).await {
+            info!("Websocket connection failed: {}", err);
+            break;
+        }
+    }
+    agent
+        .get_live_delivery_ref()
+        .unregister_session(&live_session);
+}
+
+async fn handle_websocket_message(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    didcomm_msg: &[u8],
+    live_session: &LiveSession,
+) -> Option<Vec<u8>> {
+    match handle_aries_message(agent, didcomm_msg, Some(live_session)).await {
+        Ok(packed_response) => packed_response.map(|EncryptionEnvelope(bytes)| bytes),
+        Err(err) => {
+            info!("Error processing websocket message: {}", err);
+            None
+        }
+    }
+}
+
+/// Processes a packed message, returning the packed response if there is one. Live delivery can
+/// only be enabled for messages received over a persistent connection, given as `live_session`.
+pub async fn handle_aries_message<T: BaseWallet, P: MediatorPersistence>(
+    agent: &ArcAgent<T, P>,
+    didcomm_msg: &[u8],
+    live_session: Option<&LiveSession>,
+) -> Result<Option<EncryptionEnvelope>, String> {
+    log::info!("processing message {:?}", didcomm_msg);
+    let unpacked = agent.unpack_didcomm(didcomm_msg).await?;
     let aries_message: GeneralAriesMessage =
         serde_json::from_str(&unpacked.message).map_err(|e| e.to_string())?;
     let packed_response =
@@ -40,30 +131,35 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
             aries_message
         {
             handle_aries_connection(agent.clone(), conn).await?
+        } else if let GeneralAriesMessage::AriesVCXSupported(AriesMessage::DidExchange(
+            did_exchange,
+        )) = aries_message
+        {
+            return handle_did_exchange(agent.clone(), did_exchange, &unpacked).await;
         } else if let GeneralAriesMessage::AriesVCXSupported(AriesMessage::Routing(forward)) =
             aries_message
         {
             handle_routing_forward(agent.clone(), forward).await?;
-            return Ok(Json(json!({})));
+            return Ok(None);
         } else {
             // Authenticated flow: Auth known VerKey then process account related messages
             let account_details = agent.auth_and_get_details(&unpacked.sender_verkey).await?;
             log::info!("Processing message for {:?}", account_details.account_name);
             let aries_response = match aries_message {
                 GeneralAriesMessage::AriesVCXSupported(AriesMessage::Pickup(pickup_message)) => {
-                    let pickup_response = handle_pickup_protocol(
-                        &agent,
+                    handle_pickup_protocol(
+                        agent,
                         pickup_message,
                         &account_details.auth_pubkey,
+                        live_session,
                     )
-                    .await?;
-                    AriesMessage::Pickup(pickup_response)
+                    .await?
                 }
                 GeneralAriesMessage::AriesVCXSupported(AriesMessage::CoordinateMediation(
                     coord_message,
                 )) => {
                     let coord_response =
-                        handle_mediation_coord(&agent, coord_message, &account_details.auth_pubkey)
+                        handle_mediation_coord(agent, coord_message, &account_details.auth_pubkey)
                             .await?;
                     AriesMessage::CoordinateMediation(coord_response)
                 }
@@ -74,14 +170,8 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
             let aries_response_bytes =
                 serde_json::to_vec(&aries_response).map_err(string_from_std_error)?;
             agent
-                .pack_didcomm(
-                    &aries_response_bytes,
-                    &account_details.our_signing_key,
-                    &account_details.their_did_doc,
-                )
+                .pack_didcomm_for_account(&aries_response_bytes, &account_details)
                 .await?
         };
-    let EncryptionEnvelope(packed_message_bytes) = packed_response;
-    let packed_json = serde_json::from_slice(&packed_message_bytes[..]).unwrap();
-    Ok(Json(packed_json))
+    Ok(Some(packed_response))
 }
@@ -1,18 +1,125 @@
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use messages::msg_fields::protocols::pickup::Pickup;
+use messages::{
+    decorators::{
+        thread::Thread,
+        transport::{ReturnRoute, Transport},
+    },
+    msg_fields::protocols::{
+        pickup::{v2::PickupV2, v3::PickupV3, Pickup},
+        report_problem::{
+            Description, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+        },
+    },
+    AriesMessage,
+};
+use uuid::Uuid;
 
 use super::utils::prelude::*;
+use crate::mediation::{live_delivery::LiveSession, pickup::PickupVersion};
 
 pub async fn handle_pickup_protocol(
     agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Result<Pickup, String> {
+    live_session: Option<&LiveSession>,
+) -> Result<AriesMessage, String> {
+    let live_delivery_change = match &pickup_message {
+        Pickup::V2(PickupV2::LiveDeliveryChange(live_delivery_change)) => {
+            info!("Received {:#?}", live_delivery_change);
+            Some(LiveDeliveryChangeRequest {
+                id: &live_delivery_change.id,
+                live_delivery: live_delivery_change.content.live_delivery,
+                transport: live_delivery_change.decorators.transport.as_ref(),
+                thread: live_delivery_change.decorators.thread.as_ref(),
+            })
+        }
+        Pickup::V3(PickupV3::LiveDeliveryChange(live_delivery_change)) => {
+            info!("Received {:#?}", live_delivery_change);
+            Some(LiveDeliveryChangeRequest {
+                id: &live_delivery_change.id,
+                live_delivery: live_delivery_change.content.live_delivery,
+                transport: live_delivery_change.decorators.transport.as_ref(),
+                thread: live_delivery_change.decorators.thread.as_ref(),
+            })
+        }
+        _ => None,
+    };
+    if let Some(live_delivery_change) = live_delivery_change {
+        let version = PickupVersion::of(&pickup_message);
+        if let Err(problem_report) = handle_live_delivery_change(
+            agent,
+            live_delivery_change,
+            version,
+            auth_pubkey,
+            live_session,
+        ) {
+            return Ok(AriesMessage::ReportProblem(problem_report));
+        }
+    }
     let pickup_response = crate::mediation::pickup::handle_pickup_authenticated(
         agent.get_persistence_ref(),
         pickup_message,
         auth_pubkey,
     )
-    .await;
-    Ok(pickup_response)
+    .await?;
+    Ok(AriesMessage::Pickup(pickup_response))
+}
+
+/// Fields of a `live-delivery-change`, common to Pickup 2.0 and 3.0
+struct LiveDeliveryChangeRequest<'a> {
+    id: &'a str,
+    live_delivery: bool,
+    transport: Option<&'a Transport>,
+    thread: Option<&'a Thread>,
+}
+
+/// Live mode can only be enabled over a persistent connection, with return route set to all.
+fn handle_live_delivery_change(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    live_delivery_change: LiveDeliveryChangeRequest,
+    version: PickupVersion,
+    auth_pubkey: &str,
+    live_session: Option<&LiveSession>,
+) -> Result<(), ProblemReport> {
+    let live_delivery = agent.get_live_delivery_ref();
+    if !live_delivery_change.live_delivery {
+        live_delivery.unregister(auth_pubkey);
+        return Ok(());
+    }
+    let return_route_all = matches!(
+        live_delivery_change.transport,
+        Some(Transport {
+            return_route: ReturnRoute::All,
+            ..
+        })
+    );
+    match live_session {
+        Some(live_session) if return_route_all => {
+            live_delivery.register(auth_pubkey, live_session.clone(), version);
+            Ok(())
+        }
+        _ => {
+            let thid = live_delivery_change
+                .thread
+                .map(|thread| thread.thid.clone())
+                .unwrap_or_else(|| live_delivery_change.id.to_owned());
+            Err(ProblemReport::builder()
+                .id(Uuid::new_v4().to_string())
+                .content(
+                    ProblemReportContent::builder()
+                        .description(
+                            Description::builder()
+                                .code("e.msg.live-mode-not-supported".to_owned())
+                                .build(),
+                        )
+                        .build(),
+                )
+                .decorators(
+                    ProblemReportDecorators::builder()
+                        .thread(Thread::builder().thid(thid).build())
+                        .build(),
+                )
+                .build())
+        }
+    }
 }
@@ -0,0 +1,219 @@
+//! Admin API, for operators to inspect and manage accounts and their message queues.
+//!
+//! Requests are authenticated with the `Authorization: Bearer <token>` header, the token being
+//! configured with [Agent::set_admin_api_token](crate::aries_agent::Agent::set_admin_api_token).
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use axum::{
+    extract::{Path, Request, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use messages::msg_fields::protocols::out_of_band::invitation::Invitation as OOBInvitation;
+use serde::Serialize;
+
+use crate::{
+    aries_agent::ArcAgent,
+    persistence::{
+        errors::{GetAccountDetailsError, PurgePendingMessagesError, VaporizeAccountError},
+        AccountDidDoc, MediatorPersistence, QueueUsage,
+    },
+    utils::structs::VerKey,
+};
+
+type AdminError = (StatusCode, String);
+
+#[derive(Debug, Serialize)]
+pub struct AccountSummary {
+    pub account_name: String,
+    pub auth_pubkey: VerKey,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountInfo {
+    pub account_name: String,
+    pub auth_pubkey: VerKey,
+    pub our_signing_key: VerKey,
+    pub their_did_doc: AccountDidDoc,
+    pub recipient_keys: Vec<VerKey>,
+    pub queue: QueueUsage,
+    /// Whether the account has a live delivery session open
+    pub live_delivery: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PurgedMessages {
+    pub removed: u64,
+}
+
+fn internal_error(err: impl std::fmt::Display) -> AdminError {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+fn not_found(err: impl std::fmt::Display) -> AdminError {
+    (StatusCode::NOT_FOUND, err.to_string())
+}
+
+/// Compares tokens in constant time, not to leak the expected token through timing
+fn token_matches(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |diff, (given, expected)| diff | (given ^ expected))
+            == 0
+}
+
+async fn require_admin_token<T: BaseWallet, P: MediatorPersistence>(
+    State(agent): State<ArcAgent<T, P>>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let given_token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match (given_token, agent.get_admin_api_token()) {
+        (Some(given_token), Some(expected_token))
+            if token_matches(given_token.as_bytes(), expected_token.as_bytes()) =>
+        {
+            next.run(request).await
+        }
+        _ => StatusCode::UNAUTHORIZED.into_response(),
+    }
+}
+
+pub async fn list_accounts(
+    State(agent): State<ArcAgent<impl BaseWallet, impl MediatorPersistence>>,
+) -> Result<Json<Vec<AccountSummary>>, AdminError> {
+    let accounts = agent
+        .get_persistence_ref()
+        .list_accounts()
+        .await
+        .map_err(internal_error)?
+        .into_iter()
+        .map(|(account_name, auth_pubkey)| AccountSummary {
+            account_name,
+            auth_pubkey,
+        })
+        .collect();
+    Ok(Json(accounts))
+}
+
+pub async fn get_account(
+    State(agent): State<ArcAgent<impl BaseWallet, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<Json<AccountInfo>, AdminError> {
+    let persistence = agent.get_persistence_ref();
+    let account_details = persistence
+        .get_account_details(&auth_pubkey)
+        .await
+        .map_err(|err| match err {
+            GetAccountDetailsError::AccountNotFound(_) => not_found(err),
+            _ => internal_error(err),
+        })?;
+    let recipient_keys = persistence
+        .list_recipient_keys(&auth_pubkey)
+        .await
+        .map_err(internal_error)?;
+    let queue = persistence
+        .retrieve_queue_usage(&auth_pubkey)
+        .await
+        .map_err(internal_error)?;
+    let live_delivery = agent.get_live_delivery_ref().get(&auth_pubkey).is_some();
+    Ok(Json(AccountInfo {
+        account_name: account_details.account_name,
+        auth_pubkey: account_details.auth_pubkey,
+        our_signing_key: account_details.our_signing_key,
+        their_did_doc: account_details.their_did_doc,
+        recipient_keys,
+        queue,
+        live_delivery,
+    }))
+}
+
+/// Removes the account along with its recipient keys and queued messages, closing its live
+/// delivery
+pub async fn delete_account(
+    State(agent): State<ArcAgent<impl BaseWallet, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<StatusCode, AdminError> {
+    agent
+        .get_persistence_ref()
+        .vaporize_account(&auth_pubkey)
+        .await
+        .map_err(|err| match err {
+            VaporizeAccountError::AccountNotFound(_) => not_found(err),
+            _ => internal_error(err),
+        })?;
+    agent.get_live_delivery_ref().unregister(&auth_pubkey);
+    agent.get_queue_overflows_ref().clear(&auth_pubkey);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn purge_account_messages(
+    State(agent): State<ArcAgent<impl BaseWallet, impl MediatorPersistence>>,
+    Path(auth_pubkey): Path<VerKey>,
+) -> Result<Json<PurgedMessages>, AdminError> {
+    let removed = agent
+        .get_persistence_ref()
+        .purge_pending_messages(&auth_pubkey)
+        .await
+        .map_err(|err| match err {
+            PurgePendingMessagesError::AccountNotFound(_) => not_found(err),
+            _ => internal_error(err),
+        })?;
+    agent.get_queue_overflows_ref().clear(&auth_pubkey);
+    Ok(Json(PurgedMessages { removed }))
+}
+
+/// Replaces the keys of the mediator's service, returning the invitation issued from now on
+pub async fn rotate_service_keys(
+    State(agent): State<ArcAgent<impl BaseWallet, impl MediatorPersistence>>,
+) -> Result<Json<OOBInvitation>, AdminError> {
+    let service = agent
+        .get_service()
+        .ok_or_else(|| internal_error("Mediator has no service to rotate keys of"))?;
+    agent
+        .reset_service(service.routing_keys, service.service_endpoint)
+        .await
+        .map_err(internal_error)?;
+    let invitation = agent.get_oob_invite().map_err(internal_error)?;
+    Ok(Json(invitation))
+}
+
+pub fn build_admin_router<T: BaseWallet + 'static, P: MediatorPersistence>(
+    agent: ArcAgent<T, P>,
+) -> Router<ArcAgent<T, P>> {
+    Router::default()
+        .route("/accounts", get(list_accounts))
+        .route(
+            "/accounts/{auth_pubkey}",
+            get(get_account).delete(delete_account),
+        )
+        .route(
+            "/accounts/{auth_pubkey}/messages",
+            delete(purge_account_messages),
+        )
+        .route("/service/rotate-keys", post(rotate_service_keys))
+        .route_layer(middleware::from_fn_with_state(
+            agent,
+            require_admin_token::<T, P>,
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches(b"secret", b"secret"));
+        assert!(!token_matches(b"secreT", b"secret"));
+        assert!(!token_matches(b"secret", b"secret2"));
+        assert!(!token_matches(b"", b"secret"));
+    }
+}
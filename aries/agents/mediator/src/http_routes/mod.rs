@@ -3,7 +3,7 @@ use std::sync::Arc;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use axum::{
     body::Bytes,
-    extract::State,
+    extract::{State, WebSocketUpgrade},
     http::header::{HeaderMap, ACCEPT},
     response::{Html, IntoResponse, Response},
     routing::get,
@@ -18,6 +18,8 @@ use crate::{
     persistence::MediatorPersistence,
 };
 
+pub mod admin;
+
 fn detect_mime_type(headers: &HeaderMap) -> &str {
     headers
         .get(ACCEPT)
@@ -39,6 +41,13 @@ pub async fn handle_didcomm(
     didcomm_handlers::handle_aries(State(agent), didcomm_msg).await
 }
 
+pub async fn handle_didcomm_ws(
+    State(agent): State<ArcAgent<impl BaseWallet + 'static, impl MediatorPersistence>>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.on_upgrade(move |socket| didcomm_handlers::handle_aries_websocket(agent, socket))
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ReadmeInfo {
     message: String,
@@ -58,13 +67,21 @@ pub async fn readme(headers: HeaderMap) -> Response {
     }
 }
 
+/// Builds the router of the mediator. The admin API is served under `/admin` if the agent has an
+/// admin API token.
 pub async fn build_router(
     agent: Agent<impl BaseWallet + 'static, impl MediatorPersistence>,
 ) -> Router {
-    Router::default()
+    let agent = Arc::new(agent);
+    let mut router = Router::default()
         .route("/", get(readme))
         .route("/invitation", get(oob_invite_json))
         .route("/didcomm", get(handle_didcomm).post(handle_didcomm))
+        .route("/ws", get(handle_didcomm_ws));
+    if agent.get_admin_api_token().is_some() {
+        router = router.nest("/admin", admin::build_admin_router(agent.clone()));
+    }
+    router
         .layer(tower_http::catch_panic::CatchPanicLayer::new())
-        .with_state(Arc::new(agent))
+        .with_state(agent)
 }
@@ -1,44 +1,76 @@
 // Copyright 2023 Naian G.
 // SPDX-License-Identifier: Apache-2.0
 
+//! Coordinate Mediation 1.0 and 2.0 handling.
+//!
+//! Replies are sent in the version of the message they answer. Recipients registered through 2.0
+//! are DIDs rather than keys; they are stored as given, except `did:key` ones which are stored as
+//! base58 keys like 1.0 recipient keys, and forwards addressed to them are routed the same way.
+//! Recipients stored as keys are listed as `did:key` in 2.0.
+
 use std::sync::Arc;
 
 use messages::msg_fields::protocols::coordinate_mediation::{
-    keylist::KeylistItem,
-    keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
-    keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
-    CoordinateMediation, Keylist, KeylistContent, KeylistDecorators, KeylistQueryContent,
-    KeylistUpdateContent, KeylistUpdateResponse, KeylistUpdateResponseContent,
-    KeylistUpdateResponseDecorators, MediateDeny, MediateDenyContent, MediateDenyDecorators,
-    MediateGrant, MediateGrantContent, MediateGrantDecorators,
+    v1::{
+        keylist::KeylistItem,
+        keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+        keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
+        CoordinateMediationV1, Keylist, KeylistContent, KeylistDecorators, KeylistQueryContent,
+        KeylistUpdateContent, KeylistUpdateResponse, KeylistUpdateResponseContent,
+        KeylistUpdateResponseDecorators, MediateDeny, MediateDenyContent, MediateDenyDecorators,
+        MediateGrant, MediateGrantContent, MediateGrantDecorators,
+    },
+    v2::{
+        recipient::RecipientItem,
+        recipient_update::{RecipientUpdateItem, RecipientUpdateItemAction},
+        recipient_update_response::{RecipientUpdateItemResult, RecipientUpdateResponseItem},
+        CoordinateMediationV2, Recipient, RecipientContent, RecipientDecorators,
+        RecipientQueryContent, RecipientUpdateContent, RecipientUpdateResponse,
+        RecipientUpdateResponseContent, RecipientUpdateResponseDecorators,
+    },
+    CoordinateMediation,
 };
 use uuid::Uuid;
 
-use crate::persistence::MediatorPersistence;
+use crate::{
+    mediation::forward::{normalize_recipient_key, recipient_did},
+    persistence::MediatorPersistence,
+};
 
 pub async fn handle_coord_authenticated(
     storage: Arc<impl MediatorPersistence>,
     message: CoordinateMediation,
     auth_pubkey: &str,
-) -> CoordinateMediation {
+) -> Result<CoordinateMediation, String> {
     match message {
-        CoordinateMediation::MediateRequest(_mediate_request) => {
-            panic!(
-                "Use handle_mediate_request directly. This handler is for preregistered clients."
-            );
-        }
-        CoordinateMediation::KeylistUpdate(keylist_update) => {
-            handle_keylist_update(storage, keylist_update.content, auth_pubkey).await
+        CoordinateMediation::V1(CoordinateMediationV1::MediateRequest(_))
+        | CoordinateMediation::V2(CoordinateMediationV2::MediateRequest(_)) => Err(
+            "Use handle_mediate_request directly. This handler is for preregistered clients."
+                .to_owned(),
+        ),
+        CoordinateMediation::V1(CoordinateMediationV1::KeylistUpdate(keylist_update)) => {
+            Ok(handle_keylist_update(storage, keylist_update.content, auth_pubkey).await)
         }
-        CoordinateMediation::KeylistQuery(keylist_query) => {
+        CoordinateMediation::V1(CoordinateMediationV1::KeylistQuery(keylist_query)) => {
             handle_keylist_query(storage, keylist_query.content, auth_pubkey).await
         }
-        _ => handle_unimplemented().await,
+        CoordinateMediation::V2(CoordinateMediationV2::RecipientUpdate(recipient_update)) => {
+            Ok(handle_recipient_update(storage, recipient_update.content, auth_pubkey).await)
+        }
+        CoordinateMediation::V2(CoordinateMediationV2::RecipientQuery(recipient_query)) => {
+            handle_recipient_query(storage, recipient_query.content, auth_pubkey).await
+        }
+        message => handle_unimplemented(message).await,
     }
 }
 
-pub async fn handle_unimplemented() -> CoordinateMediation {
-    todo!("This error should ideally be handled on outer layer. Panicking for now.")
+pub async fn handle_unimplemented(
+    message: CoordinateMediation,
+) -> Result<CoordinateMediation, String> {
+    Err(format!(
+        "Unsupported coordinate mediation message for a mediator: {:?}",
+        message
+    ))
 }
 
 pub async fn handle_mediate_request<T: MediatorPersistence>(
@@ -58,7 +90,7 @@ pub async fn handle_mediate_request<T: MediatorPersistence>(
                 .decorators(MediateGrantDecorators::default())
                 .id(Uuid::new_v4().to_string())
                 .build();
-            CoordinateMediation::MediateGrant(mediate_grant_msg)
+            CoordinateMediation::V1(CoordinateMediationV1::MediateGrant(mediate_grant_msg))
         }
         Err(_msg) => {
             let mediate_deny_msg = MediateDeny::builder()
@@ -66,7 +98,7 @@ pub async fn handle_mediate_request<T: MediatorPersistence>(
                 .decorators(MediateDenyDecorators::default())
                 .id(Uuid::new_v4().to_string())
                 .build();
-            CoordinateMediation::MediateDeny(mediate_deny_msg)
+            CoordinateMediation::V1(CoordinateMediationV1::MediateDeny(mediate_deny_msg))
         }
     }
 }
@@ -76,17 +108,14 @@ pub async fn handle_keylist_query<T: MediatorPersistence>(
     //todo: use the limits mentioned in the KeylistQueryData to modify response
     _keylist_query_data: KeylistQueryContent,
     auth_pubkey: &str,
-) -> CoordinateMediation {
-    let keylist_items: Vec<KeylistItem> = match storage.list_recipient_keys(auth_pubkey).await {
-        Ok(recipient_keys) => recipient_keys
-            .into_iter()
-            .map(|recipient_key| KeylistItem { recipient_key })
-            .collect(),
-        Err(err) => todo!(
-            "This error should ideally be handled on outer layer. Panicking for now{}",
-            err
-        ),
-    };
+) -> Result<CoordinateMediation, String> {
+    let keylist_items: Vec<KeylistItem> = storage
+        .list_recipient_keys(auth_pubkey)
+        .await
+        .map_err(|err| format!("Failed to list recipient keys: {err}"))?
+        .into_iter()
+        .map(|recipient_key| KeylistItem { recipient_key })
+        .collect();
     let keylist = Keylist::builder()
         .content(KeylistContent {
             keys: keylist_items,
@@ -95,7 +124,9 @@ pub async fn handle_keylist_query<T: MediatorPersistence>(
         .decorators(KeylistDecorators::default())
         .id(Uuid::new_v4().to_string())
         .build();
-    CoordinateMediation::Keylist(keylist)
+    Ok(CoordinateMediation::V1(CoordinateMediationV1::Keylist(
+        keylist,
+    )))
 }
 
 pub async fn handle_keylist_update<T: MediatorPersistence>(
@@ -106,21 +137,17 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
     let updates: Vec<KeylistUpdateItem> = keylist_update_data.updates;
     let mut updated: Vec<KeylistUpdateResponseItem> = Vec::new();
     for update_item in updates.into_iter() {
-        let result = match &update_item.action {
-            KeylistUpdateItemAction::Add => {
-                storage
-                    .add_recipient(auth_pubkey, &update_item.recipient_key)
-                    .await
-            }
-            KeylistUpdateItemAction::Remove => {
-                storage
-                    .remove_recipient(auth_pubkey, &update_item.recipient_key)
-                    .await
-            }
-        };
-        let update_item_result = match result {
-            Ok(()) => KeylistUpdateItemResult::Success,
-            Err(_msg) => KeylistUpdateItemResult::ServerError,
+        let update_item_result = if update_recipient(
+            storage.as_ref(),
+            auth_pubkey,
+            &update_item.recipient_key,
+            &update_item.action,
+        )
+        .await
+        {
+            KeylistUpdateItemResult::Success
+        } else {
+            KeylistUpdateItemResult::ServerError
         };
         updated.push(KeylistUpdateResponseItem {
             recipient_key: update_item.recipient_key,
@@ -133,5 +160,170 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
         .decorators(KeylistUpdateResponseDecorators::default())
         .id(Uuid::new_v4().to_string())
         .build();
-    CoordinateMediation::KeylistUpdateResponse(keylist_update_response)
+    CoordinateMediation::V1(CoordinateMediationV1::KeylistUpdateResponse(
+        keylist_update_response,
+    ))
+}
+
+pub async fn handle_recipient_query<T: MediatorPersistence>(
+    storage: Arc<T>,
+    //todo: use the limits mentioned in the RecipientQueryContent to modify response
+    _recipient_query_data: RecipientQueryContent,
+    auth_pubkey: &str,
+) -> Result<CoordinateMediation, String> {
+    let recipient_items: Vec<RecipientItem> = storage
+        .list_recipient_keys(auth_pubkey)
+        .await
+        .map_err(|err| format!("Failed to list recipients: {err}"))?
+        .into_iter()
+        .map(|recipient_key| RecipientItem {
+            recipient_did: recipient_did(&recipient_key),
+        })
+        .collect();
+    let recipient = Recipient::builder()
+        .content(RecipientContent {
+            dids: recipient_items,
+            pagination: None,
+        })
+        .decorators(RecipientDecorators::default())
+        .id(Uuid::new_v4().to_string())
+        .build();
+    Ok(CoordinateMediation::V2(CoordinateMediationV2::Recipient(
+        recipient,
+    )))
+}
+
+pub async fn handle_recipient_update<T: MediatorPersistence>(
+    storage: Arc<T>,
+    recipient_update_data: RecipientUpdateContent,
+    auth_pubkey: &str,
+) -> CoordinateMediation {
+    let updates: Vec<RecipientUpdateItem> = recipient_update_data.updates;
+    let mut updated: Vec<RecipientUpdateResponseItem> = Vec::new();
+    for update_item in updates.into_iter() {
+        let action = match update_item.action {
+            RecipientUpdateItemAction::Add => KeylistUpdateItemAction::Add,
+            RecipientUpdateItemAction::Remove => KeylistUpdateItemAction::Remove,
+        };
+        let update_item_result = if update_recipient(
+            storage.as_ref(),
+            auth_pubkey,
+            &update_item.recipient_did,
+            &action,
+        )
+        .await
+        {
+            RecipientUpdateItemResult::Success
+        } else {
+            RecipientUpdateItemResult::ServerError
+        };
+        updated.push(RecipientUpdateResponseItem {
+            recipient_did: update_item.recipient_did,
+            action: update_item.action,
+            result: update_item_result,
+        });
+    }
+    let recipient_update_response = RecipientUpdateResponse::builder()
+        .content(RecipientUpdateResponseContent { updated })
+        .decorators(RecipientUpdateResponseDecorators::default())
+        .id(Uuid::new_v4().to_string())
+        .build();
+    CoordinateMediation::V2(CoordinateMediationV2::RecipientUpdateResponse(
+        recipient_update_response,
+    ))
+}
+
+/// Adds or removes a recipient of the account, as requested by a keylist or recipient update.
+/// Returns whether the update succeeded.
+async fn update_recipient<T: MediatorPersistence>(
+    storage: &T,
+    auth_pubkey: &str,
+    recipient: &str,
+    action: &KeylistUpdateItemAction,
+) -> bool {
+    // Keys are stored as base58 verkeys, forwards being matched against them
+    let recipient = normalize_recipient_key(recipient);
+    match action {
+        KeylistUpdateItemAction::Add => {
+            storage.add_recipient(auth_pubkey, &recipient).await.is_ok()
+        }
+        KeylistUpdateItemAction::Remove => storage
+            .remove_recipient(auth_pubkey, &recipient)
+            .await
+            .is_ok(),
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use public_key::{Key, KeyType};
+
+    use super::*;
+    use crate::persistence::get_db_pool_from_url;
+
+    #[tokio::test]
+    async fn test_recipient_update_and_query() {
+        let storage = Arc::new(get_db_pool_from_url("sqlite::memory:").await.unwrap());
+        storage
+            .create_account("auth_pubkey", "our_signing_key", "{}")
+            .await
+            .unwrap();
+        let did_key = format!(
+            "did:key:{}",
+            Key::new(vec![1; 32], KeyType::Ed25519)
+                .unwrap()
+                .fingerprint()
+        );
+        let update = RecipientUpdateContent {
+            updates: vec![
+                RecipientUpdateItem {
+                    recipient_did: "did:peer:2.recipient".to_owned(),
+                    action: RecipientUpdateItemAction::Add,
+                },
+                RecipientUpdateItem {
+                    recipient_did: did_key.clone(),
+                    action: RecipientUpdateItemAction::Add,
+                },
+            ],
+        };
+        let CoordinateMediation::V2(CoordinateMediationV2::RecipientUpdateResponse(response)) =
+            handle_recipient_update(Arc::clone(&storage), update, "auth_pubkey").await
+        else {
+            panic!("Expected recipient update response");
+        };
+        assert!(response
+            .content
+            .updated
+            .iter()
+            .all(|item| item.result == RecipientUpdateItemResult::Success));
+
+        let query = RecipientQueryContent::default();
+        let CoordinateMediation::V2(CoordinateMediationV2::Recipient(recipient)) =
+            handle_recipient_query(storage, query, "auth_pubkey")
+                .await
+                .unwrap()
+        else {
+            panic!("Expected recipient list");
+        };
+        // did:key recipients are stored as base58 keys, but listed as they were registered
+        let mut dids: Vec<String> = recipient
+            .content
+            .dids
+            .into_iter()
+            .map(|item| item.recipient_did)
+            .collect();
+        dids.sort();
+        assert_eq!(dids, vec![did_key, "did:peer:2.recipient".to_owned()]);
+    }
+
+    #[tokio::test]
+    async fn test_recipient_query_of_unknown_account_fails() {
+        let storage = Arc::new(get_db_pool_from_url("sqlite::memory:").await.unwrap());
+        let query = RecipientQueryContent::default();
+        assert!(
+            handle_recipient_query(storage, query, "unknown_auth_pubkey")
+                .await
+                .is_err()
+        );
+    }
 }
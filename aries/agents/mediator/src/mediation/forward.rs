@@ -3,6 +3,8 @@
 
 use std::sync::Arc;
 
+use aries_vcx::utils::{base64::URL_SAFE_LENIENT, didcomm_utils::parse_ed25519_key};
+use base64::Engine;
 use log::{debug, info};
 use messages::{
     decorators::thread::Thread,
@@ -11,35 +13,100 @@ use messages::{
         routing::Forward,
     },
 };
+use public_key::{Key, KeyType};
+use serde_json::Value;
 use uuid::Uuid;
 
-use crate::persistence::MediatorPersistence;
+use crate::persistence::{errors::PersistForwardMessageError, MediatorPersistence};
 
-pub async fn handle_forward<T>(storage: Arc<T>, forward_msg: Forward) -> Ack
+/// Queues the forwarded message for the account of the recipient key, returning the id of the
+/// queued message.
+pub async fn handle_forward<T>(
+    storage: Arc<T>,
+    recipient_key: &str,
+    message: &Value,
+) -> Result<String, PersistForwardMessageError>
 where
     T: MediatorPersistence,
 {
-    info!("Persisting forward message");
-    debug!("{forward_msg:#?}");
-    let _ack_status = match storage
-        .persist_forward_message(
-            &forward_msg.content.to,
-            &serde_json::to_string(&forward_msg.content.msg).unwrap(),
-        )
+    info!("Persisting forward message for {}", recipient_key);
+    debug!("{message:#?}");
+    let message_id = storage
+        .persist_forward_message(recipient_key, &message.to_string())
         .await
-    {
-        Ok(_) => {
-            info!("Persisted forward");
-            AckStatus::Ok
+        .inspect_err(|e| info!("Error when persisting forward: {}", e))?;
+    info!("Persisted forward {}", message_id);
+    Ok(message_id)
+}
+
+/// Returns the recipient key as base58 verkey, the form recipient keys are stored in, if it is a
+/// `did:key` or `did:key` URL. Other recipients, such as DIDs, are returned unchanged.
+pub fn normalize_recipient_key(recipient_key: &str) -> String {
+    if !recipient_key.starts_with("did:key:") {
+        return recipient_key.to_owned();
+    }
+    match parse_ed25519_key(recipient_key) {
+        Ok(key) => key.base58(),
+        Err(err) => {
+            info!("Invalid did:key recipient {}: {}", recipient_key, err);
+            recipient_key.to_owned()
         }
-        Err(e) => {
-            info!("Error when persisting forward: {}", e);
-            AckStatus::Pending
+    }
+}
+
+/// Returns the `did:key` of a stored recipient key, the form Coordinate Mediation 2.0 lists
+/// recipients in. Recipients stored as DIDs are returned unchanged.
+pub fn recipient_did(recipient_key: &str) -> String {
+    if recipient_key.starts_with("did:") {
+        return recipient_key.to_owned();
+    }
+    match Key::from_base58(recipient_key, KeyType::Ed25519) {
+        Ok(key) => format!("did:key:{}", key.fingerprint()),
+        Err(err) => {
+            info!("Invalid recipient key {}: {}", recipient_key, err);
+            recipient_key.to_owned()
         }
+    }
+}
+
+/// Returns the keys the forwarded message is for: the recipient named by the forward, followed by
+/// the other recipients of the envelope, e.g. the other keys of a service with several recipient
+/// keys.
+pub fn forward_recipient_keys(forward: &Forward) -> Vec<String> {
+    let mut recipient_keys = vec![normalize_recipient_key(&forward.content.to)];
+    for kid in envelope_recipient_kids(&forward.content.msg) {
+        let recipient_key = normalize_recipient_key(&kid);
+        if !recipient_keys.contains(&recipient_key) {
+            recipient_keys.push(recipient_key);
+        }
+    }
+    recipient_keys
+}
+
+/// Returns the kids of the recipients listed in the protected header of the envelope
+fn envelope_recipient_kids(envelope: &Value) -> Vec<String> {
+    let Some(protected) = envelope.get("protected").and_then(Value::as_str) else {
+        return vec![];
+    };
+    let Ok(protected) = URL_SAFE_LENIENT.decode(protected) else {
+        return vec![];
+    };
+    let Ok(protected) = serde_json::from_slice::<Value>(&protected) else {
+        return vec![];
     };
+    protected["recipients"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|recipient| recipient["header"]["kid"].as_str())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+pub fn build_forward_ack(forward_msg_id: String) -> Ack {
     let ack_content = AckContent::builder().status(AckStatus::Ok).build();
     let ack_deco = AckDecorators::builder()
-        .thread(Thread::builder().thid(forward_msg.id).build())
+        .thread(Thread::builder().thid(forward_msg_id).build())
         .build();
     Ack::builder()
         .content(ack_content)
@@ -47,3 +114,103 @@ where
         .id(Uuid::new_v4().to_string())
         .build()
 }
+
+#[cfg(test)]
+mod tests {
+    use messages::msg_fields::protocols::routing::ForwardContent;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_forward_recipient_keys_include_envelope_recipients() {
+        let key = Key::new(vec![1; 32], KeyType::Ed25519).unwrap();
+        let other_key = Key::new(vec![2; 32], KeyType::Ed25519).unwrap();
+        let protected = json!({
+            "enc": "xchacha20poly1305_ietf",
+            "typ": "JWM/1.0",
+            "alg": "Anoncrypt",
+            "recipients": [
+                { "encrypted_key": "", "header": { "kid": key.base58() } },
+                { "encrypted_key": "", "header": { "kid": other_key.base58() } },
+            ],
+        });
+        let forward: Forward = Forward::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(
+                ForwardContent::builder()
+                    .to(format!("did:key:{0}#{0}", key.fingerprint()))
+                    .msg(json!({
+                        "protected": URL_SAFE_LENIENT.encode(protected.to_string()),
+                        "iv": "",
+                        "ciphertext": "",
+                        "tag": "",
+                    }))
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            forward_recipient_keys(&forward),
+            vec![key.base58(), other_key.base58()]
+        );
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_handle_forward_queues_message_of_known_recipient() {
+        let storage = Arc::new(
+            crate::persistence::get_db_pool_from_url("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        storage
+            .create_account("auth_pubkey", "our_signing_key", "{}")
+            .await
+            .unwrap();
+        storage
+            .add_recipient("auth_pubkey", "recipient_key")
+            .await
+            .unwrap();
+        handle_forward(Arc::clone(&storage), "recipient_key", &json!({}))
+            .await
+            .unwrap();
+        let count = storage
+            .retrieve_pending_message_count("auth_pubkey", None)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[tokio::test]
+    async fn test_handle_forward_to_unknown_recipient_fails() {
+        let storage = Arc::new(
+            crate::persistence::get_db_pool_from_url("sqlite::memory:")
+                .await
+                .unwrap(),
+        );
+        assert!(handle_forward(storage, "unknown_recipient_key", &json!({}))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_normalize_recipient_key_keeps_dids() {
+        assert_eq!(
+            normalize_recipient_key("did:peer:4zQmExample"),
+            "did:peer:4zQmExample"
+        );
+    }
+
+    #[test]
+    fn test_recipient_did_restores_did_key() {
+        let key = Key::new(vec![1; 32], KeyType::Ed25519).unwrap();
+        let did_key = format!("did:key:{}", key.fingerprint());
+        assert_eq!(normalize_recipient_key(&did_key), key.base58());
+        assert_eq!(recipient_did(&key.base58()), did_key);
+        assert_eq!(
+            recipient_did("did:peer:4zQmExample"),
+            "did:peer:4zQmExample"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Live mode of Pickup 2.0 and 3.0.
+//!
+//! A recipient connected over a persistent transport (websocket) with `~transport.return_route`
+//! set to `all` may enable live delivery. While its session is open, messages forwarded to any
+//! of its recipient keys are pushed over the session right away, in the Pickup version live
+//! delivery was enabled with. Pushed messages are queued all the same, and like requested
+//! deliveries, stay queued until the recipient acknowledges them with `messages-received`.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use uuid::Uuid;
+
+use super::pickup::PickupVersion;
+use crate::utils::structs::VerKey;
+
+/// Handle to an open persistent connection, through which packed frames can be pushed
+#[derive(Clone, Debug)]
+pub struct LiveSession {
+    id: String,
+    sender: UnboundedSender<Vec<u8>>,
+}
+
+impl LiveSession {
+    /// Returns the session along with the receiving end the connection should drain
+    pub fn new() -> (Self, UnboundedReceiver<Vec<u8>>) {
+        let (sender, receiver) = unbounded_channel();
+        let session = Self {
+            id: Uuid::new_v4().to_string(),
+            sender,
+        };
+        (session, receiver)
+    }
+    /// Queues a packed frame for sending. Fails if the connection has been closed.
+    pub fn push(&self, frame: Vec<u8>) -> Result<(), String> {
+        self.sender
+            .send(frame)
+            .map_err(|_| "Live delivery session is closed".to_owned())
+    }
+}
+
+/// Live sessions of accounts, keyed by auth_pubkey
+#[derive(Clone, Debug, Default)]
+pub struct LiveDeliverySessions {
+    sessions: Arc<Mutex<HashMap<VerKey, (LiveSession, PickupVersion)>>>,
+}
+
+impl LiveDeliverySessions {
+    /// Enables live delivery for the account, replacing its previous session if any
+    pub fn register(&self, auth_pubkey: &str, session: LiveSession, version: PickupVersion) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .insert(auth_pubkey.to_owned(), (session, version));
+    }
+    /// Disables live delivery for the account
+    pub fn unregister(&self, auth_pubkey: &str) {
+        self.sessions.lock().unwrap().remove(auth_pubkey);
+    }
+    /// Disables live delivery for every account using given session, e.g. once it's closed
+    pub fn unregister_session(&self, session: &LiveSession) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|_, (registered, _)| registered.id != session.id);
+    }
+    pub fn is_empty(&self) -> bool {
+        self.sessions.lock().unwrap().is_empty()
+    }
+    /// Returns the live session of the account, with the Pickup version deliveries are pushed in
+    pub fn get(&self, auth_pubkey: &str) -> Option<(LiveSession, PickupVersion)> {
+        self.sessions.lock().unwrap().get(auth_pubkey).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_live_sessions() {
+        let sessions = LiveDeliverySessions::default();
+        let (session, mut receiver) = LiveSession::new();
+        let (other_session, _other_receiver) = LiveSession::new();
+        sessions.register("auth_pubkey", session.clone(), PickupVersion::V3);
+        sessions.register("other_pubkey", other_session.clone(), PickupVersion::V2);
+
+        sessions.unregister("other_pubkey");
+        assert!(sessions.get("other_pubkey").is_none());
+        sessions.unregister_session(&other_session);
+        let (registered, version) = sessions.get("auth_pubkey").unwrap();
+        assert_eq!(version, PickupVersion::V3);
+        registered.push(b"frame".to_vec()).unwrap();
+        assert_eq!(receiver.recv().await.unwrap(), b"frame".to_vec());
+
+        sessions.unregister_session(&session);
+        assert!(sessions.get("auth_pubkey").is_none());
+
+        drop(receiver);
+        assert!(session.push(b"frame".to_vec()).is_err());
+    }
+}
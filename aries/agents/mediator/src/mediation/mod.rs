@@ -1,3 +1,6 @@
 pub mod coordination;
 pub mod forward;
+pub mod live_delivery;
 pub mod pickup;
+pub mod queue_limits;
+pub mod upstream;
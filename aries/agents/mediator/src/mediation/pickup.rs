@@ -1,5 +1,12 @@
 // Copyright 2023 Naian G.
 // SPDX-License-Identifier: Apache-2.0
+
+//! Pickup 2.0 and 3.0 handling. Replies are sent in the version of the message they answer.
+//!
+//! Delivery is at-least-once: delivering messages does not remove them from the queue. Messages
+//! stay queued, are counted in status replies and are delivered again on subsequent delivery
+//! requests, until the recipient acknowledges them with `messages-received`. Acknowledged
+//! messages are deleted, and an updated status is returned.
 use std::sync::Arc;
 
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
@@ -7,88 +14,163 @@ use log::info;
 use messages::{
     decorators::attachment::{Attachment, AttachmentData, AttachmentType},
     msg_fields::protocols::pickup::{
-        Delivery, DeliveryContent, DeliveryRequestContent, Pickup, Status, StatusContent,
-        StatusDecorators, StatusRequestContent,
+        v2::{self, PickupV2},
+        v3::{self, PickupV3},
+        Pickup,
     },
 };
 use uuid::Uuid;
 
+use super::forward::normalize_recipient_key;
 use crate::persistence::MediatorPersistence;
 
+/// Version of the Pickup protocol a message is exchanged in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickupVersion {
+    V2,
+    V3,
+}
+
+impl PickupVersion {
+    pub fn of(pickup_message: &Pickup) -> Self {
+        match pickup_message {
+            Pickup::V2(_) => Self::V2,
+            Pickup::V3(_) => Self::V3,
+        }
+    }
+}
+
 pub async fn handle_pickup_authenticated<T: MediatorPersistence>(
     storage: Arc<T>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Pickup {
+) -> Result<Pickup, String> {
+    let version = PickupVersion::of(&pickup_message);
     match &pickup_message {
-        Pickup::StatusRequest(status_request) => {
-            handle_pickup_status_req(&status_request.content, storage, auth_pubkey).await
+        Pickup::V2(PickupV2::StatusRequest(status_request)) => {
+            info!("Received {:#?}", &status_request);
+            let recipient_key = status_request.content.recipient_key.as_ref();
+            handle_pickup_status_req(recipient_key, version, storage, auth_pubkey).await
+        }
+        Pickup::V3(PickupV3::StatusRequest(status_request)) => {
+            info!("Received {:#?}", &status_request);
+            let recipient_did = status_request.content.recipient_did.as_ref();
+            handle_pickup_status_req(recipient_did, version, storage, auth_pubkey).await
         }
         // Why is client sending us status? That's server's job.
-        Pickup::Status(_status) =>
+        Pickup::V2(PickupV2::Status(_)) | Pickup::V3(PickupV3::Status(_)) =>
         // StatusCode::BAD_REQUEST,
         {
-            handle_pickup_default_status(storage, auth_pubkey).await
+            handle_pickup_default_status(version, storage, auth_pubkey).await
         }
 
-        Pickup::DeliveryRequest(delivery_request) => {
-            handle_pickup_delivery_req(&delivery_request.content, storage, auth_pubkey).await
+        Pickup::V2(PickupV2::DeliveryRequest(delivery_request)) => {
+            info!("Received {:#?}", &delivery_request);
+            let content = &delivery_request.content;
+            let recipient_key = content.recipient_key.as_ref();
+            handle_pickup_delivery_req(content.limit, recipient_key, version, storage, auth_pubkey)
+                .await
+        }
+        Pickup::V3(PickupV3::DeliveryRequest(delivery_request)) => {
+            info!("Received {:#?}", &delivery_request);
+            let content = &delivery_request.content;
+            let recipient_did = content.recipient_did.as_ref();
+            handle_pickup_delivery_req(content.limit, recipient_did, version, storage, auth_pubkey)
+                .await
+        }
+        Pickup::V2(PickupV2::MessagesReceived(messages_received)) => {
+            info!("Received {:#?}", &messages_received);
+            let message_id_list = &messages_received.content.message_id_list;
+            handle_pickup_messages_received(message_id_list, version, storage, auth_pubkey).await
+        }
+        Pickup::V3(PickupV3::MessagesReceived(messages_received)) => {
+            info!("Received {:#?}", &messages_received);
+            let message_id_list = &messages_received.content.message_id_list;
+            handle_pickup_messages_received(message_id_list, version, storage, auth_pubkey).await
         }
         _ => {
             info!("Received {:#?}", &pickup_message);
             // StatusCode::NOT_IMPLEMENTED,
-            handle_pickup_default_status(storage, auth_pubkey).await
+            handle_pickup_default_status(version, storage, auth_pubkey).await
         }
     }
 }
 
+/// `recipient` is a recipient key in Pickup 2.0, a recipient DID in 3.0
 async fn handle_pickup_status_req<T: MediatorPersistence>(
-    status_request: &StatusRequestContent,
+    recipient: Option<&String>,
+    version: PickupVersion,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
-    info!("Received {:#?}", &status_request);
+) -> Result<Pickup, String> {
+    // Recipient keys are stored as base58 keys, see coordination
+    let recipient_key = recipient.map(|recipient| normalize_recipient_key(recipient));
     let message_count = storage
-        .retrieve_pending_message_count(auth_pubkey, status_request.recipient_key.as_ref())
+        .retrieve_pending_message_count(auth_pubkey, recipient_key.as_ref())
         .await
-        .unwrap();
-    let status_content = if let Some(recipient_key) = status_request.recipient_key.clone() {
-        StatusContent::builder()
-            .message_count(message_count)
-            .recipient_key(recipient_key)
-            .build()
-    } else {
-        StatusContent::builder()
-            .message_count(message_count)
-            .build()
-    };
-    let status = Status::builder()
-        .content(status_content)
-        .decorators(StatusDecorators::default())
-        .id(Uuid::new_v4().to_string())
-        .build();
-
+        .map_err(|err| format!("Failed to count pending messages: {err}"))?;
+    let status = build_status(version, message_count, recipient.cloned());
     info!("Sending {:#?}", &status);
-    Pickup::Status(status)
+    Ok(status)
+}
+
+fn build_status(version: PickupVersion, message_count: u32, recipient: Option<String>) -> Pickup {
+    let id = Uuid::new_v4().to_string();
+    match version {
+        PickupVersion::V2 => {
+            let status = v2::Status::builder()
+                .content(v2::StatusContent {
+                    message_count,
+                    recipient_key: recipient,
+                })
+                .decorators(v2::StatusDecorators::default())
+                .id(id)
+                .build();
+            Pickup::V2(PickupV2::Status(status))
+        }
+        PickupVersion::V3 => {
+            let status = v3::Status::builder()
+                .content(v3::StatusContent {
+                    message_count,
+                    recipient_did: recipient,
+                    ..Default::default()
+                })
+                .decorators(v3::StatusDecorators::default())
+                .id(id)
+                .build();
+            Pickup::V3(PickupV3::Status(status))
+        }
+    }
 }
 
 async fn handle_pickup_delivery_req<T: MediatorPersistence>(
-    delivery_request: &DeliveryRequestContent,
+    limit: u32,
+    recipient: Option<&String>,
+    version: PickupVersion,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
-    info!("Received {:#?}", &delivery_request);
+) -> Result<Pickup, String> {
+    let recipient_key = recipient.map(|recipient| normalize_recipient_key(recipient));
     let messages = storage
-        .retrieve_pending_messages(
-            auth_pubkey,
-            delivery_request.limit,
-            delivery_request.recipient_key.as_ref(),
-        )
+        .retrieve_pending_messages(auth_pubkey, limit, recipient_key.as_ref())
         .await
-        .unwrap();
+        .map_err(|err| format!("Failed to retrieve pending messages: {err}"))?;
     // for (message_id, message_content) in messages.into_iter() {
     //     info!("Message {:#?} {:#?}", message_id, String::from_utf8(message_content).unwrap())
     // }
+    if !messages.is_empty() {
+        Ok(build_delivery(version, recipient.cloned(), messages))
+    } else {
+        // send default status message instead
+        handle_pickup_default_status(version, storage, auth_pubkey).await
+    }
+}
+/// Builds a delivery attaching given (message_id, message_data) pairs
+pub fn build_delivery(
+    version: PickupVersion,
+    recipient: Option<String>,
+    messages: Vec<(String, Vec<u8>)>,
+) -> Pickup {
     let attach: Vec<Attachment> = messages
         .into_iter()
         .map(|(message_id, message_content)| {
@@ -104,21 +186,41 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
                 .build()
         })
         .collect();
-    if !attach.is_empty() {
-        Pickup::Delivery(
-            Delivery::builder()
-                .content(DeliveryContent {
-                    recipient_key: delivery_request.recipient_key.to_owned(),
+    let id = Uuid::new_v4().to_string();
+    match version {
+        PickupVersion::V2 => Pickup::V2(PickupV2::Delivery(
+            v2::Delivery::builder()
+                .content(v2::DeliveryContent {
+                    recipient_key: recipient,
                     attach,
                 })
-                .id(Uuid::new_v4().to_string())
+                .id(id)
                 .build(),
-        )
-    } else {
-        // send default status message instead
-        handle_pickup_default_status(storage, auth_pubkey).await
+        )),
+        PickupVersion::V3 => Pickup::V3(PickupV3::Delivery(
+            v3::Delivery::builder()
+                .content(v3::DeliveryContent {
+                    recipient_did: recipient,
+                    attach,
+                })
+                .id(id)
+                .build(),
+        )),
     }
 }
+/// Removes the acknowledged messages from the queue, replying with the updated status
+async fn handle_pickup_messages_received<T: MediatorPersistence>(
+    message_id_list: &[String],
+    version: PickupVersion,
+    storage: Arc<T>,
+    auth_pubkey: &str,
+) -> Result<Pickup, String> {
+    storage
+        .mark_messages_received(auth_pubkey, message_id_list)
+        .await
+        .map_err(|err| format!("Failed to remove received messages: {err}"))?;
+    handle_pickup_default_status(version, storage, auth_pubkey).await
+}
 // Returns global status message for user (not restricted to recipient key)
 // async fn handle_pickup_default<T: MediatorPersistence>(
 //     storage: Arc<T>,
@@ -137,10 +239,101 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
 
 /// Return status by default
 async fn handle_pickup_default_status(
+    version: PickupVersion,
     storage: Arc<impl MediatorPersistence>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> Result<Pickup, String> {
     info!("Default behavior: responding with status");
-    let status_request = StatusRequestContent::builder().build();
-    handle_pickup_status_req(&status_request, storage, auth_pubkey).await
+    handle_pickup_status_req(None, version, storage, auth_pubkey).await
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use public_key::{Key, KeyType};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{mediation::forward::handle_forward, persistence::get_db_pool_from_url};
+
+    #[tokio::test]
+    async fn test_pickup_v3_of_did_key_recipient() {
+        let storage = Arc::new(get_db_pool_from_url("sqlite::memory:").await.unwrap());
+        let key = Key::new(vec![1; 32], KeyType::Ed25519).unwrap();
+        let did_key = format!("did:key:{}", key.fingerprint());
+        storage
+            .create_account("auth_pubkey", "our_signing_key", "{}")
+            .await
+            .unwrap();
+        storage
+            .add_recipient("auth_pubkey", &key.base58())
+            .await
+            .unwrap();
+        handle_forward(Arc::clone(&storage), &key.base58(), &json!({}))
+            .await
+            .unwrap();
+
+        let status_request = Pickup::V3(PickupV3::StatusRequest(
+            v3::StatusRequest::builder()
+                .content(
+                    v3::StatusRequestContent::builder()
+                        .recipient_did(did_key.clone())
+                        .build(),
+                )
+                .decorators(v3::StatusRequestDecorators::default())
+                .id("request-status".to_owned())
+                .build(),
+        ));
+        let Pickup::V3(PickupV3::Status(status)) =
+            handle_pickup_authenticated(Arc::clone(&storage), status_request, "auth_pubkey")
+                .await
+                .unwrap()
+        else {
+            panic!("Expected status");
+        };
+        assert_eq!(status.content.message_count, 1);
+        assert_eq!(status.content.recipient_did, Some(did_key.clone()));
+
+        let delivery_request = Pickup::V3(PickupV3::DeliveryRequest(
+            v3::DeliveryRequest::builder()
+                .content(
+                    v3::DeliveryRequestContent::builder()
+                        .limit(10)
+                        .recipient_did(did_key.clone())
+                        .build(),
+                )
+                .decorators(v3::DeliveryRequestDecorators::default())
+                .id("request-delivery".to_owned())
+                .build(),
+        ));
+        let Pickup::V3(PickupV3::Delivery(delivery)) =
+            handle_pickup_authenticated(storage, delivery_request, "auth_pubkey")
+                .await
+                .unwrap()
+        else {
+            panic!("Expected delivery");
+        };
+        assert_eq!(delivery.content.attach.len(), 1);
+        assert_eq!(delivery.content.recipient_did, Some(did_key));
+    }
+
+    #[tokio::test]
+    async fn test_messages_received_of_unknown_account_fails() {
+        let storage = Arc::new(get_db_pool_from_url("sqlite::memory:").await.unwrap());
+        let messages_received = Pickup::V2(PickupV2::MessagesReceived(
+            v2::MessagesReceived::builder()
+                .content(
+                    v2::MessagesReceivedContent::builder()
+                        .message_id_list(vec!["message_id".to_owned()])
+                        .build(),
+                )
+                .decorators(v2::MessagesReceivedDecorators::default())
+                .id("messages-received".to_owned())
+                .build(),
+        ));
+        assert!(
+            handle_pickup_authenticated(storage, messages_received, "unknown_auth_pubkey")
+                .await
+                .is_err()
+        );
+    }
 }
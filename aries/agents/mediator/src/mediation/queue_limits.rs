@@ -0,0 +1,185 @@
+//! Bounds on the message queues of accounts.
+//!
+//! Forwarded messages which would take an account's queue over its message count or byte quota
+//! are dropped, the recipient being notified once with a problem report until its queue has room
+//! again. Queued messages older than the configured TTL are purged periodically.
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context;
+use log::{info, warn};
+use messages::msg_fields::protocols::report_problem::{
+    Description, Impact, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::{
+    persistence::{database::unix_timestamp_now, MediatorPersistence, QueueUsage},
+    utils::structs::VerKey,
+};
+
+const DEFAULT_PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Per-account queue quotas and message TTL. Unset limits are not enforced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueLimits {
+    /// Maximum number of messages queued for an account
+    pub max_messages: Option<u64>,
+    /// Maximum total size of the messages queued for an account, in bytes
+    pub max_bytes: Option<u64>,
+    /// Time after which undelivered messages are purged
+    pub message_ttl: Option<Duration>,
+    /// How often expired messages are looked for
+    pub purge_interval: Duration,
+}
+
+impl Default for QueueLimits {
+    fn default() -> Self {
+        Self {
+            max_messages: None,
+            max_bytes: None,
+            message_ttl: None,
+            purge_interval: DEFAULT_PURGE_INTERVAL,
+        }
+    }
+}
+
+impl QueueLimits {
+    /// Reads limits from `QUEUE_MAX_MESSAGES`, `QUEUE_MAX_BYTES`, `MESSAGE_TTL_SECONDS` and
+    /// `MESSAGE_PURGE_INTERVAL_SECONDS`. Fails if one of them is not a number.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let purge_interval = read_env_u64("MESSAGE_PURGE_INTERVAL_SECONDS")?
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_PURGE_INTERVAL);
+        Ok(Self {
+            max_messages: read_env_u64("QUEUE_MAX_MESSAGES")?,
+            max_bytes: read_env_u64("QUEUE_MAX_BYTES")?,
+            message_ttl: read_env_u64("MESSAGE_TTL_SECONDS")?.map(Duration::from_secs),
+            purge_interval,
+        })
+    }
+    pub fn has_quota(&self) -> bool {
+        self.max_messages.is_some() || self.max_bytes.is_some()
+    }
+    /// Whether a message of `message_len` bytes fits in a queue of given usage
+    pub fn admits(&self, usage: &QueueUsage, message_len: u64) -> bool {
+        let count_ok = self
+            .max_messages
+            .map_or(true, |max_messages| usage.message_count < max_messages);
+        let bytes_ok = self.max_bytes.map_or(true, |max_bytes| {
+            usage.total_bytes + message_len <= max_bytes
+        });
+        count_ok && bytes_ok
+    }
+}
+
+fn read_env_u64(name: &str) -> anyhow::Result<Option<u64>> {
+    let Ok(value) = std::env::var(name) else {
+        return Ok(None);
+    };
+    value
+        .parse()
+        .map(Some)
+        .with_context(|| format!("Invalid value {value:?} of {name}"))
+}
+
+/// Accounts whose queue overflowed, and which have been notified of it
+#[derive(Clone, Debug, Default)]
+pub struct QueueOverflows {
+    accounts: Arc<Mutex<HashSet<VerKey>>>,
+}
+
+impl QueueOverflows {
+    /// Records the overflow of the account's queue. Returns false if it was already recorded,
+    /// i.e. the account has already been notified.
+    pub fn mark(&self, auth_pubkey: &str) -> bool {
+        self.accounts.lock().unwrap().insert(auth_pubkey.to_owned())
+    }
+    /// Clears the overflow of the account's queue, once it accepts messages again
+    pub fn clear(&self, auth_pubkey: &str) {
+        self.accounts.lock().unwrap().remove(auth_pubkey);
+    }
+}
+
+pub fn build_queue_full_problem_report() -> ProblemReport {
+    let content = ProblemReportContent::builder()
+        .description(
+            Description::builder()
+                .code("e.msg.queue-full".to_owned())
+                .build(),
+        )
+        .fix_hint("Forwarded messages are dropped until queued messages are picked up".to_owned())
+        .impact(Impact::MessageContent)
+        .build();
+    ProblemReport::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(ProblemReportDecorators::default())
+        .build()
+}
+
+/// Periodically purges messages older than the TTL of `limits`, if there is one
+pub fn spawn_purge_task(
+    storage: Arc<impl MediatorPersistence>,
+    limits: &QueueLimits,
+) -> Option<JoinHandle<()>> {
+    let message_ttl = limits.message_ttl?;
+    let mut interval = tokio::time::interval(limits.purge_interval);
+    Some(tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+            let received_before =
+                unix_timestamp_now() - i64::try_from(message_ttl.as_secs()).unwrap_or(i64::MAX);
+            match storage.purge_expired_messages(received_before).await {
+                Ok(0) => (),
+                Ok(purged) => info!("Purged {} expired messages", purged),
+                Err(err) => warn!("Failed to purge expired messages: {}", err),
+            }
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_limits_admit() {
+        let usage = QueueUsage {
+            message_count: 2,
+            total_bytes: 100,
+        };
+        assert!(QueueLimits::default().admits(&usage, 1_000_000));
+        let limits = QueueLimits {
+            max_messages: Some(3),
+            max_bytes: Some(150),
+            ..Default::default()
+        };
+        assert!(limits.admits(&usage, 50));
+        assert!(!limits.admits(&usage, 51));
+        let full = QueueUsage {
+            message_count: 3,
+            total_bytes: 100,
+        };
+        assert!(!limits.admits(&full, 1));
+
+        let overflows = QueueOverflows::default();
+        assert!(overflows.mark("auth_pubkey"));
+        assert!(!overflows.mark("auth_pubkey"));
+        overflows.clear("auth_pubkey");
+        assert!(overflows.mark("auth_pubkey"));
+    }
+
+    #[test]
+    fn test_read_env_u64_rejects_malformed_values() {
+        assert_eq!(read_env_u64("QUEUE_LIMITS_TEST_UNSET").unwrap(), None);
+        std::env::set_var("QUEUE_LIMITS_TEST_VALID", "42");
+        assert_eq!(read_env_u64("QUEUE_LIMITS_TEST_VALID").unwrap(), Some(42));
+        std::env::set_var("QUEUE_LIMITS_TEST_MALFORMED", "42s");
+        assert!(read_env_u64("QUEUE_LIMITS_TEST_MALFORMED").is_err());
+    }
+}
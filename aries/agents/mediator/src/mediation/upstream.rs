@@ -0,0 +1,267 @@
+//! Mediation of this mediator by an upstream mediator, forming a mediator chain.
+//!
+//! A mediator behind an upstream mediator connects to it as a recipient, and has its service key
+//! registered there. Mediation it grants then routes through the upstream mediator: the granted
+//! endpoint is the upstream one, and our service key is followed by the upstream routing keys.
+//! Messages queued upstream for us are picked up periodically, and the forwards they contain are
+//! handled as if they had been posted to us directly.
+use std::{sync::Arc, time::Duration};
+
+use aries_vcx::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::{
+        mediation::recipient::{MediationGrant, MediationRecipient},
+        util::AnyInvitation,
+    },
+    protocols::{
+        connection::{
+            invitee::{
+                states::{completed::Completed, initial::Initial},
+                InviteeConnection,
+            },
+            GenericConnection,
+        },
+        mediated_connection::pairwise_info::PairwiseInfo,
+    },
+    transport::Transport,
+    utils::encryption_envelope::EncryptionEnvelope,
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use log::{info, warn};
+use messages::{
+    msg_fields::protocols::{
+        connection::Connection, out_of_band::invitation::Invitation as OOBInvitation,
+        routing::Forward,
+    },
+    AriesMessage,
+};
+use test_utils::mockdata::mock_ledger::MockLedger;
+use tokio::task::JoinHandle;
+use url::Url;
+
+use crate::{
+    aries_agent::{Agent, ArcAgent},
+    didcomm_handlers::handle_routing_forward,
+    persistence::MediatorPersistence,
+    utils::{string_from_std_error, structs::VerKey},
+};
+
+pub const DEFAULT_PICKUP_INTERVAL: Duration = Duration::from_secs(5);
+const PICKUP_BATCH_SIZE: u32 = 100;
+
+/// HTTP transport returning the body of responses as replies
+#[derive(Clone, Debug, Default)]
+pub struct HttpTransport(reqwest::Client);
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.send_message_and_receive(msg, service_endpoint)
+            .await
+            .map(|_| ())
+    }
+
+    async fn send_message_and_receive(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        let post_failed = |err: reqwest::Error| {
+            AriesVcxError::from_msg(AriesVcxErrorKind::PostMessageFailed, err.to_string())
+        };
+        let reply = self
+            .0
+            .post(service_endpoint.clone())
+            .body(msg)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(post_failed)?
+            .bytes()
+            .await
+            .map_err(post_failed)?;
+        Ok((!reply.is_empty()).then(|| reply.to_vec()))
+    }
+}
+
+/// Connects to the mediator of the invitation, requests mediation and registers our service key
+/// with it. The returned recipient picks up the messages queued for us upstream, see
+/// [spawn_upstream_pickup_task].
+///
+/// Service keys created later on, e.g. by rotating keys, are not registered upstream.
+pub async fn connect_upstream_mediator<T: BaseWallet, P: MediatorPersistence>(
+    agent: &Agent<T, P>,
+    invitation: OOBInvitation,
+    transport: &HttpTransport,
+) -> Result<MediationRecipient<GenericConnection>, String> {
+    let wallet = agent.get_wallet_ref();
+    let service_key = agent
+        .get_service()
+        .and_then(|service| service.recipient_keys.first().cloned())
+        .ok_or("Mediation agent must have service defined.")?;
+    let connection = establish_connection(wallet.as_ref(), invitation, transport)
+        .await
+        .map_err(string_from_std_error)?;
+    let mut recipient = MediationRecipient::new(GenericConnection::from(connection));
+    let grant = recipient
+        .request_mediation(wallet.as_ref(), transport)
+        .await
+        .map_err(string_from_std_error)?
+        .clone();
+    recipient
+        .add_recipient_key(wallet.as_ref(), transport, &service_key)
+        .await
+        .map_err(string_from_std_error)?;
+    info!(
+        "Mediated by upstream mediator at {}, with routing keys {:?}",
+        grant.endpoint, grant.routing_keys
+    );
+    agent.set_upstream_grant(Some(grant));
+    Ok(recipient)
+}
+
+/// Connects to the inviter with the connection protocol, the response being returned over the
+/// same HTTP request.
+async fn establish_connection(
+    wallet: &impl BaseWallet,
+    invitation: OOBInvitation,
+    transport: &HttpTransport,
+) -> VcxResult<InviteeConnection<Completed>> {
+    let did_data = wallet.create_and_store_my_did(None, None).await?;
+    let connection = InviteeConnection::<Initial>::new_invitee(
+        "upstream-mediator".into(),
+        PairwiseInfo {
+            pw_did: did_data.did().into(),
+            pw_vk: did_data.verkey().base58(),
+        },
+    )
+    .accept_invitation(&MockLedger {}, AnyInvitation::Oob(invitation))
+    .await?
+    .prepare_request("didcomm:transport/queue".parse()?, vec![])
+    .await?;
+    let request = AriesMessage::Connection(Connection::Request(connection.get_request().clone()));
+    let EncryptionEnvelope(packed_request) = connection.encrypt_message(wallet, &request).await?;
+    let service_endpoint = connection.their_did_doc().get_endpoint().ok_or_else(|| {
+        AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc")
+    })?;
+    let packed_response = transport
+        .send_message_and_receive(packed_request, &service_endpoint)
+        .await?
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Upstream mediator did not return a connection response",
+            )
+        })?;
+    let unpacked = wallet.unpack_message(&packed_response).await?;
+    match serde_json::from_str(&unpacked.message)? {
+        AriesMessage::Connection(Connection::Response(response)) => {
+            connection.handle_response(wallet, response).await
+        }
+        message => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            format!("Expected connection response, got {:?}", message),
+        )),
+    }
+}
+
+/// Picks up the messages queued for us by the upstream mediator every `interval`, and handles the
+/// forwards among them.
+pub fn spawn_upstream_pickup_task<T, P>(
+    agent: ArcAgent<T, P>,
+    recipient: MediationRecipient<GenericConnection>,
+    transport: HttpTransport,
+    interval: Duration,
+) -> JoinHandle<()>
+where
+    T: BaseWallet + 'static,
+    P: MediatorPersistence,
+{
+    let mut interval = tokio::time::interval(interval);
+    tokio::spawn(async move {
+        loop {
+            interval.tick().await;
+            if let Err(err) = pickup_upstream(&agent, &recipient, &transport).await {
+                warn!("Failed to pick up messages from upstream mediator: {}", err);
+            }
+        }
+    })
+}
+
+/// Picks up the messages queued for us by the upstream mediator and handles the forwards among
+/// them. Returns the number of messages picked up.
+pub async fn pickup_upstream<T, P>(
+    agent: &ArcAgent<T, P>,
+    recipient: &MediationRecipient<GenericConnection>,
+    transport: &HttpTransport,
+) -> Result<usize, String>
+where
+    T: BaseWallet + 'static,
+    P: MediatorPersistence,
+{
+    let mut forwards: Vec<(Forward, VerKey)> = Vec::new();
+    let delivered = recipient
+        .pickup_all(
+            agent.get_wallet_ref().as_ref(),
+            transport,
+            PICKUP_BATCH_SIZE,
+            |mediated| {
+                match mediated.message {
+                    AriesMessage::Routing(forward) => {
+                        forwards.push((forward, mediated.recipient_vk.base58()))
+                    }
+                    message => warn!("Dropping non-forward message from upstream: {:?}", message),
+                }
+                Ok(())
+            },
+        )
+        .await
+        .map_err(string_from_std_error)?;
+    for (forward, recipient_vk) in forwards {
+        info!(
+            "Handling forward picked up from upstream for {}",
+            recipient_vk
+        );
+        if let Err(err) = handle_routing_forward(Arc::clone(agent), forward).await {
+            warn!("Failed to handle forward picked up from upstream: {}", err);
+        }
+    }
+    Ok(delivered)
+}
+
+/// Mediation to grant recipients of our service key, routed through `outer_grant`: the routing of
+/// our own service, or the mediation granted to us by an upstream mediator. Routing keys are listed
+/// from the innermost, ours, to the outermost.
+pub fn build_mediation_grant(service_key: String, outer_grant: MediationGrant) -> MediationGrant {
+    MediationGrant {
+        endpoint: outer_grant.endpoint,
+        routing_keys: std::iter::once(service_key)
+            .chain(outer_grant.routing_keys)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_mediation_grant_lists_our_key_first() {
+        let upstream_grant = MediationGrant {
+            endpoint: "http://upstream.example/didcomm".parse().unwrap(),
+            routing_keys: vec!["upstream".to_owned(), "outermost".to_owned()],
+        };
+        assert_eq!(
+            build_mediation_grant("ours".to_owned(), upstream_grant.clone()),
+            MediationGrant {
+                endpoint: upstream_grant.endpoint,
+                routing_keys: vec![
+                    "ours".to_owned(),
+                    "upstream".to_owned(),
+                    "outermost".to_owned()
+                ],
+            }
+        );
+    }
+}
@@ -0,0 +1,669 @@
+// Copyright 2023 Naian G.
+// SPDX-License-Identifier: Apache-2.0
+
+//! MediatorPersistence implementation shared by the database backends, which only differ in
+//! their SQL dialect and in who generates account and message ids.
+
+use crate::persistence::errors::{AccountNotFound, GetAccountIdError, StorageBackendError};
+
+/// SQL statements of a database backend. Statements are bound their parameters in the order
+/// they are listed in the comments.
+pub(super) struct Queries {
+    /// Whether the database generates account ids, along with their readable form. Otherwise,
+    /// they are bound as the leading parameters of `insert_account`.
+    pub generates_account_ids: bool,
+    /// Whether message ids are stored as binary uuids, from which the database derives their
+    /// readable form. Otherwise, the readable form is stored.
+    pub binary_message_ids: bool,
+    /// [account_id, account_name], auth_pubkey, our_signing_key, did_doc
+    pub insert_account: &'static str,
+    /// auth_pubkey
+    pub get_account_id: &'static str,
+    /// recipient_key
+    pub get_recipient_account: &'static str,
+    pub list_accounts: &'static str,
+    /// auth_pubkey; the did_doc column is selected as text
+    pub get_account_details: &'static str,
+    /// account_id, for each statement, which are run in a single transaction
+    pub delete_account: [&'static str; 3],
+    /// recipient_key
+    pub get_recipient: &'static str,
+    /// message_id, account_id, recipient_key, message_data, received_at
+    pub insert_message: &'static str,
+    /// message_id, account_id, recipient_key, message_data, received_at, max_messages,
+    /// max_bytes; inserts nothing if the queue of the account would then exceed its quota
+    pub insert_message_within_quota: &'static str,
+    /// account_id
+    pub count_messages: &'static str,
+    /// account_id, recipient_key
+    pub count_recipient_messages: &'static str,
+    /// account_id, limit
+    pub retrieve_messages: &'static str,
+    /// account_id, recipient_key, limit
+    pub retrieve_recipient_messages: &'static str,
+    /// account_id
+    pub queue_usage: &'static str,
+    /// account_id
+    pub purge_messages: &'static str,
+    /// received_before
+    pub purge_expired_messages: &'static str,
+    /// account_id, recipient_key
+    pub insert_recipient: &'static str,
+    /// account_id, recipient_key
+    pub delete_recipient: &'static str,
+    /// account_id
+    pub list_recipient_keys: &'static str,
+}
+
+/// Statements using numbered parameters, understood by postgres as well as sqlite.
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+pub(super) const NUMBERED_QUERIES: Queries = Queries {
+    generates_account_ids: false,
+    binary_message_ids: false,
+    insert_account: "INSERT INTO accounts (account_id, account_name, auth_pubkey, \
+                     our_signing_key, did_doc) VALUES ($1, $2, $3, $4, $5);",
+    get_account_id: "SELECT account_id FROM accounts WHERE auth_pubkey = $1;",
+    get_recipient_account: "SELECT accounts.auth_pubkey FROM recipients JOIN accounts ON \
+                            recipients.account_id = accounts.account_id WHERE \
+                            recipients.recipient_key = $1;",
+    list_accounts: "SELECT account_name, auth_pubkey FROM accounts ORDER BY seq_num;",
+    get_account_details: "SELECT * FROM accounts WHERE auth_pubkey = $1;",
+    delete_account: [
+        "DELETE FROM messages WHERE account_id = $1;",
+        "DELETE FROM recipients WHERE account_id = $1;",
+        "DELETE FROM accounts WHERE account_id = $1;",
+    ],
+    get_recipient: "SELECT account_id FROM recipients WHERE recipient_key = $1;",
+    insert_message: "INSERT INTO messages (message_id, account_id, recipient_key, message_data, \
+                     received_at) VALUES ($1, $2, $3, $4, $5);",
+    insert_message_within_quota: "INSERT INTO messages (message_id, account_id, recipient_key, \
+                                  message_data, received_at) SELECT * FROM (SELECT $1 AS \
+                                  message_id, $2 AS account_id, $3 AS recipient_key, $4 AS \
+                                  message_data, $5 AS received_at) AS message WHERE (SELECT \
+                                  COUNT(*) FROM messages AS queued WHERE queued.account_id = \
+                                  message.account_id) < $6 AND (SELECT \
+                                  COALESCE(SUM(LENGTH(queued.message_data)), 0) FROM messages \
+                                  AS queued WHERE queued.account_id = message.account_id) + \
+                                  LENGTH(message.message_data) <= $7;",
+    count_messages: "SELECT COUNT(*) AS message_count FROM messages WHERE (account_id = $1);",
+    count_recipient_messages: "SELECT COUNT(*) AS message_count FROM messages WHERE (account_id \
+                               = $1) AND (recipient_key = $2);",
+    retrieve_messages: "SELECT message_id, message_data FROM messages WHERE (account_id = $1) \
+                        ORDER BY seq_num LIMIT $2;",
+    retrieve_recipient_messages: "SELECT message_id, message_data FROM messages WHERE \
+                                  (account_id = $1) AND (recipient_key = $2) ORDER BY seq_num \
+                                  LIMIT $3;",
+    queue_usage: "SELECT COUNT(*) AS message_count, COALESCE(SUM(LENGTH(message_data)), 0) AS \
+                  total_bytes FROM messages WHERE account_id = $1;",
+    purge_messages: "DELETE FROM messages WHERE account_id = $1;",
+    purge_expired_messages: "DELETE FROM messages WHERE received_at < $1;",
+    insert_recipient: "INSERT INTO recipients (account_id, recipient_key) VALUES ($1, $2);",
+    delete_recipient: "DELETE FROM recipients WHERE (account_id = $1) AND (recipient_key = $2);",
+    list_recipient_keys: "SELECT recipient_key FROM recipients WHERE account_id = $1;",
+};
+
+/// Maps a failure to find the account id of `auth_pubkey` into the error of the calling method.
+pub(super) fn map_account_id_error<E>(auth_pubkey: &str) -> impl FnOnce(GetAccountIdError) -> E + '_
+where
+    E: From<AccountNotFound> + From<StorageBackendError> + From<anyhow::Error>,
+{
+    move |err| match err {
+        GetAccountIdError::AccountNotFound(anf) => anf.into(),
+        GetAccountIdError::StorageBackendError(s) => s.into(),
+        GetAccountIdError::ZFhOt01Rdb0Error(anye) => anye
+            .context(format!("Couldn't get account id of pubkey {auth_pubkey}"))
+            .into(),
+    }
+}
+
+/// Implements MediatorPersistence for the connection pool `$pool` of the sqlx database
+/// `$database`, running the statements of `$queries`.
+macro_rules! impl_mediator_persistence {
+    ($pool:ty, $database:ty, $queries:ident) => {
+        const _: () = {
+            use anyhow::anyhow;
+            use log::info;
+            use sqlx::{QueryBuilder, Row};
+            use uuid::Uuid;
+
+            use $crate::{
+                persistence::{
+                    database::{common::map_account_id_error, unix_timestamp_now},
+                    errors::{
+                        AccountNotFound, AddRecipientError, CreateAccountError, DecodeError,
+                        GetAccountDetailsError, GetAccountIdError, GetRecipientAccountError,
+                        ListAccountsError, ListRecipientKeysError, MarkMessagesReceivedError,
+                        PersistForwardMessageError, PurgeExpiredMessagesError,
+                        PurgePendingMessagesError, RemoveRecipientError,
+                        RetrievePendingMessageCountError, RetrievePendingMessagesError,
+                        RetrieveQueueUsageError, StorageBackendError, VaporizeAccountError,
+                    },
+                    AccountDetails, AccountDidDoc, MediatorPersistence, QueueUsage,
+                },
+                utils::structs::VerKey,
+            };
+
+            /// Returns the id of the account which registered `recipient_key`
+            async fn get_recipient_account_id(
+                pool: &$pool,
+                recipient_key: &str,
+            ) -> Result<Vec<u8>, PersistForwardMessageError> {
+                // Fetch recipient with given recipient_key
+                info!("Fetching recipient with recipient_key {:#?}", recipient_key);
+                let recipient_row = sqlx::query($queries.get_recipient)
+                    .bind(recipient_key)
+                    .fetch_one(pool)
+                    .await;
+                match recipient_row {
+                    Ok(row) => Ok(row.get("account_id")),
+                    Err(err) => {
+                        info!("Error while finding target recipient, {:#}", err);
+                        Err(match err {
+                            sqlx::Error::RowNotFound => {
+                                PersistForwardMessageError::AccountNotFound(AccountNotFound(
+                                    format!("reipient_key={}", recipient_key.to_owned()),
+                                ))
+                            }
+                            _ => StorageBackendError { source: err.into() }.into(),
+                        })
+                    }
+                }
+            }
+
+            #[async_trait::async_trait]
+            impl MediatorPersistence for $pool {
+                async fn create_account(
+                    &self,
+                    auth_pubkey: &str,
+                    our_signing_key: &str,
+                    did_doc: &str,
+                ) -> Result<(), CreateAccountError> {
+                    info!(
+                        "Adding new account to database with auth_pubkey {:#?}",
+                        &auth_pubkey
+                    );
+                    let mut insert = sqlx::query($queries.insert_account);
+                    if !$queries.generates_account_ids {
+                        let account_id = Uuid::new_v4();
+                        insert = insert
+                            .bind(account_id.as_bytes().to_vec())
+                            .bind(account_id.to_string());
+                    }
+                    let insert_result = insert
+                        .bind(auth_pubkey)
+                        .bind(our_signing_key)
+                        .bind(did_doc)
+                        .execute(self)
+                        .await;
+                    if let Err(err) = insert_result {
+                        info!("Error during creating new account, {:#?}", err);
+                        return Err(StorageBackendError {
+                            source: Box::new(err),
+                        }
+                        .into());
+                    };
+                    let account_details =
+                        self.get_account_details(auth_pubkey).await.map_err(|e| {
+                            anyhow!(e.to_string()).context(
+                                "Possibly created account, but failed to retrieve created \
+                                 account's ID",
+                            )
+                        })?;
+                    info!(
+                        "Created account {:?} for auth_pubkey {:#?}",
+                        &account_details, &auth_pubkey
+                    );
+                    Ok(())
+                }
+                /// Get account id associated with auth_pubkey
+                async fn get_account_id(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<Vec<u8>, GetAccountIdError> {
+                    let account_id: Vec<u8> = sqlx::query($queries.get_account_id)
+                        .bind(auth_pubkey)
+                        .fetch_one(self)
+                        .await
+                        .map_err(|e| match e {
+                            sqlx::Error::RowNotFound => GetAccountIdError::AccountNotFound(
+                                AccountNotFound(format!("auth_pubkey={}", auth_pubkey.to_owned())),
+                            ),
+                            _ => StorageBackendError { source: e.into() }.into(),
+                        })?
+                        .get("account_id");
+                    Ok(account_id)
+                }
+                async fn get_recipient_account(
+                    &self,
+                    recipient_key: &str,
+                ) -> Result<VerKey, GetRecipientAccountError> {
+                    let auth_pubkey: VerKey = sqlx::query($queries.get_recipient_account)
+                        .bind(recipient_key)
+                        .fetch_one(self)
+                        .await
+                        .map_err(|e| match e {
+                            sqlx::Error::RowNotFound => {
+                                GetRecipientAccountError::AccountNotFound(AccountNotFound(format!(
+                                    "recipient_key={}",
+                                    recipient_key.to_owned()
+                                )))
+                            }
+                            _ => StorageBackendError { source: e.into() }.into(),
+                        })?
+                        .get("auth_pubkey");
+                    Ok(auth_pubkey)
+                }
+                /// Returns list of accounts in form of tuples containing
+                /// account_name and associated auth_pubkey
+                async fn list_accounts(&self) -> Result<Vec<(String, VerKey)>, ListAccountsError> {
+                    let accounts_rows = sqlx::query($queries.list_accounts)
+                        .fetch_all(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?;
+                    let mut vec_tup = vec![];
+                    for row in accounts_rows {
+                        vec_tup.push((
+                            row.try_get("account_name")
+                                .map_err(|e| DecodeError(e.into()))?,
+                            row.try_get("auth_pubkey")
+                                .map_err(|e| DecodeError(e.into()))?,
+                        ))
+                    }
+                    Ok(vec_tup)
+                }
+                async fn get_account_details(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<AccountDetails, GetAccountDetailsError> {
+                    let row = sqlx::query($queries.get_account_details)
+                        .bind(auth_pubkey)
+                        .fetch_one(self)
+                        .await
+                        .map_err(|e| match e {
+                            sqlx::error::Error::RowNotFound => {
+                                GetAccountDetailsError::AccountNotFound(AccountNotFound(format!(
+                                    "auth_pubkey={}",
+                                    auth_pubkey.to_owned()
+                                )))
+                            }
+                            _ => StorageBackendError { source: e.into() }.into(),
+                        })?;
+                    let account_id = row
+                        .try_get("account_id")
+                        .map_err(|e| DecodeError(e.into()))?;
+                    let account_name = row
+                        .try_get("account_name")
+                        .map_err(|e| DecodeError(e.into()))?;
+                    let auth_pubkey = row
+                        .try_get("auth_pubkey")
+                        .map_err(|e| DecodeError(e.into()))?;
+                    let our_signing_key = row
+                        .try_get("our_signing_key")
+                        .map_err(|e| DecodeError(e.into()))?;
+                    let did_doc_json = row
+                        .try_get::<String, &str>("did_doc")
+                        .map_err(|e| DecodeError(e.into()))?;
+                    let account_details = AccountDetails {
+                        account_id,
+                        account_name,
+                        auth_pubkey,
+                        our_signing_key,
+                        their_did_doc: serde_json::from_str(&did_doc_json)
+                            .and_then(AccountDidDoc::from_value)
+                            .map_err(|e| DecodeError(e.into()))?,
+                    };
+                    Ok(account_details)
+                }
+                async fn vaporize_account(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<(), VaporizeAccountError> {
+                    info!(
+                        "Removing account with auth_pubkey {:#?}, along with its recipient keys \
+                         and messages",
+                        auth_pubkey
+                    );
+                    let account_id: Vec<u8> = self
+                        .get_account_id(auth_pubkey)
+                        .await
+                        .map_err(map_account_id_error::<VaporizeAccountError>(auth_pubkey))?;
+                    let mut transaction = self
+                        .begin()
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?;
+                    for statement in $queries.delete_account {
+                        sqlx::query(statement)
+                            .bind(&account_id)
+                            .execute(&mut *transaction)
+                            .await
+                            .map_err(|e| StorageBackendError { source: e.into() })?;
+                    }
+                    transaction
+                        .commit()
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?;
+                    Ok(())
+                }
+                async fn persist_forward_message(
+                    &self,
+                    recipient_key: &str,
+                    message_data: &str,
+                ) -> Result<String, PersistForwardMessageError> {
+                    let account_id = get_recipient_account_id(self, recipient_key).await?;
+                    // Save message for recipient
+                    info!("Persisting message for account {:x?}", account_id);
+                    let message_id = Uuid::new_v4();
+                    let insert = sqlx::query($queries.insert_message);
+                    let insert = if $queries.binary_message_ids {
+                        insert.bind(message_id.as_bytes().to_vec())
+                    } else {
+                        insert.bind(message_id.to_string())
+                    };
+                    let insert_result = insert
+                        .bind(&account_id)
+                        .bind(recipient_key)
+                        .bind(message_data.as_bytes())
+                        .bind(unix_timestamp_now())
+                        .execute(self)
+                        .await;
+                    if let Err(err) = insert_result {
+                        info!(
+                            "Error while saving message for recipient {:x?}, {:#}",
+                            recipient_key, err
+                        );
+                        return Err(PersistForwardMessageError::StorageBackendError(
+                            StorageBackendError { source: err.into() },
+                        ));
+                    }
+                    Ok(message_id.to_string())
+                }
+                async fn persist_forward_message_within_quota(
+                    &self,
+                    recipient_key: &str,
+                    message_data: &str,
+                    max_messages: Option<u64>,
+                    max_bytes: Option<u64>,
+                ) -> Result<Option<String>, PersistForwardMessageError> {
+                    let account_id = get_recipient_account_id(self, recipient_key).await?;
+                    info!(
+                        "Persisting message for account {:x?} within quota of {:?} messages \
+                         and {:?} bytes",
+                        account_id, max_messages, max_bytes
+                    );
+                    let message_id = Uuid::new_v4();
+                    let insert = sqlx::query($queries.insert_message_within_quota);
+                    let insert = if $queries.binary_message_ids {
+                        insert.bind(message_id.as_bytes().to_vec())
+                    } else {
+                        insert.bind(message_id.to_string())
+                    };
+                    // Unset limits are not enforced
+                    let limit = |limit: Option<u64>| {
+                        limit.map_or(i64::MAX, |limit| i64::try_from(limit).unwrap_or(i64::MAX))
+                    };
+                    let inserted = insert
+                        .bind(&account_id)
+                        .bind(recipient_key)
+                        .bind(message_data.as_bytes())
+                        .bind(unix_timestamp_now())
+                        .bind(limit(max_messages))
+                        .bind(limit(max_bytes))
+                        .execute(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?
+                        .rows_affected();
+                    Ok((inserted > 0).then(|| message_id.to_string()))
+                }
+                async fn retrieve_pending_message_count(
+                    &self,
+                    auth_pubkey: &str,
+                    recipient_key: Option<&String>,
+                ) -> Result<u32, RetrievePendingMessageCountError> {
+                    let account_id: Vec<u8> =
+                        self.get_account_id(auth_pubkey)
+                            .await
+                            .map_err(map_account_id_error::<RetrievePendingMessageCountError>(
+                                auth_pubkey,
+                            ))?;
+                    let message_count_result = if let Some(recipient_key) = recipient_key {
+                        sqlx::query($queries.count_recipient_messages)
+                            .bind(&account_id)
+                            .bind(recipient_key)
+                            .fetch_one(self)
+                            .await
+                    } else {
+                        sqlx::query($queries.count_messages)
+                            .bind(&account_id)
+                            .fetch_one(self)
+                            .await
+                    };
+                    let message_count: i64 = message_count_result
+                        .map_err(|e| anyhow!(e))?
+                        .get::<i64, &str>("message_count");
+                    let message_count: u32 = message_count.try_into().map_err(|e| anyhow!(e))?;
+                    info!(
+                        "Total message count of all requested recipients: {:#?}",
+                        &message_count
+                    );
+                    Ok(message_count)
+                }
+                async fn retrieve_pending_messages(
+                    &self,
+                    auth_pubkey: &str,
+                    limit: u32,
+                    recipient_key: Option<&VerKey>,
+                ) -> Result<Vec<(String, Vec<u8>)>, RetrievePendingMessagesError> {
+                    info!(
+                        "Processing retrieve for messages to recipient_key {:#?} of auth_pubkey \
+                         {:#?}",
+                        recipient_key, auth_pubkey
+                    );
+                    let account_id: Vec<u8> =
+                        self.get_account_id(auth_pubkey)
+                            .await
+                            .map_err(map_account_id_error::<RetrievePendingMessagesError>(
+                                auth_pubkey,
+                            ))?;
+                    let message_rows = if let Some(recipient_key) = recipient_key {
+                        sqlx::query($queries.retrieve_recipient_messages)
+                            .bind(&account_id)
+                            .bind(recipient_key)
+                            .bind(i64::from(limit))
+                            .fetch_all(self)
+                            .await
+                    } else {
+                        sqlx::query($queries.retrieve_messages)
+                            .bind(&account_id)
+                            .bind(i64::from(limit))
+                            .fetch_all(self)
+                            .await
+                    }
+                    .map_err(|e| StorageBackendError { source: e.into() })?;
+                    let messages: Vec<(String, Vec<u8>)> = message_rows
+                        .into_iter()
+                        .map(|row| (row.get("message_id"), row.get("message_data")))
+                        .collect();
+                    info!(
+                        "Found total of {:#?} messages, returning them",
+                        messages.len()
+                    );
+                    Ok(messages)
+                }
+                async fn mark_messages_received(
+                    &self,
+                    auth_pubkey: &str,
+                    message_ids: &[String],
+                ) -> Result<u64, MarkMessagesReceivedError> {
+                    info!(
+                        "Removing {:#?} received messages of auth_pubkey {:#?}",
+                        message_ids.len(),
+                        auth_pubkey
+                    );
+                    let account_id: Vec<u8> =
+                        self.get_account_id(auth_pubkey)
+                            .await
+                            .map_err(map_account_id_error::<MarkMessagesReceivedError>(
+                                auth_pubkey,
+                            ))?;
+                    if message_ids.is_empty() {
+                        return Ok(0);
+                    }
+                    let mut query =
+                        QueryBuilder::<$database>::new("DELETE FROM messages WHERE (account_id = ");
+                    query.push_bind(account_id).push(") AND message_id IN (");
+                    let mut ids = query.separated(", ");
+                    for message_id in message_ids {
+                        ids.push_bind(message_id.to_owned());
+                    }
+                    ids.push_unseparated(");");
+                    let removed = query
+                        .build()
+                        .execute(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?
+                        .rows_affected();
+                    info!("Removed {:#?} messages", removed);
+                    Ok(removed)
+                }
+                async fn retrieve_queue_usage(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<QueueUsage, RetrieveQueueUsageError> {
+                    let account_id: Vec<u8> = self
+                        .get_account_id(auth_pubkey)
+                        .await
+                        .map_err(map_account_id_error::<RetrieveQueueUsageError>(auth_pubkey))?;
+                    let row = sqlx::query($queries.queue_usage)
+                        .bind(&account_id)
+                        .fetch_one(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?;
+                    let message_count: i64 = row.get("message_count");
+                    let total_bytes: i64 = row.get("total_bytes");
+                    Ok(QueueUsage {
+                        message_count: message_count.try_into().map_err(|e| anyhow!(e))?,
+                        total_bytes: total_bytes.try_into().map_err(|e| anyhow!(e))?,
+                    })
+                }
+                async fn purge_pending_messages(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<u64, PurgePendingMessagesError> {
+                    let account_id: Vec<u8> =
+                        self.get_account_id(auth_pubkey)
+                            .await
+                            .map_err(map_account_id_error::<PurgePendingMessagesError>(
+                                auth_pubkey,
+                            ))?;
+                    let removed = sqlx::query($queries.purge_messages)
+                        .bind(&account_id)
+                        .execute(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?
+                        .rows_affected();
+                    info!(
+                        "Purged {:#?} messages of auth_pubkey {:#?}",
+                        removed, auth_pubkey
+                    );
+                    Ok(removed)
+                }
+                async fn purge_expired_messages(
+                    &self,
+                    received_before: i64,
+                ) -> Result<u64, PurgeExpiredMessagesError> {
+                    let removed = sqlx::query($queries.purge_expired_messages)
+                        .bind(received_before)
+                        .execute(self)
+                        .await
+                        .map_err(|e| StorageBackendError { source: e.into() })?
+                        .rows_affected();
+                    info!("Purged {:#?} expired messages", removed);
+                    Ok(removed)
+                }
+                async fn add_recipient(
+                    &self,
+                    auth_pubkey: &str,
+                    recipient_key: &str,
+                ) -> Result<(), AddRecipientError> {
+                    info!(
+                        "Adding recipient_key to account with auth_pubkey {:#?}",
+                        auth_pubkey
+                    );
+                    let account_id: Vec<u8> = self
+                        .get_account_id(auth_pubkey)
+                        .await
+                        .map_err(map_account_id_error::<AddRecipientError>(auth_pubkey))?;
+                    info!(
+                        "Found matching account {:x?}. Proceeding with attempt to add recipient \
+                         recipient_key {:#?} ",
+                        account_id, recipient_key
+                    );
+                    sqlx::query($queries.insert_recipient)
+                        .bind(&account_id)
+                        .bind(recipient_key)
+                        .execute(self)
+                        .await
+                        .map_err(|e| {
+                            anyhow!(e)
+                                .context("Error while inserting recipient entry into the database")
+                        })?;
+                    Ok(())
+                }
+                async fn remove_recipient(
+                    &self,
+                    auth_pubkey: &str,
+                    recipient_key: &str,
+                ) -> Result<(), RemoveRecipientError> {
+                    info!(
+                        "Removing recipient_key from account with auth_pubkey {:#?}",
+                        auth_pubkey
+                    );
+                    let account_id: Vec<u8> = self
+                        .get_account_id(auth_pubkey)
+                        .await
+                        .map_err(map_account_id_error::<RemoveRecipientError>(auth_pubkey))?;
+                    info!(
+                        "Found matching account {:x?}. Proceeding with attempt to remove \
+                         recipient recipient_key {:#?} ",
+                        account_id, recipient_key
+                    );
+                    sqlx::query($queries.delete_recipient)
+                        .bind(&account_id)
+                        .bind(recipient_key)
+                        .execute(self)
+                        .await
+                        .map_err(|e| {
+                            anyhow!(e)
+                                .context("Error while deleting recipient entry from the database")
+                        })?;
+                    Ok(())
+                }
+                async fn list_recipient_keys(
+                    &self,
+                    auth_pubkey: &str,
+                ) -> Result<Vec<VerKey>, ListRecipientKeysError> {
+                    info!(
+                        "Retrieving recipient_keys for account with auth_pubkey {:#?}",
+                        auth_pubkey
+                    );
+                    let account_id: Vec<u8> = self
+                        .get_account_id(auth_pubkey)
+                        .await
+                        .map_err(map_account_id_error::<ListRecipientKeysError>(auth_pubkey))?;
+                    let recipient_keys: Vec<VerKey> = sqlx::query($queries.list_recipient_keys)
+                        .bind(&account_id)
+                        .fetch_all(self)
+                        .await
+                        .map_err(|e| {
+                            anyhow!(e).context("Error while fetching recipient_keys from database")
+                        })?
+                        .into_iter()
+                        .map(|row| row.get("recipient_key"))
+                        .collect();
+                    Ok(recipient_keys)
+                }
+            }
+        };
+    };
+}
+pub(super) use impl_mediator_persistence;
@@ -1,5 +1,429 @@
 // Copyright 2023 Naian G.
 // SPDX-License-Identifier: Apache-2.0
 
+mod common;
+#[cfg(feature = "mysql")]
 mod mysql;
-pub use mysql::get_db_pool;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+compile_error!("At least one database backend feature (mysql, postgres, sqlite) must be enabled");
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+
+use super::{
+    errors::{
+        AddRecipientError, ConnectDatabaseError, CreateAccountError, GetAccountDetailsError,
+        GetAccountIdError, GetRecipientAccountError, ListAccountsError, ListRecipientKeysError,
+        MarkMessagesReceivedError, PersistForwardMessageError, PurgeExpiredMessagesError,
+        PurgePendingMessagesError, RemoveRecipientError, RetrievePendingMessageCountError,
+        RetrievePendingMessagesError, RetrieveQueueUsageError, VaporizeAccountError,
+    },
+    AccountDetails, MediatorPersistence, QueueUsage,
+};
+use crate::utils::structs::VerKey;
+
+/// Connection pool of one of the supported databases, the backend being selected from the scheme
+/// of the database url.
+#[derive(Clone, Debug)]
+pub enum DatabasePool {
+    #[cfg(feature = "mysql")]
+    MySql(sqlx::MySqlPool),
+    #[cfg(feature = "postgres")]
+    Postgres(sqlx::PgPool),
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::SqlitePool),
+}
+
+/// Connects to the database given by `DATABASE_URL`, falling back to `MYSQL_URL`.
+pub async fn get_db_pool() -> Result<DatabasePool, ConnectDatabaseError> {
+    let _ = dotenvy::dotenv();
+    let database_url = std::env::var("DATABASE_URL")
+        .or_else(|_| std::env::var("MYSQL_URL"))
+        .map_err(|_| ConnectDatabaseError::MissingDatabaseUrl)?;
+    get_db_pool_from_url(&database_url).await
+}
+
+/// Connects to the database at `database_url`, which is migrated on connection.
+pub async fn get_db_pool_from_url(
+    database_url: &str,
+) -> Result<DatabasePool, ConnectDatabaseError> {
+    let scheme = database_url.split(':').next().unwrap_or_default();
+    let pool = match scheme {
+        #[cfg(feature = "mysql")]
+        "mysql" | "mariadb" => DatabasePool::MySql(mysql::get_db_pool(database_url).await?),
+        #[cfg(feature = "postgres")]
+        "postgres" | "postgresql" => {
+            DatabasePool::Postgres(postgres::get_db_pool(database_url).await?)
+        }
+        #[cfg(feature = "sqlite")]
+        "sqlite" => DatabasePool::Sqlite(sqlite::get_db_pool(database_url).await?),
+        _ => return Err(ConnectDatabaseError::UnsupportedScheme(scheme.to_owned())),
+    };
+    Ok(pool)
+}
+
+/// Current unix timestamp in seconds, which messages are stamped with when persisted.
+pub(crate) fn unix_timestamp_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Forwards the call to the persistence implementation of the selected backend.
+macro_rules! dispatch {
+    ($self:ident.$method:ident($($arg:expr),*)) => {
+        match $self {
+            #[cfg(feature = "mysql")]
+            DatabasePool::MySql(pool) => pool.$method($($arg),*).await,
+            #[cfg(feature = "postgres")]
+            DatabasePool::Postgres(pool) => pool.$method($($arg),*).await,
+            #[cfg(feature = "sqlite")]
+            DatabasePool::Sqlite(pool) => pool.$method($($arg),*).await,
+        }
+    };
+}
+
+#[async_trait]
+impl MediatorPersistence for DatabasePool {
+    async fn create_account(
+        &self,
+        auth_pubkey: &str,
+        our_signing_key: &str,
+        did_doc: &str,
+    ) -> Result<(), CreateAccountError> {
+        dispatch!(self.create_account(auth_pubkey, our_signing_key, did_doc))
+    }
+    async fn get_account_id(&self, auth_pubkey: &str) -> Result<Vec<u8>, GetAccountIdError> {
+        dispatch!(self.get_account_id(auth_pubkey))
+    }
+    async fn get_recipient_account(
+        &self,
+        recipient_key: &str,
+    ) -> Result<VerKey, GetRecipientAccountError> {
+        dispatch!(self.get_recipient_account(recipient_key))
+    }
+    async fn vaporize_account(&self, auth_pubkey: &str) -> Result<(), VaporizeAccountError> {
+        dispatch!(self.vaporize_account(auth_pubkey))
+    }
+    async fn add_recipient(
+        &self,
+        auth_pubkey: &str,
+        recipient_key: &str,
+    ) -> Result<(), AddRecipientError> {
+        dispatch!(self.add_recipient(auth_pubkey, recipient_key))
+    }
+    async fn remove_recipient(
+        &self,
+        auth_pubkey: &str,
+        recipient_key: &str,
+    ) -> Result<(), RemoveRecipientError> {
+        dispatch!(self.remove_recipient(auth_pubkey, recipient_key))
+    }
+    async fn list_recipient_keys(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<Vec<VerKey>, ListRecipientKeysError> {
+        dispatch!(self.list_recipient_keys(auth_pubkey))
+    }
+    async fn persist_forward_message(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+    ) -> Result<String, PersistForwardMessageError> {
+        dispatch!(self.persist_forward_message(recipient_key, message_data))
+    }
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        max_messages: Option<u64>,
+        max_bytes: Option<u64>,
+    ) -> Result<Option<String>, PersistForwardMessageError> {
+        dispatch!(self.persist_forward_message_within_quota(
+            recipient_key,
+            message_data,
+            max_messages,
+            max_bytes
+        ))
+    }
+    async fn retrieve_pending_message_count(
+        &self,
+        auth_pubkey: &str,
+        recipient_key: Option<&String>,
+    ) -> Result<u32, RetrievePendingMessageCountError> {
+        dispatch!(self.retrieve_pending_message_count(auth_pubkey, recipient_key))
+    }
+    async fn retrieve_pending_messages(
+        &self,
+        auth_pubkey: &str,
+        limit: u32,
+        recipient_key: Option<&VerKey>,
+    ) -> Result<Vec<(String, Vec<u8>)>, RetrievePendingMessagesError> {
+        dispatch!(self.retrieve_pending_messages(auth_pubkey, limit, recipient_key))
+    }
+    async fn mark_messages_received(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> Result<u64, MarkMessagesReceivedError> {
+        dispatch!(self.mark_messages_received(auth_pubkey, message_ids))
+    }
+    async fn retrieve_queue_usage(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<QueueUsage, RetrieveQueueUsageError> {
+        dispatch!(self.retrieve_queue_usage(auth_pubkey))
+    }
+    async fn purge_pending_messages(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<u64, PurgePendingMessagesError> {
+        dispatch!(self.purge_pending_messages(auth_pubkey))
+    }
+    async fn purge_expired_messages(
+        &self,
+        received_before: i64,
+    ) -> Result<u64, PurgeExpiredMessagesError> {
+        dispatch!(self.purge_expired_messages(received_before))
+    }
+    async fn list_accounts(&self) -> Result<Vec<(String, VerKey)>, ListAccountsError> {
+        dispatch!(self.list_accounts())
+    }
+    async fn get_account_details(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<AccountDetails, GetAccountDetailsError> {
+        dispatch!(self.get_account_details(auth_pubkey))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    const DID_DOC: &str = r#"{
+        "@context": "https://w3id.org/did/v1",
+        "id": "test",
+        "publicKey": [],
+        "authentication": [],
+        "service": []
+    }"#;
+
+    #[tokio::test]
+    async fn test_sqlite_persist_within_quota() {
+        let persistence = get_db_pool_from_url("sqlite::memory:").await.unwrap();
+        persistence
+            .create_account("auth_pubkey", "our_signing_key", DID_DOC)
+            .await
+            .unwrap();
+        persistence
+            .add_recipient("auth_pubkey", "recipient_key")
+            .await
+            .unwrap();
+        let persist = |message, max_messages, max_bytes| {
+            persistence.persist_forward_message_within_quota(
+                "recipient_key",
+                message,
+                max_messages,
+                max_bytes,
+            )
+        };
+        assert!(persist("first", Some(2), None).await.unwrap().is_some());
+        assert!(persist("second", Some(2), None).await.unwrap().is_some());
+        assert!(persist("third", Some(2), None).await.unwrap().is_none());
+        assert!(persist("third", None, Some(16)).await.unwrap().is_some());
+        assert!(persist("fourth", None, Some(16)).await.unwrap().is_none());
+        assert!(persist("fourth", None, None).await.unwrap().is_some());
+        assert_eq!(
+            persistence
+                .retrieve_pending_message_count("auth_pubkey", None)
+                .await
+                .unwrap(),
+            4
+        );
+        assert!(persistence
+            .persist_forward_message_within_quota("unknown_key", "lost", None, None)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_persistence() {
+        let persistence = get_db_pool_from_url("sqlite::memory:").await.unwrap();
+        persistence
+            .create_account("auth_pubkey", "our_signing_key", DID_DOC)
+            .await
+            .unwrap();
+        let account = persistence
+            .get_account_details("auth_pubkey")
+            .await
+            .unwrap();
+        assert_eq!(account.our_signing_key, "our_signing_key");
+        assert_eq!(
+            persistence.list_accounts().await.unwrap(),
+            vec![(account.account_name, "auth_pubkey".to_owned())]
+        );
+
+        persistence
+            .add_recipient("auth_pubkey", "recipient_key")
+            .await
+            .unwrap();
+        assert_eq!(
+            persistence
+                .list_recipient_keys("auth_pubkey")
+                .await
+                .unwrap(),
+            vec!["recipient_key".to_owned()]
+        );
+        assert_eq!(
+            persistence
+                .get_recipient_account("recipient_key")
+                .await
+                .unwrap(),
+            "auth_pubkey"
+        );
+        assert!(persistence
+            .get_recipient_account("unknown_key")
+            .await
+            .is_err());
+        let mut persisted_ids = Vec::new();
+        for message in ["first", "second", "third"] {
+            persisted_ids.push(
+                persistence
+                    .persist_forward_message("recipient_key", message)
+                    .await
+                    .unwrap(),
+            );
+        }
+        assert!(persistence
+            .persist_forward_message("unknown_key", "lost")
+            .await
+            .is_err());
+        assert_eq!(
+            persistence
+                .retrieve_pending_message_count("auth_pubkey", None)
+                .await
+                .unwrap(),
+            3
+        );
+        let messages = persistence
+            .retrieve_pending_messages("auth_pubkey", 2, Some(&"recipient_key".to_owned()))
+            .await
+            .unwrap();
+        let (message_ids, messages): (Vec<String>, Vec<Vec<u8>>) = messages.into_iter().unzip();
+        assert_eq!(messages, vec![b"first".to_vec(), b"second".to_vec()]);
+        assert_eq!(message_ids, persisted_ids[..2]);
+
+        persistence
+            .create_account("other_pubkey", "our_signing_key", DID_DOC)
+            .await
+            .unwrap();
+        assert_eq!(
+            persistence
+                .mark_messages_received("other_pubkey", &message_ids)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            persistence
+                .mark_messages_received("auth_pubkey", &message_ids[..1])
+                .await
+                .unwrap(),
+            1
+        );
+        let messages = persistence
+            .retrieve_pending_messages("auth_pubkey", 10, None)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].0, message_ids[1]);
+        assert_eq!(
+            persistence
+                .retrieve_queue_usage("auth_pubkey")
+                .await
+                .unwrap(),
+            QueueUsage {
+                message_count: 2,
+                total_bytes: ("second".len() + "third".len()) as u64
+            }
+        );
+        assert_eq!(
+            persistence
+                .purge_expired_messages(unix_timestamp_now() - 60)
+                .await
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            persistence
+                .purge_expired_messages(unix_timestamp_now() + 1)
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            persistence
+                .retrieve_queue_usage("auth_pubkey")
+                .await
+                .unwrap(),
+            QueueUsage::default()
+        );
+
+        persistence
+            .remove_recipient("auth_pubkey", "recipient_key")
+            .await
+            .unwrap();
+        assert!(persistence
+            .list_recipient_keys("auth_pubkey")
+            .await
+            .unwrap()
+            .is_empty());
+
+        persistence
+            .add_recipient("auth_pubkey", "recipient_key")
+            .await
+            .unwrap();
+        persistence
+            .persist_forward_message("recipient_key", "fourth")
+            .await
+            .unwrap();
+        assert_eq!(
+            persistence
+                .purge_pending_messages("auth_pubkey")
+                .await
+                .unwrap(),
+            1
+        );
+        persistence
+            .persist_forward_message("recipient_key", "fifth")
+            .await
+            .unwrap();
+        persistence.vaporize_account("auth_pubkey").await.unwrap();
+        assert!(persistence
+            .get_account_details("auth_pubkey")
+            .await
+            .is_err());
+        assert!(persistence
+            .get_recipient_account("recipient_key")
+            .await
+            .is_err());
+        assert!(persistence.vaporize_account("auth_pubkey").await.is_err());
+        assert_eq!(persistence.list_accounts().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_database_url_scheme() {
+        assert!(matches!(
+            get_db_pool_from_url("mongodb://localhost/mediator").await,
+            Err(ConnectDatabaseError::UnsupportedScheme(scheme)) if scheme == "mongodb"
+        ));
+    }
+}
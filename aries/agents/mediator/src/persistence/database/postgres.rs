@@ -0,0 +1,14 @@
+use sqlx::{postgres::PgPoolOptions, PgPool, Postgres};
+
+use super::common::{impl_mediator_persistence, NUMBERED_QUERIES};
+use crate::persistence::errors::ConnectDatabaseError;
+
+/// Connects to the postgres database at `database_url`, and brings its schema up to date.
+pub async fn get_db_pool(database_url: &str) -> Result<PgPool, ConnectDatabaseError> {
+    let pool = PgPoolOptions::new().connect(database_url).await?;
+    sqlx::migrate!("./migrations/postgres").run(&pool).await?;
+    Ok(pool)
+}
+
+// MediatorPersistence trait implementation for Postgres Database
+impl_mediator_persistence!(PgPool, Postgres, NUMBERED_QUERIES);
@@ -0,0 +1,33 @@
+use std::str::FromStr;
+
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Sqlite, SqlitePool,
+};
+
+use super::common::{impl_mediator_persistence, NUMBERED_QUERIES};
+use crate::persistence::errors::ConnectDatabaseError;
+
+/// Connects to the sqlite database at `database_url`, creating it if missing, and brings its
+/// schema up to date.
+/// In-memory databases only live as long as their connection, so the pool is then limited to a
+/// single connection which is never closed.
+pub async fn get_db_pool(database_url: &str) -> Result<SqlitePool, ConnectDatabaseError> {
+    let connect_options = SqliteConnectOptions::from_str(database_url)?.create_if_missing(true);
+    let pool_options = if database_url.contains(":memory:") || database_url.contains("mode=memory")
+    {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        SqlitePoolOptions::new()
+    };
+    let pool = pool_options.connect_with(connect_options).await?;
+    sqlx::migrate!("./migrations/sqlite").run(&pool).await?;
+    Ok(pool)
+}
+
+// MediatorPersistence trait implementation for Sqlite Database
+impl_mediator_persistence!(SqlitePool, Sqlite, NUMBERED_QUERIES);
@@ -49,8 +49,10 @@ pub enum CreateAccountError {
 }
 // Composed
 error_compose!(GetAccountIdError[StorageBackendError, AccountNotFound]);
+error_compose!(GetRecipientAccountError[StorageBackendError, AccountNotFound]);
 error_compose!(GetAccountDetailsError[StorageBackendError, AccountNotFound, DecodeError]);
 error_compose!(ListAccountsError[StorageBackendError, DecodeError]);
+error_compose!(VaporizeAccountError[StorageBackendError, AccountNotFound]);
 
 error_compose!(AddRecipientError[StorageBackendError, AccountNotFound]);
 // Expected to fail similarly
@@ -60,3 +62,23 @@ error_compose!(ListRecipientKeysError[StorageBackendError, AccountNotFound]);
 error_compose!(PersistForwardMessageError[StorageBackendError, AccountNotFound]);
 error_compose!(RetrievePendingMessageCountError[StorageBackendError, AccountNotFound]);
 error_compose!(RetrievePendingMessagesError[StorageBackendError, AccountNotFound]);
+error_compose!(MarkMessagesReceivedError[StorageBackendError, AccountNotFound]);
+error_compose!(PurgePendingMessagesError[StorageBackendError, AccountNotFound]);
+error_compose!(RetrieveQueueUsageError[StorageBackendError, AccountNotFound]);
+error_compose!(PurgeExpiredMessagesError[StorageBackendError]);
+
+/// Error setting up the database given by the database url
+#[derive(Error, Debug)]
+pub enum ConnectDatabaseError {
+    #[error("Required environment variable DATABASE_URL on command line or in .env")]
+    MissingDatabaseUrl,
+    #[error(
+        "Unsupported database url scheme {0:?}, or mediator was built without the corresponding \
+         database feature"
+    )]
+    UnsupportedScheme(String),
+    #[error("Failed to connect to database")]
+    Connect(#[from] sqlx::Error),
+    #[error("Failed to migrate database")]
+    Migrate(#[from] sqlx::migrate::MigrateError),
+}
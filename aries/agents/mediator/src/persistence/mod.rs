@@ -3,15 +3,21 @@
 
 pub mod database;
 pub mod errors;
+use aries_vcx::did_doc::schema::did_doc::DidDocument;
 use async_trait::async_trait;
 /// Database backend is used for default implementation of MediatorPersistence trait
 pub use database::get_db_pool as get_persistence;
+pub use database::{get_db_pool_from_url, DatabasePool};
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use serde::Serialize;
+use serde_json::Value;
 
 use self::errors::{
     AddRecipientError, CreateAccountError, GetAccountDetailsError, GetAccountIdError,
-    ListAccountsError, ListRecipientKeysError, PersistForwardMessageError, RemoveRecipientError,
-    RetrievePendingMessageCountError, RetrievePendingMessagesError,
+    GetRecipientAccountError, ListAccountsError, ListRecipientKeysError, MarkMessagesReceivedError,
+    PersistForwardMessageError, PurgeExpiredMessagesError, PurgePendingMessagesError,
+    RemoveRecipientError, RetrievePendingMessageCountError, RetrievePendingMessagesError,
+    RetrieveQueueUsageError, VaporizeAccountError,
 };
 use crate::utils::structs::VerKey;
 
@@ -24,7 +30,13 @@ pub trait MediatorPersistence: Send + Sync + 'static {
         did_doc: &str,
     ) -> Result<(), CreateAccountError>;
     async fn get_account_id(&self, auth_pubkey: &str) -> Result<Vec<u8>, GetAccountIdError>;
-    // async fn vaporize_account(&self, auth_pubkey: String);
+    /// Returns auth_pubkey of the account which registered given recipient key
+    async fn get_recipient_account(
+        &self,
+        recipient_key: &str,
+    ) -> Result<VerKey, GetRecipientAccountError>;
+    /// Removes the account, along with its recipient keys and queued messages
+    async fn vaporize_account(&self, auth_pubkey: &str) -> Result<(), VaporizeAccountError>;
     async fn add_recipient(
         &self,
         auth_pubkey: &str,
@@ -39,11 +51,24 @@ pub trait MediatorPersistence: Send + Sync + 'static {
         &self,
         auth_pubkey: &str,
     ) -> Result<Vec<String>, ListRecipientKeysError>;
+    /// Queues the message for the account of the recipient key, returning the id of the queued
+    /// message
     async fn persist_forward_message(
         &self,
         recipient_key: &str,
         message_data: &str,
-    ) -> Result<(), PersistForwardMessageError>;
+    ) -> Result<String, PersistForwardMessageError>;
+    /// Queues the message like [Self::persist_forward_message], unless the queue of the account
+    /// would then hold more than `max_messages` messages or `max_bytes` bytes. The quota is
+    /// checked by the statement inserting the message, so that concurrent forwards cannot exceed
+    /// it. Returns `None` if the message was not queued.
+    async fn persist_forward_message_within_quota(
+        &self,
+        recipient_key: &str,
+        message_data: &str,
+        max_messages: Option<u64>,
+        max_bytes: Option<u64>,
+    ) -> Result<Option<String>, PersistForwardMessageError>;
     async fn retrieve_pending_message_count(
         &self,
         auth_pubkey: &str,
@@ -55,7 +80,30 @@ pub trait MediatorPersistence: Send + Sync + 'static {
         limit: u32,
         recipient_key: Option<&String>,
     ) -> Result<Vec<(String, Vec<u8>)>, RetrievePendingMessagesError>;
-    // async fn mark_messages_received(&self, message_id: Vec<u32>);
+    /// Removes the messages with given ids from the queue of the account, once their delivery
+    /// has been acknowledged. Ids of unknown messages, or of messages queued for other accounts,
+    /// are ignored. Returns the number of removed messages.
+    async fn mark_messages_received(
+        &self,
+        auth_pubkey: &str,
+        message_ids: &[String],
+    ) -> Result<u64, MarkMessagesReceivedError>;
+    /// Returns the number and total size of the messages queued for the account
+    async fn retrieve_queue_usage(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<QueueUsage, RetrieveQueueUsageError>;
+    /// Removes all messages queued for the account. Returns the number of removed messages.
+    async fn purge_pending_messages(
+        &self,
+        auth_pubkey: &str,
+    ) -> Result<u64, PurgePendingMessagesError>;
+    /// Removes the messages of all accounts received before `received_before` (unix timestamp,
+    /// in seconds). Returns the number of removed messages.
+    async fn purge_expired_messages(
+        &self,
+        received_before: i64,
+    ) -> Result<u64, PurgeExpiredMessagesError>;
     /// Returns vector of (account_name, auth_pubkey)
     async fn list_accounts(&self) -> Result<Vec<(String, String)>, ListAccountsError>;
     /// Returns account details (sr.no, account_name, our_signing_key, did_doc)
@@ -65,6 +113,14 @@ pub trait MediatorPersistence: Send + Sync + 'static {
     ) -> Result<AccountDetails, GetAccountDetailsError>;
 }
 
+/// Size of the message queue of an account
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct QueueUsage {
+    pub message_count: u64,
+    /// Sum of the sizes of queued messages, in bytes
+    pub total_bytes: u64,
+}
+
 #[derive(Debug)]
 pub struct AccountDetails {
     // Unique ID for account
@@ -74,5 +130,44 @@ pub struct AccountDetails {
     pub account_name: String,
     pub auth_pubkey: VerKey,
     pub our_signing_key: VerKey,
-    pub their_did_doc: AriesDidDoc,
+    pub their_did_doc: AccountDidDoc,
+}
+
+/// DID document of the account holder, depending on the protocol the connection was established
+/// with.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AccountDidDoc {
+    /// Connection established with the Connections 1.0 protocol
+    Legacy(AriesDidDoc),
+    /// Connection established with the DID Exchange protocol, the peer DID of the account holder
+    /// being the id of the document
+    Peer(DidDocument),
+}
+
+impl AccountDidDoc {
+    /// Decodes a stored DID document, the documents of peer DIDs being told apart by their id.
+    pub fn from_value(did_doc_json: Value) -> Result<Self, serde_json::Error> {
+        let is_peer_did = did_doc_json
+            .get("id")
+            .and_then(Value::as_str)
+            .is_some_and(|id| id.starts_with("did:peer:"));
+        if is_peer_did {
+            serde_json::from_value(did_doc_json).map(AccountDidDoc::Peer)
+        } else {
+            serde_json::from_value(did_doc_json).map(AccountDidDoc::Legacy)
+        }
+    }
+}
+
+impl From<AriesDidDoc> for AccountDidDoc {
+    fn from(did_doc: AriesDidDoc) -> Self {
+        AccountDidDoc::Legacy(did_doc)
+    }
+}
+
+impl From<DidDocument> for AccountDidDoc {
+    fn from(did_doc: DidDocument) -> Self {
+        AccountDidDoc::Peer(did_doc)
+    }
 }
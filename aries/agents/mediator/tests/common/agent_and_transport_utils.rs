@@ -8,16 +8,24 @@ use aries_vcx::{
 use aries_vcx_wallet::wallet::{askar::AskarWallet, base_wallet::BaseWallet};
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
 use mediator::{
-    aries_agent::{client::transports::AriesTransport, Agent},
-    persistence::MediatorPersistence,
+    aries_agent::{client::transports::AriesTransport, Agent, AgentBuilder},
+    mediation::{
+        queue_limits::QueueLimits,
+        upstream::{connect_upstream_mediator, spawn_upstream_pickup_task, HttpTransport},
+    },
+    persistence::{get_db_pool_from_url, MediatorPersistence},
     utils::{structs::VerKey, GenericStringError},
 };
 use messages::{
     msg_fields::protocols::{
+        basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
         coordinate_mediation::{
-            keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
-            CoordinateMediation, KeylistUpdate, KeylistUpdateContent, MediateGrantContent,
-            MediateRequest, MediateRequestContent,
+            v1::{
+                keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+                CoordinateMediationV1, KeylistUpdate, KeylistUpdateContent, MediateGrantContent,
+                MediateRequest, MediateRequestContent,
+            },
+            CoordinateMediation,
         },
         out_of_band::invitation::Invitation as OOBInvitation,
     },
@@ -27,15 +35,95 @@ use test_utils::mockdata::mock_ledger::MockLedger;
 
 use super::prelude::*;
 
-const ENDPOINT_ROOT: &str = "http://localhost:8005";
+/// Database the test agents persist to, given by `TEST_DATABASE_URL`. Defaults to in-memory
+/// sqlite, every agent then getting a database of its own.
+fn test_database_url() -> String {
+    std::env::var("TEST_DATABASE_URL").unwrap_or_else(|_| "sqlite::memory:".to_owned())
+}
+
+/// Returns a demo agent backed by the test database, see [test_database_url].
+pub async fn gen_demo_agent() -> Result<Agent<impl BaseWallet, impl MediatorPersistence>> {
+    let persistence = get_db_pool_from_url(&test_database_url()).await?;
+    Ok(AgentBuilder::<AskarWallet>::new_demo_agent_with_persistence(persistence).await?)
+}
+
+/// Starts a mediator backed by the test database, listening on a free local port.
+/// Returns the endpoint root of the mediator.
+pub async fn start_mediator() -> Result<Url> {
+    start_mediator_with_queue_limits(QueueLimits::default()).await
+}
+
+/// Starts a mediator like [start_mediator], enforcing given message queue limits.
+pub async fn start_mediator_with_queue_limits(queue_limits: QueueLimits) -> Result<Url> {
+    start_mediator_with(queue_limits, None).await
+}
+
+/// Starts a mediator like [start_mediator], serving the admin API with given token.
+pub async fn start_mediator_with_admin_api(admin_api_token: &str) -> Result<Url> {
+    start_mediator_with(QueueLimits::default(), Some(admin_api_token.to_owned())).await
+}
+
+async fn start_mediator_with(
+    queue_limits: QueueLimits,
+    admin_api_token: Option<String>,
+) -> Result<Url> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint_root: Url = format!("http://{}", listener.local_addr()?).parse()?;
+    let mut agent = gen_demo_agent().await?;
+    agent
+        .init_service(vec![], endpoint_root.join("didcomm")?)
+        .await?;
+    agent.set_queue_limits(queue_limits);
+    agent.set_admin_api_token(admin_api_token);
+    let app_router = mediator::http_routes::build_router(agent).await;
+    tokio::spawn(async move { axum::serve(listener, app_router.into_make_service()).await });
+    info!("Started mediator at {}", endpoint_root);
+    Ok(endpoint_root)
+}
+
+/// Starts a mediator like [start_mediator], mediated by the mediator at the upstream endpoint
+/// root. Messages queued upstream are picked up every `pickup_interval`.
+pub async fn start_mediator_behind(
+    upstream_endpoint_root: &Url,
+    pickup_interval: std::time::Duration,
+) -> Result<Url> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let endpoint_root: Url = format!("http://{}", listener.local_addr()?).parse()?;
+    let mut agent = gen_demo_agent().await?;
+    agent
+        .init_service(vec![], endpoint_root.join("didcomm")?)
+        .await?;
+    let invitation: OOBInvitation = reqwest::get(upstream_endpoint_root.join("invitation")?)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let transport = HttpTransport::default();
+    let recipient = connect_upstream_mediator(&agent, invitation, &transport)
+        .await
+        .map_err(|msg| GenericStringError { msg })?;
+    spawn_upstream_pickup_task(
+        std::sync::Arc::new(agent.clone()),
+        recipient,
+        transport,
+        pickup_interval,
+    );
+    let app_router = mediator::http_routes::build_router(agent).await;
+    tokio::spawn(async move { axum::serve(listener, app_router.into_make_service()).await });
+    info!(
+        "Started mediator at {}, behind {}",
+        endpoint_root, upstream_endpoint_root
+    );
+    Ok(endpoint_root)
+}
 
 pub async fn didcomm_connection(
+    mediator_endpoint_root: &Url,
     agent: &Agent<impl BaseWallet, impl MediatorPersistence>,
     aries_transport: &mut impl AriesTransport,
 ) -> Result<InviteeConnection<Completed>> {
     let client = reqwest::Client::new();
-    let base: Url = ENDPOINT_ROOT.parse().unwrap();
-    let endpoint_register = base.join("invitation").unwrap();
+    let endpoint_register = mediator_endpoint_root.join("invitation").unwrap();
 
     let oobi: OOBInvitation = client
         .get(endpoint_register)
@@ -59,9 +147,23 @@ pub async fn gen_mediator_connected_agent() -> Result<(
     VerKey,
     AriesDidDoc,
 )> {
-    let agent = mediator::aries_agent::AgentBuilder::<AskarWallet>::new_demo_agent().await?;
+    connect_agent_to_mediator(&start_mediator().await?).await
+}
+
+/// Returns agent connected to the mediator at given endpoint root, like
+/// [gen_mediator_connected_agent].
+pub async fn connect_agent_to_mediator(
+    mediator_endpoint_root: &Url,
+) -> Result<(
+    Agent<impl BaseWallet, impl MediatorPersistence>,
+    impl AriesTransport,
+    VerKey,
+    AriesDidDoc,
+)> {
+    let agent = gen_demo_agent().await?;
     let mut aries_transport = reqwest::Client::new();
-    let completed_connection = didcomm_connection(&agent, &mut aries_transport).await?;
+    let completed_connection =
+        didcomm_connection(mediator_endpoint_root, &agent, &mut aries_transport).await?;
     let our_verkey: VerKey = completed_connection.pairwise_info().pw_vk.clone();
     let their_diddoc = completed_connection.their_did_doc().clone();
     Ok((agent, aries_transport, our_verkey, their_diddoc))
@@ -124,7 +226,9 @@ pub async fn gen_and_register_recipient_key(
         )
         .id("register-key-with-mediator".to_owned())
         .build();
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(key_update));
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdate(key_update),
+    ));
     info!("Sending {:?}", serde_json::to_string(&message).unwrap());
     let message_bytes = serde_json::to_vec(&message)?;
     let _response_message = send_message_and_pop_response_message(
@@ -145,11 +249,13 @@ pub async fn get_mediator_grant_data(
     mediator_diddoc: &AriesDidDoc,
 ) -> MediateGrantContent {
     // prepare request message
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::MediateRequest(
-        MediateRequest::builder()
-            .content(MediateRequestContent::default())
-            .id("mediate-requets".to_owned())
-            .build(),
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::MediateRequest(
+            MediateRequest::builder()
+                .content(MediateRequestContent::default())
+                .id("mediate-requets".to_owned())
+                .build(),
+        ),
     ));
     let message_bytes = serde_json::to_vec(&message).unwrap();
     // send message and get response
@@ -163,8 +269,9 @@ pub async fn get_mediator_grant_data(
     .await
     .unwrap();
     // extract routing parameters
-    if let AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(grant_data)) =
-        serde_json::from_str(&response_message).unwrap()
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::MediateGrant(grant_data),
+    )) = serde_json::from_str(&response_message).unwrap()
     {
         info!("Grant Data {:?}", grant_data);
         grant_data.content
@@ -175,3 +282,41 @@ pub async fn get_mediator_grant_data(
         )
     }
 }
+
+/// Forwards a basic message, anoncrypted for the agent, to its mediator
+pub async fn forward_basic_anoncrypt_message(
+    agent_diddoc: &AriesDidDoc,
+    message_text: &str,
+) -> Result<()> {
+    // Prepare forwarding agent
+    let agent_f = gen_demo_agent().await?;
+    // Prepare forwarding agent transport
+    let mut agent_f_aries_transport = reqwest::Client::new();
+    // Prepare message and wrap into anoncrypt forward message
+    let message: BasicMessage = BasicMessage::builder()
+        .content(
+            BasicMessageContent::builder()
+                .content(message_text.to_string())
+                .sent_time(chrono::DateTime::default())
+                .build(),
+        )
+        .decorators(BasicMessageDecorators::default())
+        .id("JustHello".to_string())
+        .build();
+
+    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create_from_legacy(
+        agent_f.get_wallet_ref().as_ref(),
+        &serde_json::to_vec(&message)?,
+        None,
+        agent_diddoc,
+    )
+    .await?;
+    // Send forward message to provided endpoint
+    let packed_json = serde_json::from_slice(&packed_message)?;
+    info!("Sending anoncrypt packed message{}", packed_json);
+    let response_envelope = agent_f_aries_transport
+        .send_aries_envelope(packed_json, agent_diddoc)
+        .await?;
+    info!("Response of forward{:?}", response_envelope);
+    Ok(())
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use messages::msg_fields::protocols::out_of_band::invitation::Invitation as OOBInvitation;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::common::{
+    agent_and_transport_utils::{
+        connect_agent_to_mediator, forward_basic_anoncrypt_message, gen_and_register_recipient_key,
+        get_mediator_grant_data, start_mediator, start_mediator_with_admin_api,
+    },
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+const ADMIN_API_TOKEN: &str = "admin-api-test-token";
+
+#[tokio::test]
+async fn test_admin_api_requires_token() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let client = reqwest::Client::new();
+    let base = start_mediator_with_admin_api(ADMIN_API_TOKEN).await?;
+    let endpoint_accounts = base.join("admin/accounts")?;
+    let res = client.get(endpoint_accounts.clone()).send().await?;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let res = client
+        .get(endpoint_accounts)
+        .bearer_auth("wrong-token")
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    // Not served without a token configured
+    let base = start_mediator().await?;
+    let res = client
+        .get(base.join("admin/accounts")?)
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_admin_api_manages_accounts() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let client = reqwest::Client::new();
+    let base = start_mediator_with_admin_api(ADMIN_API_TOKEN).await?;
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        connect_agent_to_mediator(&base).await?;
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    let (agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, from AgentF").await?;
+
+    let accounts: Value = client
+        .get(base.join("admin/accounts")?)
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    // Other tests' accounts are listed too when sharing the test database
+    assert!(accounts
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|account| account["auth_pubkey"] == agent_verkey.as_str()));
+
+    let endpoint_account = base.join(&format!("admin/accounts/{agent_verkey}"))?;
+    let account: Value = client
+        .get(endpoint_account.clone())
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(account["recipient_keys"][0], agent_recipient_key.as_str());
+    assert_eq!(account["queue"]["message_count"], 1);
+
+    let purged: Value = client
+        .delete(base.join(&format!("admin/accounts/{agent_verkey}/messages"))?)
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_eq!(purged["removed"], 1);
+
+    // Invitations are issued with the new keys
+    let invitation: OOBInvitation = client
+        .get(base.join("invitation")?)
+        .send()
+        .await?
+        .json()
+        .await?;
+    let rotated_invitation: OOBInvitation = client
+        .post(base.join("admin/service/rotate-keys")?)
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    assert_ne!(
+        invitation.content.services,
+        rotated_invitation.content.services
+    );
+    let current_invitation: OOBInvitation = client
+        .get(base.join("invitation")?)
+        .send()
+        .await?
+        .json()
+        .await?;
+    assert_eq!(
+        current_invitation.content.services,
+        rotated_invitation.content.services
+    );
+
+    let res = client
+        .delete(endpoint_account.clone())
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+    let res = client
+        .get(endpoint_account)
+        .bearer_auth(ADMIN_API_TOKEN)
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    Ok(())
+}
@@ -1,19 +1,32 @@
 mod common;
 
-use aries_vcx_wallet::wallet::askar::AskarWallet;
-use messages::msg_fields::protocols::out_of_band::invitation::Invitation as OOBInvitation;
+use aries_vcx::{protocols::connection::GenericConnection, transport::MessageConnection};
+use mediator::mediation::upstream::HttpTransport;
+use messages::{
+    decorators::transport::ReturnRoute,
+    msg_fields::protocols::{
+        out_of_band::invitation::Invitation as OOBInvitation,
+        pickup::{
+            v2::{PickupV2, StatusRequest, StatusRequestContent, StatusRequestDecorators},
+            Pickup,
+        },
+    },
+    AriesMessage,
+};
 
-use crate::common::{prelude::*, test_setup::setup_env_logging};
+use crate::common::{
+    agent_and_transport_utils::{didcomm_connection, gen_demo_agent, start_mediator},
+    prelude::*,
+    test_setup::setup_env_logging,
+};
 
 static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
 
-const ENDPOINT_ROOT: &str = "http://localhost:8005";
-
 #[tokio::test]
 async fn didcomm_connection_succeeds() -> Result<()> {
     LOGGING_INIT.call_once(setup_env_logging);
     let client = reqwest::Client::new();
-    let base: Url = ENDPOINT_ROOT.parse().unwrap();
+    let base: Url = start_mediator().await?;
     let endpoint_invitation = base.join("invitation").unwrap();
 
     let oobi: OOBInvitation = client
@@ -27,7 +40,7 @@ async fn didcomm_connection_succeeds() -> Result<()> {
         "Got invitation {}",
         serde_json::to_string_pretty(&oobi.clone()).unwrap()
     );
-    let agent = mediator::aries_agent::AgentBuilder::<AskarWallet>::new_demo_agent().await?;
+    let agent = gen_demo_agent().await?;
     let mut aries_transport = reqwest::Client::new();
     let _state = agent
         .establish_connection(oobi, &mut aries_transport)
@@ -35,3 +48,33 @@ async fn didcomm_connection_succeeds() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn reply_is_returned_over_connection() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let base: Url = start_mediator().await?;
+    let agent = gen_demo_agent().await?;
+    let connection = GenericConnection::from(
+        didcomm_connection(&base, &agent, &mut reqwest::Client::new()).await?,
+    );
+    let status_request = AriesMessage::Pickup(Pickup::V2(PickupV2::StatusRequest(
+        StatusRequest::builder()
+            .content(StatusRequestContent::builder().build())
+            .decorators(StatusRequestDecorators::default())
+            .id("request-status".to_owned())
+            .build(),
+    )));
+    let reply = MessageConnection::send_message_with_return_route(
+        &connection,
+        agent.get_wallet_ref().as_ref(),
+        &status_request,
+        ReturnRoute::All,
+        &HttpTransport::default(),
+    )
+    .await?;
+    let Some(AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status)))) = reply else {
+        panic!("Expected status reply, got {:?}", reply)
+    };
+    assert_eq!(status.content.message_count, 0);
+    Ok(())
+}
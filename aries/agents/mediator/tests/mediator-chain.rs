@@ -0,0 +1,119 @@
+mod common;
+
+use std::time::Duration;
+
+use aries_vcx::{
+    handlers::mediation::recipient::MediationRecipient,
+    protocols::connection::GenericConnection,
+    transport::Transport,
+    utils::{didcomm_utils::parse_ed25519_key, encryption_envelope::EncryptionEnvelope},
+};
+use mediator::mediation::upstream::HttpTransport;
+use messages::{
+    msg_fields::protocols::{
+        basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
+        out_of_band::invitation::{Invitation as OOBInvitation, OobService},
+    },
+    AriesMessage,
+};
+
+use crate::common::{
+    agent_and_transport_utils::{
+        didcomm_connection, gen_demo_agent, start_mediator, start_mediator_behind,
+    },
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+const PICKUP_INTERVAL: Duration = Duration::from_millis(100);
+
+#[tokio::test]
+async fn test_two_hop_mediator_chain() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let upstream_endpoint_root = start_mediator().await?;
+    let mediator_endpoint_root =
+        start_mediator_behind(&upstream_endpoint_root, PICKUP_INTERVAL).await?;
+    let upstream_invitation: OOBInvitation =
+        reqwest::get(upstream_endpoint_root.join("invitation")?)
+            .await?
+            .json()
+            .await?;
+    let Some(OobService::AriesService(upstream_service)) =
+        upstream_invitation.content.services.first()
+    else {
+        panic!("Expected inline service in {:?}", upstream_invitation)
+    };
+
+    // The recipient is granted the upstream endpoint, its mediator's key wrapping the innermost
+    // forward and the upstream mediator's key the outermost
+    let agent = gen_demo_agent().await?;
+    let wallet = agent.get_wallet_ref();
+    let transport = HttpTransport::default();
+    let connection =
+        didcomm_connection(&mediator_endpoint_root, &agent, &mut reqwest::Client::new()).await?;
+    let mut recipient = MediationRecipient::new(GenericConnection::from(connection));
+    let grant = recipient
+        .request_mediation(wallet.as_ref(), &transport)
+        .await?
+        .clone();
+    assert_eq!(grant.endpoint, upstream_endpoint_root.join("didcomm")?);
+    assert_eq!(grant.routing_keys.len(), 2);
+    assert_eq!(grant.routing_keys[1], upstream_service.recipient_keys[0]);
+
+    // Messages sent to the upstream mediator reach the recipient's queue at its mediator
+    let service = recipient
+        .create_invitation_service(wallet.as_ref(), &transport)
+        .await?;
+    assert_eq!(service.routing_keys, grant.routing_keys);
+    let message: BasicMessage = BasicMessage::builder()
+        .content(
+            BasicMessageContent::builder()
+                .content("Hi, through two mediators".to_string())
+                .sent_time(chrono::DateTime::default())
+                .build(),
+        )
+        .decorators(BasicMessageDecorators::default())
+        .id("JustHello".to_string())
+        .build();
+    let sender = gen_demo_agent().await?;
+    let parse_keys = |keys: &[String]| {
+        keys.iter()
+            .map(|key| parse_ed25519_key(key))
+            .collect::<std::result::Result<Vec<_>, _>>()
+    };
+    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create_from_recipient_keys(
+        sender.get_wallet_ref().as_ref(),
+        &serde_json::to_vec(&message)?,
+        None,
+        parse_keys(&service.recipient_keys)?,
+        parse_keys(&service.routing_keys)?,
+    )
+    .await?;
+    transport
+        .send_message(packed_message, &service.service_endpoint)
+        .await?;
+
+    let mut status = 0;
+    for _ in 0..50 {
+        status = recipient.status(wallet.as_ref(), &transport).await?;
+        if status > 0 {
+            break;
+        }
+        tokio::time::sleep(PICKUP_INTERVAL).await;
+    }
+    assert_eq!(status, 1);
+    let mut received = Vec::new();
+    recipient
+        .pickup_all(wallet.as_ref(), &transport, 10, |message| {
+            received.push(message);
+            Ok(())
+        })
+        .await?;
+    let AriesMessage::BasicMessage(basic_message) = &received[0].message else {
+        panic!("Expected basic message, got {:?}", received[0].message)
+    };
+    assert_eq!(basic_message.content.content, "Hi, through two mediators");
+    Ok(())
+}
@@ -3,9 +3,19 @@ mod common;
 use aries_vcx_wallet::wallet::base_wallet::did_wallet::DidWallet;
 use messages::{
     msg_fields::protocols::coordinate_mediation::{
-        keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
-        CoordinateMediation, KeylistQuery, KeylistQueryContent, KeylistUpdate,
-        KeylistUpdateContent, MediateRequest, MediateRequestContent,
+        v1::{
+            keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+            CoordinateMediationV1, KeylistQuery, KeylistQueryContent, KeylistUpdate,
+            KeylistUpdateContent, MediateRequest, MediateRequestContent,
+        },
+        v2::{
+            recipient_update::{RecipientUpdateItem, RecipientUpdateItemAction},
+            recipient_update_response::RecipientUpdateItemResult,
+            CoordinateMediationV2, MediateRequest as MediateRequestV2,
+            MediateRequestContent as MediateRequestContentV2, RecipientQuery,
+            RecipientQueryContent, RecipientUpdate, RecipientUpdateContent,
+        },
+        CoordinateMediation,
     },
     AriesMessage,
 };
@@ -27,12 +37,12 @@ async fn test_mediate_grant() -> Result<()> {
     let (agent, mut aries_transport, our_verkey, their_diddoc) =
         gen_mediator_connected_agent().await?;
     // prepare request message
-    let mediate_request = CoordinateMediation::MediateRequest(
+    let mediate_request = CoordinateMediation::V1(CoordinateMediationV1::MediateRequest(
         MediateRequest::builder()
             .content(MediateRequestContent::default())
             .id("mediate-request-test".to_owned())
             .build(),
-    );
+    ));
     let message_bytes = serde_json::to_vec(&AriesMessage::CoordinateMediation(mediate_request))?;
     // send message and get response
     let response_message = send_message_and_pop_response_message(
@@ -44,12 +54,14 @@ async fn test_mediate_grant() -> Result<()> {
     )
     .await?;
     // verify response
-    if let AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(grant_data)) =
-        serde_json::from_str(&response_message).unwrap()
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::MediateGrant(grant_data),
+    )) = serde_json::from_str(&response_message).unwrap()
     {
         info!("Grant Data {:?}", grant_data);
-    } else if let AriesMessage::CoordinateMediation(CoordinateMediation::MediateDeny(deny_data)) =
-        serde_json::from_str(&response_message).unwrap()
+    } else if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::MediateDeny(deny_data),
+    )) = serde_json::from_str(&response_message).unwrap()
     {
         info!("Deny Data {:?}", deny_data);
     } else {
@@ -83,8 +95,8 @@ async fn test_mediate_keylist_update_add() -> Result<()> {
         .id("key-add".to_owned())
         .build();
 
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(
-        keylist_update_request,
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdate(keylist_update_request),
     ));
     info!("Sending {:?}", serde_json::to_string(&message).unwrap());
     let message_bytes = serde_json::to_vec(&message)?;
@@ -98,8 +110,8 @@ async fn test_mediate_keylist_update_add() -> Result<()> {
     )
     .await?;
     // verify response
-    if let AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(
-        update_response_data,
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdateResponse(update_response_data),
     )) = serde_json::from_str(&response_message)?
     {
         info!("Received update response {:?}", update_response_data);
@@ -134,8 +146,8 @@ async fn test_mediate_keylist_query() -> Result<()> {
         .id("key-add".to_owned())
         .build();
 
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(
-        keylist_update_request,
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdate(keylist_update_request),
     ));
     let message_bytes = serde_json::to_vec(&message)?;
     // send message and get response
@@ -153,8 +165,9 @@ async fn test_mediate_keylist_query() -> Result<()> {
         .content(KeylistQueryContent::default())
         .id("keylist-query".to_owned())
         .build();
-    let message =
-        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistQuery(keylist_query));
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistQuery(keylist_query),
+    ));
     info!("Sending {:?}", serde_json::to_string(&message).unwrap());
     let message_bytes = serde_json::to_vec(&message)?;
     // send message and get response
@@ -167,8 +180,9 @@ async fn test_mediate_keylist_query() -> Result<()> {
     )
     .await?;
     // verify
-    if let AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(keylist)) =
-        serde_json::from_str(&response_message)?
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::Keylist(keylist),
+    )) = serde_json::from_str(&response_message)?
     {
         info!("Keylist mediator sent {:?}", keylist.content)
     } else {
@@ -202,8 +216,8 @@ async fn test_mediate_keylist_update_remove() -> Result<()> {
         .id("key-add".to_owned())
         .build();
 
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(
-        keylist_update_request,
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdate(keylist_update_request),
     ));
     let message_bytes = serde_json::to_vec(&message)?;
     // send message and get response
@@ -227,8 +241,8 @@ async fn test_mediate_keylist_update_remove() -> Result<()> {
         .id("key-remove".to_owned())
         .build();
 
-    let message = AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(
-        keylist_update_request,
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdate(keylist_update_request),
     ));
     info!("Sending {:?}", serde_json::to_string(&message).unwrap());
     let message_bytes = serde_json::to_vec(&message)?;
@@ -241,8 +255,8 @@ async fn test_mediate_keylist_update_remove() -> Result<()> {
         &their_diddoc,
     )
     .await?;
-    if let AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(
-        update_response_data,
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+        CoordinateMediationV1::KeylistUpdateResponse(update_response_data),
     )) = serde_json::from_str(&response_message)?
     {
         info!("Received update response {:?}", update_response_data);
@@ -254,3 +268,105 @@ async fn test_mediate_keylist_update_remove() -> Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn test_mediate_grant_v2() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let (agent, mut aries_transport, our_verkey, their_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let mediate_request = MediateRequestV2::builder()
+        .content(MediateRequestContentV2::default())
+        .decorators(Default::default())
+        .id("mediate-request-test".to_owned())
+        .build();
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::MediateRequest(mediate_request),
+    ));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&message)?,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    // Replied to in the version of the request
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::MediateGrant(grant),
+    )) = serde_json::from_str(&response_message)?
+    {
+        assert_eq!(grant.content.routing_did.len(), 1);
+        assert!(grant.content.routing_did[0].starts_with("did:peer:4"));
+    } else {
+        panic!("Expected message of type MediateGrant 2.0. Found {response_message:?}")
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mediate_recipient_update_and_query_v2() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let (agent, mut aries_transport, our_verkey, their_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let recipient_did = "did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc".to_owned();
+    let recipient_update = RecipientUpdate::builder()
+        .content(RecipientUpdateContent {
+            updates: vec![RecipientUpdateItem {
+                recipient_did: recipient_did.clone(),
+                action: RecipientUpdateItemAction::Add,
+            }],
+        })
+        .decorators(Default::default())
+        .id("recipient-add".to_owned())
+        .build();
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::RecipientUpdate(recipient_update),
+    ));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&message)?,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::RecipientUpdateResponse(update_response),
+    )) = serde_json::from_str(&response_message)?
+    {
+        assert_eq!(update_response.content.updated.len(), 1);
+        assert_eq!(
+            update_response.content.updated[0].result,
+            RecipientUpdateItemResult::Success
+        );
+    } else {
+        panic!("Expected message of type RecipientUpdateResponse. Found {response_message:?}")
+    }
+
+    let recipient_query = RecipientQuery::builder()
+        .content(RecipientQueryContent::default())
+        .decorators(Default::default())
+        .id("recipient-query".to_owned())
+        .build();
+    let message = AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::RecipientQuery(recipient_query),
+    ));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&message)?,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    if let AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+        CoordinateMediationV2::Recipient(recipient),
+    )) = serde_json::from_str(&response_message)?
+    {
+        assert_eq!(recipient.content.dids.len(), 1);
+        assert_eq!(recipient.content.dids[0].recipient_did, recipient_did);
+    } else {
+        panic!("Expected message of type Recipient. Found {response_message:?}")
+    }
+    Ok(())
+}
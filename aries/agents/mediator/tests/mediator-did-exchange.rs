@@ -0,0 +1,157 @@
+mod common;
+
+use std::sync::Arc;
+
+use aries_vcx::{
+    did_doc::schema::service::typed::ServiceType,
+    protocols::did_exchange::{
+        resolve_enc_key_from_invitation,
+        state_machine::{
+            generic::GenericDidExchange, helpers::create_peer_did_4,
+            requester::helpers::invitation_get_first_did_service,
+        },
+    },
+    utils::encryption_envelope::EncryptionEnvelope,
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_peer::resolver::PeerDidResolver;
+use did_resolver_registry::ResolverRegistry;
+use mediator::{aries_agent::Agent, persistence::MediatorPersistence, utils::GenericStringError};
+use messages::{
+    msg_fields::protocols::{
+        did_exchange::{
+            v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, v1_x::response::AnyResponse, DidExchange,
+        },
+        out_of_band::invitation::Invitation as OOBInvitation,
+        pickup::{
+            v2::{PickupV2, StatusRequest, StatusRequestContent, StatusRequestDecorators},
+            Pickup,
+        },
+    },
+    msg_types::protocols::did_exchange::DidExchangeTypeV1,
+    AriesMessage,
+};
+
+use crate::common::{
+    agent_and_transport_utils::{gen_demo_agent, start_mediator},
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+/// Packs message for the counterparty of the exchange, posts it to the mediator and returns the
+/// unpacked reply, if any
+async fn send_over_did_exchange(
+    agent: &Agent<impl BaseWallet, impl MediatorPersistence>,
+    exchange: &GenericDidExchange,
+    endpoint: &Url,
+    message: &AriesMessage,
+) -> Result<Option<AriesMessage>> {
+    let service = exchange
+        .their_did_doc()
+        .get_service_of_type(&ServiceType::DIDCommV1)?;
+    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create(
+        agent.get_wallet_ref().as_ref(),
+        &serde_json::to_vec(message)?,
+        exchange.our_did_document(),
+        exchange.their_did_doc(),
+        service.id(),
+    )
+    .await?;
+    let reply = reqwest::Client::new()
+        .post(endpoint.clone())
+        .body(packed_message)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    if serde_json::from_slice::<serde_json::Value>(&reply)?
+        .get("protected")
+        .is_none()
+    {
+        return Ok(None);
+    }
+    let unpacked = agent
+        .unpack_didcomm(&reply)
+        .await
+        .map_err(|e| GenericStringError { msg: e })?;
+    Ok(Some(serde_json::from_str(&unpacked.message)?))
+}
+
+#[tokio::test]
+async fn test_did_exchange_with_mediator() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let base: Url = start_mediator().await?;
+    let endpoint = base.join("didcomm")?;
+    let oobi: OOBInvitation = reqwest::Client::new()
+        .get(base.join("invitation")?)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let agent = gen_demo_agent().await?;
+    let resolver_registry =
+        Arc::new(ResolverRegistry::new().register_resolver("peer".into(), PeerDidResolver::new()));
+
+    // Request, addressed to the peer DID of the invitation
+    let invitation_key = resolve_enc_key_from_invitation(&oobi, &resolver_registry).await?;
+    let their_did = invitation_get_first_did_service(&oobi)?;
+    let (our_peer_did, _our_verkey) =
+        create_peer_did_4(agent.get_wallet_ref().as_ref(), endpoint.clone(), vec![]).await?;
+    let (requester, request) = GenericDidExchange::construct_request(
+        &resolver_registry,
+        Some(oobi.id.clone()),
+        &their_did,
+        &our_peer_did,
+        "mediator-did-exchange-test".to_owned(),
+        DidExchangeTypeV1::new_v1_1(),
+    )
+    .await?;
+    let reply = send_over_did_exchange(&agent, &requester, &endpoint, &request.into())
+        .await?
+        .expect("Mediator should respond to the request");
+    let response = match reply {
+        AriesMessage::DidExchange(DidExchange::V1_0(DidExchangeV1_0::Response(response))) => {
+            AnyResponse::V1_0(response)
+        }
+        AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::Response(response))) => {
+            AnyResponse::V1_1(response)
+        }
+        other => panic!("Expected DID Exchange response, got {:?}", other),
+    };
+
+    // Complete
+    let (requester, complete) = requester
+        .handle_response(
+            agent.get_wallet_ref().as_ref(),
+            &invitation_key,
+            response,
+            &resolver_registry,
+        )
+        .await
+        .map_err(|(_, err)| err)?;
+    let reply = send_over_did_exchange(&agent, &requester, &endpoint, &complete.into()).await?;
+    assert!(reply.is_none());
+
+    // The mediator now authenticates us by our peer DID
+    let status_request: AriesMessage = Pickup::V2(PickupV2::StatusRequest(
+        StatusRequest::builder()
+            .content(StatusRequestContent::builder().build())
+            .decorators(StatusRequestDecorators::default())
+            .id("request-status".to_owned())
+            .build(),
+    ))
+    .into();
+    let reply = send_over_did_exchange(&agent, &requester, &endpoint, &status_request)
+        .await?
+        .expect("Mediator should respond to the status request");
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) = reply {
+        assert_eq!(status.content.message_count, 0);
+    } else {
+        panic!("Expected status, got {:?}", reply);
+    }
+    Ok(())
+}
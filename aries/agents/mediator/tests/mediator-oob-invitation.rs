@@ -4,18 +4,18 @@ use anyhow::Result;
 use messages::msg_fields::protocols::out_of_band::invitation::Invitation as OOBInvitation;
 use url::Url;
 
-use crate::common::{prelude::*, test_setup::setup_env_logging};
+use crate::common::{
+    agent_and_transport_utils::start_mediator, prelude::*, test_setup::setup_env_logging,
+};
 
 static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
 
-const ENDPOINT_ROOT: &str = "http://localhost:8005";
-
 #[tokio::test]
 async fn endpoint_invitation_returns_oob() -> Result<()> {
     LOGGING_INIT.call_once(setup_env_logging);
 
     let client = reqwest::Client::new();
-    let base: Url = ENDPOINT_ROOT.parse().unwrap();
+    let base: Url = start_mediator().await?;
     let endpoint_register_json = base.join("/invitation").unwrap();
 
     let res = client
@@ -1,25 +1,39 @@
 mod common;
 
 use aries_vcx::utils::encryption_envelope::EncryptionEnvelope;
-use aries_vcx_wallet::wallet::askar::AskarWallet;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
-use mediator::aries_agent::client::transports::AriesTransport;
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use mediator::{
+    aries_agent::Agent,
+    persistence::MediatorPersistence,
+    utils::{structs::VerKey, GenericStringError},
+};
 use messages::{
-    decorators::attachment::AttachmentType,
-    msg_fields::protocols::{
-        basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
-        pickup::{
-            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, Pickup,
-            StatusRequest, StatusRequestContent, StatusRequestDecorators,
+    decorators::{
+        attachment::AttachmentType,
+        transport::{ReturnRoute, Transport},
+    },
+    msg_fields::protocols::pickup::{
+        v2::{
+            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, LiveDeliveryChange,
+            LiveDeliveryChangeContent, LiveDeliveryChangeDecorators, MessagesReceived,
+            MessagesReceivedContent, MessagesReceivedDecorators, PickupV2, StatusRequest,
+            StatusRequestContent, StatusRequestDecorators,
         },
+        v3::{self, PickupV3},
+        Pickup,
     },
     AriesMessage,
 };
 
+use tokio_tungstenite::tungstenite::{Error as WsError, Message as WsMessage};
+
 use crate::common::{
     agent_and_transport_utils::{
-        gen_and_register_recipient_key, gen_mediator_connected_agent, get_mediator_grant_data,
+        forward_basic_anoncrypt_message, gen_and_register_recipient_key,
+        gen_mediator_connected_agent, get_mediator_grant_data,
         send_message_and_pop_response_message,
     },
     prelude::*,
@@ -28,43 +42,6 @@ use crate::common::{
 
 static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
 
-async fn forward_basic_anoncrypt_message(
-    agent_diddoc: &AriesDidDoc,
-    message_text: &str,
-) -> Result<()> {
-    // Prepare forwarding agent
-    let agent_f = mediator::aries_agent::AgentBuilder::<AskarWallet>::new_demo_agent().await?;
-    // Prepare forwarding agent transport
-    let mut agent_f_aries_transport = reqwest::Client::new();
-    // Prepare message and wrap into anoncrypt forward message
-    let message: BasicMessage = BasicMessage::builder()
-        .content(
-            BasicMessageContent::builder()
-                .content(message_text.to_string())
-                .sent_time(chrono::DateTime::default())
-                .build(),
-        )
-        .decorators(BasicMessageDecorators::default())
-        .id("JustHello".to_string())
-        .build();
-
-    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create_from_legacy(
-        agent_f.get_wallet_ref().as_ref(),
-        &serde_json::to_vec(&message)?,
-        None,
-        agent_diddoc,
-    )
-    .await?;
-    // Send forward message to provided endpoint
-    let packed_json = serde_json::from_slice(&packed_message)?;
-    info!("Sending anoncrypt packed message{}", packed_json);
-    let response_envelope = agent_f_aries_transport
-        .send_aries_envelope(packed_json, agent_diddoc)
-        .await?;
-    info!("Response of forward{:?}", response_envelope);
-    Ok(())
-}
-
 #[tokio::test]
 async fn test_pickup_flow() -> Result<()> {
     LOGGING_INIT.call_once(setup_env_logging);
@@ -95,13 +72,13 @@ async fn test_pickup_flow() -> Result<()> {
     forward_basic_anoncrypt_message(&agent_diddoc, "Hi again, from AgentF").await?;
     // Pickup flow
     // // Status
-    let pickup_status_req = Pickup::StatusRequest(
+    let pickup_status_req = Pickup::V2(PickupV2::StatusRequest(
         StatusRequest::builder()
             .content(StatusRequestContent::builder().build())
             .decorators(StatusRequestDecorators::default())
             .id("request-status".to_owned())
             .build(),
-    );
+    ));
     let aries_message = AriesMessage::Pickup(pickup_status_req);
     let message_bytes = serde_json::to_vec(&aries_message)?;
     // send message and get response
@@ -114,7 +91,9 @@ async fn test_pickup_flow() -> Result<()> {
     )
     .await?;
     // Verify expected
-    if let AriesMessage::Pickup(Pickup::Status(status)) = serde_json::from_str(&response_message)? {
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    {
         info!("Received status as expected {:?}", status);
         assert_eq!(status.content.message_count, 2)
     } else {
@@ -124,13 +103,13 @@ async fn test_pickup_flow() -> Result<()> {
         )
     }
     // // Delivery
-    let pickup_delivery_req = Pickup::DeliveryRequest(
+    let pickup_delivery_req = Pickup::V2(PickupV2::DeliveryRequest(
         DeliveryRequest::builder()
             .content(DeliveryRequestContent::builder().limit(10).build())
             .decorators(DeliveryRequestDecorators::builder().build())
             .id("request-delivery".to_owned())
             .build(),
-    );
+    ));
     let aries_message = AriesMessage::Pickup(pickup_delivery_req);
     let message_bytes = serde_json::to_vec(&aries_message)?;
     // send message and get response
@@ -143,7 +122,7 @@ async fn test_pickup_flow() -> Result<()> {
     )
     .await?;
     // Verify expected
-    let delivery = if let AriesMessage::Pickup(Pickup::Delivery(delivery)) =
+    let delivery = if let AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) =
         serde_json::from_str(&response_message)?
     {
         info!("Received delivery as expected {:?}", delivery);
@@ -170,3 +149,479 @@ async fn test_pickup_flow() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_pickup_messages_received() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // prepare receiver connection parameters
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        gen_mediator_connected_agent().await?;
+    // setup receiver routing
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    // register recipient key with mediator
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, from AgentF").await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi again, from AgentF").await?;
+
+    let delivery_request = AriesMessage::Pickup(Pickup::V2(PickupV2::DeliveryRequest(
+        DeliveryRequest::builder()
+            .content(DeliveryRequestContent::builder().limit(10).build())
+            .decorators(DeliveryRequestDecorators::builder().build())
+            .id("request-delivery".to_owned())
+            .build(),
+    )));
+    let delivered_ids = |response_message: &str| -> Vec<String> {
+        match serde_json::from_str(response_message).unwrap() {
+            AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) => delivery
+                .content
+                .attach
+                .into_iter()
+                .map(|attach| attach.id.unwrap())
+                .collect(),
+            other => panic!("Expected delivery, received {:?}", other),
+        }
+    };
+    // // Unacknowledged messages are delivered again
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let first_delivery = delivered_ids(&response_message);
+    assert_eq!(first_delivery.len(), 2);
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    assert_eq!(delivered_ids(&response_message), first_delivery);
+    // // Acknowledged messages are removed from the queue
+    let messages_received = AriesMessage::Pickup(Pickup::V2(PickupV2::MessagesReceived(
+        MessagesReceived::builder()
+            .content(
+                MessagesReceivedContent::builder()
+                    .message_id_list(vec![first_delivery[0].clone()])
+                    .build(),
+            )
+            .decorators(MessagesReceivedDecorators::default())
+            .id("messages-received".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&messages_received)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    {
+        assert_eq!(status.content.message_count, 1)
+    } else {
+        panic!(
+            "Expected status with message count = 1, received {:?}",
+            response_message
+        )
+    }
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    assert_eq!(delivered_ids(&response_message), first_delivery[1..]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pickup_flow_v3() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, from AgentF").await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi again, from AgentF").await?;
+
+    // Replies are in the version of the requests
+    let status_request = AriesMessage::Pickup(Pickup::V3(PickupV3::StatusRequest(
+        v3::StatusRequest::builder()
+            .content(v3::StatusRequestContent::builder().build())
+            .decorators(v3::StatusRequestDecorators::default())
+            .id("request-status".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&status_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V3(PickupV3::Status(status))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected status 3.0, received {:?}", response_message)
+    };
+    assert_eq!(status.content.message_count, 2);
+
+    let delivery_request = AriesMessage::Pickup(Pickup::V3(PickupV3::DeliveryRequest(
+        v3::DeliveryRequest::builder()
+            .content(v3::DeliveryRequestContent::builder().limit(10).build())
+            .decorators(v3::DeliveryRequestDecorators::builder().build())
+            .id("request-delivery".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V3(PickupV3::Delivery(delivery))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected delivery 3.0, received {:?}", response_message)
+    };
+    let delivered_ids: Vec<String> = delivery
+        .content
+        .attach
+        .into_iter()
+        .map(|attach| attach.id.unwrap())
+        .collect();
+    assert_eq!(delivered_ids.len(), 2);
+
+    let messages_received = AriesMessage::Pickup(Pickup::V3(PickupV3::MessagesReceived(
+        v3::MessagesReceived::builder()
+            .content(
+                v3::MessagesReceivedContent::builder()
+                    .message_id_list(delivered_ids)
+                    .build(),
+            )
+            .decorators(v3::MessagesReceivedDecorators::default())
+            .id("messages-received".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&messages_received)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V3(PickupV3::Status(status))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected status 3.0, received {:?}", response_message)
+    };
+    assert_eq!(status.content.message_count, 0);
+    Ok(())
+}
+
+/// Reads next frame from the websocket and returns the unpacked message
+async fn next_ws_message(
+    socket: &mut (impl StreamExt<Item = Result<WsMessage, WsError>> + Unpin),
+    agent: &Agent<impl BaseWallet, impl MediatorPersistence>,
+) -> Result<String> {
+    let Some(WsMessage::Text(frame)) = socket.next().await.transpose()? else {
+        panic!("Expected text frame");
+    };
+    let unpacked = agent.unpack_didcomm(frame.as_bytes()).await.unwrap();
+    Ok(unpacked.message)
+}
+
+fn live_delivery_change() -> AriesMessage {
+    AriesMessage::Pickup(Pickup::V2(PickupV2::LiveDeliveryChange(
+        LiveDeliveryChange::builder()
+            .content(
+                LiveDeliveryChangeContent::builder()
+                    .live_delivery(true)
+                    .build(),
+            )
+            .decorators(
+                LiveDeliveryChangeDecorators::builder()
+                    .transport(Transport::builder().return_route(ReturnRoute::All).build())
+                    .build(),
+            )
+            .id("live-delivery-change".to_owned())
+            .build(),
+    )))
+}
+
+/// Opens a websocket to the mediator and enables live delivery over it
+async fn open_live_session(
+    agent: &Agent<impl BaseWallet, impl MediatorPersistence>,
+    agent_verkey: &VerKey,
+    mediator_diddoc: &AriesDidDoc,
+) -> Result<impl Sink<WsMessage, Error = WsError> + Stream<Item = Result<WsMessage, WsError>> + Unpin>
+{
+    let mut ws_url = mediator_diddoc.get_endpoint().unwrap().join("/ws")?;
+    ws_url.set_scheme("ws").unwrap();
+    let (mut socket, _) = tokio_tungstenite::connect_async(ws_url.as_str()).await?;
+    let EncryptionEnvelope(packed_message) = agent
+        .pack_didcomm(
+            &serde_json::to_vec(&live_delivery_change())?,
+            agent_verkey,
+            mediator_diddoc,
+        )
+        .await
+        .map_err(|e| GenericStringError { msg: e })?;
+    socket
+        .send(WsMessage::text(String::from_utf8(packed_message)?))
+        .await?;
+    let response_message = next_ws_message(&mut socket, agent).await?;
+    let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(_))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected status, received {:?}", response_message)
+    };
+    Ok(socket)
+}
+
+#[tokio::test]
+async fn test_pickup_live_delivery() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // prepare receiver connection parameters
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        gen_mediator_connected_agent().await?;
+    // setup receiver routing
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    // register recipient key with mediator
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let message_bytes = serde_json::to_vec(&live_delivery_change())?;
+    // // Live mode is not supported over http
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::ReportProblem(problem_report) = serde_json::from_str(&response_message)? {
+        assert_eq!(
+            problem_report.content.description.code,
+            "e.msg.live-mode-not-supported"
+        );
+    } else {
+        panic!("Expected problem report, received {:?}", response_message)
+    }
+    // // Enable live mode over websocket
+    let mut socket = open_live_session(&agent, &agent_verkey, &mediator_diddoc).await?;
+    // // Forwarded messages are pushed right away
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, live from AgentF").await?;
+    let pushed_message = next_ws_message(&mut socket, &agent).await?;
+    let AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) =
+        serde_json::from_str(&pushed_message)?
+    else {
+        panic!("Expected delivery, received {:?}", pushed_message)
+    };
+    assert_eq!(delivery.content.attach.len(), 1);
+    let AttachmentType::Base64(base64message) = &delivery.content.attach[0].data.content else {
+        panic!("Expected base64 attachment")
+    };
+    let unpacked = agent
+        .unpack_didcomm(&URL_SAFE_NO_PAD.decode(base64message)?)
+        .await
+        .unwrap();
+    let AriesMessage::BasicMessage(basic_message) = serde_json::from_str(&unpacked.message)? else {
+        panic!("Expected basic message, received {:?}", unpacked.message)
+    };
+    assert_eq!(basic_message.content.content, "Hi, live from AgentF");
+
+    // // Pushed messages stay queued until acknowledged
+    let status_request = AriesMessage::Pickup(Pickup::V2(PickupV2::StatusRequest(
+        StatusRequest::builder()
+            .content(StatusRequestContent::builder().build())
+            .decorators(StatusRequestDecorators::default())
+            .id("request-status".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&status_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    {
+        assert_eq!(status.content.message_count, 1)
+    } else {
+        panic!("Expected status, received {:?}", response_message)
+    }
+    let messages_received = AriesMessage::Pickup(Pickup::V2(PickupV2::MessagesReceived(
+        MessagesReceived::builder()
+            .content(
+                MessagesReceivedContent::builder()
+                    .message_id_list(vec![delivery.content.attach[0].id.clone().unwrap()])
+                    .build(),
+            )
+            .decorators(MessagesReceivedDecorators::default())
+            .id("messages-received".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&messages_received)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    {
+        assert_eq!(status.content.message_count, 0)
+    } else {
+        panic!("Expected status, received {:?}", response_message)
+    }
+    // // Messages are only queued once the connection is closed
+    socket.close().await?;
+    drop(socket);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi again, from AgentF").await?;
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&status_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    if let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    {
+        assert_eq!(status.content.message_count, 1)
+    } else {
+        panic!("Expected status, received {:?}", response_message)
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pickup_live_delivery_connection_closed_after_push() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let mut socket = open_live_session(&agent, &agent_verkey, &mediator_diddoc).await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, live from AgentF").await?;
+    // The recipient goes away without processing the pushed delivery
+    socket.close().await?;
+    drop(socket);
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let delivery_request = AriesMessage::Pickup(Pickup::V2(PickupV2::DeliveryRequest(
+        DeliveryRequest::builder()
+            .content(DeliveryRequestContent::builder().limit(10).build())
+            .decorators(DeliveryRequestDecorators::builder().build())
+            .id("request-delivery".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected delivery, received {:?}", response_message)
+    };
+    assert_eq!(delivery.content.attach.len(), 1);
+    let AttachmentType::Base64(base64message) = &delivery.content.attach[0].data.content else {
+        panic!("Expected base64 attachment")
+    };
+    let unpacked = agent
+        .unpack_didcomm(&URL_SAFE_NO_PAD.decode(base64message)?)
+        .await
+        .unwrap();
+    let AriesMessage::BasicMessage(basic_message) = serde_json::from_str(&unpacked.message)? else {
+        panic!("Expected basic message, received {:?}", unpacked.message)
+    };
+    assert_eq!(basic_message.content.content, "Hi, live from AgentF");
+    Ok(())
+}
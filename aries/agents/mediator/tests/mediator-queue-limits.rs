@@ -0,0 +1,124 @@
+mod common;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use mediator::{mediation::queue_limits::QueueLimits, utils::GenericStringError};
+use messages::{
+    decorators::attachment::AttachmentType,
+    msg_fields::protocols::pickup::{
+        v2::{
+            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, PickupV2,
+            StatusRequest, StatusRequestContent, StatusRequestDecorators,
+        },
+        Pickup,
+    },
+    AriesMessage,
+};
+
+use crate::common::{
+    agent_and_transport_utils::{
+        connect_agent_to_mediator, forward_basic_anoncrypt_message, gen_and_register_recipient_key,
+        get_mediator_grant_data, send_message_and_pop_response_message,
+        start_mediator_with_queue_limits,
+    },
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+#[tokio::test]
+async fn test_queue_overflow() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let mediator_endpoint_root = start_mediator_with_queue_limits(QueueLimits {
+        max_messages: Some(2),
+        ..Default::default()
+    })
+    .await?;
+    let (mut agent, mut agent_aries_transport, agent_verkey, mediator_diddoc) =
+        connect_agent_to_mediator(&mediator_endpoint_root).await?;
+    let grant_data = get_mediator_grant_data(
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await;
+    agent
+        .init_service(grant_data.routing_keys, grant_data.endpoint.parse()?)
+        .await?;
+    let (_agent_recipient_key, agent_diddoc) = gen_and_register_recipient_key(
+        &mut agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi, from AgentF").await?;
+    forward_basic_anoncrypt_message(&agent_diddoc, "Hi again, from AgentF").await?;
+    // The queue is full, further messages are rejected
+    assert!(forward_basic_anoncrypt_message(&agent_diddoc, "Dropped")
+        .await
+        .is_err());
+    assert!(
+        forward_basic_anoncrypt_message(&agent_diddoc, "Dropped too")
+            .await
+            .is_err()
+    );
+
+    // Only one problem report is queued, past the quota
+    let status_request = AriesMessage::Pickup(Pickup::V2(PickupV2::StatusRequest(
+        StatusRequest::builder()
+            .content(StatusRequestContent::builder().build())
+            .decorators(StatusRequestDecorators::default())
+            .id("request-status".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&status_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected status, received {:?}", response_message)
+    };
+    assert_eq!(status.content.message_count, 3);
+
+    let delivery_request = AriesMessage::Pickup(Pickup::V2(PickupV2::DeliveryRequest(
+        DeliveryRequest::builder()
+            .content(DeliveryRequestContent::builder().limit(10).build())
+            .decorators(DeliveryRequestDecorators::builder().build())
+            .id("request-delivery".to_owned())
+            .build(),
+    )));
+    let response_message = send_message_and_pop_response_message(
+        &serde_json::to_vec(&delivery_request)?,
+        &agent,
+        &mut agent_aries_transport,
+        &agent_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected delivery, received {:?}", response_message)
+    };
+    let AttachmentType::Base64(base64message) = &delivery.content.attach[2].data.content else {
+        panic!("Expected base64 attachment")
+    };
+    let unpacked = agent
+        .unpack_didcomm(&URL_SAFE_NO_PAD.decode(base64message)?)
+        .await
+        .map_err(|e| GenericStringError { msg: e })?;
+    let AriesMessage::ReportProblem(problem_report) = serde_json::from_str(&unpacked.message)?
+    else {
+        panic!("Expected problem report, received {:?}", unpacked.message)
+    };
+    assert_eq!(problem_report.content.description.code, "e.msg.queue-full");
+    Ok(())
+}
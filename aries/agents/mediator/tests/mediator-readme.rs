@@ -6,18 +6,16 @@ use mediator::http_routes::ReadmeInfo;
 use reqwest::header::ACCEPT;
 use url::Url;
 
-use crate::common::test_setup::setup_env_logging;
+use crate::common::{agent_and_transport_utils::start_mediator, test_setup::setup_env_logging};
 
 static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
 
-const ENDPOINT_ROOT: &str = "http://localhost:8005";
-
 #[tokio::test]
 async fn base_path_returns_readme() -> Result<()> {
     LOGGING_INIT.call_once(setup_env_logging);
 
     let client = reqwest::Client::new();
-    let endpoint: Url = ENDPOINT_ROOT.parse().unwrap();
+    let endpoint: Url = start_mediator().await?;
 
     let res = client
         .get(endpoint)
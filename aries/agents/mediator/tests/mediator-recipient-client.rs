@@ -0,0 +1,95 @@
+mod common;
+
+use aries_vcx::{
+    handlers::mediation::recipient::MediationRecipient, protocols::connection::GenericConnection,
+    transport::Transport, utils::encryption_envelope::EncryptionEnvelope,
+};
+use mediator::mediation::upstream::HttpTransport;
+use messages::{
+    msg_fields::protocols::basic_message::{
+        BasicMessage, BasicMessageContent, BasicMessageDecorators,
+    },
+    AriesMessage,
+};
+use public_key::{Key, KeyType};
+
+use crate::common::{
+    agent_and_transport_utils::{didcomm_connection, gen_demo_agent, start_mediator},
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+#[tokio::test]
+async fn test_recipient_client_flow() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let mediator_endpoint_root = start_mediator().await?;
+    let agent = gen_demo_agent().await?;
+    let wallet = agent.get_wallet_ref();
+    let transport = HttpTransport::default();
+    let connection =
+        didcomm_connection(&mediator_endpoint_root, &agent, &mut reqwest::Client::new()).await?;
+    let mut recipient = MediationRecipient::new(GenericConnection::from(connection));
+
+    let grant = recipient
+        .request_mediation(wallet.as_ref(), &transport)
+        .await?;
+    assert_eq!(grant.endpoint, mediator_endpoint_root.join("didcomm")?);
+    assert!(!grant.routing_keys.is_empty());
+
+    // The service of the invitation routes through the mediator
+    let service = recipient
+        .create_invitation_service(wallet.as_ref(), &transport)
+        .await?;
+    assert_eq!(recipient.recipient_keys(), service.recipient_keys);
+    let message: BasicMessage = BasicMessage::builder()
+        .content(
+            BasicMessageContent::builder()
+                .content("Hi, through the mediator".to_string())
+                .sent_time(chrono::DateTime::default())
+                .build(),
+        )
+        .decorators(BasicMessageDecorators::default())
+        .id("JustHello".to_string())
+        .build();
+    let sender = gen_demo_agent().await?;
+    let EncryptionEnvelope(packed_message) = EncryptionEnvelope::create_from_keys(
+        sender.get_wallet_ref().as_ref(),
+        &serde_json::to_vec(&message)?,
+        None,
+        Key::from_base58(&service.recipient_keys[0], KeyType::Ed25519)?,
+        service
+            .routing_keys
+            .iter()
+            .map(|routing_key| Key::from_base58(routing_key, KeyType::Ed25519))
+            .collect::<std::result::Result<_, _>>()?,
+    )
+    .await?;
+    transport
+        .send_message(packed_message, &service.service_endpoint)
+        .await?;
+
+    // Picked up messages are handed to the callback and removed from the queue
+    assert_eq!(recipient.status(wallet.as_ref(), &transport).await?, 1);
+    let mut received = Vec::new();
+    let delivered = recipient
+        .pickup_all(wallet.as_ref(), &transport, 10, |message| {
+            received.push(message);
+            Ok(())
+        })
+        .await?;
+    assert_eq!(delivered, 1);
+    let AriesMessage::BasicMessage(basic_message) = &received[0].message else {
+        panic!("Expected basic message, got {:?}", received[0].message)
+    };
+    assert_eq!(basic_message.content.content, "Hi, through the mediator");
+    assert_eq!(recipient.status(wallet.as_ref(), &transport).await?, 0);
+
+    // Peer DIDs are registered too
+    let (_peer_did, key) = recipient
+        .create_peer_did_4(wallet.as_ref(), &transport)
+        .await?;
+    assert!(recipient.recipient_keys().contains(&key.base58()));
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::{BaseAnonCreds, CredentialId, LinkSecretId};
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+    record_tags::RecordTag,
+};
+use public_key::Key;
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    global::settings::DEFAULT_LINK_SECRET_ALIAS,
+};
+
+/// Wallet record tag marking the link secret used for new credential requests.
+pub const DEFAULT_LINK_SECRET_TAG: &str = "default";
+/// Wallet record tag on stored credentials, holding the id of the link secret the credential is
+/// bound to.
+pub const CREDENTIAL_LINK_SECRET_TAG: &str = "link_secret_id";
+
+/// Link secret as carried inside an encrypted link secret export.
+#[derive(Serialize, Deserialize)]
+struct LinkSecretExport {
+    id: LinkSecretId,
+    value: String,
+}
+
+/// Lists the ids of all link secrets stored in the wallet.
+pub async fn list_link_secrets(wallet: &impl BaseWallet) -> VcxResult<Vec<LinkSecretId>> {
+    Ok(wallet
+        .search_record(RecordCategory::LinkSecret, None)
+        .await?
+        .iter()
+        .map(|record| record.name().to_owned())
+        .collect())
+}
+
+/// Returns the id of the link secret used for new credential requests. Falls back to
+/// [DEFAULT_LINK_SECRET_ALIAS] if no link secret was explicitly set as default.
+pub async fn get_default_link_secret_id(wallet: &impl BaseWallet) -> VcxResult<LinkSecretId> {
+    let query = json!({ DEFAULT_LINK_SECRET_TAG: "true" }).to_string();
+    let default_id = wallet
+        .search_record(RecordCategory::LinkSecret, Some(query))
+        .await?
+        .first()
+        .map(|record| record.name().to_owned());
+    Ok(default_id.unwrap_or_else(|| DEFAULT_LINK_SECRET_ALIAS.to_owned()))
+}
+
+/// Sets the link secret stored under `link_secret_id` as the one used for new credential
+/// requests.
+pub async fn set_default_link_secret(
+    wallet: &impl BaseWallet,
+    link_secret_id: &LinkSecretId,
+) -> VcxResult<()> {
+    trace!(
+        "set_default_link_secret >>> link_secret_id: {}",
+        link_secret_id
+    );
+    // fails early if the link secret does not exist
+    wallet
+        .get_record(RecordCategory::LinkSecret, link_secret_id)
+        .await?;
+    // only the default tag is changed, other tags of the link secrets are kept
+    for record in wallet
+        .search_record(RecordCategory::LinkSecret, None)
+        .await?
+    {
+        let mut tags = record.tags().clone();
+        tags.remove(RecordTag::new(DEFAULT_LINK_SECRET_TAG, "true"));
+        if record.name() == link_secret_id {
+            tags.add(RecordTag::new(DEFAULT_LINK_SECRET_TAG, "true"));
+        }
+        if &tags != record.tags() {
+            wallet
+                .update_record_tags(RecordCategory::LinkSecret, record.name(), tags)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Creates a new link secret under `new_link_secret_id` and sets it as default, so it is used
+/// for all future credential requests. Previously issued credentials stay bound to the link
+/// secret they were issued against, which is kept in the wallet.
+pub async fn rotate_link_secret(
+    wallet: &impl BaseWallet,
+    anoncreds: &impl BaseAnonCreds,
+    new_link_secret_id: &LinkSecretId,
+) -> VcxResult<()> {
+    trace!(
+        "rotate_link_secret >>> new_link_secret_id: {}",
+        new_link_secret_id
+    );
+    anoncreds
+        .prover_create_link_secret(wallet, new_link_secret_id)
+        .await?;
+    set_default_link_secret(wallet, new_link_secret_id).await
+}
+
+/// Exports the link secret stored under `link_secret_id`, encrypted for `recipient_key`.
+/// Typically the recipient key is the verkey of a DID created from the wallet seed, so that the
+/// link secret can be imported on any device the same seed is used on.
+pub async fn export_link_secret(
+    wallet: &impl BaseWallet,
+    link_secret_id: &LinkSecretId,
+    recipient_key: Key,
+) -> VcxResult<Vec<u8>> {
+    trace!("export_link_secret >>> link_secret_id: {}", link_secret_id);
+    let record = wallet
+        .get_record(RecordCategory::LinkSecret, link_secret_id)
+        .await?;
+    let export = LinkSecretExport {
+        id: link_secret_id.to_owned(),
+        value: record.value().to_owned(),
+    };
+    let data = serde_json::to_vec(&export)?;
+    Ok(wallet
+        .pack_message(None, vec![recipient_key], &data)
+        .await?)
+}
+
+/// Imports a link secret exported by [export_link_secret]. The wallet has to hold the private
+/// key of the recipient key the export was encrypted for. Returns the id the link secret was
+/// stored under.
+pub async fn import_link_secret(
+    wallet: &impl BaseWallet,
+    encrypted_link_secret: &[u8],
+) -> VcxResult<LinkSecretId> {
+    let unpacked = wallet.unpack_message(encrypted_link_secret).await?;
+    let export: LinkSecretExport = serde_json::from_str(&unpacked.message)?;
+    trace!("import_link_secret >>> link_secret_id: {}", export.id);
+
+    if wallet
+        .get_record(RecordCategory::LinkSecret, &export.id)
+        .await
+        .is_ok()
+    {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::DuplicationMasterSecret,
+            format!("Link secret id: {} already exists in wallet", export.id),
+        ));
+    }
+    let record = Record::builder()
+        .name(export.id.clone())
+        .category(RecordCategory::LinkSecret)
+        .value(export.value)
+        .build();
+    wallet.add_record(record).await?;
+    Ok(export.id)
+}
+
+/// Returns the id of the link secret the given credentials are bound to, which is the link
+/// secret a presentation using them has to be created with. Credentials stored without the
+/// link secret being recorded are assumed to be bound to the default link secret.
+pub async fn get_link_secret_id_for_credentials(
+    wallet: &impl BaseWallet,
+    cred_ids: &[&CredentialId],
+) -> VcxResult<LinkSecretId> {
+    let mut link_secret_id: Option<LinkSecretId> = None;
+    for cred_id in cred_ids {
+        let record = wallet.get_record(RecordCategory::Cred, cred_id).await?;
+        let cred_link_secret_id = match record
+            .tags()
+            .clone()
+            .into_iter()
+            .find(|tag| tag.key() == CREDENTIAL_LINK_SECRET_TAG)
+        {
+            Some(tag) => tag.value().to_owned(),
+            None => DEFAULT_LINK_SECRET_ALIAS.to_owned(),
+        };
+        match &link_secret_id {
+            Some(id) if *id != cred_link_secret_id => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    "Selected credentials are bound to different link secrets and can not be \
+                     presented together",
+                ))
+            }
+            Some(_) => {}
+            None => link_secret_id = Some(cred_link_secret_id),
+        }
+    }
+    match link_secret_id {
+        Some(id) => Ok(id),
+        None => get_default_link_secret_id(wallet).await,
+    }
+}
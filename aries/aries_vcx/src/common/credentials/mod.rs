@@ -6,6 +6,8 @@ use time::OffsetDateTime;
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
 
 pub mod encoding;
+pub mod link_secret;
+pub mod revocation_status;
 
 pub async fn get_cred_rev_id(
     wallet: &impl BaseWallet,
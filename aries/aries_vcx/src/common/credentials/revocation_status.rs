@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use anoncreds_types::data_types::{
+    identifiers::rev_reg_def_id::RevocationRegistryDefinitionId,
+    ledger::rev_status_list::RevocationStatusList,
+    messages::cred_selection::RetrievedCredentialInfo,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::{BaseAnonCreds, CredentialId};
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record_category::RecordCategory, BaseWallet},
+    record_tags::RecordTag,
+};
+use time::OffsetDateTime;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Wallet record tag holding the last known revocation status of a credential.
+pub const REVOCATION_STATUS_TAG: &str = "revocation_status";
+/// Wallet record tag holding the ledger timestamp of the status list the last known revocation
+/// status was read from.
+pub const REVOCATION_STATUS_TIMESTAMP_TAG: &str = "revocation_status_timestamp";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RevocationStatus {
+    Active,
+    Revoked,
+}
+
+impl RevocationStatus {
+    fn as_tag_value(&self) -> &'static str {
+        match self {
+            RevocationStatus::Active => "active",
+            RevocationStatus::Revoked => "revoked",
+        }
+    }
+}
+
+/// Revocation status of a credential stored in the holder's wallet, as found in the revocation
+/// status list published at `timestamp`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialRevocationStatus {
+    pub cred_id: CredentialId,
+    pub rev_reg_id: String,
+    pub cred_rev_id: u32,
+    pub status: RevocationStatus,
+    pub timestamp: u64,
+}
+
+impl CredentialRevocationStatus {
+    pub fn is_revoked(&self) -> bool {
+        self.status == RevocationStatus::Revoked
+    }
+}
+
+/// Checks the current revocation status of the credential stored in the wallet under `cred_id`.
+/// The revocation registry and revocation index are read from the stored credential, so this
+/// works without the issuance state machine the credential was received through.
+///
+/// The credential's wallet record is tagged with the resulting status, see
+/// [REVOCATION_STATUS_TAG] and [REVOCATION_STATUS_TIMESTAMP_TAG].
+pub async fn check_credential_revocation_status(
+    wallet: &impl BaseWallet,
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+    cred_id: &CredentialId,
+) -> VcxResult<CredentialRevocationStatus> {
+    trace!(
+        "check_credential_revocation_status >>> cred_id: {:?}",
+        cred_id
+    );
+    let cred_info = anoncreds.prover_get_credential(wallet, cred_id).await?;
+    let (rev_reg_id, cred_rev_id) = revocation_details(&cred_info).ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("Unable to check revocation status - credential {cred_id} is not revokable"),
+        )
+    })?;
+    let (status_list, timestamp) = fetch_current_status_list(ledger, &rev_reg_id).await?;
+    record_revocation_status(
+        wallet,
+        cred_id.to_owned(),
+        rev_reg_id,
+        cred_rev_id,
+        &status_list,
+        timestamp,
+    )
+    .await
+}
+
+/// Credential whose revocation status could not be refreshed by
+/// [refresh_credentials_revocation_status].
+#[derive(Debug)]
+pub struct RevocationStatusFailure {
+    pub cred_id: CredentialId,
+    pub error: AriesVcxError,
+}
+
+/// Outcome of [refresh_credentials_revocation_status].
+#[derive(Debug, Default)]
+pub struct RevocationStatusRefresh {
+    pub statuses: Vec<CredentialRevocationStatus>,
+    pub failures: Vec<RevocationStatusFailure>,
+}
+
+/// Refreshes the revocation status of every revocable credential stored in the wallet.
+/// The revocation status list of each revocation registry is fetched from the ledger only once,
+/// regardless of how many credentials were issued from it. Credentials which are not revocable
+/// are skipped.
+///
+/// The wallet record of each checked credential is tagged with its resulting status, see
+/// [REVOCATION_STATUS_TAG] and [REVOCATION_STATUS_TIMESTAMP_TAG]. A credential whose status
+/// can not be checked, e.g. because the status list of its registry can not be fetched, keeps
+/// its last known status and is reported among the failures, without stopping the refresh of
+/// the other credentials.
+pub async fn refresh_credentials_revocation_status(
+    wallet: &impl BaseWallet,
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+) -> VcxResult<RevocationStatusRefresh> {
+    trace!("refresh_credentials_revocation_status >>>");
+    let credentials = anoncreds.prover_get_credentials(wallet, None).await?;
+
+    let mut status_lists: HashMap<String, VcxResult<(RevocationStatusList, u64)>> = HashMap::new();
+    let mut refresh = RevocationStatusRefresh::default();
+    for cred_info in credentials {
+        let Some((rev_reg_id, cred_rev_id)) = revocation_details(&cred_info) else {
+            continue;
+        };
+        if !status_lists.contains_key(&rev_reg_id) {
+            let status_list = fetch_current_status_list(ledger, &rev_reg_id).await;
+            status_lists.insert(rev_reg_id.clone(), status_list);
+        }
+        let cred_id = cred_info.referent;
+        let status = match &status_lists[&rev_reg_id] {
+            Ok((status_list, timestamp)) => {
+                record_revocation_status(
+                    wallet,
+                    cred_id.clone(),
+                    rev_reg_id,
+                    cred_rev_id,
+                    status_list,
+                    *timestamp,
+                )
+                .await
+            }
+            Err(err) => Err(AriesVcxError::from_msg(
+                err.kind(),
+                format!("Unable to fetch the revocation status list of {rev_reg_id}: {err}"),
+            )),
+        };
+        match status {
+            Ok(status) => refresh.statuses.push(status),
+            Err(error) => {
+                warn!("Unable to refresh revocation status of credential {cred_id}: {error}");
+                refresh
+                    .failures
+                    .push(RevocationStatusFailure { cred_id, error });
+            }
+        }
+    }
+    Ok(refresh)
+}
+
+/// Reads the status of the credential from `status_list`, tagging its wallet record with it.
+async fn record_revocation_status(
+    wallet: &impl BaseWallet,
+    cred_id: CredentialId,
+    rev_reg_id: String,
+    cred_rev_id: u32,
+    status_list: &RevocationStatusList,
+    timestamp: u64,
+) -> VcxResult<CredentialRevocationStatus> {
+    let status = CredentialRevocationStatus {
+        cred_id,
+        status: status_in_list(status_list, cred_rev_id)?,
+        rev_reg_id,
+        cred_rev_id,
+        timestamp,
+    };
+    store_revocation_status(wallet, &status).await?;
+    Ok(status)
+}
+
+fn revocation_details(cred_info: &RetrievedCredentialInfo) -> Option<(String, u32)> {
+    Some((cred_info.rev_reg_id.clone()?, cred_info.cred_rev_id?))
+}
+
+async fn fetch_current_status_list(
+    ledger: &impl AnoncredsLedgerRead,
+    rev_reg_id: &str,
+) -> VcxResult<(RevocationStatusList, u64)> {
+    let rev_reg_id = RevocationRegistryDefinitionId::new(rev_reg_id)?;
+    let now = OffsetDateTime::now_utc().unix_timestamp() as u64;
+    Ok(ledger.get_rev_status_list(&rev_reg_id, now, None).await?)
+}
+
+fn status_in_list(
+    status_list: &RevocationStatusList,
+    cred_rev_id: u32,
+) -> VcxResult<RevocationStatus> {
+    match status_list.state().get(cred_rev_id as usize) {
+        Some(revoked) if *revoked => Ok(RevocationStatus::Revoked),
+        Some(_) => Ok(RevocationStatus::Active),
+        None => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidRevocationDetails,
+            format!(
+                "Credential revocation id {cred_rev_id} is out of range of the revocation status \
+                 list"
+            ),
+        )),
+    }
+}
+
+async fn store_revocation_status(
+    wallet: &impl BaseWallet,
+    status: &CredentialRevocationStatus,
+) -> VcxResult<()> {
+    let record = wallet
+        .get_record(RecordCategory::Cred, &status.cred_id)
+        .await?;
+    let mut tags = record.tags().clone();
+    tags.remove(RecordTag::new(REVOCATION_STATUS_TAG, ""));
+    tags.remove(RecordTag::new(REVOCATION_STATUS_TIMESTAMP_TAG, ""));
+    tags.add(RecordTag::new(
+        REVOCATION_STATUS_TAG,
+        status.status.as_tag_value(),
+    ));
+    tags.add(RecordTag::new(
+        REVOCATION_STATUS_TIMESTAMP_TAG,
+        &status.timestamp.to_string(),
+    ));
+    wallet
+        .update_record_tags(RecordCategory::Cred, &status.cred_id, tags)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use anoncreds_types::data_types::identifiers::issuer_id::IssuerId;
+
+    use super::*;
+
+    fn status_list(revoked: &[bool]) -> RevocationStatusList {
+        RevocationStatusList::new(
+            None,
+            IssuerId::new("V4SGRU86Z58d6TV7PBUe6f").unwrap(),
+            revoked.iter().copied().collect(),
+            None,
+            Some(1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_status_in_list() {
+        let list = status_list(&[false, true, false]);
+        assert_eq!(status_in_list(&list, 0).unwrap(), RevocationStatus::Active);
+        assert_eq!(status_in_list(&list, 1).unwrap(), RevocationStatus::Revoked);
+        assert_eq!(status_in_list(&list, 2).unwrap(), RevocationStatus::Active);
+    }
+
+    #[test]
+    fn test_status_in_list_out_of_range() {
+        let list = status_list(&[false, true]);
+        assert_eq!(
+            status_in_list(&list, 2).unwrap_err().kind(),
+            AriesVcxErrorKind::InvalidRevocationDetails
+        );
+    }
+}
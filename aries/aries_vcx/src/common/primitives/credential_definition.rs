@@ -13,14 +13,27 @@ use aries_vcx_ledger::{
     errors::error::VcxLedgerError,
     ledger::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite},
 };
-use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::{
+        base_wallet::{record_category::RecordCategory, BaseWallet},
+        record_tags::RecordTag,
+    },
+};
 use did_parser_nom::Did;
 
+use super::revocation_registry::RevocationRegistry;
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
     utils::serialization::ObjectWithVersion,
 };
 
+/// Wallet record tag marking a credential definition as retired. Retired credential definitions
+/// are no longer offered, but credentials issued under them can still be verified and revoked.
+pub const CRED_DEF_RETIRED_TAG: &str = "retired";
+/// Wallet record tag pointing from a retired credential definition to the one superseding it.
+pub const CRED_DEF_SUPERSEDED_BY_TAG: &str = "superseded_by";
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Default)]
 #[serde(try_from = "u8")]
 #[repr(u8)]
@@ -231,6 +244,216 @@ impl CredentialDef {
     pub fn get_state(&self) -> u32 {
         self.state as u32
     }
+
+    pub fn get_tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub async fn is_retired(&self, wallet: &impl BaseWallet) -> VcxResult<bool> {
+        is_cred_def_retired(wallet, &self.id).await
+    }
+
+    /// Rotates the issuer keys by creating a new credential definition for the same schema under
+    /// `new_tag`, and retiring this one in favour of it.
+    ///
+    /// Revocation registries are bound to the keys of their credential definition, so that of a
+    /// revocable credential definition cannot be carried over. Its current registry, `rev_reg`,
+    /// is instead replaced by a new one of the same capacity and tails directory, created for the
+    /// rotated credential definition. The returned credential definition and registry still need
+    /// to be published.
+    ///
+    /// This credential definition is only retired once everything else succeeded. If any step
+    /// fails, the records created for the rotated credential definition and registry are deleted
+    /// again, leaving the wallet unchanged.
+    pub async fn rotate(
+        &self,
+        wallet: &impl BaseWallet,
+        ledger_read: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        new_tag: String,
+        rev_reg: Option<&RevocationRegistry>,
+    ) -> VcxResult<(Self, Option<RevocationRegistry>)> {
+        trace!(
+            "CredentialDef::rotate >>> cred_def_id: {}, new_tag: {}",
+            self.id,
+            new_tag
+        );
+        if new_tag == self.tag {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Cannot rotate credential definition {} to the tag it already uses: {}",
+                    self.id, new_tag
+                ),
+            ));
+        }
+        match rev_reg {
+            None if self.support_revocation => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!(
+                        "Cannot rotate revocable credential definition {} without its \
+                         revocation registry",
+                        self.id
+                    ),
+                ))
+            }
+            Some(_) if !self.support_revocation => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!(
+                        "Credential definition {} does not support revocation",
+                        self.id
+                    ),
+                ))
+            }
+            Some(rev_reg) if rev_reg.get_cred_def_id() != self.id.to_string() => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!(
+                        "Revocation registry {} belongs to credential definition {}, not {}",
+                        rev_reg.get_rev_reg_id(),
+                        rev_reg.get_cred_def_id(),
+                        self.id
+                    ),
+                ))
+            }
+            _ => (),
+        }
+        let rotated = Self::create(
+            wallet,
+            ledger_read,
+            anoncreds,
+            self.source_id.clone(),
+            self.issuer_did.clone(),
+            self.schema_id.clone(),
+            new_tag,
+            self.support_revocation,
+        )
+        .await?;
+        let rotated_rev_reg = match rev_reg {
+            Some(rev_reg) => match RevocationRegistry::create(
+                wallet,
+                anoncreds,
+                &self.issuer_did,
+                &rotated.id,
+                &rev_reg.tails_dir,
+                rev_reg.max_creds,
+                1,
+            )
+            .await
+            {
+                Ok(rotated_rev_reg) => Some(rotated_rev_reg),
+                Err(err) => {
+                    discard_rotation(wallet, &rotated.id, None).await;
+                    return Err(err);
+                }
+            },
+            None => None,
+        };
+        if let Err(err) = retire_cred_def(wallet, &self.id, Some(&rotated.id)).await {
+            let rotated_rev_reg_id = rotated_rev_reg.as_ref().map(|rev_reg| &rev_reg.rev_reg_id);
+            discard_rotation(wallet, &rotated.id, rotated_rev_reg_id).await;
+            return Err(err);
+        }
+        Ok((rotated, rotated_rev_reg))
+    }
+}
+
+/// Deletes the wallet records of a credential definition, and of its revocation registry,
+/// created by a rotation which failed, so that the wallet is left as it was before. Failures are
+/// only logged, so as to surface the error which made the rotation fail.
+async fn discard_rotation(
+    wallet: &impl BaseWallet,
+    cred_def_id: &CredentialDefinitionId,
+    rev_reg_id: Option<&String>,
+) {
+    let cred_def_records = [
+        RecordCategory::CredDef,
+        RecordCategory::CredDefPriv,
+        RecordCategory::CredKeyCorrectnessProof,
+        RecordCategory::CredMapSchemaId,
+    ]
+    .map(|category| (category, &cred_def_id.0));
+    let rev_reg_records = rev_reg_id.into_iter().flat_map(|rev_reg_id| {
+        [
+            RecordCategory::RevRegInfo,
+            RecordCategory::RevRegDef,
+            RecordCategory::RevRegDefPriv,
+            RecordCategory::RevReg,
+        ]
+        .map(|category| (category, rev_reg_id))
+    });
+    for (category, name) in cred_def_records.into_iter().chain(rev_reg_records) {
+        if let Err(err) = wallet.delete_record(category, name).await {
+            warn!(
+                "Failed to discard {} record {} of a failed rotation: {}",
+                category, name, err
+            );
+        }
+    }
+}
+
+/// Marks the credential definition as retired in the wallet, optionally recording which
+/// credential definition supersedes it. The credential definition keys are kept, so credentials
+/// already issued under it can still be revoked.
+pub async fn retire_cred_def(
+    wallet: &impl BaseWallet,
+    cred_def_id: &CredentialDefinitionId,
+    superseded_by: Option<&CredentialDefinitionId>,
+) -> VcxResult<()> {
+    trace!(
+        "retire_cred_def >>> cred_def_id: {}, superseded_by: {:?}",
+        cred_def_id,
+        superseded_by
+    );
+    let record = wallet
+        .get_record(RecordCategory::CredDef, &cred_def_id.0)
+        .await?;
+    let mut tags = record.tags().clone();
+    tags.remove(RecordTag::new(CRED_DEF_RETIRED_TAG, ""));
+    tags.remove(RecordTag::new(CRED_DEF_SUPERSEDED_BY_TAG, ""));
+    tags.add(RecordTag::new(CRED_DEF_RETIRED_TAG, "true"));
+    if let Some(superseded_by) = superseded_by {
+        tags.add(RecordTag::new(CRED_DEF_SUPERSEDED_BY_TAG, &superseded_by.0));
+    }
+    wallet
+        .update_record_tags(RecordCategory::CredDef, &cred_def_id.0, tags)
+        .await?;
+    Ok(())
+}
+
+/// Whether the credential definition was retired, see [retire_cred_def]. Credential definitions
+/// not stored in the wallet are not considered retired.
+pub async fn is_cred_def_retired(
+    wallet: &impl BaseWallet,
+    cred_def_id: &CredentialDefinitionId,
+) -> VcxResult<bool> {
+    match wallet
+        .get_record(RecordCategory::CredDef, &cred_def_id.0)
+        .await
+    {
+        Ok(record) => Ok(record
+            .tags()
+            .clone()
+            .into_iter()
+            .any(|tag| tag.key() == CRED_DEF_RETIRED_TAG && tag.value() == "true")),
+        Err(VcxWalletError::RecordNotFound(_)) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Identifiers of the credential definitions stored in the wallet which were not retired.
+pub async fn get_active_cred_def_ids(
+    wallet: &impl BaseWallet,
+) -> VcxResult<Vec<CredentialDefinitionId>> {
+    let query = json!({ "$not": { CRED_DEF_RETIRED_TAG: "true" } }).to_string();
+    wallet
+        .search_record(RecordCategory::CredDef, Some(query))
+        .await?
+        .into_iter()
+        .map(|record| Ok(CredentialDefinitionId::new(record.name())?))
+        .collect()
 }
 
 #[allow(clippy::too_many_arguments)]
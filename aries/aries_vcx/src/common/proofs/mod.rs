@@ -1,2 +1,3 @@
+pub mod presentation_exchange;
 pub mod prover;
 pub mod verifier;
@@ -0,0 +1,817 @@
+//! Presentation exchange over anoncreds credentials, using their W3C representation.
+//!
+//! Stored anoncreds credentials are matched against presentation definitions in their W3C form,
+//! as produced by [credential_to_w3c]. The anoncreds proof backing a submission is created for
+//! a presentation request derived from the definition by [derive_presentation_request]: each
+//! input descriptor becomes an attribute group of the credential subject fields it refers to,
+//! restricted by the `credentialSchema` and `issuer` fields it constrains to a constant, and
+//! fields marked with `predicate: required` become predicates. Both parties derive the same
+//! request, using the challenge of the exchange as the nonce.
+
+use std::collections::HashMap;
+
+use anoncreds_types::{
+    data_types::messages::{
+        cred_selection::{
+            RetrievedCredentialInfo, SelectedCredentialForReferent,
+            SelectedCredentialForReferentCredential, SelectedCredentialInfo, SelectedCredentials,
+        },
+        nonce::Nonce,
+        pres_request::{
+            AttributeInfo, PredicateInfo, PredicateTypes, PresentationRequest,
+            PresentationRequestPayload,
+        },
+        presentation::{Identifier, Presentation, RequestedProof},
+    },
+    utils::query::Query,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use base64::{engine::general_purpose, Engine};
+use serde_json::{Map, Number, Value};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{
+    definition::{InputDescriptor, Optionality, PresentationDefinition},
+    evaluation::{evaluate_submission, find_candidates, select_credentials, InputDescriptorMatch},
+    json_path::{self, PathSegment},
+    submission::{DescriptorMapEntry, PresentationSubmission},
+};
+use crate::{
+    common::proofs::{
+        prover::generate_indy_proof,
+        verifier::{
+            verification_report::{VerificationPolicy, VerificationReport},
+            verify_indy_proof_with_report,
+        },
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+};
+
+const W3C_CREDENTIALS_CONTEXT: &str = "https://www.w3.org/2018/credentials/v1";
+const DATA_INTEGRITY_CONTEXT: &str = "https://w3id.org/security/data-integrity/v2";
+const ANONCREDS_CRYPTOSUITE: &str = "anoncreds-2023";
+const CREDENTIAL_FORMAT: &str = "di_vc";
+
+/// Returns the W3C representation of a stored anoncreds credential. Attribute values which
+/// anoncreds encodes as integers are represented as numbers.
+pub fn credential_to_w3c(credential: &RetrievedCredentialInfo) -> Value {
+    let subject: Map<String, Value> = credential
+        .attributes
+        .iter()
+        .map(|(name, value)| (name.clone(), attribute_value(value)))
+        .collect();
+    json!({
+        "@context": [W3C_CREDENTIALS_CONTEXT, DATA_INTEGRITY_CONTEXT],
+        "type": ["VerifiableCredential"],
+        "issuer": issuer_id(&credential.cred_def_id.0),
+        "credentialSchema": credential_schema(
+            &credential.cred_def_id.0,
+            &credential.schema_id.0,
+            credential.rev_reg_id.as_deref(),
+        ),
+        "credentialSubject": subject,
+    })
+}
+
+fn credential_schema(cred_def_id: &str, schema_id: &str, rev_reg_id: Option<&str>) -> Value {
+    let mut schema = json!({
+        "type": "AnonCredsDefinition",
+        "definition": cred_def_id,
+        "schema": schema_id,
+    });
+    if let Some(rev_reg_id) = rev_reg_id {
+        schema["revocation"] = json!(rev_reg_id);
+    }
+    schema
+}
+
+fn attribute_value(raw: &str) -> Value {
+    match raw.parse::<i32>() {
+        Ok(number) => Value::from(number),
+        Err(_) => Value::from(raw),
+    }
+}
+
+fn raw_attribute_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn issuer_id(cred_def_id: &str) -> &str {
+    let separator = if cred_def_id.starts_with("did:") {
+        '/'
+    } else {
+        ':'
+    };
+    cred_def_id.split(separator).next().unwrap_or(cred_def_id)
+}
+
+/// Finds, for each input descriptor of `definition`, the stored credentials satisfying it.
+pub async fn match_stored_credentials(
+    wallet: &impl BaseWallet,
+    anoncreds: &impl BaseAnonCreds,
+    definition: &PresentationDefinition,
+) -> VcxResult<HashMap<String, Vec<RetrievedCredentialInfo>>> {
+    let credentials = anoncreds.prover_get_credentials(wallet, None).await?;
+    let w3c_credentials: Vec<Value> = credentials.iter().map(credential_to_w3c).collect();
+    Ok(find_candidates(definition, &w3c_credentials)?
+        .into_iter()
+        .zip(&definition.input_descriptors)
+        .map(|(candidates, descriptor)| {
+            let matched = candidates
+                .iter()
+                .map(|candidate| credentials[candidate.credential_index].clone())
+                .collect();
+            (descriptor.id.clone(), matched)
+        })
+        .collect())
+}
+
+enum FieldTarget {
+    Attribute(String),
+    Restriction(&'static str),
+}
+
+fn field_target(path: &str) -> VcxResult<Option<FieldTarget>> {
+    let segments = json_path::parse(path)?;
+    let segments = match segments.first() {
+        Some(PathSegment::Child(root)) if root == "vc" => &segments[1..],
+        _ => &segments[..],
+    };
+    let names: Option<Vec<&str>> = segments
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Child(name) => Some(name.as_str()),
+            _ => None,
+        })
+        .collect();
+    Ok(match names.as_deref() {
+        Some(["credentialSubject", attribute]) => {
+            Some(FieldTarget::Attribute((*attribute).to_owned()))
+        }
+        Some(["credentialSchema", "definition"]) => Some(FieldTarget::Restriction("cred_def_id")),
+        Some(["credentialSchema", "schema"]) => Some(FieldTarget::Restriction("schema_id")),
+        Some(["issuer"]) => Some(FieldTarget::Restriction("issuer_did")),
+        _ => None,
+    })
+}
+
+fn predicate_referent(descriptor_id: &str, attribute: &str, p_type: &PredicateTypes) -> String {
+    format!("{descriptor_id}::{attribute}::{p_type}")
+}
+
+fn predicate_bound(number: &Number) -> VcxResult<i32> {
+    number
+        .as_i64()
+        .and_then(|number| i32::try_from(number).ok())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidProofRequest,
+                format!("Predicate bound {number} is not a 32-bit integer"),
+            )
+        })
+}
+
+fn nonce_from_challenge(challenge: &str) -> VcxResult<Nonce> {
+    let digest = Sha256::digest(challenge.as_bytes());
+    // Anoncreds nonces are limited to 80 bits.
+    let value = digest[..10]
+        .iter()
+        .fold(0u128, |acc, byte| (acc << 8) | u128::from(*byte));
+    Ok(Nonce::try_from(value)?)
+}
+
+type DescriptorReferents = (Option<AttributeInfo>, Vec<(String, PredicateInfo)>);
+
+fn descriptor_referents(descriptor: &InputDescriptor) -> VcxResult<DescriptorReferents> {
+    let mut names = Vec::new();
+    let mut predicates = Vec::new();
+    let mut restrictions = Vec::new();
+
+    for field in &descriptor.constraints.fields {
+        let mut target = None;
+        for path in &field.path {
+            if let Some(found) = field_target(path)? {
+                target = Some(found);
+                break;
+            }
+        }
+        match target {
+            Some(FieldTarget::Attribute(attribute))
+                if field.predicate == Some(Optionality::Required) =>
+            {
+                let filter = field
+                    .filter
+                    .as_ref()
+                    .filter(|filter| filter.has_numeric_bounds())
+                    .ok_or_else(|| {
+                        AriesVcxError::from_msg(
+                            AriesVcxErrorKind::InvalidProofRequest,
+                            format!(
+                                "Field {attribute} of input descriptor {} requires a predicate, \
+                                 but anoncreds can only prove numeric bounds",
+                                descriptor.id
+                            ),
+                        )
+                    })?;
+                let bounds = [
+                    (PredicateTypes::GE, &filter.minimum),
+                    (PredicateTypes::GT, &filter.exclusive_minimum),
+                    (PredicateTypes::LE, &filter.maximum),
+                    (PredicateTypes::LT, &filter.exclusive_maximum),
+                ];
+                for (p_type, bound) in bounds {
+                    let Some(bound) = bound else {
+                        continue;
+                    };
+                    let predicate = PredicateInfo::builder()
+                        .name(attribute.clone())
+                        .p_type(p_type.clone())
+                        .p_value(predicate_bound(bound)?)
+                        .build();
+                    predicates.push((
+                        predicate_referent(&descriptor.id, &attribute, &p_type),
+                        predicate,
+                    ));
+                }
+            }
+            // Optional fields cannot be requested, as anoncreds requires every requested
+            // attribute to be present in the credential.
+            Some(FieldTarget::Attribute(attribute)) if !field.optional => names.push(attribute),
+            Some(FieldTarget::Restriction(tag)) => {
+                if let Some(Value::String(value)) = field
+                    .filter
+                    .as_ref()
+                    .and_then(|filter| filter.const_value.as_ref())
+                {
+                    restrictions.push(Query::Eq(tag.to_owned(), value.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if names.is_empty() && predicates.is_empty() {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidProofRequest,
+            format!(
+                "Input descriptor {} does not constrain any credential subject field",
+                descriptor.id
+            ),
+        ));
+    }
+    let restrictions = match restrictions.len() {
+        0 => None,
+        1 => restrictions.pop(),
+        _ => Some(Query::And(restrictions)),
+    };
+    for (_, predicate) in &mut predicates {
+        predicate.restrictions = restrictions.clone();
+    }
+    let attribute = (!names.is_empty()).then(|| AttributeInfo {
+        names: Some(names),
+        restrictions,
+        ..Default::default()
+    });
+    Ok((attribute, predicates))
+}
+
+/// Derives the anoncreds presentation request backing submissions to `definition`.
+pub fn derive_presentation_request(
+    definition: &PresentationDefinition,
+    challenge: &str,
+) -> VcxResult<PresentationRequest> {
+    let mut requested_attributes = HashMap::new();
+    let mut requested_predicates = HashMap::new();
+    for descriptor in &definition.input_descriptors {
+        let (attribute, predicates) = descriptor_referents(descriptor)?;
+        if let Some(attribute) = attribute {
+            requested_attributes.insert(descriptor.id.clone(), attribute);
+        }
+        requested_predicates.extend(predicates);
+    }
+    Ok(PresentationRequestPayload::builder()
+        .nonce(nonce_from_challenge(challenge)?)
+        .name(
+            definition
+                .name
+                .clone()
+                .unwrap_or_else(|| definition.id.clone()),
+        )
+        .requested_attributes(requested_attributes)
+        .requested_predicates(requested_predicates)
+        .build()
+        .into_v1())
+}
+
+fn referent_belongs_to(referent: &str, descriptor_id: &str) -> bool {
+    referent == descriptor_id
+        || referent
+            .strip_prefix(descriptor_id)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Builds a verifiable presentation satisfying `definition` out of `credentials`, together
+/// with its presentation submission. The presentation embeds the W3C representation of the
+/// selected credentials, disclosing only the requested credential subject fields, and carries
+/// the anoncreds proof as its `proofValue`.
+pub async fn build_anoncreds_submission(
+    wallet: &impl BaseWallet,
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+    definition: &PresentationDefinition,
+    challenge: &str,
+    credentials: &[RetrievedCredentialInfo],
+) -> VcxResult<Value> {
+    let w3c_credentials: Vec<Value> = credentials.iter().map(credential_to_w3c).collect();
+    let selected: Vec<InputDescriptorMatch> = select_credentials(definition, &w3c_credentials)?;
+    let presentation_request = derive_presentation_request(definition, challenge)?;
+
+    let payload = presentation_request.value();
+    let referents = payload
+        .requested_attributes
+        .keys()
+        .chain(payload.requested_predicates.keys());
+    let mut selected_credentials = SelectedCredentials::default();
+    for referent in referents {
+        let Some(matched) = selected
+            .iter()
+            .find(|matched| referent_belongs_to(referent, &matched.descriptor_id))
+        else {
+            continue;
+        };
+        let credential = &credentials[matched.credential_index];
+        selected_credentials.credential_for_referent.insert(
+            referent.clone(),
+            SelectedCredentialForReferent {
+                credential: SelectedCredentialForReferentCredential {
+                    cred_info: SelectedCredentialInfo {
+                        referent: credential.referent.clone(),
+                        schema_id: credential.schema_id.clone(),
+                        cred_def_id: credential.cred_def_id.clone(),
+                        rev_reg_id: credential.rev_reg_id.clone(),
+                        cred_rev_id: credential.cred_rev_id,
+                        revealed: Some(true),
+                    },
+                },
+                tails_dir: None,
+            },
+        );
+    }
+
+    let mut verifiable_credentials = Vec::new();
+    let mut descriptor_map = Vec::new();
+    for (index, matched) in selected.iter().enumerate() {
+        let disclosed = payload
+            .requested_attributes
+            .get(&matched.descriptor_id)
+            .and_then(|attribute| attribute.names.clone())
+            .unwrap_or_default();
+        let mut credential = w3c_credentials[matched.credential_index].clone();
+        if let Some(Value::Object(subject)) = credential.get_mut("credentialSubject") {
+            subject.retain(|name, _| disclosed.contains(name));
+        }
+        verifiable_credentials.push(credential);
+        descriptor_map.push(DescriptorMapEntry {
+            id: matched.descriptor_id.clone(),
+            format: CREDENTIAL_FORMAT.to_owned(),
+            path: format!("$.verifiableCredential[{index}]"),
+            path_nested: None,
+        });
+    }
+    let submission = PresentationSubmission {
+        id: Uuid::new_v4().to_string(),
+        definition_id: definition.id.clone(),
+        descriptor_map,
+    };
+
+    let proof = generate_indy_proof(
+        wallet,
+        ledger,
+        anoncreds,
+        &selected_credentials,
+        HashMap::new(),
+        presentation_request,
+    )
+    .await?;
+
+    Ok(json!({
+        "@context": [W3C_CREDENTIALS_CONTEXT, DATA_INTEGRITY_CONTEXT],
+        "type": ["VerifiablePresentation"],
+        "presentation_submission": submission,
+        "verifiableCredential": verifiable_credentials,
+        "proof": {
+            "type": "DataIntegrityProof",
+            "cryptosuite": ANONCREDS_CRYPTOSUITE,
+            "challenge": challenge,
+            "proofValue": general_purpose::URL_SAFE_NO_PAD.encode(serde_json::to_vec(&proof)?),
+        },
+    }))
+}
+
+fn invalid_presentation(msg: impl Into<String>) -> AriesVcxError {
+    AriesVcxError::from_msg(AriesVcxErrorKind::InvalidProof, msg.into())
+}
+
+/// Verifies a presentation built by [build_anoncreds_submission]: the submission has to
+/// satisfy `definition`, the anoncreds proof has to be valid for the derived presentation
+/// request, and the disclosed credentials have to match what the proof reveals.
+pub async fn verify_anoncreds_submission(
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+    definition: &PresentationDefinition,
+    challenge: &str,
+    presentation: &Value,
+    policy: &VerificationPolicy,
+) -> VcxResult<VerificationReport> {
+    let submission: PresentationSubmission = serde_json::from_value(
+        presentation
+            .get("presentation_submission")
+            .cloned()
+            .ok_or_else(|| invalid_presentation("Presentation has no presentation_submission"))?,
+    )?;
+    evaluate_submission(definition, &submission, presentation)?;
+
+    let proof = presentation
+        .get("proof")
+        .ok_or_else(|| invalid_presentation("Presentation has no proof"))?;
+    if proof.get("cryptosuite").and_then(Value::as_str) != Some(ANONCREDS_CRYPTOSUITE) {
+        return Err(invalid_presentation(
+            "Presentation proof is not an anoncreds proof",
+        ));
+    }
+    if proof.get("challenge").and_then(Value::as_str) != Some(challenge) {
+        return Err(invalid_presentation(
+            "Presentation proof challenge does not match",
+        ));
+    }
+    let proof_value = proof
+        .get("proofValue")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid_presentation("Presentation proof has no proofValue"))?;
+    let proof_json = String::from_utf8(general_purpose::URL_SAFE_NO_PAD.decode(proof_value)?)?;
+    let anoncreds_presentation: Presentation = serde_json::from_str(&proof_json)?;
+
+    for entry in &submission.descriptor_map {
+        check_disclosed_credential(
+            entry.resolve(presentation)?,
+            &entry.id,
+            &anoncreds_presentation.requested_proof,
+            &anoncreds_presentation.identifiers,
+        )?;
+    }
+
+    let presentation_request = derive_presentation_request(definition, challenge)?;
+    verify_indy_proof_with_report(
+        ledger,
+        anoncreds,
+        &proof_json,
+        &serde_json::to_string(&presentation_request)?,
+        policy,
+    )
+    .await
+}
+
+/// Checks that the credential disclosed for `descriptor_id` holds exactly the attribute values
+/// revealed by the anoncreds proof, and that its issuer and `credentialSchema` are those of the
+/// credential the proof is for. The submission is evaluated against the disclosed credential,
+/// so this is what makes filters on these fields apply to the proven credential rather than to
+/// values chosen by the prover.
+fn check_disclosed_credential(
+    credential: &Value,
+    descriptor_id: &str,
+    requested_proof: &RequestedProof,
+    identifiers: &[Identifier],
+) -> VcxResult<()> {
+    let subject = credential
+        .get("credentialSubject")
+        .and_then(Value::as_object)
+        .ok_or_else(|| invalid_presentation("Disclosed credential has no credentialSubject"))?;
+    let group = requested_proof.revealed_attr_groups.get(descriptor_id);
+    let values_match = match group {
+        Some(group) => {
+            subject.len() == group.values.len()
+                && group.values.iter().all(|(name, value)| {
+                    subject.get(name).and_then(raw_attribute_value).as_deref()
+                        == Some(value.raw.as_str())
+                })
+        }
+        None => subject.is_empty(),
+    };
+    if !values_match {
+        return Err(invalid_presentation(format!(
+            "Credential disclosed for {descriptor_id} does not match the proof"
+        )));
+    }
+
+    let mut sub_proof_indexes = group.map(|group| group.sub_proof_index).into_iter().chain(
+        requested_proof
+            .predicates
+            .iter()
+            .filter(|(referent, _)| referent_belongs_to(referent, descriptor_id))
+            .map(|(_, predicate)| predicate.sub_proof_index),
+    );
+    let sub_proof_index = sub_proof_indexes.next().ok_or_else(|| {
+        invalid_presentation(format!(
+            "The proof does not cover input descriptor {descriptor_id}"
+        ))
+    })?;
+    if sub_proof_indexes.any(|index| index != sub_proof_index) {
+        return Err(invalid_presentation(format!(
+            "The proof for input descriptor {descriptor_id} uses several credentials"
+        )));
+    }
+    let identifier = identifiers
+        .get(sub_proof_index as usize)
+        .ok_or_else(|| invalid_presentation("The proof has no identifiers for a sub proof"))?;
+
+    let proven_schema = credential_schema(
+        &identifier.cred_def_id.0,
+        &identifier.schema_id.0,
+        identifier.rev_reg_id.as_ref().map(|id| id.0.as_str()),
+    );
+    if credential.get("issuer").and_then(Value::as_str)
+        != Some(issuer_id(&identifier.cred_def_id.0))
+        || credential.get("credentialSchema") != Some(&proven_schema)
+    {
+        return Err(invalid_presentation(format!(
+            "Credential disclosed for {descriptor_id} is not the credential the proof is for"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use anoncreds_types::data_types::identifiers::{
+        cred_def_id::CredentialDefinitionId, schema_id::SchemaId,
+    };
+    use test_utils::{
+        constants::PROOF_JSON,
+        mockdata::{mock_anoncreds::MockAnoncreds, mock_ledger::MockLedger},
+    };
+
+    use super::*;
+
+    fn credential() -> RetrievedCredentialInfo {
+        RetrievedCredentialInfo {
+            referent: "cred-1".to_owned(),
+            attributes: HashMap::from([
+                ("name".to_owned(), "Alice".to_owned()),
+                ("age".to_owned(), "30".to_owned()),
+            ]),
+            schema_id: SchemaId::new_unchecked("V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0"),
+            cred_def_id: CredentialDefinitionId::new_unchecked(
+                "V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag",
+            ),
+            rev_reg_id: None,
+            cred_rev_id: None,
+        }
+    }
+
+    fn definition() -> PresentationDefinition {
+        serde_json::from_value(json!({
+            "id": "adult",
+            "input_descriptors": [{
+                "id": "identity",
+                "constraints": {
+                    "fields": [
+                        {"path": ["$.credentialSubject.name"]},
+                        {
+                            "path": ["$.credentialSubject.age"],
+                            "filter": {"type": "number", "minimum": 18},
+                            "predicate": "required"
+                        },
+                        {
+                            "path": ["$.credentialSchema.definition"],
+                            "filter": {"const": "V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag"}
+                        }
+                    ]
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_credential_to_w3c() {
+        let credential = credential_to_w3c(&credential());
+        assert_eq!(credential["issuer"], json!("V4SGRU86Z58d6TV7PBUe6f"));
+        assert_eq!(credential["credentialSubject"]["age"], json!(30));
+        assert_eq!(credential["credentialSubject"]["name"], json!("Alice"));
+        assert_eq!(
+            credential["credentialSchema"]["definition"],
+            json!("V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag")
+        );
+        assert_eq!(
+            issuer_id("did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f/anoncreds/v0/CLAIM_DEF/10/tag"),
+            "did:indy:sovrin:V4SGRU86Z58d6TV7PBUe6f"
+        );
+    }
+
+    #[test]
+    fn test_derive_presentation_request() {
+        let request = derive_presentation_request(&definition(), "challenge").unwrap();
+        let payload = request.value();
+        let restriction = Some(Query::Eq(
+            "cred_def_id".to_owned(),
+            "V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag".to_owned(),
+        ));
+
+        let attribute = &payload.requested_attributes["identity"];
+        assert_eq!(attribute.names, Some(vec!["name".to_owned()]));
+        assert_eq!(attribute.restrictions, restriction);
+
+        let predicate = &payload.requested_predicates["identity::age::GE"];
+        assert_eq!(predicate.p_type, PredicateTypes::GE);
+        assert_eq!(predicate.p_value, 18);
+        assert_eq!(predicate.restrictions, restriction);
+
+        let same_challenge = derive_presentation_request(&definition(), "challenge").unwrap();
+        assert_eq!(same_challenge.value().nonce, payload.nonce);
+        let other_challenge = derive_presentation_request(&definition(), "other").unwrap();
+        assert_ne!(other_challenge.value().nonce, payload.nonce);
+    }
+
+    #[test]
+    fn test_check_disclosed_credential() {
+        let requested_proof: RequestedProof = serde_json::from_value(json!({
+            "revealed_attrs": {},
+            "revealed_attr_groups": {
+                "identity": {
+                    "sub_proof_index": 0,
+                    "values": {"name": {"raw": "Alice", "encoded": "1"}}
+                }
+            }
+        }))
+        .unwrap();
+        let identifiers: Vec<Identifier> = serde_json::from_value(json!([{
+            "schema_id": "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0",
+            "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag"
+        }]))
+        .unwrap();
+        let check = |disclosed: &Value| {
+            check_disclosed_credential(disclosed, "identity", &requested_proof, &identifiers)
+        };
+        let mut disclosed = credential_to_w3c(&credential());
+        assert!(check(&disclosed).is_err());
+
+        disclosed["credentialSubject"]
+            .as_object_mut()
+            .unwrap()
+            .remove("age");
+        check(&disclosed).unwrap();
+
+        disclosed["credentialSubject"]["name"] = json!("Mallory");
+        assert!(check(&disclosed).is_err());
+    }
+
+    fn proven_identifiers() -> Vec<Identifier> {
+        serde_json::from_value(json!([{
+            "schema_id": "V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0",
+            "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:10:tag"
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_check_disclosed_credential_with_predicates_only() {
+        let requested_proof: RequestedProof = serde_json::from_value(json!({
+            "revealed_attrs": {},
+            "predicates": {"identity::age::GE": {"sub_proof_index": 0}}
+        }))
+        .unwrap();
+        let check = |disclosed: &Value| {
+            check_disclosed_credential(
+                disclosed,
+                "identity",
+                &requested_proof,
+                &proven_identifiers(),
+            )
+        };
+        let mut disclosed = credential_to_w3c(&credential());
+        disclosed["credentialSubject"] = json!({});
+        check(&disclosed).unwrap();
+
+        disclosed["issuer"] = json!("TrustedIssuer111111111");
+        assert!(check(&disclosed).is_err());
+
+        assert!(check_disclosed_credential(
+            &credential_to_w3c(&credential()),
+            "other",
+            &requested_proof,
+            &proven_identifiers()
+        )
+        .is_err());
+    }
+
+    /// A presentation disclosing `credential` for the `identity` descriptor, backed by a proof
+    /// revealing its name and proving its age, for the credential of [proven_identifiers].
+    fn presentation(credential: Value) -> Value {
+        let mut proof: Presentation = serde_json::from_str(PROOF_JSON).unwrap();
+        proof.requested_proof = serde_json::from_value(json!({
+            "revealed_attrs": {},
+            "revealed_attr_groups": {
+                "identity": {
+                    "sub_proof_index": 0,
+                    "values": {"name": {"raw": "Alice", "encoded": "1"}}
+                }
+            },
+            "predicates": {"identity::age::GE": {"sub_proof_index": 0}}
+        }))
+        .unwrap();
+        proof.identifiers = proven_identifiers();
+        json!({
+            "presentation_submission": {
+                "id": "submission",
+                "definition_id": "trusted",
+                "descriptor_map": [{
+                    "id": "identity",
+                    "format": CREDENTIAL_FORMAT,
+                    "path": "$.verifiableCredential[0]"
+                }]
+            },
+            "verifiableCredential": [credential],
+            "proof": {
+                "type": "DataIntegrityProof",
+                "cryptosuite": ANONCREDS_CRYPTOSUITE,
+                "challenge": "challenge",
+                "proofValue": general_purpose::URL_SAFE_NO_PAD
+                    .encode(serde_json::to_vec(&proof).unwrap()),
+            },
+        })
+    }
+
+    /// Verifies a presentation whose disclosed credential satisfies `field` only because of
+    /// the value set by `forge`.
+    async fn verify_forged(field: Value, forge: impl FnOnce(&mut Value)) -> AriesVcxError {
+        let definition: PresentationDefinition = serde_json::from_value(json!({
+            "id": "trusted",
+            "input_descriptors": [{
+                "id": "identity",
+                "constraints": {
+                    "fields": [
+                        {"path": ["$.credentialSubject.name"]},
+                        {
+                            "path": ["$.credentialSubject.age"],
+                            "filter": {"type": "number", "minimum": 18},
+                            "predicate": "required"
+                        },
+                        field
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+        let mut credential = credential_to_w3c(&credential());
+        credential["credentialSubject"] = json!({"name": "Alice"});
+        forge(&mut credential);
+
+        verify_anoncreds_submission(
+            &MockLedger,
+            &MockAnoncreds,
+            &definition,
+            "challenge",
+            &presentation(credential),
+            &VerificationPolicy::default(),
+        )
+        .await
+        .unwrap_err()
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_forged_issuer() {
+        let err = verify_forged(
+            json!({"path": ["$.issuer"], "filter": {"pattern": "^TrustedIssuer"}}),
+            |credential| credential["issuer"] = json!("TrustedIssuer111111111"),
+        )
+        .await;
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidProof);
+        assert!(err
+            .to_string()
+            .contains("is not the credential the proof is for"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_forged_schema_id() {
+        let forged_schema_id = "TrustedIssuer111111111:2:degree:1.0";
+        let err = verify_forged(
+            json!({
+                "path": ["$.credentialSchema.schema"],
+                "filter": {"enum": [forged_schema_id]}
+            }),
+            |credential| credential["credentialSchema"]["schema"] = json!(forged_schema_id),
+        )
+        .await;
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidProof);
+        assert!(err
+            .to_string()
+            .contains("is not the credential the proof is for"));
+    }
+}
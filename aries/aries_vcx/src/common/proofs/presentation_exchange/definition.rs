@@ -0,0 +1,87 @@
+use super::filter::Filter;
+
+/// A [presentation definition](<https://identity.foundation/presentation-exchange/spec/v2.0.0/#presentation-definition>),
+/// describing the proofs a verifier requires. Submission requirements are not supported, so
+/// every input descriptor has to be satisfied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresentationDefinition {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    /// Claim formats the verifier accepts, kept as is.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+    pub input_descriptors: Vec<InputDescriptor>,
+}
+
+/// Describes a single credential the verifier requires, through the constraints it has to
+/// satisfy.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputDescriptor {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub group: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<serde_json::Value>,
+    #[serde(default)]
+    pub constraints: Constraints,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+    /// Whether the holder should only disclose the fields the constraints refer to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_disclosure: Option<Optionality>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+}
+
+/// A constraint on the credential: at least one of `path` has to resolve to a value which
+/// satisfies `filter`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Filter>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    /// Whether the holder should prove the filter is satisfied without disclosing the value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<Optionality>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Optionality {
+    Required,
+    Preferred,
+}
+
+/// Content of a `dif/presentation-exchange/definitions@v1.0` request attachment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresentationExchangeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<PresentationExchangeOptions>,
+    pub presentation_definition: PresentationDefinition,
+}
+
+/// Options binding the presentation to the exchange.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresentationExchangeOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+}
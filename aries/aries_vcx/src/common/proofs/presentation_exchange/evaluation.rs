@@ -0,0 +1,237 @@
+use serde_json::Value;
+
+use super::{
+    definition::{InputDescriptor, Optionality, PresentationDefinition},
+    json_path,
+    submission::PresentationSubmission,
+};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// A field of an input descriptor satisfied by a credential.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldMatch {
+    pub field_id: Option<String>,
+    /// The path of the field which resolved to `value`.
+    pub path: String,
+    pub value: Value,
+}
+
+/// A credential satisfying an input descriptor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputDescriptorMatch {
+    pub descriptor_id: String,
+    /// Index of the credential among the evaluated credentials.
+    pub credential_index: usize,
+    /// The satisfied fields. Optional fields the credential does not satisfy are omitted.
+    pub fields: Vec<FieldMatch>,
+}
+
+/// Evaluates the constraints of `descriptor` against `credential`, returning the matched fields
+/// if the credential satisfies every non-optional field.
+pub fn evaluate_input_descriptor(
+    descriptor: &InputDescriptor,
+    credential: &Value,
+) -> VcxResult<Option<Vec<FieldMatch>>> {
+    evaluate_fields(descriptor, credential, false)
+}
+
+fn evaluate_fields(
+    descriptor: &InputDescriptor,
+    credential: &Value,
+    predicates_proven: bool,
+) -> VcxResult<Option<Vec<FieldMatch>>> {
+    let mut matches = Vec::new();
+    'fields: for field in &descriptor.constraints.fields {
+        for path in &field.path {
+            for value in json_path::select(credential, path)? {
+                let satisfied = match &field.filter {
+                    Some(filter) => filter.matches(value)?,
+                    None => true,
+                };
+                if satisfied {
+                    matches.push(FieldMatch {
+                        field_id: field.id.clone(),
+                        path: path.clone(),
+                        value: value.clone(),
+                    });
+                    continue 'fields;
+                }
+            }
+        }
+        // Values proven by predicates are not disclosed, so their filters are checked by the
+        // proof rather than against the presented credential.
+        let proven_by_predicate =
+            predicates_proven && field.predicate == Some(Optionality::Required);
+        if !field.optional && !proven_by_predicate {
+            return Ok(None);
+        }
+    }
+    Ok(Some(matches))
+}
+
+/// Finds, for each input descriptor of `definition`, all `credentials` satisfying it. The
+/// candidates are returned in the order of the input descriptors.
+pub fn find_candidates(
+    definition: &PresentationDefinition,
+    credentials: &[Value],
+) -> VcxResult<Vec<Vec<InputDescriptorMatch>>> {
+    definition
+        .input_descriptors
+        .iter()
+        .map(|descriptor| {
+            let mut candidates = Vec::new();
+            for (credential_index, credential) in credentials.iter().enumerate() {
+                if let Some(fields) = evaluate_input_descriptor(descriptor, credential)? {
+                    candidates.push(InputDescriptorMatch {
+                        descriptor_id: descriptor.id.clone(),
+                        credential_index,
+                        fields,
+                    });
+                }
+            }
+            Ok(candidates)
+        })
+        .collect()
+}
+
+/// Selects a credential for every input descriptor of `definition`, preferring the credential
+/// satisfying the most optional fields. Fails if any descriptor cannot be satisfied.
+pub fn select_credentials(
+    definition: &PresentationDefinition,
+    credentials: &[Value],
+) -> VcxResult<Vec<InputDescriptorMatch>> {
+    find_candidates(definition, credentials)?
+        .into_iter()
+        .zip(&definition.input_descriptors)
+        .map(|(candidates, descriptor)| {
+            candidates
+                .into_iter()
+                .rev()
+                .max_by_key(|candidate| candidate.fields.len())
+                .ok_or_else(|| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidInput,
+                        format!("No credential satisfies input descriptor {}", descriptor.id),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Checks that `submission` satisfies `definition` within `presentation`: every input
+/// descriptor has to be mapped to a credential of the presentation which satisfies its
+/// constraints. Fields proven through predicates are expected to be checked by the proof of
+/// the presentation.
+pub fn evaluate_submission(
+    definition: &PresentationDefinition,
+    submission: &PresentationSubmission,
+    presentation: &Value,
+) -> VcxResult<()> {
+    if submission.definition_id != definition.id {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidProof,
+            format!(
+                "Submission is for definition {}, expected {}",
+                submission.definition_id, definition.id
+            ),
+        ));
+    }
+    for descriptor in &definition.input_descriptors {
+        let entry = submission
+            .descriptor_map
+            .iter()
+            .find(|entry| entry.id == descriptor.id)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidProof,
+                    format!(
+                        "Submission does not satisfy input descriptor {}",
+                        descriptor.id
+                    ),
+                )
+            })?;
+        let credential = entry.resolve(presentation)?;
+        if evaluate_fields(descriptor, credential, true)?.is_none() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidProof,
+                format!(
+                    "Credential submitted for input descriptor {} does not satisfy its \
+                     constraints",
+                    descriptor.id
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn definition() -> PresentationDefinition {
+        serde_json::from_value(json!({
+            "id": "degree-check",
+            "input_descriptors": [{
+                "id": "degree",
+                "constraints": {
+                    "fields": [
+                        {"path": ["$.credentialSubject.degree"], "filter": {"const": "Bachelor"}},
+                        {"path": ["$.credentialSubject.honors"], "optional": true},
+                        {
+                            "path": ["$.credentialSubject.age"],
+                            "filter": {"type": "number", "minimum": 18},
+                            "predicate": "required"
+                        }
+                    ]
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_select_credentials() {
+        let credentials = vec![
+            json!({"credentialSubject": {"degree": "Master", "age": 30}}),
+            json!({"credentialSubject": {"degree": "Bachelor", "age": 30}}),
+            json!({"credentialSubject": {"degree": "Bachelor", "age": 30, "honors": "cum laude"}}),
+            json!({"credentialSubject": {"degree": "Bachelor", "age": 16}}),
+        ];
+        let candidates = find_candidates(&definition(), &credentials).unwrap();
+        assert_eq!(candidates[0].len(), 2);
+
+        let selected = select_credentials(&definition(), &credentials).unwrap();
+        assert_eq!(selected[0].descriptor_id, "degree");
+        assert_eq!(selected[0].credential_index, 2);
+        assert_eq!(selected[0].fields.len(), 3);
+
+        assert!(select_credentials(&definition(), &credentials[..1]).is_err());
+    }
+
+    #[test]
+    fn test_evaluate_submission() {
+        let presentation = json!({
+            "verifiableCredential": [{"credentialSubject": {"degree": "Bachelor"}}]
+        });
+        let submission: PresentationSubmission = serde_json::from_value(json!({
+            "id": "submission",
+            "definition_id": "degree-check",
+            "descriptor_map": [
+                {"id": "degree", "format": "di_vc", "path": "$.verifiableCredential[0]"}
+            ]
+        }))
+        .unwrap();
+        evaluate_submission(&definition(), &submission, &presentation).unwrap();
+
+        let mut wrong_path = submission.clone();
+        wrong_path.descriptor_map[0].path = "$.verifiableCredential[1]".to_owned();
+        assert!(evaluate_submission(&definition(), &wrong_path, &presentation).is_err());
+
+        let mut wrong_definition = submission;
+        wrong_definition.definition_id = "other".to_owned();
+        assert!(evaluate_submission(&definition(), &wrong_definition, &presentation).is_err());
+    }
+}
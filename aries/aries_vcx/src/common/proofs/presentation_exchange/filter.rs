@@ -0,0 +1,217 @@
+use regex::Regex;
+use serde_json::{Map, Number, Value};
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Annotation keywords, which do not constrain values.
+const ANNOTATION_KEYWORDS: [&str; 5] = ["$schema", "$comment", "title", "description", "examples"];
+
+/// JSON Schema subset used by presentation definition fields to constrain the value a path
+/// resolves to. Filters using keywords outside of this subset cannot be evaluated.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+    pub const_value: Option<Value>,
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_values: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_minimum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_maximum: Option<Number>,
+    /// For arrays, at least one item has to match this filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<Box<Filter>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub not: Option<Box<Filter>>,
+    /// Keywords outside of the supported subset.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl Filter {
+    /// Whether `value` satisfies every keyword of the filter. Fails if the filter is malformed,
+    /// such as with an invalid `pattern`, or uses a keyword outside of the supported subset, so
+    /// that values are never accepted without checking a constraint.
+    pub fn matches(&self, value: &Value) -> VcxResult<bool> {
+        if let Some(keyword) = self
+            .other
+            .keys()
+            .find(|keyword| !ANNOTATION_KEYWORDS.contains(&keyword.as_str()))
+        {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Unsupported filter keyword {keyword}"),
+            ));
+        }
+        if let Some(value_type) = &self.value_type {
+            if !matches_type(value_type, value) {
+                return Ok(false);
+            }
+        }
+        if let Some(const_value) = &self.const_value {
+            if const_value != value {
+                return Ok(false);
+            }
+        }
+        if let Some(enum_values) = &self.enum_values {
+            if !enum_values.contains(value) {
+                return Ok(false);
+            }
+        }
+        if let Value::String(string) = value {
+            if !self.matches_string(string)? {
+                return Ok(false);
+            }
+        }
+        if let Some(number) = value.as_f64() {
+            if !self.matches_number(number) {
+                return Ok(false);
+            }
+        }
+        if let Some(contains) = &self.contains {
+            let Value::Array(items) = value else {
+                return Ok(false);
+            };
+            let mut any_matched = false;
+            for item in items {
+                if contains.matches(item)? {
+                    any_matched = true;
+                    break;
+                }
+            }
+            if !any_matched {
+                return Ok(false);
+            }
+        }
+        if let Some(not) = &self.not {
+            if not.matches(value)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn matches_string(&self, string: &str) -> VcxResult<bool> {
+        let length = string.chars().count();
+        if self.min_length.is_some_and(|min| length < min)
+            || self.max_length.is_some_and(|max| length > max)
+        {
+            return Ok(false);
+        }
+        if let Some(pattern) = &self.pattern {
+            let regex = Regex::new(pattern).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Invalid filter pattern {pattern}: {err}"),
+                )
+            })?;
+            if !regex.is_match(string) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn matches_number(&self, number: f64) -> bool {
+        let bound = |bound: &Option<Number>| bound.as_ref().and_then(Number::as_f64);
+        !(bound(&self.minimum).is_some_and(|min| number < min)
+            || bound(&self.exclusive_minimum).is_some_and(|min| number <= min)
+            || bound(&self.maximum).is_some_and(|max| number > max)
+            || bound(&self.exclusive_maximum).is_some_and(|max| number >= max))
+    }
+
+    /// Whether the filter bounds numeric values, which anoncreds can prove as predicates.
+    pub fn has_numeric_bounds(&self) -> bool {
+        self.minimum.is_some()
+            || self.exclusive_minimum.is_some()
+            || self.maximum.is_some()
+            || self.exclusive_maximum.is_some()
+    }
+}
+
+fn matches_type(value_type: &str, value: &Value) -> bool {
+    match value_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_filter_keywords() {
+        let filter: Filter = serde_json::from_value(json!({
+            "type": "string",
+            "pattern": "^did:",
+            "minLength": 8
+        }))
+        .unwrap();
+        assert!(filter.matches(&json!("did:sov:123")).unwrap());
+        assert!(!filter.matches(&json!("did:sov")).unwrap());
+        assert!(!filter.matches(&json!("sov:did:123")).unwrap());
+        assert!(!filter.matches(&json!(42)).unwrap());
+
+        let filter: Filter =
+            serde_json::from_value(json!({"type": "number", "exclusiveMinimum": 18})).unwrap();
+        assert!(filter.has_numeric_bounds());
+        assert!(filter.matches(&json!(19)).unwrap());
+        assert!(!filter.matches(&json!(18)).unwrap());
+
+        let filter: Filter =
+            serde_json::from_value(json!({"contains": {"const": "UniversityDegree"}})).unwrap();
+        assert!(filter
+            .matches(&json!(["VerifiableCredential", "UniversityDegree"]))
+            .unwrap());
+        assert!(!filter.matches(&json!(["VerifiableCredential"])).unwrap());
+
+        let filter: Filter = serde_json::from_value(json!({"not": {"enum": ["a", "b"]}})).unwrap();
+        assert!(filter.matches(&json!("c")).unwrap());
+        assert!(!filter.matches(&json!("a")).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_pattern_fails() {
+        let filter = Filter {
+            pattern: Some("(".to_owned()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&json!("value")).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_keyword_fails() {
+        let filter: Filter =
+            serde_json::from_value(json!({"type": "string", "format": "date"})).unwrap();
+        assert!(filter.matches(&json!("not a date")).is_err());
+
+        let filter: Filter = serde_json::from_value(json!({"not": {"multipleOf": 2}})).unwrap();
+        assert!(filter.matches(&json!(3)).is_err());
+
+        let filter: Filter =
+            serde_json::from_value(json!({"type": "string", "description": "A name"})).unwrap();
+        assert!(filter.matches(&json!("Alice")).unwrap());
+    }
+}
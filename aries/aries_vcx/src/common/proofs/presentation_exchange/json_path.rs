@@ -0,0 +1,190 @@
+//! Minimal [JSONPath](<https://goessner.net/articles/JsonPath/>) support, covering the subset of
+//! the syntax used by presentation definitions: child access by name (`.name`, `['name']`),
+//! array indexing (`[0]`), wildcards (`.*`, `[*]`) and recursive descent (`..name`).
+
+use serde_json::Value;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathSegment {
+    Child(String),
+    Index(usize),
+    Wildcard,
+    /// Recursive descent, matching the given child (or any value, if [None]) at any depth.
+    Descendant(Option<String>),
+}
+
+/// Parses a JSONPath expression, which has to start at the document root (`$`).
+pub fn parse(path: &str) -> VcxResult<Vec<PathSegment>> {
+    let invalid = |reason: &str| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("Invalid JSONPath {path}: {reason}"),
+        )
+    };
+    let rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| invalid("must start with $"))?;
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '.' if chars.get(i + 1) == Some(&'.') => {
+                i += 2;
+                let name = read_name(&chars, &mut i);
+                match name.as_str() {
+                    "" => return Err(invalid("recursive descent without a name")),
+                    "*" => segments.push(PathSegment::Descendant(None)),
+                    _ => segments.push(PathSegment::Descendant(Some(name))),
+                }
+            }
+            '.' => {
+                i += 1;
+                let name = read_name(&chars, &mut i);
+                match name.as_str() {
+                    "" => return Err(invalid("empty child name")),
+                    "*" => segments.push(PathSegment::Wildcard),
+                    _ => segments.push(PathSegment::Child(name)),
+                }
+            }
+            '[' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|offset| i + offset)
+                    .ok_or_else(|| invalid("unclosed bracket"))?;
+                let inner: String = chars[i + 1..end].iter().collect();
+                let inner = inner.trim();
+                let segment = if inner == "*" {
+                    PathSegment::Wildcard
+                } else if let Some(name) = quoted(inner) {
+                    PathSegment::Child(name.to_owned())
+                } else {
+                    PathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| invalid("unsupported bracket expression"))?,
+                    )
+                };
+                segments.push(segment);
+                i = end + 1;
+            }
+            _ => return Err(invalid("unexpected character")),
+        }
+    }
+    Ok(segments)
+}
+
+fn read_name(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && chars[*i] != '.' && chars[*i] != '[' {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+fn quoted(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+        .or_else(|| {
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+        })
+}
+
+/// Returns all values of `document` selected by `path`.
+pub fn select<'a>(document: &'a Value, path: &str) -> VcxResult<Vec<&'a Value>> {
+    let segments = parse(path)?;
+    Ok(select_segments(document, &segments))
+}
+
+/// Returns all values of `document` selected by the already parsed `segments`.
+pub fn select_segments<'a>(document: &'a Value, segments: &[PathSegment]) -> Vec<&'a Value> {
+    let mut selected = vec![document];
+    for segment in segments {
+        selected = selected
+            .into_iter()
+            .flat_map(|value| apply_segment(value, segment))
+            .collect();
+    }
+    selected
+}
+
+fn apply_segment<'a>(value: &'a Value, segment: &PathSegment) -> Vec<&'a Value> {
+    match segment {
+        PathSegment::Child(name) => value.get(name).into_iter().collect(),
+        PathSegment::Index(index) => value.get(index).into_iter().collect(),
+        PathSegment::Wildcard => children(value),
+        PathSegment::Descendant(name) => {
+            let mut found = Vec::new();
+            collect_descendants(value, name.as_deref(), &mut found);
+            found
+        }
+    }
+}
+
+fn children(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Object(map) => map.values().collect(),
+        Value::Array(items) => items.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn collect_descendants<'a>(value: &'a Value, name: Option<&str>, found: &mut Vec<&'a Value>) {
+    match name {
+        Some(name) => found.extend(value.get(name)),
+        None => found.extend(children(value)),
+    }
+    for child in children(value) {
+        collect_descendants(child, name, found);
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse("$.credentialSubject['first name'][0]..id").unwrap(),
+            vec![
+                PathSegment::Child("credentialSubject".to_owned()),
+                PathSegment::Child("first name".to_owned()),
+                PathSegment::Index(0),
+                PathSegment::Descendant(Some("id".to_owned())),
+            ]
+        );
+        assert!(parse("credentialSubject.name").is_err());
+        assert!(parse("$.a[?(@.b)]").is_err());
+    }
+
+    #[test]
+    fn test_select_values() {
+        let document = json!({
+            "type": ["VerifiableCredential", "UniversityDegree"],
+            "credentialSubject": {"name": "Alice", "degree": {"name": "Bachelor"}},
+        });
+        assert_eq!(
+            select(&document, "$.credentialSubject.name").unwrap(),
+            vec![&json!("Alice")]
+        );
+        assert_eq!(
+            select(&document, "$.type[1]").unwrap(),
+            vec![&json!("UniversityDegree")]
+        );
+        assert_eq!(select(&document, "$.type[*]").unwrap().len(), 2);
+        assert_eq!(select(&document, "$..name").unwrap().len(), 2);
+        assert!(select(&document, "$.credentialSubject.age")
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+//! Support for [DIF Presentation Exchange](<https://identity.foundation/presentation-exchange/spec/v2.0.0/>):
+//! presentation definitions, the evaluation of credentials against their input descriptors,
+//! and presentation submissions. The [anoncreds] module builds and verifies submissions backed
+//! by anoncreds proofs.
+
+pub mod anoncreds;
+pub mod definition;
+pub mod evaluation;
+pub mod filter;
+pub mod json_path;
+pub mod submission;
@@ -0,0 +1,49 @@
+use serde_json::Value;
+
+use super::json_path;
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// A [presentation submission](<https://identity.foundation/presentation-exchange/spec/v2.0.0/#presentation-submission>),
+/// describing where the credential satisfying each input descriptor is found in a
+/// presentation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PresentationSubmission {
+    pub id: String,
+    pub definition_id: String,
+    pub descriptor_map: Vec<DescriptorMapEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DescriptorMapEntry {
+    /// Id of the input descriptor satisfied.
+    pub id: String,
+    /// Claim format of the value `path` resolves to, such as `di_vc`.
+    pub format: String,
+    pub path: String,
+    /// Path into the value `path` resolves to, if the credential is nested further.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_nested: Option<Box<DescriptorMapEntry>>,
+}
+
+impl DescriptorMapEntry {
+    /// Resolves the value this entry points to within `presentation`, following nested paths.
+    pub fn resolve<'a>(&self, presentation: &'a Value) -> VcxResult<&'a Value> {
+        let mut selected = json_path::select(presentation, &self.path)?;
+        if selected.len() != 1 {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidProof,
+                format!(
+                    "Path {} of descriptor {} resolves to {} values instead of one",
+                    self.path,
+                    self.id,
+                    selected.len()
+                ),
+            ));
+        }
+        let value = selected.remove(0);
+        match &self.path_nested {
+            Some(nested) => nested.resolve(value),
+            None => Ok(value),
+        }
+    }
+}
@@ -1,12 +1,20 @@
+pub mod verification_report;
 mod verifier_internal;
 
-use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use anoncreds_types::data_types::messages::{
+    pres_request::PresentationRequest, presentation::Presentation,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::{BaseAnonCreds, CredentialDefinitionsMap};
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use time::OffsetDateTime;
 
 use crate::{
-    common::proofs::verifier::verifier_internal::{
-        build_cred_defs_json_verifier, build_rev_reg_defs_json, build_rev_reg_json,
-        build_schemas_json_verifier, get_credential_info, validate_proof_revealed_attributes,
+    common::proofs::verifier::{
+        verification_report::{build_verification_report, VerificationPolicy, VerificationReport},
+        verifier_internal::{
+            build_cred_defs_json_verifier, build_rev_reg_defs_json, build_rev_reg_json,
+            build_schemas_json_verifier, get_credential_info, validate_proof_revealed_attributes,
+        },
     },
     errors::error::prelude::*,
 };
@@ -18,7 +26,50 @@ pub async fn validate_indy_proof(
     proof_req_json: &str,
 ) -> VcxResult<bool> {
     validate_proof_revealed_attributes(proof_json)?;
+    let (valid, _) = verify_proof(ledger, anoncreds, proof_json, proof_req_json).await?;
+    Ok(valid)
+}
+
+/// Verifies the proof against the proof request and evaluates it against `policy`, returning a
+/// [VerificationReport] detailing the outcome for each requested referent. A proof which fails
+/// verification yields a report rather than an error, so that the reason for rejecting it can
+/// be recorded as well.
+pub async fn verify_indy_proof_with_report(
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+    proof_json: &str,
+    proof_req_json: &str,
+    policy: &VerificationPolicy,
+) -> VcxResult<VerificationReport> {
+    let encoding_valid = match validate_proof_revealed_attributes(proof_json) {
+        Ok(()) => true,
+        Err(err) if err.kind() == AriesVcxErrorKind::InvalidProof => {
+            warn!("verify_indy_proof_with_report >> {err}");
+            false
+        }
+        Err(err) => return Err(err),
+    };
+    let (valid, cred_defs) = verify_proof(ledger, anoncreds, proof_json, proof_req_json).await?;
 
+    let presentation: Presentation = serde_json::from_str(proof_json)?;
+    let proof_req: PresentationRequest = serde_json::from_str(proof_req_json)?;
+    Ok(build_verification_report(
+        proof_req.value(),
+        &presentation.requested_proof,
+        &presentation.identifiers,
+        &cred_defs,
+        encoding_valid && valid,
+        policy,
+        OffsetDateTime::now_utc().unix_timestamp() as u64,
+    ))
+}
+
+async fn verify_proof(
+    ledger: &impl AnoncredsLedgerRead,
+    anoncreds: &impl BaseAnonCreds,
+    proof_json: &str,
+    proof_req_json: &str,
+) -> VcxResult<(bool, CredentialDefinitionsMap)> {
     let credential_data = get_credential_info(proof_json)?;
     debug!("validate_indy_proof >> credential_data: {credential_data:?}");
     let credential_defs_json = build_cred_defs_json_verifier(ledger, &credential_data).await?;
@@ -36,7 +87,7 @@ pub async fn validate_indy_proof(
     debug!("validate_indy_proof >> proof_req_json: {proof_req_json}");
     debug!("validate_indy_proof >> rev_reg_defs_json: {rev_reg_defs_json}");
     debug!("validate_indy_proof >> rev_regs_json: {rev_regs_json}");
-    anoncreds
+    let valid = anoncreds
         .verifier_verify_proof(
             serde_json::from_str(proof_req_json)?,
             serde_json::from_str(proof_json)?,
@@ -45,6 +96,6 @@ pub async fn validate_indy_proof(
             serde_json::from_str(&rev_reg_defs_json)?,
             serde_json::from_str(&rev_regs_json)?,
         )
-        .await
-        .map_err(|err| err.into())
+        .await?;
+    Ok((valid, serde_json::from_str(&credential_defs_json)?))
 }
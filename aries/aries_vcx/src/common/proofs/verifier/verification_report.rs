@@ -0,0 +1,534 @@
+use std::collections::HashMap;
+
+use anoncreds_types::data_types::messages::{
+    pres_request::{PredicateTypes, PredicateValue, PresentationRequestPayload},
+    presentation::{Identifier, RequestedProof},
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::CredentialDefinitionsMap;
+
+use crate::protocols::proof_presentation::verifier::verification_status::PresentationVerificationStatus;
+
+/// Verifier-defined requirements a presentation has to meet on top of being cryptographically
+/// valid. The default policy places no additional requirements.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationPolicy {
+    /// Issuer ids credentials have to be issued by. If not set, credentials from any issuer are
+    /// accepted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accepted_issuers: Option<Vec<String>>,
+    /// Maximum age in seconds of the point in time a credential is proven to be valid at, which
+    /// is the timestamp of its non-revocation proof. Credentials presented without a timestamp
+    /// can not prove their age and do not satisfy this requirement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_credential_age: Option<u64>,
+    /// Whether every credential has to be presented with a non-revocation proof timestamp.
+    #[serde(default)]
+    pub require_timestamps: bool,
+}
+
+/// How a requested referent was answered in the presentation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentKind {
+    RevealedAttribute,
+    RevealedAttributeGroup,
+    UnrevealedAttribute,
+    SelfAttestedAttribute,
+    Predicate,
+    Missing,
+}
+
+/// Identifiers of the credential a referent was answered from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialReport {
+    pub issuer_id: Option<String>,
+    pub schema_id: String,
+    pub cred_def_id: String,
+    pub rev_reg_id: Option<String>,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredicateReport {
+    pub name: String,
+    pub p_type: PredicateTypes,
+    pub p_value: PredicateValue,
+    pub satisfied: bool,
+}
+
+/// Outcome of the revocation check of the credential a referent was answered from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum RevocationCheck {
+    /// The credential is not revocable, or the referent was not answered from a credential.
+    NotApplicable,
+    /// The credential is revocable, but non-revocation was neither requested nor proven.
+    NotChecked,
+    /// The credential was proven not to be revoked at `timestamp`.
+    Passed { timestamp: u64 },
+    /// Non-revocation could not be proven.
+    Failed { timestamp: Option<u64> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum PolicyViolation {
+    IssuerNotAccepted {
+        issuer_id: Option<String>,
+    },
+    TimestampMissing,
+    CredentialTooOld {
+        timestamp: u64,
+        max_credential_age: u64,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferentReport {
+    pub referent: String,
+    pub kind: ReferentKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential: Option<CredentialReport>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub revealed_values: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub predicate: Option<PredicateReport>,
+    pub revocation: RevocationCheck,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_violations: Vec<PolicyViolation>,
+}
+
+/// Detailed outcome of a presentation verification, recording for each requested referent
+/// which credential it was answered from, the disclosed values and the checks performed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// Whether the presentation is cryptographically valid with respect to the request.
+    pub proof_valid: bool,
+    /// Unix timestamp of the verification.
+    pub verified_at: u64,
+    pub policy: VerificationPolicy,
+    pub referents: Vec<ReferentReport>,
+}
+
+impl VerificationReport {
+    pub fn policy_satisfied(&self) -> bool {
+        self.referents
+            .iter()
+            .all(|referent| referent.policy_violations.is_empty())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.proof_valid && self.policy_satisfied()
+    }
+
+    pub fn verification_status(&self) -> PresentationVerificationStatus {
+        if self.is_valid() {
+            PresentationVerificationStatus::Valid
+        } else {
+            PresentationVerificationStatus::Invalid
+        }
+    }
+}
+
+/// Builds the verification report of a presentation answering `request`. `proof_valid` is the
+/// outcome of the cryptographic verification, which is what predicate and revocation results
+/// are derived from. Self-attested attributes are not subject to the issuer and timestamp
+/// requirements of `policy`, as the verifier explicitly allowed them to be self-attested.
+pub fn build_verification_report(
+    request: &PresentationRequestPayload,
+    requested_proof: &RequestedProof,
+    identifiers: &[Identifier],
+    cred_defs: &CredentialDefinitionsMap,
+    proof_valid: bool,
+    policy: &VerificationPolicy,
+    verified_at: u64,
+) -> VerificationReport {
+    let ctx = ReportContext {
+        identifiers,
+        cred_defs,
+        proof_valid,
+        policy,
+        verified_at,
+        revocation_requested: request.non_revoked.is_some(),
+    };
+
+    let mut referents = Vec::new();
+    for (referent, attr_info) in &request.requested_attributes {
+        let revocation_requested = attr_info.non_revoked.is_some();
+        let report = if let Some(info) = requested_proof.revealed_attrs.get(referent) {
+            let name = attr_info.name.clone().unwrap_or_default();
+            ctx.credential_referent(
+                referent,
+                ReferentKind::RevealedAttribute,
+                info.sub_proof_index,
+                HashMap::from([(name, info.raw.clone())]),
+                None,
+                revocation_requested,
+            )
+        } else if let Some(info) = requested_proof.revealed_attr_groups.get(referent) {
+            let values = info
+                .values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.raw.clone()))
+                .collect();
+            ctx.credential_referent(
+                referent,
+                ReferentKind::RevealedAttributeGroup,
+                info.sub_proof_index,
+                values,
+                None,
+                revocation_requested,
+            )
+        } else if let Some(info) = requested_proof.unrevealed_attrs.get(referent) {
+            ctx.credential_referent(
+                referent,
+                ReferentKind::UnrevealedAttribute,
+                info.sub_proof_index,
+                HashMap::new(),
+                None,
+                revocation_requested,
+            )
+        } else if let Some(value) = requested_proof.self_attested_attrs.get(referent) {
+            let name = attr_info.name.clone().unwrap_or_default();
+            uncredentialed_referent(
+                referent,
+                ReferentKind::SelfAttestedAttribute,
+                HashMap::from([(name, value.clone())]),
+            )
+        } else {
+            uncredentialed_referent(referent, ReferentKind::Missing, HashMap::new())
+        };
+        referents.push(report);
+    }
+
+    for (referent, pred_info) in &request.requested_predicates {
+        let report = match requested_proof.predicates.get(referent) {
+            Some(info) => ctx.credential_referent(
+                referent,
+                ReferentKind::Predicate,
+                info.sub_proof_index,
+                HashMap::new(),
+                Some(PredicateReport {
+                    name: pred_info.name.clone(),
+                    p_type: pred_info.p_type.clone(),
+                    p_value: pred_info.p_value,
+                    satisfied: proof_valid,
+                }),
+                pred_info.non_revoked.is_some(),
+            ),
+            None => uncredentialed_referent(referent, ReferentKind::Missing, HashMap::new()),
+        };
+        referents.push(report);
+    }
+    referents.sort_by(|a, b| a.referent.cmp(&b.referent));
+
+    VerificationReport {
+        proof_valid,
+        verified_at,
+        policy: policy.clone(),
+        referents,
+    }
+}
+
+struct ReportContext<'a> {
+    identifiers: &'a [Identifier],
+    cred_defs: &'a CredentialDefinitionsMap,
+    proof_valid: bool,
+    policy: &'a VerificationPolicy,
+    verified_at: u64,
+    revocation_requested: bool,
+}
+
+impl ReportContext<'_> {
+    fn credential_referent(
+        &self,
+        referent: &str,
+        kind: ReferentKind,
+        sub_proof_index: u32,
+        revealed_values: HashMap<String, String>,
+        predicate: Option<PredicateReport>,
+        revocation_requested: bool,
+    ) -> ReferentReport {
+        // a sub proof index without identifiers fails the cryptographic verification anyway
+        let Some(identifier) = self.identifiers.get(sub_proof_index as usize) else {
+            return ReferentReport {
+                revealed_values,
+                predicate,
+                ..uncredentialed_referent(referent, kind, HashMap::new())
+            };
+        };
+        let credential = CredentialReport {
+            issuer_id: self
+                .cred_defs
+                .get(&identifier.cred_def_id)
+                .map(|cred_def| cred_def.issuer_id.0.clone()),
+            schema_id: identifier.schema_id.0.clone(),
+            cred_def_id: identifier.cred_def_id.0.clone(),
+            rev_reg_id: identifier.rev_reg_id.as_ref().map(|id| id.0.clone()),
+            timestamp: identifier.timestamp,
+        };
+        let revocation = match (&credential.rev_reg_id, credential.timestamp) {
+            (None, _) => RevocationCheck::NotApplicable,
+            (Some(_), Some(timestamp)) if self.proof_valid => RevocationCheck::Passed { timestamp },
+            (Some(_), None) if !(revocation_requested || self.revocation_requested) => {
+                RevocationCheck::NotChecked
+            }
+            (Some(_), timestamp) => RevocationCheck::Failed { timestamp },
+        };
+        let policy_violations = self.policy_violations(&credential);
+        ReferentReport {
+            referent: referent.to_owned(),
+            kind,
+            credential: Some(credential),
+            revealed_values,
+            predicate,
+            revocation,
+            policy_violations,
+        }
+    }
+
+    fn policy_violations(&self, credential: &CredentialReport) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+        if let Some(accepted_issuers) = &self.policy.accepted_issuers {
+            let accepted = credential
+                .issuer_id
+                .as_ref()
+                .is_some_and(|issuer_id| accepted_issuers.contains(issuer_id));
+            if !accepted {
+                violations.push(PolicyViolation::IssuerNotAccepted {
+                    issuer_id: credential.issuer_id.clone(),
+                });
+            }
+        }
+        match (credential.timestamp, self.policy.max_credential_age) {
+            (Some(timestamp), Some(max_credential_age))
+                if self.verified_at.saturating_sub(timestamp) > max_credential_age =>
+            {
+                violations.push(PolicyViolation::CredentialTooOld {
+                    timestamp,
+                    max_credential_age,
+                });
+            }
+            (None, max_credential_age)
+                if self.policy.require_timestamps || max_credential_age.is_some() =>
+            {
+                violations.push(PolicyViolation::TimestampMissing);
+            }
+            _ => {}
+        }
+        violations
+    }
+}
+
+fn uncredentialed_referent(
+    referent: &str,
+    kind: ReferentKind,
+    revealed_values: HashMap<String, String>,
+) -> ReferentReport {
+    ReferentReport {
+        referent: referent.to_owned(),
+        kind,
+        credential: None,
+        revealed_values,
+        predicate: None,
+        revocation: RevocationCheck::NotApplicable,
+        policy_violations: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use anoncreds_types::data_types::{
+        identifiers::{
+            cred_def_id::CredentialDefinitionId, rev_reg_def_id::RevocationRegistryDefinitionId,
+            schema_id::SchemaId,
+        },
+        messages::{
+            nonce::Nonce,
+            pres_request::{AttributeInfo, NonRevokedInterval, PredicateInfo},
+            presentation::{RevealedAttributeInfo, SubProofReferent},
+        },
+    };
+
+    use super::*;
+
+    const ISSUER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+    const CRED_DEF_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag";
+    const REV_REG_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag:\
+                              CL_ACCUM:tag1";
+
+    fn request() -> PresentationRequestPayload {
+        PresentationRequestPayload::builder()
+            .nonce(Nonce::new().unwrap())
+            .name("test".to_owned())
+            .requested_attributes(HashMap::from([
+                (
+                    "attr_name".to_owned(),
+                    AttributeInfo::builder().name("name".to_owned()).build(),
+                ),
+                (
+                    "attr_self".to_owned(),
+                    AttributeInfo::builder().name("nickname".to_owned()).build(),
+                ),
+            ]))
+            .requested_predicates(HashMap::from([(
+                "pred_age".to_owned(),
+                PredicateInfo::builder()
+                    .name("age".to_owned())
+                    .p_type(PredicateTypes::GE)
+                    .p_value(18)
+                    .build(),
+            )]))
+            .non_revoked(Some(NonRevokedInterval::new(None, Some(2000))))
+            .build()
+    }
+
+    fn requested_proof() -> RequestedProof {
+        RequestedProof {
+            revealed_attrs: HashMap::from([(
+                "attr_name".to_owned(),
+                RevealedAttributeInfo {
+                    sub_proof_index: 0,
+                    raw: "Alice".to_owned(),
+                    encoded: "1".to_owned(),
+                },
+            )]),
+            self_attested_attrs: HashMap::from([("attr_self".to_owned(), "Ali".to_owned())]),
+            predicates: HashMap::from([(
+                "pred_age".to_owned(),
+                SubProofReferent { sub_proof_index: 0 },
+            )]),
+            ..Default::default()
+        }
+    }
+
+    fn identifiers(timestamp: Option<u64>) -> Vec<Identifier> {
+        vec![Identifier {
+            schema_id: SchemaId::new_unchecked(format!("{ISSUER_DID}:2:test:1.0")),
+            cred_def_id: CredentialDefinitionId::new_unchecked(CRED_DEF_ID),
+            rev_reg_id: Some(RevocationRegistryDefinitionId::new_unchecked(REV_REG_ID)),
+            timestamp,
+        }]
+    }
+
+    fn report(
+        timestamp: Option<u64>,
+        proof_valid: bool,
+        policy: &VerificationPolicy,
+    ) -> VerificationReport {
+        build_verification_report(
+            &request(),
+            &requested_proof(),
+            &identifiers(timestamp),
+            &HashMap::new(),
+            proof_valid,
+            policy,
+            2000,
+        )
+    }
+
+    #[test]
+    fn test_report_lists_referents() {
+        let report = report(Some(1500), true, &VerificationPolicy::default());
+        assert!(report.is_valid());
+        assert_eq!(
+            report.verification_status(),
+            PresentationVerificationStatus::Valid
+        );
+        let referents: Vec<_> = report.referents.iter().map(|r| &r.kind).collect();
+        assert_eq!(
+            referents,
+            vec![
+                &ReferentKind::RevealedAttribute,
+                &ReferentKind::SelfAttestedAttribute,
+                &ReferentKind::Predicate
+            ]
+        );
+
+        let attr = &report.referents[0];
+        assert_eq!(attr.revealed_values["name"], "Alice");
+        let credential = attr.credential.as_ref().unwrap();
+        assert_eq!(credential.cred_def_id, CRED_DEF_ID);
+        assert_eq!(attr.revocation, RevocationCheck::Passed { timestamp: 1500 });
+
+        let self_attested = &report.referents[1];
+        assert_eq!(self_attested.revealed_values["nickname"], "Ali");
+        assert!(self_attested.credential.is_none());
+
+        let predicate = report.referents[2].predicate.as_ref().unwrap();
+        assert!(predicate.satisfied);
+        assert_eq!(predicate.p_value, 18);
+    }
+
+    #[test]
+    fn test_report_invalid_proof() {
+        let report = report(Some(1500), false, &VerificationPolicy::default());
+        assert!(!report.is_valid());
+        assert!(report.policy_satisfied());
+        assert_eq!(
+            report.referents[0].revocation,
+            RevocationCheck::Failed {
+                timestamp: Some(1500)
+            }
+        );
+        assert!(!report.referents[2].predicate.as_ref().unwrap().satisfied);
+    }
+
+    #[test]
+    fn test_report_policy_accepted_issuers() {
+        let policy = VerificationPolicy {
+            accepted_issuers: Some(vec!["did:sov:unknown".to_owned()]),
+            ..Default::default()
+        };
+        let report = report(Some(1500), true, &policy);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.referents[0].policy_violations,
+            vec![PolicyViolation::IssuerNotAccepted { issuer_id: None }]
+        );
+        // self attested attributes are not subject to the policy
+        assert!(report.referents[1].policy_violations.is_empty());
+    }
+
+    #[test]
+    fn test_report_policy_credential_age() {
+        let policy = VerificationPolicy {
+            max_credential_age: Some(300),
+            ..Default::default()
+        };
+        assert!(report(Some(1800), true, &policy).is_valid());
+
+        let report_too_old = report(Some(1500), true, &policy);
+        assert_eq!(
+            report_too_old.referents[0].policy_violations,
+            vec![PolicyViolation::CredentialTooOld {
+                timestamp: 1500,
+                max_credential_age: 300
+            }]
+        );
+
+        let report_no_timestamp = report(None, true, &policy);
+        assert_eq!(
+            report_no_timestamp.referents[0].policy_violations,
+            vec![PolicyViolation::TimestampMissing]
+        );
+    }
+
+    #[test]
+    fn test_report_policy_required_timestamps() {
+        let policy = VerificationPolicy {
+            require_timestamps: true,
+            ..Default::default()
+        };
+        let report = report(None, true, &policy);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.referents[0].revocation,
+            RevocationCheck::Failed { timestamp: None }
+        );
+        assert_eq!(
+            report.referents[2].policy_violations,
+            vec![PolicyViolation::TimestampMissing]
+        );
+    }
+}
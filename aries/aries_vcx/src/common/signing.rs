@@ -1,8 +1,11 @@
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use base64::{self, Engine};
-use messages::msg_fields::protocols::connection::{
-    response::{ConnectionSignature, ResponseContent},
-    ConnectionData,
+use messages::msg_fields::protocols::{
+    connection::{
+        response::{ConnectionSignature, ResponseContent},
+        ConnectionData,
+    },
+    question_answer::question::QuestionContent,
 };
 use public_key::{Key, KeyType};
 use time;
@@ -97,6 +100,75 @@ pub async fn decode_signed_connection_response(
     Ok(connection)
 }
 
+/// Data signed by the responder of a question: the question text, the response and the nonce of
+/// the question, concatenated.
+fn answer_signed_data(question: &QuestionContent, response: &str) -> String {
+    format!("{}{}{}", question.question_text, response, question.nonce)
+}
+
+pub async fn sign_answer_response(
+    wallet: &impl BaseWallet,
+    key: &str,
+    question: &QuestionContent,
+    response: &str,
+) -> VcxResult<ConnectionSignature> {
+    let data = answer_signed_data(question, response);
+    let (signature, sig_data) = get_signature_data(wallet, data, key).await?;
+
+    let sig_data = URL_SAFE_LENIENT.encode(sig_data);
+    let signature = URL_SAFE_LENIENT.encode(signature);
+
+    Ok(ConnectionSignature::new(
+        signature,
+        sig_data,
+        key.to_string(),
+    ))
+}
+
+pub async fn verify_answer_response(
+    wallet: &impl BaseWallet,
+    response_sig: &ConnectionSignature,
+    question: &QuestionContent,
+    response: &str,
+    their_vk: &str,
+) -> VcxResult<()> {
+    if response_sig.signer != their_vk {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!(
+                "Answer was signed by {}, expected signer {their_vk}",
+                response_sig.signer
+            ),
+        ));
+    }
+
+    let signature = base64url_decode(&response_sig.signature)?;
+    let sig_data = base64url_decode(&response_sig.sig_data)?;
+
+    if !wallet
+        .verify(
+            &Key::from_base58(their_vk, KeyType::Ed25519)?,
+            &sig_data,
+            &signature,
+        )
+        .await?
+    {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Answer signature is invalid for the responder key",
+        ));
+    }
+
+    if sig_data.get(8..) != Some(answer_signed_data(question, response).as_bytes()) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Signed answer data does not match the question and response",
+        ));
+    }
+
+    Ok(())
+}
+
 // #[cfg(test)]
 // pub mod unit_tests {
 //     use crate::common::test_utils::{create_trustee_key, indy_handles_to_profile};
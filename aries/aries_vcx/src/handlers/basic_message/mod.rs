@@ -0,0 +1,226 @@
+//! Handler for the basic message protocol, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0095-basic-message/README.md>).
+//!
+//! Basic messages are sent over any established connection implementing [MessageConnection]. Sent
+//! and received messages are stored in the wallet, so that the chat history of each connection
+//! can be retrieved later on.
+
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+    record_tags::{RecordTag, RecordTags},
+};
+use chrono::{DateTime, Utc};
+use messages::{
+    decorators::localization::{Locale, MsgLocalization},
+    msg_fields::protocols::basic_message::{
+        BasicMessage, BasicMessageContent, BasicMessageDecorators,
+    },
+};
+use uuid::Uuid;
+
+use crate::{
+    errors::error::VcxResult,
+    transport::{MessageConnection, Transport},
+};
+
+/// Wallet record tag on stored basic messages, holding the id of the connection the message was
+/// exchanged over.
+pub const CONNECTION_ID_TAG: &str = "connection_id";
+/// Wallet record tag on stored basic messages, holding the [MessageDirection] of the message.
+pub const DIRECTION_TAG: &str = "direction";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+impl MessageDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MessageDirection::Sent => "sent",
+            MessageDirection::Received => "received",
+        }
+    }
+}
+
+/// A basic message exchanged over a connection, as stored in the chat history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub connection_id: String,
+    pub direction: MessageDirection,
+    pub message: BasicMessage,
+}
+
+impl ChatMessage {
+    pub fn content(&self) -> &str {
+        &self.message.content.content
+    }
+
+    pub fn sent_time(&self) -> DateTime<Utc> {
+        self.message.content.sent_time
+    }
+
+    pub fn locale(&self) -> Option<Locale> {
+        get_locale(&self.message)
+    }
+}
+
+/// Builds a basic message with `content`. If `locale` is set, the message is decorated with
+/// `~l10n`, telling the recipient which language the content is written in.
+pub fn build_basic_message(content: String, locale: Option<Locale>) -> BasicMessage {
+    let content = BasicMessageContent::builder()
+        .content(content)
+        .sent_time(Utc::now())
+        .build();
+    let decorators = match locale {
+        Some(locale) => BasicMessageDecorators::builder()
+            .l10n(MsgLocalization::builder().locale(locale).build())
+            .build(),
+        None => BasicMessageDecorators::default(),
+    };
+    BasicMessage::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+/// Returns the locale the content of `message` is written in, if the sender specified it.
+pub fn get_locale(message: &BasicMessage) -> Option<Locale> {
+    let l10n = message.decorators.l10n.as_ref()?;
+    if let Some(locale) = l10n.locale {
+        return Some(locale);
+    }
+    l10n.locales.as_ref().and_then(|locales| {
+        locales
+            .iter()
+            .find(|(_, fields)| fields.iter().any(|field| field == "content"))
+            .map(|(locale, _)| *locale)
+    })
+}
+
+/// Sends `message` over `connection` and stores it in the chat history of `connection_id`.
+pub async fn send_basic_message(
+    wallet: &impl BaseWallet,
+    connection: &impl MessageConnection,
+    transport: &impl Transport,
+    connection_id: &str,
+    message: BasicMessage,
+) -> VcxResult<ChatMessage> {
+    trace!(
+        "send_basic_message >>> connection_id: {}, message id: {}",
+        connection_id,
+        message.id
+    );
+    connection
+        .send_message(wallet, &message.clone().into(), transport)
+        .await?;
+    store_message(wallet, connection_id, MessageDirection::Sent, message).await
+}
+
+/// Stores `message`, received over the connection identified by `connection_id`, in its chat
+/// history.
+pub async fn receive_basic_message(
+    wallet: &impl BaseWallet,
+    connection_id: &str,
+    message: BasicMessage,
+) -> VcxResult<ChatMessage> {
+    trace!(
+        "receive_basic_message >>> connection_id: {}, message id: {}",
+        connection_id,
+        message.id
+    );
+    store_message(wallet, connection_id, MessageDirection::Received, message).await
+}
+
+async fn store_message(
+    wallet: &impl BaseWallet,
+    connection_id: &str,
+    direction: MessageDirection,
+    message: BasicMessage,
+) -> VcxResult<ChatMessage> {
+    let chat_message = ChatMessage {
+        connection_id: connection_id.to_owned(),
+        direction,
+        message,
+    };
+    let record = Record::builder()
+        .category(RecordCategory::BasicMessage)
+        .name(format!(
+            "{}::{}::{}",
+            connection_id,
+            direction.as_str(),
+            chat_message.message.id
+        ))
+        .value(serde_json::to_string(&chat_message)?)
+        .tags(RecordTags::new(vec![
+            RecordTag::new(CONNECTION_ID_TAG, connection_id),
+            RecordTag::new(DIRECTION_TAG, direction.as_str()),
+        ]))
+        .build();
+    wallet.add_record(record).await?;
+    Ok(chat_message)
+}
+
+/// Returns the basic messages exchanged over the connection identified by `connection_id`,
+/// ordered by the time they were sent.
+pub async fn get_chat_history(
+    wallet: &impl BaseWallet,
+    connection_id: &str,
+) -> VcxResult<Vec<ChatMessage>> {
+    let query = json!({ CONNECTION_ID_TAG: connection_id }).to_string();
+    let mut history = wallet
+        .search_record(RecordCategory::BasicMessage, Some(query))
+        .await?
+        .iter()
+        .map(|record| serde_json::from_str(record.value()))
+        .collect::<Result<Vec<ChatMessage>, _>>()?;
+    history.sort_by_key(ChatMessage::sent_time);
+    Ok(history)
+}
+
+/// Deletes the chat history of the connection identified by `connection_id`.
+pub async fn delete_chat_history(wallet: &impl BaseWallet, connection_id: &str) -> VcxResult<()> {
+    let query = json!({ CONNECTION_ID_TAG: connection_id }).to_string();
+    for record in wallet
+        .search_record(RecordCategory::BasicMessage, Some(query))
+        .await?
+    {
+        wallet
+            .delete_record(RecordCategory::BasicMessage, record.name())
+            .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_build_basic_message_with_locale() {
+        let locale = Locale::default();
+        let message = build_basic_message("hello".to_owned(), Some(locale));
+        assert_eq!(message.content.content, "hello");
+        assert_eq!(get_locale(&message), Some(locale));
+
+        let message = build_basic_message("hello".to_owned(), None);
+        assert_eq!(message.decorators.l10n, None);
+        assert_eq!(get_locale(&message), None);
+    }
+
+    #[test]
+    fn test_get_locale_from_locales() {
+        let message: BasicMessage = serde_json::from_value(json!({
+            "@id": "123",
+            "@type": "https://didcomm.org/basicmessage/1.0/message",
+            "content": "bonjour",
+            "sent_time": "2024-01-01T00:00:00Z",
+            "~l10n": { "locales": { "fr": ["content"] } }
+        }))
+        .unwrap();
+        let expected: Locale = serde_json::from_value(json!("fr")).unwrap();
+        assert_eq!(get_locale(&message), Some(expected));
+    }
+}
@@ -0,0 +1,347 @@
+//! Handler for the discover features protocol, in both its [1.0](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0031-discover-features/README.md>)
+//! and [2.0](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0557-discover-features-v2/README.md>)
+//! versions.
+//!
+//! Queries are answered from a [FeatureRegistry] holding what the agent actually supports. Query
+//! patterns may contain `*` wildcards, matching any sequence of characters; patterns without
+//! wildcards match every identifier they are a prefix of.
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::discover_features::{
+        disclose::{Disclose, DiscloseContent, DiscloseDecorators},
+        query::{Query, QueryContent, QueryDecorators},
+        v2::{
+            disclosures::{Disclosures, DisclosuresContent, DisclosuresDecorators},
+            queries::{Queries, QueriesContent, QueriesDecorators},
+            FeatureDisclosure, FeatureQuery, FeatureType,
+        },
+        ProtocolDescriptor,
+    },
+    msg_types::{registry::PROTOCOL_REGISTRY, Protocol, Role},
+};
+use shared::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+/// The features an agent discloses to discover features queries.
+#[derive(Clone, Debug, Default)]
+pub struct FeatureRegistry {
+    protocols: Vec<(Protocol, Vec<MaybeKnown<Role>>)>,
+    goal_codes: Vec<String>,
+}
+
+impl FeatureRegistry {
+    /// Creates an empty registry, disclosing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry disclosing every protocol of the [PROTOCOL_REGISTRY], with all of its
+    /// roles.
+    pub fn from_protocol_registry() -> Self {
+        let mut entries: Vec<_> = PROTOCOL_REGISTRY.values().flatten().collect();
+        entries.sort_by(|a, b| a.str_pid.cmp(&b.str_pid));
+        let protocols = entries
+            .into_iter()
+            .map(|entry| (entry.protocol, entry.roles.clone()))
+            .collect();
+        Self {
+            protocols,
+            goal_codes: Vec::new(),
+        }
+    }
+
+    /// Registers `protocol` as supported in `roles`, replacing any previous registration of it.
+    pub fn with_protocol(mut self, protocol: impl Into<Protocol>, roles: Vec<Role>) -> Self {
+        let protocol = protocol.into();
+        let roles = roles.into_iter().map(MaybeKnown::Known).collect();
+        match self.protocols.iter_mut().find(|(p, _)| *p == protocol) {
+            Some(entry) => entry.1 = roles,
+            None => self.protocols.push((protocol, roles)),
+        }
+        self
+    }
+
+    /// Stops disclosing `protocol`.
+    pub fn without_protocol(mut self, protocol: impl Into<Protocol>) -> Self {
+        let protocol = protocol.into();
+        self.protocols.retain(|(p, _)| *p != protocol);
+        self
+    }
+
+    /// Registers a goal code the agent can pursue. Goal codes are only disclosed through
+    /// discover features 2.0.
+    pub fn with_goal_code(mut self, goal_code: impl Into<String>) -> Self {
+        let goal_code = goal_code.into();
+        if !self.goal_codes.contains(&goal_code) {
+            self.goal_codes.push(goal_code);
+        }
+        self
+    }
+
+    /// Returns the registered protocols matching `pattern`.
+    pub fn query_protocols(&self, pattern: &str) -> Vec<ProtocolDescriptor> {
+        self.protocols
+            .iter()
+            .filter(|(protocol, _)| matches_pattern(pattern, &protocol.to_string()))
+            .map(|(protocol, roles)| ProtocolDescriptor {
+                pid: MaybeKnown::Known(*protocol),
+                roles: (!roles.is_empty()).then(|| roles.clone()),
+            })
+            .collect()
+    }
+
+    /// Returns the registered features matching any of `queries`. Queries for feature types
+    /// the registry does not know about match nothing.
+    pub fn query_features(&self, queries: &[FeatureQuery]) -> Vec<FeatureDisclosure> {
+        let mut disclosures: Vec<FeatureDisclosure> = Vec::new();
+        for query in queries {
+            let matched = match query.feature_type {
+                MaybeKnown::Known(FeatureType::Protocol) => self
+                    .query_protocols(&query.pattern)
+                    .into_iter()
+                    .map(|descriptor| FeatureDisclosure {
+                        feature_type: MaybeKnown::Known(FeatureType::Protocol),
+                        id: match descriptor.pid {
+                            MaybeKnown::Known(protocol) => protocol.to_string(),
+                            MaybeKnown::Unknown(pid) => pid,
+                        },
+                        roles: descriptor.roles,
+                    })
+                    .collect(),
+                MaybeKnown::Known(FeatureType::GoalCode) => self
+                    .goal_codes
+                    .iter()
+                    .filter(|goal_code| matches_pattern(&query.pattern, goal_code))
+                    .map(|goal_code| FeatureDisclosure {
+                        feature_type: MaybeKnown::Known(FeatureType::GoalCode),
+                        id: goal_code.clone(),
+                        roles: None,
+                    })
+                    .collect(),
+                MaybeKnown::Unknown(_) => Vec::new(),
+            };
+            for disclosure in matched {
+                if !disclosures.contains(&disclosure) {
+                    disclosures.push(disclosure);
+                }
+            }
+        }
+        disclosures
+    }
+
+    /// Answers a discover features 1.0 query.
+    pub fn handle_query(&self, query: &Query) -> Disclose {
+        let content = DiscloseContent::builder()
+            .protocols(self.query_protocols(&query.content.query))
+            .build();
+        let decorators = DiscloseDecorators::builder()
+            .thread(Thread::builder().thid(query.id.clone()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        Disclose::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build()
+    }
+
+    /// Answers discover features 2.0 queries.
+    pub fn handle_queries(&self, queries: &Queries) -> Disclosures {
+        let content = DisclosuresContent::builder()
+            .disclosures(self.query_features(&queries.content.queries))
+            .build();
+        let decorators = DisclosuresDecorators::builder()
+            .thread(Thread::builder().thid(queries.id.clone()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        Disclosures::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build()
+    }
+
+    /// Builds unsolicited discover features 2.0 disclosures of every registered feature.
+    pub fn disclose_all(&self) -> Disclosures {
+        let queries = [
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::Protocol))
+                .pattern("*".to_owned())
+                .build(),
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::GoalCode))
+                .pattern("*".to_owned())
+                .build(),
+        ];
+        let content = DisclosuresContent::builder()
+            .disclosures(self.query_features(&queries))
+            .build();
+        Disclosures::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(DisclosuresDecorators::default())
+            .build()
+    }
+}
+
+/// Builds a discover features 1.0 query for protocols matching `pattern`.
+pub fn build_query(pattern: String, comment: Option<String>) -> Query {
+    let content = QueryContent {
+        query: pattern,
+        comment,
+    };
+    let decorators = QueryDecorators::builder()
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    Query::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+/// Builds discover features 2.0 queries.
+pub fn build_queries(queries: Vec<FeatureQuery>) -> Queries {
+    let decorators = QueriesDecorators::builder()
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    Queries::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(QueriesContent::builder().queries(queries).build())
+        .decorators(decorators)
+        .build()
+}
+
+fn matches_pattern(pattern: &str, id: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let prefix = parts.next().unwrap_or_default();
+    let Some(mut rest) = id.strip_prefix(prefix) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((suffix, middle)) = parts.split_last() else {
+        // no wildcard, the pattern is a prefix of the id
+        return true;
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(suffix)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::msg_types::{
+        connection::ConnectionTypeV1,
+        discover_features::DiscoverFeaturesTypeV2,
+        present_proof::{PresentProofTypeV1, PresentProofTypeV2},
+    };
+
+    use super::*;
+
+    fn registry() -> FeatureRegistry {
+        FeatureRegistry::new()
+            .with_protocol(ConnectionTypeV1::new_v1_0(), vec![Role::Invitee])
+            .with_protocol(PresentProofTypeV1::new_v1_0(), vec![Role::Prover])
+            .with_protocol(PresentProofTypeV2::new_v2_0(), vec![Role::Prover])
+            .with_goal_code("aries.vc.verify")
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        let pid = "https://didcomm.org/present-proof/2.0";
+        assert!(matches_pattern("*", pid));
+        assert!(matches_pattern("https://didcomm.org/present-proof/", pid));
+        assert!(matches_pattern(
+            "https://didcomm.org/present-proof/2.*",
+            pid
+        ));
+        assert!(matches_pattern("https://didcomm.org/*/2.0", pid));
+        assert!(matches_pattern("*present-proof*", pid));
+        assert!(!matches_pattern(
+            "https://didcomm.org/present-proof/1.*",
+            pid
+        ));
+        assert!(!matches_pattern("*/1.0", pid));
+    }
+
+    #[test]
+    fn test_handle_query() {
+        let query = build_query("https://didcomm.org/present-proof/*".to_owned(), None);
+        let disclose = registry().handle_query(&query);
+        assert_eq!(disclose.decorators.thread.thid, query.id);
+        assert_eq!(
+            disclose.content.protocols,
+            vec![
+                ProtocolDescriptor::builder()
+                    .pid(MaybeKnown::Known(PresentProofTypeV1::new_v1_0().into()))
+                    .roles(vec![MaybeKnown::Known(Role::Prover)])
+                    .build(),
+                ProtocolDescriptor::builder()
+                    .pid(MaybeKnown::Known(PresentProofTypeV2::new_v2_0().into()))
+                    .roles(vec![MaybeKnown::Known(Role::Prover)])
+                    .build(),
+            ]
+        );
+
+        let registry = registry().without_protocol(PresentProofTypeV1::new_v1_0());
+        assert_eq!(registry.handle_query(&query).content.protocols.len(), 1);
+    }
+
+    #[test]
+    fn test_handle_queries() {
+        let queries = build_queries(vec![
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::Protocol))
+                .pattern("https://didcomm.org/connections/1.*".to_owned())
+                .build(),
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::GoalCode))
+                .pattern("aries.*".to_owned())
+                .build(),
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Unknown("gov-fw".to_owned()))
+                .pattern("*".to_owned())
+                .build(),
+        ]);
+        let disclosures = registry().handle_queries(&queries);
+        assert_eq!(
+            disclosures.decorators.thread.map(|thread| thread.thid),
+            Some(queries.id)
+        );
+        assert_eq!(
+            disclosures.content.disclosures,
+            vec![
+                FeatureDisclosure::builder()
+                    .feature_type(MaybeKnown::Known(FeatureType::Protocol))
+                    .id("https://didcomm.org/connections/1.0".to_owned())
+                    .roles(vec![MaybeKnown::Known(Role::Invitee)])
+                    .build(),
+                FeatureDisclosure::builder()
+                    .feature_type(MaybeKnown::Known(FeatureType::GoalCode))
+                    .id("aries.vc.verify".to_owned())
+                    .build(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_registry_from_protocol_registry() {
+        let registry = FeatureRegistry::from_protocol_registry();
+        let discover_features = registry.query_protocols("https://didcomm.org/discover-features/");
+        assert_eq!(discover_features.len(), 2);
+        assert_eq!(
+            discover_features[1].pid,
+            MaybeKnown::Known(DiscoverFeaturesTypeV2::new_v2_0().into())
+        );
+        assert_eq!(
+            registry.disclose_all().content.disclosures.len(),
+            PROTOCOL_REGISTRY.values().flatten().count()
+        );
+    }
+}
@@ -20,6 +20,7 @@ use messages::{
 };
 
 use crate::{
+    common::primitives::credential_definition::is_cred_def_retired,
     errors::error::prelude::*,
     handlers::util::OfferInfo,
     protocols::issuance::issuer::state_machine::{IssuerSM, IssuerState, RevocationInfoV1},
@@ -147,6 +148,15 @@ impl Issuer {
         offer_info: OfferInfo,
         comment: Option<String>,
     ) -> VcxResult<()> {
+        if is_cred_def_retired(wallet, &offer_info.cred_def_id).await? {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Credential definition {} has been retired and can no longer be offered",
+                    offer_info.cred_def_id
+                ),
+            ));
+        }
         let credential_preview = _build_credential_preview(&offer_info.credential_json)?;
         let libindy_cred_offer = anoncreds
             .issuer_create_credential_offer(wallet, &offer_info.cred_def_id)
@@ -0,0 +1,4 @@
+//! Handlers for using a mediator, as defined in the coordinate mediation [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0211-route-coordination/README.md>)
+//! and the pickup [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0685-pickup-v2/README.md>).
+
+pub mod recipient;
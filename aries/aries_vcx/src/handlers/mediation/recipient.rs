@@ -0,0 +1,660 @@
+//! Recipient side of mediation, for agents receiving their messages through a mediator.
+//!
+//! A [MediationRecipient] wraps an established connection to the mediator. Once mediation is
+//! granted, the keys of the invitations and peer DIDs created through it are registered with the
+//! mediator, and their services carry the endpoint and routing keys of the mediator, so that
+//! counterparties send messages through it. Queued messages are then picked up by polling, or
+//! pushed by the mediator in live mode, and handed unpacked to a callback.
+//!
+//! Requests are sent with `~transport.return_route` set and the mediator replies over the same
+//! connection, so the [Transport] used must support receiving replies.
+
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use base64::Engine;
+use did_key::DidKey;
+use did_peer::peer_did::{numalgos::numalgo4::Numalgo4, PeerDid};
+use diddoc_legacy::aries::service::AriesService;
+use messages::{
+    decorators::{attachment::AttachmentType, transport::ReturnRoute},
+    msg_fields::protocols::{
+        coordinate_mediation::{
+            v1::{
+                keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+                keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
+                CoordinateMediationV1, KeylistUpdate, KeylistUpdateContent, MediateGrantContent,
+                MediateRequest, MediateRequestContent,
+            },
+            CoordinateMediation,
+        },
+        pickup::{
+            v2::{
+                Delivery, DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators,
+                LiveDeliveryChange, LiveDeliveryChangeContent, LiveDeliveryChangeDecorators,
+                MessagesReceived, MessagesReceivedContent, MessagesReceivedDecorators, PickupV2,
+                StatusRequest, StatusRequestContent, StatusRequestDecorators,
+            },
+            Pickup,
+        },
+    },
+    AriesMessage,
+};
+use public_key::{Key, KeyType};
+use url::Url;
+use uuid::Uuid;
+
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        connection::pairwise_info::PairwiseInfo,
+        did_exchange::state_machine::helpers::create_peer_did_4,
+    },
+    transport::{MessageConnection, Transport},
+    utils::{
+        base64::URL_SAFE_LENIENT, didcomm_utils::parse_ed25519_key,
+        encryption_envelope::EncryptionEnvelope,
+    },
+};
+
+/// Endpoint and routing keys granted by the mediator, to be set on the services of our
+/// invitations and DID documents.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MediationGrant {
+    pub endpoint: Url,
+    /// Routing keys, as base58 verkeys
+    pub routing_keys: Vec<String>,
+}
+
+impl MediationGrant {
+    /// Parses the content of a grant. Routing keys are accepted as base58 verkeys, `did:key`s or
+    /// `did:key` URLs.
+    pub fn from_grant_content(content: MediateGrantContent) -> VcxResult<Self> {
+        let routing_keys = content
+            .routing_keys
+            .iter()
+            .map(|routing_key| Ok(parse_ed25519_key(routing_key)?.base58()))
+            .collect::<VcxResult<Vec<_>>>()?;
+        Ok(Self {
+            endpoint: Url::parse(&content.endpoint)?,
+            routing_keys,
+        })
+    }
+
+    /// Returns the routing keys as `did:key`s, the form DID document services refer to them in.
+    pub fn routing_did_keys(&self) -> VcxResult<Vec<String>> {
+        self.routing_keys
+            .iter()
+            .map(|routing_key| {
+                let key = Key::from_base58(routing_key, KeyType::Ed25519)?;
+                Ok(DidKey::try_from(key)?.to_string())
+            })
+            .collect()
+    }
+}
+
+/// Message delivered by the mediator, unpacked.
+#[derive(Clone, Debug)]
+pub struct MediatedMessage {
+    /// Id of the message in the queue of the mediator
+    pub id: String,
+    pub message: AriesMessage,
+    /// Key of the sender, unless the message was anoncrypted
+    pub sender_vk: Option<Key>,
+    pub recipient_vk: Key,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MediationRecipient<C> {
+    connection: C,
+    grant: Option<MediationGrant>,
+    recipient_keys: Vec<String>,
+}
+
+impl<C> MediationRecipient<C>
+where
+    C: MessageConnection,
+{
+    /// Creates the recipient of mediation by the counterparty of `connection`.
+    pub fn new(connection: C) -> Self {
+        Self {
+            connection,
+            grant: None,
+            recipient_keys: Vec::new(),
+        }
+    }
+
+    pub fn connection(&self) -> &C {
+        &self.connection
+    }
+
+    pub fn grant(&self) -> Option<&MediationGrant> {
+        self.grant.as_ref()
+    }
+
+    /// Returns the keys registered with the mediator, as base58 verkeys.
+    pub fn recipient_keys(&self) -> &[String] {
+        &self.recipient_keys
+    }
+
+    fn get_grant(&self) -> VcxResult<&MediationGrant> {
+        self.grant.as_ref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Mediation has not been granted",
+            )
+        })
+    }
+
+    async fn send_request(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        message: AriesMessage,
+    ) -> VcxResult<AriesMessage> {
+        self.connection
+            .send_message_with_return_route(wallet, &message, ReturnRoute::All, transport)
+            .await?
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Mediator did not reply, the transport must support return routes",
+                )
+            })
+    }
+
+    /// Requests mediation, returning the endpoint and routing keys granted by the mediator.
+    pub async fn request_mediation(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+    ) -> VcxResult<&MediationGrant> {
+        let request: AriesMessage = MediateRequest::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(MediateRequestContent::default())
+            .build();
+        match self.send_request(wallet, transport, request).await? {
+            AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+                CoordinateMediationV1::MediateGrant(grant),
+            )) => Ok(self
+                .grant
+                .insert(MediationGrant::from_grant_content(grant.content)?)),
+            AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+                CoordinateMediationV1::MediateDeny(_),
+            )) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                "Mediator denied mediation",
+            )),
+            reply => Err(unexpected_reply(&reply)),
+        }
+    }
+
+    /// Adds and removes recipient keys of the mediator's keylist, returning the result of each
+    /// update as reported by the mediator.
+    pub async fn update_keylist(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        updates: Vec<KeylistUpdateItem>,
+    ) -> VcxResult<Vec<KeylistUpdateResponseItem>> {
+        let update: AriesMessage = KeylistUpdate::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(KeylistUpdateContent::builder().updates(updates).build())
+            .build();
+        let response = match self.send_request(wallet, transport, update).await? {
+            AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+                CoordinateMediationV1::KeylistUpdateResponse(response),
+            )) => response,
+            reply => return Err(unexpected_reply(&reply)),
+        };
+        for item in &response.content.updated {
+            if !is_update_applied(&item.result) {
+                continue;
+            }
+            match item.action {
+                KeylistUpdateItemAction::Add => {
+                    if !self.recipient_keys.contains(&item.recipient_key) {
+                        self.recipient_keys.push(item.recipient_key.clone());
+                    }
+                }
+                KeylistUpdateItemAction::Remove => self
+                    .recipient_keys
+                    .retain(|recipient_key| recipient_key != &item.recipient_key),
+            }
+        }
+        Ok(response.content.updated)
+    }
+
+    /// Registers `recipient_key`, a base58 verkey, with the mediator, for it to accept messages
+    /// forwarded to the key.
+    pub async fn add_recipient_key(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        recipient_key: &str,
+    ) -> VcxResult<()> {
+        self.update_recipient_key(
+            wallet,
+            transport,
+            recipient_key,
+            KeylistUpdateItemAction::Add,
+        )
+        .await
+    }
+
+    /// Unregisters `recipient_key`, once messages sent to it are no longer expected, e.g. after
+    /// a DID rotation.
+    pub async fn remove_recipient_key(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        recipient_key: &str,
+    ) -> VcxResult<()> {
+        self.update_recipient_key(
+            wallet,
+            transport,
+            recipient_key,
+            KeylistUpdateItemAction::Remove,
+        )
+        .await
+    }
+
+    async fn update_recipient_key(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        recipient_key: &str,
+        action: KeylistUpdateItemAction,
+    ) -> VcxResult<()> {
+        let update = KeylistUpdateItem {
+            recipient_key: recipient_key.to_owned(),
+            action,
+        };
+        let results = self.update_keylist(wallet, transport, vec![update]).await?;
+        match results
+            .into_iter()
+            .find(|item| !is_update_applied(&item.result))
+        {
+            Some(item) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Mediator failed to apply {:?} of recipient key {}: {:?}",
+                    item.action, item.recipient_key, item.result
+                ),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a `did:peer:4` whose service has the endpoint and routing keys of the mediator,
+    /// registering its key with the mediator.
+    pub async fn create_peer_did_4(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+    ) -> VcxResult<(PeerDid<Numalgo4>, Key)> {
+        let grant = self.get_grant()?;
+        let (peer_did, key) =
+            create_peer_did_4(wallet, grant.endpoint.clone(), grant.routing_did_keys()?).await?;
+        self.add_recipient_key(wallet, transport, &key.base58())
+            .await?;
+        Ok((peer_did, key))
+    }
+
+    /// Creates a key and registers it with the mediator. Returns the service to append to
+    /// out-of-band invitations, receiving messages on the key through the mediator.
+    pub async fn create_invitation_service(
+        &mut self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+    ) -> VcxResult<AriesService> {
+        let grant = self.get_grant()?.clone();
+        let pairwise_info = PairwiseInfo::create(wallet).await?;
+        self.add_recipient_key(wallet, transport, &pairwise_info.pw_vk)
+            .await?;
+        Ok(AriesService::create()
+            .set_service_endpoint(grant.endpoint)
+            .set_recipient_keys(vec![pairwise_info.pw_vk])
+            .set_routing_keys(grant.routing_keys))
+    }
+
+    /// Returns the number of messages queued by the mediator.
+    pub async fn status(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+    ) -> VcxResult<u32> {
+        let request: AriesMessage = StatusRequest::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(StatusRequestContent::builder().build())
+            .decorators(StatusRequestDecorators::default())
+            .build();
+        match self.send_request(wallet, transport, request).await? {
+            AriesMessage::Pickup(Pickup::V2(PickupV2::Status(status))) => {
+                Ok(status.content.message_count)
+            }
+            reply => Err(unexpected_reply(&reply)),
+        }
+    }
+
+    /// Picks up to `limit` queued messages, see [Self::process_delivery]. Returns the number of
+    /// messages delivered, none being delivered once the queue is empty.
+    pub async fn pickup<F>(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        limit: u32,
+        handler: F,
+    ) -> VcxResult<usize>
+    where
+        F: FnMut(MediatedMessage) -> VcxResult<()>,
+    {
+        let request: AriesMessage = DeliveryRequest::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(DeliveryRequestContent::builder().limit(limit).build())
+            .decorators(DeliveryRequestDecorators::builder().build())
+            .build();
+        match self.send_request(wallet, transport, request).await? {
+            AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(delivery))) => {
+                self.process_delivery(wallet, transport, &delivery, handler)
+                    .await
+            }
+            // Replied to delivery requests when the queue is empty
+            AriesMessage::Pickup(Pickup::V2(PickupV2::Status(_))) => Ok(0),
+            reply => Err(unexpected_reply(&reply)),
+        }
+    }
+
+    /// Picks up queued messages in batches of up to `limit` messages, until the queue is empty.
+    /// Returns the number of messages delivered.
+    pub async fn pickup_all<F>(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        limit: u32,
+        mut handler: F,
+    ) -> VcxResult<usize>
+    where
+        F: FnMut(MediatedMessage) -> VcxResult<()>,
+    {
+        let mut delivered = 0;
+        loop {
+            match self.pickup(wallet, transport, limit, &mut handler).await? {
+                0 => return Ok(delivered),
+                count => delivered += count,
+            }
+        }
+    }
+
+    /// Unpacks the messages of `delivery`, hands them to `handler` and acknowledges them, for the
+    /// mediator to remove them from its queue. Deliveries pushed by the mediator in live mode are
+    /// to be processed with this method.
+    ///
+    /// Messages which cannot be unpacked are dropped. If `handler` fails, the messages it has not
+    /// handled are left in the queue, to be delivered again.
+    pub async fn process_delivery<F>(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        delivery: &Delivery,
+        mut handler: F,
+    ) -> VcxResult<usize>
+    where
+        F: FnMut(MediatedMessage) -> VcxResult<()>,
+    {
+        let mut received_ids = Vec::new();
+        let mut handled = Ok(());
+        for attachment in &delivery.content.attach {
+            let Some(id) = &attachment.id else {
+                warn!("Ignoring delivered message without id");
+                continue;
+            };
+            match unpack_delivered_message(wallet, id, &attachment.data.content).await {
+                Ok(message) => {
+                    if let Err(err) = handler(message) {
+                        handled = Err(err);
+                        break;
+                    }
+                }
+                Err(err) => warn!("Dropping delivered message {}: {}", id, err),
+            }
+            received_ids.push(id.clone());
+        }
+        let received_count = received_ids.len();
+        if received_count > 0 {
+            let messages_received: AriesMessage = MessagesReceived::builder()
+                .id(Uuid::new_v4().to_string())
+                .content(
+                    MessagesReceivedContent::builder()
+                        .message_id_list(received_ids)
+                        .build(),
+                )
+                .decorators(MessagesReceivedDecorators::default())
+                .build();
+            self.connection
+                .send_message(wallet, &messages_received, transport)
+                .await?;
+        }
+        handled.map(|()| received_count)
+    }
+
+    /// Asks the mediator to push messages as they arrive, or to stop doing so. Mediators push
+    /// messages over the session the request was sent on, so live mode requires a transport
+    /// keeping sessions open, such as WebSocket. Pushed deliveries are processed with
+    /// [Self::process_delivery].
+    pub async fn set_live_delivery(
+        &self,
+        wallet: &impl BaseWallet,
+        transport: &impl Transport,
+        live_delivery: bool,
+    ) -> VcxResult<()> {
+        let change: AriesMessage = LiveDeliveryChange::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(
+                LiveDeliveryChangeContent::builder()
+                    .live_delivery(live_delivery)
+                    .build(),
+            )
+            .decorators(LiveDeliveryChangeDecorators::default())
+            .build();
+        let reply = self
+            .connection
+            .send_message_with_return_route(wallet, &change, ReturnRoute::All, transport)
+            .await?;
+        match reply {
+            Some(AriesMessage::ReportProblem(problem_report)) => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!(
+                    "Mediator refused live delivery: {}",
+                    problem_report.content.description.code
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn is_update_applied(result: &KeylistUpdateItemResult) -> bool {
+    matches!(
+        result,
+        KeylistUpdateItemResult::Success | KeylistUpdateItemResult::NoChange
+    )
+}
+
+fn unexpected_reply(reply: &AriesMessage) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidMessageFormat,
+        format!("Unexpected reply of mediator: {:?}", reply),
+    )
+}
+
+async fn unpack_delivered_message(
+    wallet: &impl BaseWallet,
+    id: &str,
+    data: &AttachmentType,
+) -> VcxResult<MediatedMessage> {
+    let packed = match data {
+        AttachmentType::Base64(packed) => URL_SAFE_LENIENT.decode(packed)?,
+        AttachmentType::Json(packed) => serde_json::to_vec(packed)?,
+        _ => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Delivered message is neither base64 nor JSON",
+            ))
+        }
+    };
+    let (message, sender_vk, recipient_vk) =
+        EncryptionEnvelope::unpack_aries_msg(wallet, &packed, &None).await?;
+    Ok(MediatedMessage {
+        id: id.to_owned(),
+        message,
+        sender_vk,
+        recipient_vk,
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use messages::msg_fields::protocols::{
+        coordinate_mediation::v1::{
+            KeylistUpdateResponse, KeylistUpdateResponseContent, MediateGrant,
+        },
+        pickup::v2::{Status, StatusContent},
+    };
+    use test_utils::mock_wallet::MockWallet;
+
+    use super::*;
+
+    /// Connection replying to requests like a mediator would, recording the messages sent
+    #[derive(Default)]
+    struct MockMediatorConnection {
+        sent: Mutex<Vec<AriesMessage>>,
+    }
+
+    impl MockMediatorConnection {
+        fn reply(message: &AriesMessage) -> AriesMessage {
+            match message {
+                AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+                    CoordinateMediationV1::MediateRequest(_),
+                )) => MediateGrant::builder()
+                    .id("grant".to_owned())
+                    .content(MediateGrantContent {
+                        endpoint: "https://mediator.example.org/didcomm".to_owned(),
+                        routing_keys: vec![
+                            "did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th".to_owned(),
+                        ],
+                    })
+                    .build(),
+                AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+                    CoordinateMediationV1::KeylistUpdate(update),
+                )) => {
+                    let updated = update
+                        .content
+                        .updates
+                        .iter()
+                        .map(|item| KeylistUpdateResponseItem {
+                            recipient_key: item.recipient_key.clone(),
+                            action: item.action.clone(),
+                            result: KeylistUpdateItemResult::Success,
+                        })
+                        .collect();
+                    KeylistUpdateResponse::builder()
+                        .id("update-response".to_owned())
+                        .content(KeylistUpdateResponseContent { updated })
+                        .build()
+                }
+                _ => Status::builder()
+                    .id("status".to_owned())
+                    .content(StatusContent::builder().message_count(0).build())
+                    .build(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl MessageConnection for MockMediatorConnection {
+        async fn send_message<W, T>(
+            &self,
+            _wallet: &W,
+            message: &AriesMessage,
+            _transport: &T,
+        ) -> VcxResult<()>
+        where
+            W: BaseWallet,
+            T: Transport,
+        {
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+
+        async fn send_message_with_return_route<W, T>(
+            &self,
+            _wallet: &W,
+            message: &AriesMessage,
+            _return_route: ReturnRoute,
+            _transport: &T,
+        ) -> VcxResult<Option<AriesMessage>>
+        where
+            W: BaseWallet,
+            T: Transport,
+        {
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(Some(Self::reply(message)))
+        }
+    }
+
+    struct MockTransport;
+
+    #[async_trait]
+    impl Transport for MockTransport {
+        async fn send_message(&self, _msg: Vec<u8>, _service_endpoint: &Url) -> VcxResult<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_mediation_and_update_keylist() {
+        let mut recipient = MediationRecipient::new(MockMediatorConnection::default());
+        assert!(recipient
+            .create_invitation_service(&MockWallet, &MockTransport)
+            .await
+            .is_err());
+
+        let grant = recipient
+            .request_mediation(&MockWallet, &MockTransport)
+            .await
+            .unwrap();
+        assert_eq!(
+            grant.endpoint.as_str(),
+            "https://mediator.example.org/didcomm"
+        );
+        assert_eq!(
+            grant.routing_did_keys().unwrap(),
+            vec!["did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th"]
+        );
+
+        recipient
+            .add_recipient_key(&MockWallet, &MockTransport, "recipient_key")
+            .await
+            .unwrap();
+        assert_eq!(recipient.recipient_keys(), ["recipient_key"]);
+        recipient
+            .remove_recipient_key(&MockWallet, &MockTransport, "recipient_key")
+            .await
+            .unwrap();
+        assert!(recipient.recipient_keys().is_empty());
+
+        assert_eq!(
+            recipient.status(&MockWallet, &MockTransport).await.unwrap(),
+            0
+        );
+        let delivered = recipient
+            .pickup_all(&MockWallet, &MockTransport, 10, |message| {
+                panic!("Unexpected message {:?}", message)
+            })
+            .await
+            .unwrap();
+        assert_eq!(delivered, 0);
+    }
+}
@@ -1,4 +1,7 @@
+pub mod basic_message;
+pub mod discover_features;
 pub mod issuance;
+pub mod mediation;
 pub mod out_of_band;
 pub mod proof_presentation;
 pub mod revocation_notification;
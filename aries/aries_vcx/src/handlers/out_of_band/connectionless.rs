@@ -0,0 +1,253 @@
+//! Connectionless exchanges, as described in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0056-service-decorator/README.md>).
+//!
+//! A presentation request or a credential offer can be received attached to an out-of-band
+//! invitation, or decorated with `~service`, without a connection being established. Replies are
+//! then packed for the recipient keys of the requester's service and posted to its endpoint,
+//! using an ephemeral key of ours. Our own `~service` decorator is added on outgoing messages, so
+//! that the requester is able to respond (e.g. with an ack) in the same way.
+
+use aries_vcx_ledger::ledger::base_ledger::IndyLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_parser_nom::Did;
+use messages::{
+    decorators::service::Service,
+    msg_fields::protocols::{
+        cred_issuance::{v1::CredentialIssuanceV1, CredentialIssuance},
+        out_of_band::invitation::Invitation,
+        present_proof::{v1::PresentProofV1, PresentProof},
+    },
+    AriesMessage,
+};
+use public_key::{Key, KeyType};
+use url::Url;
+
+use crate::{
+    common::ledger::transactions::resolve_service, errors::error::prelude::*,
+    protocols::oob::normalize_keys_as_naked, transport::Transport,
+    utils::encryption_envelope::EncryptionEnvelope,
+};
+
+/// The service of the counterparty of a connectionless exchange, where replies are sent to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionlessService {
+    pub recipient_keys: Vec<Key>,
+    pub routing_keys: Vec<Key>,
+    pub service_endpoint: Url,
+}
+
+impl ConnectionlessService {
+    /// Resolves the first service of `invitation`. Services referenced by a public DID are
+    /// resolved on the ledger.
+    pub async fn from_invitation(
+        indy_ledger: &impl IndyLedgerRead,
+        invitation: &Invitation,
+    ) -> VcxResult<Self> {
+        let service = invitation.content.services.first().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Invitation {} does not contain any service", invitation.id),
+            )
+        })?;
+        let service = resolve_service(indy_ledger, service).await?;
+        Self::from_keys(
+            &service.recipient_keys,
+            &service.routing_keys,
+            service.service_endpoint,
+        )
+    }
+
+    /// Creates the service from the `~service` decorator of a received message.
+    pub fn from_service_decorator(service: &Service) -> VcxResult<Self> {
+        Self::from_keys(
+            &service.recipient_keys,
+            &service.routing_keys,
+            service.service_endpoint.clone(),
+        )
+    }
+
+    fn from_keys(
+        recipient_keys: &[String],
+        routing_keys: &[String],
+        service_endpoint: Url,
+    ) -> VcxResult<Self> {
+        let to_keys = |keys: &[String]| -> VcxResult<Vec<Key>> {
+            normalize_keys_as_naked(keys)?
+                .iter()
+                .map(|key| Key::from_base58(key, KeyType::Ed25519).map_err(AriesVcxError::from))
+                .collect()
+        };
+        let recipient_keys = to_keys(recipient_keys)?;
+        if recipient_keys.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Service of connectionless counterparty does not contain any recipient key",
+            ));
+        }
+        Ok(Self {
+            recipient_keys,
+            routing_keys: to_keys(routing_keys)?,
+            service_endpoint,
+        })
+    }
+}
+
+/// Creates a DID and its key, used only for a single connectionless exchange. Messages of the
+/// exchange are packed with the key.
+pub async fn create_ephemeral_did(wallet: &impl BaseWallet) -> VcxResult<(Did, Key)> {
+    let did_data = wallet.create_and_store_my_did(None, None).await?;
+    Ok((did_data.did().parse()?, did_data.verkey().to_owned()))
+}
+
+/// Builds the `~service` decorator telling the counterparty to reply to `ephemeral_key` at
+/// `service_endpoint`.
+pub fn build_service_decorator(
+    ephemeral_key: &Key,
+    service_endpoint: Url,
+    routing_keys: Vec<String>,
+) -> Service {
+    Service::builder()
+        .recipient_keys(vec![ephemeral_key.base58()])
+        .routing_keys(routing_keys)
+        .service_endpoint(service_endpoint)
+        .build()
+}
+
+/// Returns the `~service` decorator of `message`, if it is set.
+pub fn get_service_decorator(message: &AriesMessage) -> Option<&Service> {
+    match message {
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::RequestPresentation(msg))) => {
+            msg.decorators.service.as_ref()
+        }
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::Presentation(msg))) => {
+            msg.decorators.service.as_ref()
+        }
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::OfferCredential(msg),
+        )) => msg.decorators.service.as_ref(),
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::RequestCredential(msg),
+        )) => msg.decorators.service.as_ref(),
+        _ => None,
+    }
+}
+
+/// Sets the `~service` decorator of `message`. Only presentation requests, presentations,
+/// credential offers and credential requests can be exchanged without a connection.
+pub fn decorate_with_service(message: AriesMessage, service: Service) -> VcxResult<AriesMessage> {
+    let mut message = message;
+    match &mut message {
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::RequestPresentation(msg))) => {
+            msg.decorators.service = Some(service)
+        }
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::Presentation(msg))) => {
+            msg.decorators.service = Some(service)
+        }
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::OfferCredential(msg),
+        )) => msg.decorators.service = Some(service),
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::RequestCredential(msg),
+        )) => msg.decorators.service = Some(service),
+        _ => {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!("Message can not be sent without a connection: {message:?}"),
+            ))
+        }
+    };
+    Ok(message)
+}
+
+/// Packs `message` with `ephemeral_key` for the first recipient key of `their_service`, and
+/// posts it to its endpoint.
+pub async fn send_connectionless_message(
+    wallet: &impl BaseWallet,
+    transport: &impl Transport,
+    ephemeral_key: &Key,
+    their_service: &ConnectionlessService,
+    message: &AriesMessage,
+) -> VcxResult<()> {
+    trace!(
+        "send_connectionless_message >>> their_service: {:?}, message: {:?}",
+        their_service,
+        message
+    );
+    let envelope = EncryptionEnvelope::create_from_keys(
+        wallet,
+        json!(message).to_string().as_bytes(),
+        Some(ephemeral_key.to_owned()),
+        their_service.recipient_keys[0].to_owned(),
+        their_service.routing_keys.to_owned(),
+    )
+    .await?;
+    transport
+        .send_message(envelope.0, &their_service.service_endpoint)
+        .await
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::msg_fields::protocols::{
+        basic_message::BasicMessage,
+        present_proof::v1::present::{
+            PresentationV1, PresentationV1Content, PresentationV1Decorators,
+        },
+    };
+
+    use super::*;
+
+    const VERKEY: &str = "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K";
+
+    #[test]
+    fn test_service_from_decorator() {
+        let key = Key::from_base58(VERKEY, KeyType::Ed25519).unwrap();
+        let endpoint: Url = "https://example.org/kiosk".parse().unwrap();
+        let decorator = build_service_decorator(&key, endpoint.clone(), vec![]);
+
+        let service = ConnectionlessService::from_service_decorator(&decorator).unwrap();
+        assert_eq!(service.recipient_keys, vec![key]);
+        assert!(service.routing_keys.is_empty());
+        assert_eq!(service.service_endpoint, endpoint);
+
+        let mut decorator = decorator;
+        decorator.recipient_keys.clear();
+        assert!(ConnectionlessService::from_service_decorator(&decorator).is_err());
+    }
+
+    #[test]
+    fn test_decorate_with_service() {
+        let key = Key::from_base58(VERKEY, KeyType::Ed25519).unwrap();
+        let decorator =
+            build_service_decorator(&key, "https://example.org".parse().unwrap(), vec![]);
+
+        let presentation: AriesMessage = PresentationV1::builder()
+            .id("1".to_owned())
+            .content(
+                PresentationV1Content::builder()
+                    .presentations_attach(vec![])
+                    .build(),
+            )
+            .decorators(
+                PresentationV1Decorators::builder()
+                    .thread(
+                        messages::decorators::thread::Thread::builder()
+                            .thid("1".to_owned())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build()
+            .into();
+        let presentation = decorate_with_service(presentation, decorator.clone()).unwrap();
+        assert_eq!(get_service_decorator(&presentation), Some(&decorator));
+
+        let basic_message: BasicMessage = serde_json::from_value(json!({
+            "@id": "1",
+            "@type": "https://didcomm.org/basicmessage/1.0/message",
+            "content": "hello",
+            "sent_time": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert!(decorate_with_service(basic_message.into(), decorator).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod connectionless;
 pub mod receiver;
 pub mod sender;
 
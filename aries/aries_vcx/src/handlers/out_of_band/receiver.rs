@@ -5,7 +5,12 @@ use messages::{
     decorators::attachment::{Attachment, AttachmentType},
     msg_fields::protocols::{
         cred_issuance::v1::offer_credential::OfferCredentialV1,
-        out_of_band::{invitation::Invitation, OutOfBand},
+        out_of_band::{
+            invitation::{Invitation, OobService},
+            reuse::HandshakeReuse,
+            reuse_accepted::HandshakeReuseAccepted,
+            OutOfBand,
+        },
         present_proof::v1::request::RequestPresentationV1,
     },
     AriesMessage,
@@ -15,7 +20,10 @@ use serde_json::Value;
 use url::Url;
 
 use crate::{
-    errors::error::prelude::*, handlers::util::AttachmentId, utils::base64::URL_SAFE_LENIENT,
+    errors::error::prelude::*,
+    handlers::util::AttachmentId,
+    protocols::oob::{build_handshake_reuse_msg, normalize_keys_as_naked},
+    utils::base64::URL_SAFE_LENIENT,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -62,6 +70,55 @@ impl OutOfBandReceiver {
         self.oob.id.clone()
     }
 
+    /// Returns true if this invitation and `invitation` were issued by the same inviter, i.e. if
+    /// they share a public DID or a recipient key of an inline service.
+    pub fn matches_invitation(&self, invitation: &Invitation) -> bool {
+        let theirs = inviter_identifiers(invitation);
+        inviter_identifiers(&self.oob)
+            .iter()
+            .any(|identifier| theirs.contains(identifier))
+    }
+
+    /// Given the invitations from which existing connections were created, keyed by connection
+    /// id, returns the id of the first connection which can be reused instead of accepting this
+    /// invitation.
+    pub fn find_reusable_connection<'a>(
+        &self,
+        connections: impl IntoIterator<Item = (&'a str, &'a Invitation)>,
+    ) -> Option<&'a str> {
+        connections
+            .into_iter()
+            .find(|(_, invitation)| self.matches_invitation(invitation))
+            .map(|(connection_id, _)| connection_id)
+    }
+
+    /// Builds the handshake reuse message to be sent over the reused connection. Its parent
+    /// thread id is the id of this invitation.
+    pub fn build_handshake_reuse(&self) -> HandshakeReuse {
+        build_handshake_reuse_msg(&self.oob)
+    }
+
+    pub fn handle_handshake_reuse_accepted(
+        &self,
+        reuse_accepted: &HandshakeReuseAccepted,
+    ) -> VcxResult<()> {
+        trace!(
+            "OutOfBandReceiver::handle_handshake_reuse_accepted >>> reuse_accepted: {:?}",
+            reuse_accepted
+        );
+        match reuse_accepted.decorators.thread.pthid.as_deref() {
+            Some(pthid) if pthid == self.oob.id => Ok(()),
+            pthid => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Handshake reuse accepted message has parent thread id {:?}, expected the \
+                     invitation id {}",
+                    pthid, self.oob.id
+                ),
+            )),
+        }
+    }
+
     // TODO: There may be multiple A2AMessages in a single OoB msg
     pub fn extract_a2a_message(&self) -> VcxResult<Option<AriesMessage>> {
         trace!("OutOfBandReceiver::extract_a2a_message >>>");
@@ -125,6 +182,19 @@ fn extract_encoded_invitation_from_url(oob_url_string: &str) -> VcxResult<String
     Ok(base64_url_encoded_oob.into_owned())
 }
 
+fn inviter_identifiers(invitation: &Invitation) -> Vec<String> {
+    invitation
+        .content
+        .services
+        .iter()
+        .flat_map(|service| match service {
+            OobService::Did(did) => vec![did.clone()],
+            OobService::AriesService(service) => normalize_keys_as_naked(&service.recipient_keys)
+                .unwrap_or_else(|_| service.recipient_keys.clone()),
+        })
+        .collect()
+}
+
 impl Display for OutOfBandReceiver {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", json!(AriesMessage::from(self.oob.clone())))
@@ -337,4 +407,47 @@ mod tests {
 
         assert_eq!(OOB_URL, oob_url);
     }
+
+    #[test]
+    fn reuse_connection_of_matching_invitation() {
+        let receiver = OutOfBandReceiver::create_from_json_encoded_oob(JSON_OOB_INVITE).unwrap();
+
+        let mut other_invitation = _create_invitation();
+        other_invitation.id = "other-invitation".to_string();
+        other_invitation.content.services = vec![OobService::Did(
+            "did:sov:4ZPTGtHeXzW1E8GqmBfK2K".to_string(),
+        )];
+        let same_inviter = _create_invitation();
+
+        let connections = vec![
+            ("connection-1", &other_invitation),
+            ("connection-2", &same_inviter),
+        ];
+        assert_eq!(
+            receiver.find_reusable_connection(connections),
+            Some("connection-2")
+        );
+        assert!(!receiver.matches_invitation(&other_invitation));
+    }
+
+    #[test]
+    fn handshake_reuse_thread_correlation() {
+        let receiver = OutOfBandReceiver::create_from_json_encoded_oob(JSON_OOB_INVITE).unwrap();
+
+        let reuse = receiver.build_handshake_reuse();
+        assert_eq!(reuse.decorators.thread.thid, reuse.id);
+        assert_eq!(
+            reuse.decorators.thread.pthid.as_deref(),
+            Some(receiver.get_id().as_str())
+        );
+
+        let accepted = crate::protocols::oob::build_handshake_reuse_accepted_msg(&reuse).unwrap();
+        receiver.handle_handshake_reuse_accepted(&accepted).unwrap();
+
+        let mut unrelated = accepted;
+        unrelated.decorators.thread.pthid = Some("unrelated".to_string());
+        assert!(receiver
+            .handle_handshake_reuse_accepted(&unrelated)
+            .is_err());
+    }
 }
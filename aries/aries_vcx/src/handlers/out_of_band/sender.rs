@@ -6,6 +6,8 @@ use messages::{
         cred_issuance::{v1::CredentialIssuanceV1, CredentialIssuance},
         out_of_band::{
             invitation::{Invitation, InvitationContent, InvitationDecorators, OobService},
+            reuse::HandshakeReuse,
+            reuse_accepted::HandshakeReuseAccepted,
             OobGoalCode,
         },
         present_proof::{v1::PresentProofV1, PresentProof},
@@ -20,6 +22,7 @@ use uuid::Uuid;
 use crate::{
     errors::error::prelude::*,
     handlers::util::{make_attach_from_str, AttachmentId},
+    protocols::oob::build_handshake_reuse_accepted_msg,
     utils::base64::URL_SAFE_LENIENT,
 };
 
@@ -131,6 +134,40 @@ impl OutOfBandSender {
         Ok(self)
     }
 
+    /// Handles a handshake reuse message, received over an existing connection, whose parent
+    /// thread id references this invitation, either by its id or by one of its public DIDs.
+    /// Returns the handshake reuse accepted message to be sent back over the same connection.
+    pub fn handle_handshake_reuse(
+        &self,
+        handshake_reuse: &HandshakeReuse,
+    ) -> VcxResult<HandshakeReuseAccepted> {
+        trace!(
+            "OutOfBandSender::handle_handshake_reuse >>> handshake_reuse: {:?}",
+            handshake_reuse
+        );
+        let pthid = handshake_reuse.decorators.thread.pthid.as_deref();
+        let references_invitation = pthid.is_some_and(|pthid| {
+            pthid == self.oob.id
+                || self
+                    .oob
+                    .content
+                    .services
+                    .iter()
+                    .any(|service| matches!(service, OobService::Did(did) if did == pthid))
+        });
+        if !references_invitation {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Handshake reuse message has parent thread id {:?}, which does not \
+                     reference invitation {}",
+                    pthid, self.oob.id
+                ),
+            ));
+        }
+        build_handshake_reuse_accepted_msg(handshake_reuse)
+    }
+
     pub fn invitation_to_aries_message(&self) -> AriesMessage {
         self.oob.clone().into()
     }
@@ -241,6 +278,32 @@ mod tests {
 
         assert_eq!(OOB_URL, oob_url);
     }
+
+    #[test]
+    fn handle_handshake_reuse() {
+        let out_of_band_sender = OutOfBandSender::create_from_invitation(_create_invitation());
+
+        let reuse = crate::protocols::oob::build_handshake_reuse_msg(&out_of_band_sender.oob);
+        let accepted = out_of_band_sender.handle_handshake_reuse(&reuse).unwrap();
+        assert_eq!(accepted.decorators.thread.thid, reuse.id);
+        assert_eq!(
+            accepted.decorators.thread.pthid.as_deref(),
+            Some(out_of_band_sender.get_id().as_str())
+        );
+
+        let mut public_did_reuse = reuse.clone();
+        public_did_reuse.decorators.thread.pthid =
+            Some("did:sov:LjgpST2rjsoxYegQDRm7EL".to_string());
+        assert!(out_of_band_sender
+            .handle_handshake_reuse(&public_did_reuse)
+            .is_ok());
+
+        let mut unrelated_reuse = reuse;
+        unrelated_reuse.decorators.thread.pthid = Some("unrelated".to_string());
+        assert!(out_of_band_sender
+            .handle_handshake_reuse(&unrelated_reuse)
+            .is_err());
+    }
 }
 
 // #[cfg(test)]
@@ -17,6 +17,7 @@ use messages::{
 };
 
 use crate::{
+    common::proofs::verifier::verification_report::{VerificationPolicy, VerificationReport},
     errors::error::prelude::*,
     handlers::util::get_attach_as_string,
     protocols::{
@@ -107,6 +108,28 @@ impl Verifier {
         self.verifier_sm.get_final_message()
     }
 
+    /// Verifies the presentation like [Verifier::verify_presentation], additionally requiring it
+    /// to satisfy `policy`. The outcome is recorded in a report, see
+    /// [Verifier::get_verification_report].
+    pub async fn verify_presentation_with_policy(
+        &mut self,
+        ledger: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        presentation: PresentationV1,
+        policy: &VerificationPolicy,
+    ) -> VcxResult<AriesMessage> {
+        trace!(
+            "Verifier::verify_presentation_with_policy >>> policy: {:?}",
+            policy
+        );
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .verify_presentation_with_policy(ledger, anoncreds, presentation, policy)
+            .await?;
+        self.verifier_sm.get_final_message()
+    }
+
     pub fn set_presentation_request(
         &mut self,
         presentation_request_data: PresentationRequest,
@@ -144,6 +167,18 @@ impl Verifier {
         self.verifier_sm.get_verification_status()
     }
 
+    pub fn get_verification_report(&self) -> VcxResult<VerificationReport> {
+        self.verifier_sm
+            .get_verification_report()
+            .cloned()
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::NotReady,
+                    "Verification report is not available",
+                )
+            })
+    }
+
     pub fn get_presentation_attachment(&self) -> VcxResult<String> {
         let presentation = &self.verifier_sm.get_presentation_msg()?;
         Ok(get_attach_as_string!(
@@ -1,14 +1,18 @@
 use anoncreds_types::data_types::identifiers::cred_def_id::CredentialDefinitionId;
 use messages::{
     msg_fields::protocols::{
+        action_menu::ActionMenu,
         connection::{invitation::Invitation, Connection},
-        coordinate_mediation::CoordinateMediation,
+        coordinate_mediation::{
+            v1::CoordinateMediationV1, v2::CoordinateMediationV2, CoordinateMediation,
+        },
         cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
         did_exchange::{v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, DidExchange},
+        did_rotate::DidRotate,
         discover_features::DiscoverFeatures,
         notification::Notification,
         out_of_band::{invitation::Invitation as OobInvitation, OutOfBand},
-        pickup::Pickup,
+        pickup::{v2::PickupV2, v3::PickupV3, Pickup},
         present_proof::{
             v1::{
                 propose::{Predicate, PresentationAttr},
@@ -17,6 +21,7 @@ use messages::{
             v2::PresentProofV2,
             PresentProof,
         },
+        question_answer::QuestionAnswer,
         report_problem::ProblemReport,
         revocation::Revocation,
         trust_ping::TrustPing,
@@ -162,6 +167,10 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => {
             matches_thread_id!(msg, thread_id)
         }
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Queries(msg)) => msg.id == thread_id,
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclosures(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
         AriesMessage::Notification(Notification::Ack(msg)) => matches_thread_id!(msg, thread_id),
         AriesMessage::Notification(Notification::ProblemReport(msg)) => {
             matches_opt_thread_id!(msg, thread_id)
@@ -209,40 +218,84 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::Routing(msg) => msg.id == thread_id,
         AriesMessage::TrustPing(TrustPing::Ping(msg)) => matches_opt_thread_id!(msg, thread_id),
         AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => matches_thread_id!(msg, thread_id),
-        AriesMessage::Pickup(Pickup::Status(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Pickup(Pickup::StatusRequest(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Pickup(Pickup::Delivery(msg)) => matches_opt_thread_id!(msg, thread_id),
-        AriesMessage::Pickup(Pickup::DeliveryRequest(msg)) => {
+        AriesMessage::Pickup(Pickup::V2(PickupV2::Status(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-
-        AriesMessage::Pickup(Pickup::MessagesReceived(msg)) => {
+        AriesMessage::Pickup(Pickup::V2(PickupV2::StatusRequest(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::Pickup(Pickup::LiveDeliveryChange(msg)) => {
+        AriesMessage::Pickup(Pickup::V2(PickupV2::Delivery(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::MediateRequest(msg)) => {
-            msg.id == thread_id
+        AriesMessage::Pickup(Pickup::V2(PickupV2::DeliveryRequest(msg))) => {
+            matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::MediateDeny(msg)) => {
+        AriesMessage::Pickup(Pickup::V2(PickupV2::MessagesReceived(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(msg)) => {
+        AriesMessage::Pickup(Pickup::V2(PickupV2::LiveDeliveryChange(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdate(msg)) => {
-            msg.id == thread_id
+        AriesMessage::Pickup(Pickup::V3(PickupV3::Status(msg))) => {
+            matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(msg)) => {
+        AriesMessage::Pickup(Pickup::V3(PickupV3::StatusRequest(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::KeylistQuery(msg)) => {
-            msg.id == thread_id
+        AriesMessage::Pickup(Pickup::V3(PickupV3::Delivery(msg))) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Pickup(Pickup::V3(PickupV3::DeliveryRequest(msg))) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Pickup(Pickup::V3(PickupV3::MessagesReceived(msg))) => {
+            matches_opt_thread_id!(msg, thread_id)
         }
-        AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(msg)) => {
+        AriesMessage::Pickup(Pickup::V3(PickupV3::LiveDeliveryChange(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
         }
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::MediateRequest(msg),
+        )) => msg.id == thread_id,
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::MediateDeny(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::MediateGrant(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::KeylistUpdate(msg),
+        )) => msg.id == thread_id,
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::KeylistUpdateResponse(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::KeylistQuery(msg),
+        )) => msg.id == thread_id,
+        AriesMessage::CoordinateMediation(CoordinateMediation::V1(
+            CoordinateMediationV1::Keylist(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::MediateRequest(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::MediateDeny(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::MediateGrant(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::RecipientUpdate(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::RecipientUpdateResponse(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::RecipientQuery(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediation(CoordinateMediation::V2(
+            CoordinateMediationV2::Recipient(msg),
+        )) => matches_opt_thread_id!(msg, thread_id),
         AriesMessage::DidExchange(DidExchange::V1_0(DidExchangeV1_0::Request(msg)))
         | AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::Request(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
@@ -261,6 +314,26 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::Response(msg))) => {
             matches_thread_id!(msg, thread_id)
         }
+        AriesMessage::DidRotate(DidRotate::Rotate(msg)) => msg.id == thread_id,
+        AriesMessage::DidRotate(DidRotate::Ack(msg)) => matches_thread_id!(msg, thread_id),
+        AriesMessage::DidRotate(DidRotate::ProblemReport(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::DidRotate(DidRotate::Hangup(msg)) => msg.id == thread_id,
+        AriesMessage::ActionMenu(ActionMenu::Menu(msg)) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::ActionMenu(ActionMenu::MenuRequest(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::ActionMenu(ActionMenu::Perform(msg)) => matches_thread_id!(msg, thread_id),
+        AriesMessage::ActionMenu(ActionMenu::ProblemReport(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::QuestionAnswer(QuestionAnswer::Question(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::QuestionAnswer(QuestionAnswer::Answer(msg)) => {
+            matches_thread_id!(msg, thread_id)
+        }
     };
 
     if !is_match {
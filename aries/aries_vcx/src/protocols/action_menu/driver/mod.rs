@@ -0,0 +1,133 @@
+use messages::msg_fields::protocols::action_menu::{
+    menu::{Menu, MenuContent, MenuOption},
+    menu_request::MenuRequest,
+    perform::Perform,
+    problem_report::ActionMenuProblemReport,
+};
+
+use super::{
+    build_menu, build_problem_report,
+    states::{Completed, MenuSent, PerformReceived},
+    validate_perform, MenuProblemCode,
+};
+use crate::protocols::did_exchange::transition::transition_result::TransitionResult;
+
+/// Responder of the action menu protocol, presenting menus and acting on performed options, in
+/// state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MenuDriver<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> MenuDriver<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl MenuDriver<MenuSent> {
+    /// Presents a menu without it being requested, starting a new thread.
+    pub fn send_menu(content: MenuContent) -> Self {
+        let menu = build_menu(content, None);
+        Self {
+            thread_id: menu.id.clone(),
+            state: MenuSent { menu },
+        }
+    }
+
+    /// Presents a menu in response to `request`, on the thread of the request.
+    pub fn handle_menu_request(request: &MenuRequest, content: MenuContent) -> Self {
+        let thread_id = request
+            .decorators
+            .thread
+            .as_ref()
+            .map_or(request.id.as_str(), |thread| thread.thid.as_str())
+            .to_owned();
+        Self {
+            state: MenuSent {
+                menu: build_menu(content, Some(&thread_id)),
+            },
+            thread_id,
+        }
+    }
+
+    /// The menu to be sent to the requester.
+    pub fn get_menu(&self) -> &Menu {
+        &self.state.menu
+    }
+
+    /// Validates the option selected by `perform` against the menu sent. If the option is not
+    /// available on this thread, a problem report is to be sent back to the requester, and the
+    /// menu remains open.
+    pub fn receive_perform(
+        self,
+        perform: Perform,
+    ) -> Result<MenuDriver<PerformReceived>, TransitionResult<Self, ActionMenuProblemReport>> {
+        let validation = if perform.decorators.thread.thid == self.thread_id {
+            validate_perform(&self.state.menu, &perform).cloned()
+        } else {
+            Err(MenuProblemCode::OptionNotAvailable)
+        };
+        let option = match validation {
+            Ok(option) => option,
+            Err(code) => {
+                warn!(
+                    "Rejecting performed option {}, code: {}",
+                    perform.content.name,
+                    code.as_ref()
+                );
+                let problem_report = build_problem_report(
+                    code,
+                    &perform.content.name,
+                    &perform.decorators.thread.thid,
+                );
+                return Err(TransitionResult {
+                    state: self,
+                    output: problem_report,
+                });
+            }
+        };
+        Ok(MenuDriver {
+            state: PerformReceived { option, perform },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl MenuDriver<PerformReceived> {
+    pub fn get_perform(&self) -> &Perform {
+        &self.state.perform
+    }
+
+    /// The option of the menu which was performed.
+    pub fn get_selected_option(&self) -> &MenuOption {
+        &self.state.option
+    }
+
+    /// Presents the menu following the performed option, on the same thread.
+    pub fn send_menu(self, content: MenuContent) -> TransitionResult<MenuDriver<MenuSent>, Menu> {
+        let menu = build_menu(content, Some(&self.thread_id));
+        TransitionResult {
+            state: MenuDriver {
+                state: MenuSent { menu: menu.clone() },
+                thread_id: self.thread_id,
+            },
+            output: menu,
+        }
+    }
+
+    /// Acts upon the performed option without presenting a new menu.
+    pub fn complete(self) -> MenuDriver<Completed> {
+        MenuDriver {
+            state: Completed {
+                perform: self.state.perform,
+            },
+            thread_id: self.thread_id,
+        }
+    }
+}
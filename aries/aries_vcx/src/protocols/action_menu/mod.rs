@@ -0,0 +1,224 @@
+//! State machines for the [action menu 1.0 protocol](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0509-action-menu/README.md>).
+//!
+//! The [driver::MenuDriver] (responder) presents menus to the [requester::MenuRequester]. Each
+//! option the requester performs is validated against the menu it was selected from, after which
+//! the driver may answer with a new menu on the same thread, letting the requester navigate
+//! through the menus of the driver.
+
+pub mod driver;
+pub mod requester;
+pub mod states;
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::{
+        action_menu::{
+            menu::{Menu, MenuContent, MenuDecorators, MenuOption},
+            menu_request::{MenuRequest, MenuRequestContent, MenuRequestDecorators},
+            perform::Perform,
+            problem_report::ActionMenuProblemReport,
+        },
+        report_problem::{Description, ProblemReportContent, ProblemReportDecorators},
+    },
+};
+use strum_macros::{AsRefStr, EnumString};
+use uuid::Uuid;
+
+/// Problem codes used by the driver to reject a performed option.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsRefStr, EnumString)]
+pub enum MenuProblemCode {
+    #[strum(serialize = "e.msg.option-not-available")]
+    OptionNotAvailable,
+    #[strum(serialize = "e.msg.missing-param")]
+    MissingParam,
+}
+
+/// Builds a message asking the driver to send its current menu.
+pub fn build_menu_request() -> MenuRequest {
+    MenuRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(MenuRequestContent::default())
+        .decorators(
+            MenuRequestDecorators::builder()
+                .timing(Timing::builder().out_time(Utc::now()).build())
+                .build(),
+        )
+        .build()
+}
+
+fn build_menu(content: MenuContent, thread_id: Option<&str>) -> Menu {
+    let decorators = match thread_id {
+        Some(thread_id) => MenuDecorators::builder()
+            .thread(Thread::builder().thid(thread_id.to_owned()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build(),
+        None => MenuDecorators::builder()
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build(),
+    };
+    Menu::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+fn build_problem_report(
+    code: MenuProblemCode,
+    option_name: &str,
+    thread_id: &str,
+) -> ActionMenuProblemReport {
+    let content = ProblemReportContent::builder()
+        .description(
+            Description::builder()
+                .code(code.as_ref().to_owned())
+                .build(),
+        )
+        .problem_items(vec![HashMap::from([(
+            "name".to_owned(),
+            option_name.to_owned(),
+        )])])
+        .build();
+    let decorators = ProblemReportDecorators::builder()
+        .thread(Thread::builder().thid(thread_id.to_owned()).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    ActionMenuProblemReport::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content.into())
+        .decorators(decorators)
+        .build()
+}
+
+/// Returns the option of `menu` selected by `perform`, as long as it is enabled and all the
+/// required parameters of its form were filled in.
+fn validate_perform<'a>(
+    menu: &'a Menu,
+    perform: &Perform,
+) -> Result<&'a MenuOption, MenuProblemCode> {
+    let option = menu
+        .content
+        .options
+        .iter()
+        .find(|option| option.name == perform.content.name && !option.disabled)
+        .ok_or(MenuProblemCode::OptionNotAvailable)?;
+    let missing_param = option
+        .form
+        .iter()
+        .flat_map(|form| &form.params)
+        .any(|param| {
+            param.required
+                && perform
+                    .content
+                    .params
+                    .get(&param.name)
+                    .map_or(true, |value| value.is_empty())
+        });
+    if missing_param {
+        return Err(MenuProblemCode::MissingParam);
+    }
+    Ok(option)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::msg_fields::protocols::action_menu::{
+        menu::{MenuForm, MenuFormParam},
+        perform::{PerformContent, PerformDecorators},
+    };
+
+    use super::{driver::MenuDriver, requester::MenuRequester, *};
+    use crate::handlers::discover_features::FeatureRegistry;
+
+    fn make_menu_content(title: &str) -> MenuContent {
+        let search = MenuOption::builder()
+            .name("search".to_owned())
+            .title("Search".to_owned())
+            .form(
+                MenuForm::builder()
+                    .params(vec![MenuFormParam::builder()
+                        .name("query".to_owned())
+                        .title("Query".to_owned())
+                        .required(true)
+                        .build()])
+                    .build(),
+            )
+            .build();
+        let disabled = MenuOption::builder()
+            .name("disabled".to_owned())
+            .title("Disabled".to_owned())
+            .disabled(true)
+            .build();
+        MenuContent::builder()
+            .title(title.to_owned())
+            .options(vec![search, disabled])
+            .build()
+    }
+
+    #[test]
+    fn test_perform_leads_to_new_menu() {
+        let request = build_menu_request();
+        let driver = MenuDriver::handle_menu_request(&request, make_menu_content("root"));
+        assert_eq!(driver.get_thread_id(), request.id);
+
+        let requester = MenuRequester::receive_menu(driver.get_menu().clone());
+        assert_eq!(requester.get_thread_id(), request.id);
+        let perform = requester
+            .perform(
+                "search",
+                HashMap::from([("query".to_owned(), "Alice".to_owned())]),
+            )
+            .unwrap();
+        assert_eq!(perform.output.decorators.thread.thid, request.id);
+
+        let driver = driver.receive_perform(perform.output).unwrap();
+        assert_eq!(driver.get_selected_option().name, "search");
+        let next = driver.send_menu(make_menu_content("results"));
+        assert_eq!(
+            next.output.decorators.thread.as_ref().unwrap().thid,
+            request.id
+        );
+
+        let requester = perform.state.receive_menu(next.output).unwrap();
+        assert_eq!(requester.get_menu().content.title, "results");
+    }
+
+    #[test]
+    fn test_perform_rejected() {
+        let driver = MenuDriver::send_menu(make_menu_content("root"));
+        let requester = MenuRequester::receive_menu(driver.get_menu().clone());
+
+        let perform = Perform::builder()
+            .id("1".to_owned())
+            .content(PerformContent::builder().name("search".to_owned()).build())
+            .decorators(
+                PerformDecorators::builder()
+                    .thread(
+                        Thread::builder()
+                            .thid(driver.get_thread_id().to_owned())
+                            .build(),
+                    )
+                    .build(),
+            )
+            .build();
+        let rejected = driver.receive_perform(perform).unwrap_err();
+        assert_eq!(
+            rejected.output.content.inner.description.code,
+            MenuProblemCode::MissingParam.as_ref()
+        );
+
+        assert!(requester.clone().perform("search", HashMap::new()).is_err());
+        assert!(requester.perform("disabled", HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_action_menu_is_discoverable() {
+        let registry = FeatureRegistry::from_protocol_registry();
+        assert!(!registry
+            .query_protocols("https://didcomm.org/action-menu/*")
+            .is_empty());
+    }
+}
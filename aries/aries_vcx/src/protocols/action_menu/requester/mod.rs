@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::action_menu::{
+        menu::Menu,
+        perform::{Perform, PerformContent, PerformDecorators},
+        problem_report::ActionMenuProblemReport,
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    states::{MenuReceived, PerformSent},
+    validate_perform,
+};
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind},
+    handlers::util::matches_opt_thread_id,
+    protocols::{
+        common::thread_mismatch_error,
+        did_exchange::transition::{
+            transition_error::TransitionError, transition_result::TransitionResult,
+        },
+    },
+};
+
+/// Requester of the action menu protocol, navigating the menus of a driver, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MenuRequester<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> MenuRequester<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl MenuRequester<MenuReceived> {
+    pub fn receive_menu(menu: Menu) -> Self {
+        let thread_id = menu
+            .decorators
+            .thread
+            .as_ref()
+            .map_or(menu.id.as_str(), |thread| thread.thid.as_str())
+            .to_owned();
+        Self {
+            state: MenuReceived { menu },
+            thread_id,
+        }
+    }
+
+    pub fn get_menu(&self) -> &Menu {
+        &self.state.menu
+    }
+
+    /// Performs the option `name` of the menu, filling in its form with `params`. Fails if the
+    /// option is not available, or a required parameter is missing.
+    pub fn perform(
+        self,
+        name: &str,
+        params: HashMap<String, String>,
+    ) -> Result<TransitionResult<MenuRequester<PerformSent>, Perform>, TransitionError<Self>> {
+        let perform = Perform::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(
+                PerformContent::builder()
+                    .name(name.to_owned())
+                    .params(params)
+                    .build(),
+            )
+            .decorators(
+                PerformDecorators::builder()
+                    .thread(Thread::builder().thid(self.thread_id.clone()).build())
+                    .timing(Timing::builder().out_time(Utc::now()).build())
+                    .build(),
+            )
+            .build();
+        if let Err(code) = validate_perform(&self.state.menu, &perform) {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Option {name} can not be performed: {}", code.as_ref()),
+                ),
+                state: self,
+            });
+        }
+        Ok(TransitionResult {
+            state: MenuRequester {
+                state: PerformSent {
+                    menu: self.state.menu,
+                    perform: perform.clone(),
+                },
+                thread_id: self.thread_id,
+            },
+            output: perform,
+        })
+    }
+}
+
+impl MenuRequester<PerformSent> {
+    pub fn get_perform(&self) -> &Perform {
+        &self.state.perform
+    }
+
+    /// Receives the menu presented after the performed option.
+    pub fn receive_menu(
+        self,
+        menu: Menu,
+    ) -> Result<MenuRequester<MenuReceived>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(menu, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("menu"),
+                state: self,
+            });
+        }
+        Ok(MenuRequester {
+            state: MenuReceived { menu },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// The performed option was rejected by the driver; the previous menu is open again.
+    pub fn receive_problem_report(
+        self,
+        problem_report: ActionMenuProblemReport,
+    ) -> Result<MenuRequester<MenuReceived>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        warn!(
+            "Performed option {} was rejected: {}",
+            self.state.perform.content.name, problem_report.content.inner.description.code
+        );
+        Ok(MenuRequester {
+            state: MenuReceived {
+                menu: self.state.menu,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
@@ -0,0 +1,36 @@
+use messages::msg_fields::protocols::action_menu::{
+    menu::{Menu, MenuOption},
+    perform::Perform,
+};
+
+/// A menu was sent by the driver, awaiting an option to be performed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MenuSent {
+    pub menu: Menu,
+}
+
+/// An option of the menu was performed, and is to be acted upon by the driver.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformReceived {
+    pub option: MenuOption,
+    pub perform: Perform,
+}
+
+/// The driver acted upon the performed option without presenting a new menu.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Completed {
+    pub perform: Perform,
+}
+
+/// A menu was received by the requester, whose options can be performed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MenuReceived {
+    pub menu: Menu,
+}
+
+/// An option of the menu was performed, awaiting the next menu of the driver.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerformSent {
+    pub menu: Menu,
+    pub perform: Perform,
+}
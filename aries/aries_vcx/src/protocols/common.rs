@@ -1,11 +1,27 @@
+use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
 use messages::{
-    decorators::thread::Thread,
-    msg_fields::protocols::report_problem::{
-        Description, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        thread::Thread,
+        timing::Timing,
     },
+    misc::MimeType,
+    msg_fields::protocols::{
+        common::attachment_format_specifier::{
+            AttachmentFormatSpecifier, OptionalIdAttachmentFormatSpecifier,
+        },
+        report_problem::{
+            Description, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+        },
+    },
+    msg_parts::MsgParts,
 };
+use shared::maybe_known::MaybeKnown;
 use uuid::Uuid;
 
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
 pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> ProblemReport {
     let id = Uuid::new_v4().to_string();
     let content = ProblemReportContent::builder()
@@ -27,6 +43,145 @@ pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> Pro
         .build()
 }
 
+/// Builds the problem report of a protocol whose problem reports wrap the generic problem report
+/// content, such as the 2.0 issuance and presentation protocols.
+pub fn build_protocol_problem_report<C: From<ProblemReportContent>>(
+    reason: &str,
+    thread_id: &str,
+) -> MsgParts<C, ProblemReportDecorators> {
+    let content = ProblemReportContent::builder()
+        .description(Description::builder().code(reason.to_owned()).build())
+        .build();
+    let decorators = ProblemReportDecorators::builder()
+        .thread(Thread::builder().thid(thread_id.to_owned()).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    MsgParts {
+        id: Uuid::new_v4().to_string(),
+        content: content.into(),
+        decorators,
+    }
+}
+
+/// Finds the first attachment whose format is one of `accepted_formats`, returning the format
+/// and the decoded attachment data. Formats are considered in the order the sender listed them.
+pub fn extract_attachment_data<F: Clone + PartialEq>(
+    formats: &[AttachmentFormatSpecifier<F>],
+    attachments: &[Attachment],
+    accepted_formats: &[F],
+) -> VcxResult<(F, Vec<u8>)> {
+    find_attachment_data(
+        formats
+            .iter()
+            .map(|specifier| (Some(specifier.attach_id.as_str()), &specifier.format)),
+        attachments,
+        accepted_formats,
+    )
+}
+
+/// Same as [extract_attachment_data], for messages whose format specifiers may omit the id of
+/// the attachment they describe. A lone format without id describes the lone attachment.
+pub fn extract_optional_id_attachment_data<F: Clone + PartialEq>(
+    formats: &[OptionalIdAttachmentFormatSpecifier<F>],
+    attachments: &[Attachment],
+    accepted_formats: &[F],
+) -> VcxResult<(F, Vec<u8>)> {
+    find_attachment_data(
+        formats
+            .iter()
+            .map(|specifier| (specifier.attach_id.as_deref(), &specifier.format)),
+        attachments,
+        accepted_formats,
+    )
+}
+
+fn find_attachment_data<'a, F: Clone + PartialEq + 'a>(
+    formats: impl IntoIterator<Item = (Option<&'a str>, &'a MaybeKnown<F>)>,
+    attachments: &[Attachment],
+    accepted_formats: &[F],
+) -> VcxResult<(F, Vec<u8>)> {
+    let formats: Vec<_> = formats.into_iter().collect();
+    // a lone format without attachment id describes the lone attachment of the message
+    let lone_attachment = match (formats.as_slice(), attachments) {
+        ([_], [attachment]) => Some(attachment),
+        _ => None,
+    };
+    for (attach_id, format) in formats {
+        let MaybeKnown::Known(format) = format else {
+            continue;
+        };
+        if !accepted_formats.contains(format) {
+            continue;
+        }
+        let attachment = match attach_id {
+            Some(attach_id) => attachments
+                .iter()
+                .find(|attachment| attachment.id.as_deref() == Some(attach_id)),
+            None => lone_attachment,
+        };
+        let attachment = attachment.ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!(
+                    "Message has no attachment with id {attach_id:?} referenced by its formats"
+                ),
+            )
+        })?;
+        return Ok((format.clone(), attachment_data(attachment)?));
+    }
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidMessageFormat,
+        "Message has no attachment in a supported format",
+    ))
+}
+
+fn attachment_data(attachment: &Attachment) -> VcxResult<Vec<u8>> {
+    match &attachment.data.content {
+        AttachmentType::Base64(encoded) => {
+            general_purpose::STANDARD.decode(encoded).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::EncodeError,
+                    format!("Attachment is not valid base64: {err}"),
+                )
+            })
+        }
+        AttachmentType::Json(value) => Ok(serde_json::to_vec(value)?),
+        AttachmentType::Links(_) => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Linked attachments are not supported",
+        )),
+    }
+}
+
+/// Builds the base64 encoded attachment carrying `data`, together with the format specifier
+/// referencing it.
+pub fn build_attachment<F>(format: F, data: &[u8]) -> (AttachmentFormatSpecifier<F>, Attachment) {
+    let attach_id = Uuid::new_v4().to_string();
+    let attachment = Attachment::builder()
+        .id(attach_id.clone())
+        .mime_type(MimeType::Json)
+        .data(
+            AttachmentData::builder()
+                .content(AttachmentType::Base64(
+                    general_purpose::STANDARD.encode(data),
+                ))
+                .build(),
+        )
+        .build();
+    let specifier = AttachmentFormatSpecifier::builder()
+        .attach_id(attach_id)
+        .format(MaybeKnown::Known(format))
+        .build();
+    (specifier, attachment)
+}
+
+pub fn thread_mismatch_error(msg_name: &str) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidState,
+        format!("Cannot handle {msg_name}: thread id does not match"),
+    )
+}
+
 // #[cfg(test)]
 // mod test {
 //     use crate::protocols::common::build_problem_report_msg;
@@ -46,3 +201,63 @@ pub fn build_problem_report_msg(comment: Option<String>, thread_id: &str) -> Pro
 //         .unwrap());
 //     }
 // }
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn optional_id_specifier(
+        attach_id: Option<String>,
+    ) -> OptionalIdAttachmentFormatSpecifier<&'static str> {
+        OptionalIdAttachmentFormatSpecifier::builder()
+            .attach_id(attach_id)
+            .format(MaybeKnown::Known("format"))
+            .build()
+    }
+
+    #[test]
+    fn test_extract_attachment_data_by_id() {
+        let (_, other_attachment) = build_attachment("format", b"other");
+        let (specifier, attachment) = build_attachment("format", b"data");
+        let (format, data) =
+            extract_attachment_data(&[specifier], &[other_attachment, attachment], &["format"])
+                .unwrap();
+        assert_eq!(format, "format");
+        assert_eq!(data, b"data");
+    }
+
+    #[test]
+    fn test_extract_lone_attachment_data_without_id() {
+        let (_, attachment) = build_attachment("format", b"data");
+        let (_, data) = extract_optional_id_attachment_data(
+            &[optional_id_specifier(None)],
+            &[attachment],
+            &["format"],
+        )
+        .unwrap();
+        assert_eq!(data, b"data");
+    }
+
+    #[test]
+    fn test_extract_attachment_data_without_id_fails_if_ambiguous() {
+        let (_, attachment) = build_attachment("format", b"data");
+        let (_, other_attachment) = build_attachment("format", b"other");
+        assert!(extract_optional_id_attachment_data(
+            &[optional_id_specifier(None)],
+            &[attachment, other_attachment],
+            &["format"],
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_extract_attachment_data_fails_on_unknown_id() {
+        let (_, attachment) = build_attachment("format", b"data");
+        assert!(extract_optional_id_attachment_data(
+            &[optional_id_specifier(Some("unknown".to_owned()))],
+            &[attachment],
+            &["format"],
+        )
+        .is_err());
+    }
+}
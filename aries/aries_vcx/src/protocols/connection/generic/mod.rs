@@ -2,8 +2,10 @@ mod conversions;
 mod thin_state;
 
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
-use messages::AriesMessage;
+use messages::{decorators::transport::ReturnRoute, AriesMessage};
+use public_key::{Key, KeyType};
 
 pub use self::thin_state::{State, ThinState};
 use super::trait_bounds::BootstrapDidDoc;
@@ -20,9 +22,12 @@ use crate::{
             invited::Invited as InviterInvited, requested::Requested as InviterRequested,
         },
         pairwise_info::PairwiseInfo,
+        send_to_their_services,
         trait_bounds::{TheirDidDoc, ThreadId},
     },
-    transport::Transport,
+    transport::{
+        serialize_with_return_route, unpack_returned_message, MessageConnection, Transport,
+    },
     utils::encryption_envelope::EncryptionEnvelope,
 };
 
@@ -203,11 +208,84 @@ impl GenericConnection {
             "No DidDoc present",
         ))?;
 
-        let msg = self.encrypt_message(wallet, message).await?.0;
-        let service_endpoint = did_doc.get_endpoint().ok_or_else(|| {
-            AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc")
-        })?;
-        transport.send_message(msg, &service_endpoint).await
+        send_to_their_services(
+            wallet,
+            json!(message).to_string().as_bytes(),
+            &self.pairwise_info().pw_vk,
+            did_doc,
+            transport,
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Sends `message` asking for replies over the same connection, see
+    /// [`MessageConnection::send_message_with_return_route`].
+    pub async fn send_message_with_return_route<T>(
+        &self,
+        wallet: &impl BaseWallet,
+        message: &AriesMessage,
+        return_route: ReturnRoute,
+        transport: &T,
+    ) -> VcxResult<Option<AriesMessage>>
+    where
+        T: Transport,
+    {
+        let did_doc = self.their_did_doc().ok_or(AriesVcxError::from_msg(
+            AriesVcxErrorKind::NotReady,
+            "No DidDoc present",
+        ))?;
+
+        let data = serialize_with_return_route(message, return_route)?;
+        let reply = send_to_their_services(
+            wallet,
+            &data,
+            &self.pairwise_info().pw_vk,
+            did_doc,
+            transport,
+            true,
+        )
+        .await?;
+        let their_vk = Key::from_base58(&self.remote_vk()?, KeyType::Ed25519)?;
+        unpack_returned_message(wallet, reply, their_vk).await
+    }
+}
+
+#[async_trait]
+impl MessageConnection for GenericConnection {
+    async fn send_message<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        W: BaseWallet,
+        T: Transport,
+    {
+        GenericConnection::send_message(self, wallet, message, transport).await
+    }
+
+    async fn send_message_with_return_route<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        return_route: ReturnRoute,
+        transport: &T,
+    ) -> VcxResult<Option<AriesMessage>>
+    where
+        W: BaseWallet,
+        T: Transport,
+    {
+        GenericConnection::send_message_with_return_route(
+            self,
+            wallet,
+            message,
+            return_route,
+            transport,
+        )
+        .await
     }
 }
 
@@ -23,10 +23,46 @@ use self::{
 };
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
-    transport::Transport,
+    transport::{send_packed_message, send_to_first_available_service, Transport},
     utils::encryption_envelope::EncryptionEnvelope,
 };
 
+/// Splits the DID document of the counterparty into documents of a single service each, in order
+/// of preference, for messages to be encrypted for the keys of the service they are sent to.
+fn did_docs_per_service(did_doc: &AriesDidDoc) -> Vec<AriesDidDoc> {
+    did_doc
+        .service
+        .iter()
+        .map(|service| AriesDidDoc {
+            service: vec![service.clone()],
+            ..did_doc.clone()
+        })
+        .collect()
+}
+
+/// Encrypts `data` for the services of the counterparty in turn, sending it until one of them
+/// accepts it. If `receive_reply` is set, returns the reply received over the same connection.
+pub(crate) async fn send_to_their_services(
+    wallet: &impl BaseWallet,
+    data: &[u8],
+    sender_verkey: &str,
+    their_did_doc: &AriesDidDoc,
+    transport: &impl Transport,
+    receive_reply: bool,
+) -> VcxResult<Option<Vec<u8>>> {
+    send_to_first_available_service(did_docs_per_service(their_did_doc), |did_doc| async move {
+        let msg =
+            EncryptionEnvelope::create_from_legacy(wallet, data, Some(sender_verkey), &did_doc)
+                .await?
+                .0;
+        let service_endpoint = did_doc.get_endpoint().ok_or_else(|| {
+            AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc")
+        })?;
+        send_packed_message(transport, msg, &service_endpoint, receive_reply).await
+    })
+    .await
+}
+
 /// A state machine for progressing through the [connection protocol](https://github.com/hyperledger/aries-rfcs/blob/main/features/0160-connection-protocol/README.md).
 #[derive(Clone, Deserialize)]
 #[serde(try_from = "GenericConnection")]
@@ -133,11 +169,16 @@ where
     where
         T: Transport,
     {
-        let msg = self.encrypt_message(wallet, message).await?.0;
-        let service_endpoint = self.their_did_doc().get_endpoint().ok_or_else(|| {
-            AriesVcxError::from_msg(AriesVcxErrorKind::InvalidUrl, "No URL in DID Doc")
-        })?;
-        transport.send_message(msg, &service_endpoint).await
+        send_to_their_services(
+            wallet,
+            json!(message).to_string().as_bytes(),
+            &self.pairwise_info().pw_vk,
+            self.their_did_doc(),
+            transport,
+            false,
+        )
+        .await?;
+        Ok(())
     }
 }
 
@@ -81,11 +81,18 @@ fn resolve_first_key_agreement(did_document: &DidDocument) -> VcxResult<Verifica
     Ok(verification_method)
 }
 
+/// Resolves the invitation key from the first DID service of the invitation, which is the one a
+/// requester addresses. Inline services are only considered if the invitation has no DID service.
 pub async fn resolve_enc_key_from_invitation(
     invitation: &OobInvitation,
     resolver_registry: &Arc<ResolverRegistry>,
 ) -> Result<Key, AriesVcxError> {
-    match invitation.content.services.first().ok_or_else(|| {
+    let services = &invitation.content.services;
+    let service = services
+        .iter()
+        .find(|service| matches!(service, OobService::Did(_)))
+        .or_else(|| services.first());
+    match service.ok_or_else(|| {
         AriesVcxError::from_msg(
             AriesVcxErrorKind::InvalidInput,
             "Invitation does not contain any services",
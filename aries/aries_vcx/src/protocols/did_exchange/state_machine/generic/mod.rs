@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use did_doc::schema::did_doc::DidDocument;
+use async_trait::async_trait;
+use did_doc::schema::{
+    did_doc::DidDocument,
+    service::{typed::ServiceType, Service},
+};
 use did_parser_nom::Did;
 use did_peer::peer_did::{numalgos::numalgo4::Numalgo4, PeerDid};
 use did_resolver_registry::ResolverRegistry;
 use messages::{
+    decorators::transport::ReturnRoute,
     msg_fields::protocols::did_exchange::v1_x::{
         complete::{AnyComplete, Complete},
         problem_report::ProblemReport,
@@ -13,13 +18,14 @@ use messages::{
         response::AnyResponse,
     },
     msg_types::protocols::did_exchange::DidExchangeTypeV1,
+    AriesMessage,
 };
 use public_key::Key;
 pub use thin_state::ThinState;
 
 use super::{requester::DidExchangeRequester, responder::DidExchangeResponder};
 use crate::{
-    errors::error::{AriesVcxError, AriesVcxErrorKind},
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
     protocols::did_exchange::{
         states::{
             abandoned::Abandoned, completed::Completed, requester::request_sent::RequestSent,
@@ -27,6 +33,13 @@ use crate::{
         },
         transition::{transition_error::TransitionError, transition_result::TransitionResult},
     },
+    transport::{
+        send_packed_message, send_to_first_available_service, serialize_with_return_route,
+        unpack_returned_message, MessageConnection, Transport,
+    },
+    utils::{
+        didcomm_utils::resolve_ed25519_key_agreement, encryption_envelope::EncryptionEnvelope,
+    },
 };
 
 mod conversions;
@@ -89,6 +102,46 @@ impl GenericDidExchange {
         }
     }
 
+    /// Replaces our DID document on a completed DID exchange, once the other party acknowledged
+    /// the rotation of our DID. See [crate::protocols::did_rotate].
+    pub fn rotate_our_did_document(&mut self, did_document: DidDocument) -> VcxResult<()> {
+        match self {
+            GenericDidExchange::Requester(RequesterState::Completed(state)) => {
+                state.set_our_did_doc(did_document)
+            }
+            GenericDidExchange::Responder(ResponderState::Completed(state)) => {
+                state.set_our_did_doc(did_document)
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Only DIDs of completed DID exchanges can be rotated",
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces their DID document on a completed DID exchange, once we accepted the rotation of
+    /// their DID. See [crate::protocols::did_rotate].
+    pub fn rotate_their_did_document(&mut self, did_document: DidDocument) -> VcxResult<()> {
+        match self {
+            GenericDidExchange::Requester(RequesterState::Completed(state)) => {
+                state.set_their_did_doc(did_document)
+            }
+            GenericDidExchange::Responder(ResponderState::Completed(state)) => {
+                state.set_their_did_doc(did_document)
+            }
+            _ => {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Only DIDs of completed DID exchanges can be rotated",
+                ))
+            }
+        }
+        Ok(())
+    }
+
     pub async fn construct_request(
         resolver_registry: &Arc<ResolverRegistry>,
         invitation_id: Option<String>,
@@ -280,3 +333,98 @@ impl GenericDidExchange {
         }
     }
 }
+
+/// Returns the DIDComm V1 services of `did_doc`, in order of preference.
+fn didcomm_v1_services(did_doc: &DidDocument) -> VcxResult<Vec<&Service>> {
+    // Fails with the lookup error if there is none
+    did_doc.get_service_of_type(&ServiceType::DIDCommV1)?;
+    Ok(did_doc
+        .service()
+        .iter()
+        .filter(|service| service.service_types().contains(&ServiceType::DIDCommV1))
+        .collect())
+}
+
+/// Encrypts `data` for the DIDComm V1 services of the counterparty in turn, sending it until one
+/// of them accepts it. If `receive_reply` is set, returns the reply received over the same
+/// connection.
+async fn send_to_their_services(
+    wallet: &impl BaseWallet,
+    data: &[u8],
+    our_did_doc: &DidDocument,
+    their_did_doc: &DidDocument,
+    transport: &impl Transport,
+    receive_reply: bool,
+) -> VcxResult<Option<Vec<u8>>> {
+    send_to_first_available_service(didcomm_v1_services(their_did_doc)?, |service| async move {
+        let envelope =
+            EncryptionEnvelope::create(wallet, data, our_did_doc, their_did_doc, service.id())
+                .await?;
+        send_packed_message(
+            transport,
+            envelope.0,
+            service.service_endpoint(),
+            receive_reply,
+        )
+        .await
+    })
+    .await
+}
+
+#[async_trait]
+impl MessageConnection for GenericDidExchange {
+    async fn send_message<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        W: BaseWallet,
+        T: Transport,
+    {
+        if !matches!(self.get_state(), ThinState::Completed) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Messages can only be sent over a completed DID exchange",
+            ));
+        }
+        send_to_their_services(
+            wallet,
+            json!(message).to_string().as_bytes(),
+            self.our_did_document(),
+            self.their_did_doc(),
+            transport,
+            false,
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn send_message_with_return_route<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        return_route: ReturnRoute,
+        transport: &T,
+    ) -> VcxResult<Option<AriesMessage>>
+    where
+        W: BaseWallet,
+        T: Transport,
+    {
+        if !matches!(self.get_state(), ThinState::Completed) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Messages can only be sent over a completed DID exchange",
+            ));
+        }
+        let their_did_doc = self.their_did_doc();
+        let our_did_doc = self.our_did_document();
+        let data = serialize_with_return_route(message, return_route)?;
+        let reply =
+            send_to_their_services(wallet, &data, our_did_doc, their_did_doc, transport, true)
+                .await?;
+        let their_vk = resolve_ed25519_key_agreement(their_did_doc)?;
+        unpack_returned_message(wallet, reply, their_vk).await
+    }
+}
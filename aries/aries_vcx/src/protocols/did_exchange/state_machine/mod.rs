@@ -110,4 +110,12 @@ impl<I, S> DidExchange<I, S> {
     pub fn their_did_doc(&self) -> &DidDocument {
         &self.their_did_document
     }
+
+    pub(crate) fn set_our_did_doc(&mut self, did_document: DidDocument) {
+        self.our_did_document = did_document;
+    }
+
+    pub(crate) fn set_their_did_doc(&mut self, did_document: DidDocument) {
+        self.their_did_document = did_document;
+    }
 }
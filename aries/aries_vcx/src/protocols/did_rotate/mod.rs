@@ -0,0 +1,246 @@
+//! State machines for the [DID rotate 1.0 protocol](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0794-did-rotate/README.md>),
+//! used to move connections established through [crate::protocols::did_exchange] to a new DID,
+//! for instance when switching mediators.
+//!
+//! The [rotator::DidRotator] announces its new DID, signed with the key of the DID being rotated
+//! away from, and switches over once the rotation is acknowledged. The
+//! [observer::DidRotateObserver] verifies the signature, resolves the new DID and acknowledges the
+//! rotation. On both sides, the resulting DID document is applied to the connection through
+//! [GenericDidExchange::rotate_our_did_document] or
+//! [GenericDidExchange::rotate_their_did_document].
+//!
+//! [GenericDidExchange::rotate_our_did_document]: crate::protocols::did_exchange::state_machine::generic::GenericDidExchange::rotate_our_did_document
+//! [GenericDidExchange::rotate_their_did_document]: crate::protocols::did_exchange::state_machine::generic::GenericDidExchange::rotate_their_did_document
+
+pub mod observer;
+pub mod rotator;
+pub mod states;
+
+use std::collections::HashMap;
+
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use base64::Engine;
+use chrono::Utc;
+use did_doc::schema::did_doc::DidDocument;
+use did_parser_nom::Did;
+use messages::{
+    decorators::{
+        attachment::{Attachment, AttachmentType},
+        thread::Thread,
+        timing::Timing,
+    },
+    msg_fields::protocols::{
+        did_rotate::{
+            hangup::{Hangup, HangupContent, HangupDecorators},
+            problem_report::DidRotateProblemReport,
+            rotate::Rotate,
+        },
+        report_problem::{Description, ProblemReportContent, ProblemReportDecorators},
+    },
+};
+use strum_macros::{AsRefStr, EnumString};
+use uuid::Uuid;
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::did_exchange::state_machine::helpers::{
+        assemble_did_rotate_attachment, jws_sign_attach, jws_verify_attachment,
+    },
+    utils::{base64::URL_SAFE_LENIENT, didcomm_utils::resolve_ed25519_key_agreement},
+};
+
+/// Problem codes used by the observing party to reject a rotation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, AsRefStr, EnumString)]
+pub enum RotateProblemCode {
+    #[strum(serialize = "e.did.unresolvable")]
+    Unresolvable,
+    #[strum(serialize = "e.did.method_unsupported")]
+    MethodUnsupported,
+    #[strum(serialize = "e.did.unusable")]
+    Unusable,
+}
+
+/// Builds a hangup message, telling the other party we are ending the relationship instead of
+/// rotating to a new DID.
+pub fn build_hangup() -> Hangup {
+    Hangup::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(HangupContent::default())
+        .decorators(
+            HangupDecorators::builder()
+                .timing(Timing::builder().out_time(Utc::now()).build())
+                .build(),
+        )
+        .build()
+}
+
+fn build_problem_report(
+    code: RotateProblemCode,
+    to_did: &str,
+    thread_id: &str,
+) -> DidRotateProblemReport {
+    let content = ProblemReportContent::builder()
+        .description(
+            Description::builder()
+                .code(code.as_ref().to_owned())
+                .build(),
+        )
+        .problem_items(vec![HashMap::from([("did".to_owned(), to_did.to_owned())])])
+        .build();
+    let decorators = ProblemReportDecorators::builder()
+        .thread(Thread::builder().thid(thread_id.to_owned()).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+    DidRotateProblemReport::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content.into())
+        .decorators(decorators)
+        .build()
+}
+
+/// Verifies that the new DID announced in `rotate` was signed with the key agreement key of
+/// `signer_did_document`, the DID document being rotated away from.
+async fn verify_rotate_signature(
+    wallet: &impl BaseWallet,
+    signer_did_document: &DidDocument,
+    rotate: &Rotate,
+) -> VcxResult<()> {
+    let attach = rotate.content.to_did_attach.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "DID rotate message has no signed to_did attachment",
+        )
+    })?;
+    let signer = resolve_ed25519_key_agreement(signer_did_document)?;
+    if !jws_verify_attachment(attach, &signer, wallet).await? {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "DID rotate message was not signed by the DID being rotated away from",
+        ));
+    }
+
+    let AttachmentType::Base64(signed_did_b64) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::EncodeError,
+            "DID rotate attachment is not base64 encoded",
+        ));
+    };
+    let signed_did = URL_SAFE_LENIENT
+        .decode(signed_did_b64)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::EncodeError,
+                "DID rotate attachment does not contain a valid UTF8 DID",
+            )
+        })?;
+    if signed_did != rotate.content.to_did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!(
+                "Signed DID {signed_did} does not match the rotated to DID {}",
+                rotate.content.to_did
+            ),
+        ));
+    }
+    Ok(())
+}
+
+async fn sign_to_did(
+    wallet: &impl BaseWallet,
+    signer_did_document: &DidDocument,
+    to_did: &Did,
+) -> VcxResult<Attachment> {
+    let signer = resolve_ed25519_key_agreement(signer_did_document)?;
+    jws_sign_attach(assemble_did_rotate_attachment(to_did), signer, wallet).await
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Arc;
+
+    use did_peer::resolver::PeerDidResolver;
+    use did_resolver_registry::ResolverRegistry;
+    use test_utils::devsetup::build_setup_profile;
+    use url::Url;
+
+    use super::{observer::DidRotateObserver, rotator::DidRotator, *};
+    use crate::protocols::did_exchange::{
+        state_machine::helpers::create_peer_did_4, transition::transition_result::TransitionResult,
+    };
+
+    fn resolver_registry() -> Arc<ResolverRegistry> {
+        Arc::new(
+            ResolverRegistry::new()
+                .register_resolver::<PeerDidResolver>("peer".into(), PeerDidResolver::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_did_rotation() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let endpoint = Url::parse("http://dummy.lol").unwrap();
+        let (old_did, _) = create_peer_did_4(wallet, endpoint.clone(), vec![])
+            .await
+            .unwrap();
+        let (new_did, _) = create_peer_did_4(wallet, endpoint, vec![]).await.unwrap();
+        let old_did_document = old_did.resolve_did_doc().unwrap();
+
+        let rotator = DidRotator::prepare_rotation(wallet, &old_did_document, &new_did)
+            .await
+            .unwrap();
+        assert_eq!(
+            rotator.get_rotate().content.to_did,
+            new_did.did().to_string()
+        );
+
+        let observer = DidRotateObserver::receive_rotate(rotator.get_rotate().clone());
+        let TransitionResult {
+            state: observer,
+            output: ack,
+        } = observer
+            .accept_rotation(wallet, &resolver_registry(), &old_did_document)
+            .await
+            .unwrap();
+
+        let rotator = rotator.receive_ack(ack).unwrap();
+        assert_eq!(rotator.new_did_document(), observer.new_did_document());
+    }
+
+    #[tokio::test]
+    async fn test_did_rotation_signed_by_wrong_key() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let endpoint = Url::parse("http://dummy.lol").unwrap();
+        let (old_did, _) = create_peer_did_4(wallet, endpoint.clone(), vec![])
+            .await
+            .unwrap();
+        let (new_did, _) = create_peer_did_4(wallet, endpoint, vec![]).await.unwrap();
+        let new_did_document = new_did.resolve_did_doc().unwrap();
+
+        let rotator = DidRotator::prepare_rotation(wallet, &new_did_document, &new_did)
+            .await
+            .unwrap();
+        let observer = DidRotateObserver::receive_rotate(rotator.get_rotate().clone());
+        let TransitionResult {
+            output: problem_report,
+            ..
+        } = observer
+            .accept_rotation(
+                wallet,
+                &resolver_registry(),
+                &old_did.resolve_did_doc().unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            problem_report.content.inner.description.code,
+            RotateProblemCode::Unusable.as_ref()
+        );
+
+        let rotator = rotator.receive_problem_report(problem_report).unwrap();
+        assert_eq!(rotator.get_failure_message(), "e.did.unusable");
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use did_doc::schema::{did_doc::DidDocument, service::typed::ServiceType};
+use did_parser_nom::Did;
+use did_resolver_registry::{error::DidResolverRegistryError, ResolverRegistry};
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::{
+        did_rotate::{ack::DidRotateAck, problem_report::DidRotateProblemReport, rotate::Rotate},
+        notification::ack::{AckContent, AckDecorators, AckStatus},
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    build_problem_report,
+    states::{Complete, Failed, RotateReceived},
+    verify_rotate_signature, RotateProblemCode,
+};
+use crate::protocols::did_exchange::transition::transition_result::TransitionResult;
+
+/// Observing party of the DID rotate protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DidRotateObserver<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> DidRotateObserver<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl DidRotateObserver<RotateReceived> {
+    pub fn receive_rotate(rotate: Rotate) -> Self {
+        Self {
+            thread_id: rotate.id.clone(),
+            state: RotateReceived { rotate },
+        }
+    }
+
+    /// The DID the other party is rotating to.
+    pub fn get_to_did(&self) -> &str {
+        &self.state.rotate.content.to_did
+    }
+
+    /// Verifies that the rotation was signed by the DID being rotated away from,
+    /// `their_current_did_document`, and resolves the new DID. On success, the ack is to be sent
+    /// to the new DID; on failure, the problem report is to be sent to the current one.
+    pub async fn accept_rotation(
+        self,
+        wallet: &impl BaseWallet,
+        resolver_registry: &Arc<ResolverRegistry>,
+        their_current_did_document: &DidDocument,
+    ) -> Result<
+        TransitionResult<DidRotateObserver<Complete>, DidRotateAck>,
+        TransitionResult<DidRotateObserver<Failed>, DidRotateProblemReport>,
+    > {
+        let rotate = &self.state.rotate;
+        if let Err(err) = verify_rotate_signature(wallet, their_current_did_document, rotate).await
+        {
+            return Err(self.fail(RotateProblemCode::Unusable, err.to_string()));
+        }
+
+        let did = match Did::parse(rotate.content.to_did.clone()) {
+            Ok(did) => did,
+            Err(err) => return Err(self.fail(RotateProblemCode::Unresolvable, err.to_string())),
+        };
+        let new_did_document = match resolver_registry.resolve(&did, &Default::default()).await {
+            Ok(output) => output.did_document,
+            Err(err) => {
+                let code = match err.downcast_ref::<DidResolverRegistryError>() {
+                    Some(DidResolverRegistryError::UnsupportedMethod) => {
+                        RotateProblemCode::MethodUnsupported
+                    }
+                    _ => RotateProblemCode::Unresolvable,
+                };
+                return Err(self.fail(code, err.to_string()));
+            }
+        };
+        if let Err(err) = new_did_document.get_service_of_type(&ServiceType::DIDCommV1) {
+            return Err(self.fail(RotateProblemCode::Unusable, err.to_string()));
+        }
+
+        let ack = DidRotateAck::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(AckContent::builder().status(AckStatus::Ok).build())
+            .decorators(
+                AckDecorators::builder()
+                    .thread(Thread::builder().thid(self.thread_id.clone()).build())
+                    .timing(Timing::builder().out_time(Utc::now()).build())
+                    .build(),
+            )
+            .build();
+        Ok(TransitionResult {
+            state: DidRotateObserver {
+                state: Complete { new_did_document },
+                thread_id: self.thread_id,
+            },
+            output: ack,
+        })
+    }
+
+    fn fail(
+        self,
+        code: RotateProblemCode,
+        reason: String,
+    ) -> TransitionResult<DidRotateObserver<Failed>, DidRotateProblemReport> {
+        warn!(
+            "Rejecting rotation to {}, code: {}, reason: {reason}",
+            self.get_to_did(),
+            code.as_ref()
+        );
+        let problem_report = build_problem_report(code, self.get_to_did(), &self.thread_id);
+        TransitionResult {
+            state: DidRotateObserver {
+                state: Failed {
+                    failure_message: reason,
+                },
+                thread_id: self.thread_id,
+            },
+            output: problem_report,
+        }
+    }
+}
+
+impl DidRotateObserver<Complete> {
+    /// Their new DID document, to be applied to the connection.
+    pub fn new_did_document(&self) -> &DidDocument {
+        &self.state.new_did_document
+    }
+}
+
+impl DidRotateObserver<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
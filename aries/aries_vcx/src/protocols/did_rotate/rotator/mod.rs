@@ -0,0 +1,129 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use did_doc::schema::did_doc::DidDocument;
+use did_peer::peer_did::{numalgos::numalgo4::Numalgo4, PeerDid};
+use messages::{
+    decorators::timing::Timing,
+    msg_fields::protocols::did_rotate::{
+        ack::DidRotateAck,
+        problem_report::DidRotateProblemReport,
+        rotate::{Rotate, RotateContent, RotateDecorators},
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    sign_to_did,
+    states::{Complete, Failed, RotatePrepared},
+};
+use crate::{
+    errors::error::VcxResult,
+    handlers::util::{matches_opt_thread_id, matches_thread_id},
+    protocols::{
+        common::thread_mismatch_error, did_exchange::transition::transition_error::TransitionError,
+    },
+};
+
+/// Rotating party of the DID rotate protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DidRotator<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> DidRotator<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl DidRotator<RotatePrepared> {
+    /// Prepares the rotation of our side of a connection from `current_did_document` to
+    /// `new_peer_did`. The new DID is signed with the key agreement key of
+    /// `current_did_document`, proving the rotation comes from the current DID controller.
+    pub async fn prepare_rotation(
+        wallet: &impl BaseWallet,
+        current_did_document: &DidDocument,
+        new_peer_did: &PeerDid<Numalgo4>,
+    ) -> VcxResult<Self> {
+        let new_did_document = new_peer_did.resolve_did_doc()?;
+        let to_did_attach = sign_to_did(wallet, current_did_document, new_peer_did.did()).await?;
+        let content = RotateContent::builder()
+            .to_did(new_peer_did.did().to_string())
+            .to_did_attach(to_did_attach)
+            .build();
+        let decorators = RotateDecorators::builder()
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        let rotate = Rotate::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build();
+        Ok(Self {
+            thread_id: rotate.id.clone(),
+            state: RotatePrepared {
+                rotate,
+                new_did_document,
+            },
+        })
+    }
+
+    /// The rotate message to be sent over the connection, still using the current DIDs.
+    pub fn get_rotate(&self) -> &Rotate {
+        &self.state.rotate
+    }
+
+    pub fn receive_ack(
+        self,
+        ack: DidRotateAck,
+    ) -> Result<DidRotator<Complete>, TransitionError<Self>> {
+        if !matches_thread_id!(ack, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("ack"),
+                state: self,
+            });
+        }
+        Ok(DidRotator {
+            state: Complete {
+                new_did_document: self.state.new_did_document,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    pub fn receive_problem_report(
+        self,
+        problem_report: DidRotateProblemReport,
+    ) -> Result<DidRotator<Failed>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        Ok(DidRotator {
+            state: Failed {
+                failure_message: problem_report.content.inner.description.code,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl DidRotator<Complete> {
+    /// Our new DID document, to be applied to the connection.
+    pub fn new_did_document(&self) -> &DidDocument {
+        &self.state.new_did_document
+    }
+}
+
+impl DidRotator<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
@@ -0,0 +1,27 @@
+use did_doc::schema::did_doc::DidDocument;
+use messages::msg_fields::protocols::did_rotate::rotate::Rotate;
+
+/// The rotate message was prepared, to be sent over the connection being rotated.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotatePrepared {
+    pub rotate: Rotate,
+    pub new_did_document: DidDocument,
+}
+
+/// A rotate message was received and awaits verification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RotateReceived {
+    pub rotate: Rotate,
+}
+
+/// The rotation was acknowledged; the connection should now use the new DID document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Complete {
+    pub new_did_document: DidDocument,
+}
+
+/// The rotation was rejected, the connection keeps using the DID being rotated away from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Failed {
+    pub failure_message: String,
+}
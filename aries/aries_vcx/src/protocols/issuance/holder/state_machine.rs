@@ -30,9 +30,10 @@ use messages::{
 use uuid::Uuid;
 
 use crate::{
-    common::credentials::{get_cred_rev_id, is_cred_revoked},
+    common::credentials::{
+        get_cred_rev_id, is_cred_revoked, link_secret::get_default_link_secret_id,
+    },
     errors::error::prelude::*,
-    global::settings,
     handlers::util::{
         get_attach_as_string, make_attach_from_str, verify_thread_id, AttachmentId, Status,
     },
@@ -609,14 +610,14 @@ pub async fn create_anoncreds_credential_request(
 
     let cred_def_json = ledger.get_cred_def(&cred_def_id, None).await?;
 
-    let master_secret_id = settings::DEFAULT_LINK_SECRET_ALIAS;
+    let link_secret_id = get_default_link_secret_id(wallet).await?;
     anoncreds
         .prover_create_credential_req(
             wallet,
             prover_did,
             offer,
             cred_def_json.try_clone()?,
-            &master_secret_id.to_string(),
+            &link_secret_id,
         )
         .await
         .map_err(|err| {
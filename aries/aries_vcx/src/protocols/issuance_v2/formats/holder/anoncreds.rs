@@ -0,0 +1,229 @@
+use std::{fmt, marker::PhantomData};
+
+use anoncreds_types::data_types::{
+    identifiers::schema_id::SchemaId,
+    messages::{cred_offer::CredentialOffer, credential::Credential},
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::{BaseAnonCreds, CredentialId};
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use did_parser_nom::Did;
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::{IssueCredentialAttachmentFormatType, IssueCredentialV2},
+    offer_credential::OfferCredentialV2,
+    propose_credential::ProposeCredentialAttachmentFormatType,
+    request_credential::RequestCredentialAttachmentFormatType,
+};
+
+use super::HolderCredentialIssuanceFormat;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        common::extract_attachment_data,
+        issuance::holder::state_machine::create_anoncreds_credential_request,
+        issuance_v2::formats::{AnoncredsCredentialFilter, AnoncredsFormatFamily},
+    },
+};
+
+/// Holder side handler of the `anoncreds/...@v1.0` and `hlindy/...@v2.0` attachment formats.
+pub struct AnoncredsHolderCredentialIssuanceFormat<W, R, A> {
+    _marker: PhantomData<fn() -> (W, R, A)>,
+}
+
+// Implemented manually, as derives would require the wallet, ledger and anoncreds types to
+// implement these traits as well.
+impl<W, R, A> fmt::Debug for AnoncredsHolderCredentialIssuanceFormat<W, R, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnoncredsHolderCredentialIssuanceFormat")
+    }
+}
+
+impl<W, R, A> Clone for AnoncredsHolderCredentialIssuanceFormat<W, R, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<W, R, A> PartialEq for AnoncredsHolderCredentialIssuanceFormat<W, R, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+pub struct AnoncredsCreateProposalInput {
+    pub family: AnoncredsFormatFamily,
+    pub filter: AnoncredsCredentialFilter,
+}
+
+#[derive(Debug)]
+pub struct AnoncredsOfferDetails {
+    pub family: AnoncredsFormatFamily,
+    pub cred_offer: CredentialOffer,
+}
+
+pub struct AnoncredsCreateRequestInput<'a, W, R, A> {
+    pub wallet: &'a W,
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+    pub prover_did: &'a Did,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnoncredsCreatedRequestMetadata {
+    pub family: AnoncredsFormatFamily,
+    pub credential_request_metadata: String,
+    pub cred_def_json: String,
+    pub schema_id: SchemaId,
+}
+
+pub struct AnoncredsStoreCredentialInput<'a, W, R, A> {
+    pub wallet: &'a W,
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnoncredsStoredCredentialMetadata {
+    pub credential_id: CredentialId,
+    pub rev_reg_id: Option<String>,
+}
+
+#[async_trait]
+impl<W, R, A> HolderCredentialIssuanceFormat for AnoncredsHolderCredentialIssuanceFormat<W, R, A>
+where
+    W: BaseWallet,
+    R: AnoncredsLedgerRead,
+    A: BaseAnonCreds,
+{
+    type CreateProposalInput = AnoncredsCreateProposalInput;
+    type OfferDetails = AnoncredsOfferDetails;
+
+    type CreateRequestInput<'a>
+        = AnoncredsCreateRequestInput<'a, W, R, A>
+    where
+        Self: 'a;
+    type CreatedRequestMetadata = AnoncredsCreatedRequestMetadata;
+
+    type StoreCredentialInput<'a>
+        = AnoncredsStoreCredentialInput<'a, W, R, A>
+    where
+        Self: 'a;
+    type StoredCredentialMetadata = AnoncredsStoredCredentialMetadata;
+
+    fn create_proposal_attachment_content(
+        input: &AnoncredsCreateProposalInput,
+    ) -> VcxResult<(ProposeCredentialAttachmentFormatType, Vec<u8>)> {
+        Ok((
+            input.family.proposal_format(),
+            serde_json::to_vec(&input.filter)?,
+        ))
+    }
+
+    fn extract_offer_details(offer: &OfferCredentialV2) -> VcxResult<AnoncredsOfferDetails> {
+        let (family, data) = extract_offer_data(offer)?;
+        Ok(AnoncredsOfferDetails {
+            family,
+            cred_offer: serde_json::from_slice(&data)?,
+        })
+    }
+
+    async fn create_request_attachment_content(
+        offer: &OfferCredentialV2,
+        input: &AnoncredsCreateRequestInput<'_, W, R, A>,
+    ) -> VcxResult<(
+        RequestCredentialAttachmentFormatType,
+        Vec<u8>,
+        AnoncredsCreatedRequestMetadata,
+    )> {
+        let (family, data) = extract_offer_data(offer)?;
+        let cred_offer = String::from_utf8(data).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::EncodeError,
+                format!("Credential offer attachment is not valid UTF-8: {err}"),
+            )
+        })?;
+        let (request, credential_request_metadata, _cred_def_id, cred_def_json, schema_id) =
+            create_anoncreds_credential_request(
+                input.wallet,
+                input.ledger,
+                input.anoncreds,
+                input.prover_did,
+                &cred_offer,
+            )
+            .await?;
+        let metadata = AnoncredsCreatedRequestMetadata {
+            family,
+            credential_request_metadata,
+            cred_def_json,
+            schema_id,
+        };
+        Ok((family.request_format(), request.into_bytes(), metadata))
+    }
+
+    fn credential_format(
+        request_format: &RequestCredentialAttachmentFormatType,
+    ) -> Option<IssueCredentialAttachmentFormatType> {
+        AnoncredsFormatFamily::from_request_format(request_format)
+            .map(|family| family.credential_format())
+    }
+
+    async fn process_and_store_credential(
+        credential: &IssueCredentialV2,
+        input: &AnoncredsStoreCredentialInput<'_, W, R, A>,
+        request_metadata: &AnoncredsCreatedRequestMetadata,
+    ) -> VcxResult<AnoncredsStoredCredentialMetadata> {
+        let credential_formats =
+            AnoncredsFormatFamily::all().map(|family| family.credential_format());
+        let (_, data) = extract_attachment_data(
+            &credential.content.formats,
+            &credential.content.credentials_attach,
+            &credential_formats,
+        )?;
+        let credential: Credential = serde_json::from_slice(&data)?;
+
+        let schema = input
+            .ledger
+            .get_schema(&request_metadata.schema_id, None)
+            .await?;
+        let rev_reg_id = credential.rev_reg_id.as_ref().map(|id| id.0.clone());
+        let rev_reg_def = match &credential.rev_reg_id {
+            Some(rev_reg_id) => Some(input.ledger.get_rev_reg_def_json(rev_reg_id).await?.0),
+            None => None,
+        };
+
+        let credential_id = input
+            .anoncreds
+            .prover_store_credential(
+                input.wallet,
+                serde_json::from_str(&request_metadata.credential_request_metadata)?,
+                credential,
+                schema,
+                serde_json::from_str(&request_metadata.cred_def_json)?,
+                rev_reg_def,
+            )
+            .await?;
+        Ok(AnoncredsStoredCredentialMetadata {
+            credential_id,
+            rev_reg_id,
+        })
+    }
+}
+
+fn extract_offer_data(offer: &OfferCredentialV2) -> VcxResult<(AnoncredsFormatFamily, Vec<u8>)> {
+    let offer_formats = AnoncredsFormatFamily::all().map(|family| family.offer_format());
+    let (format, data) = extract_attachment_data(
+        &offer.content.formats,
+        &offer.content.offers_attach,
+        &offer_formats,
+    )?;
+    let family = AnoncredsFormatFamily::from_offer_format(&format).ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Credential offer is not in an anoncreds format",
+        )
+    })?;
+    Ok((family, data))
+}
@@ -0,0 +1,78 @@
+pub mod anoncreds;
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::{IssueCredentialAttachmentFormatType, IssueCredentialV2},
+    offer_credential::OfferCredentialV2,
+    propose_credential::ProposeCredentialAttachmentFormatType,
+    request_credential::RequestCredentialAttachmentFormatType,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::error::VcxResult;
+
+/// Attachment format handler used by [crate::protocols::issuance_v2::holder::HolderV2] to
+/// create and process the format-specific attachments of the protocol messages.
+#[async_trait]
+pub trait HolderCredentialIssuanceFormat {
+    /// Input used to create the attachment of a credential proposal.
+    type CreateProposalInput: Send + Sync;
+    /// Details of a received credential offer, as extracted from its attachment.
+    type OfferDetails;
+
+    /// Input used to create the attachment of a credential request.
+    type CreateRequestInput<'a>: Send + Sync
+    where
+        Self: 'a;
+    /// Data created along with the credential request and needed to process the issued
+    /// credential.
+    type CreatedRequestMetadata: Debug
+        + Clone
+        + PartialEq
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync;
+
+    /// Input used to process and store the issued credential.
+    type StoreCredentialInput<'a>: Send + Sync
+    where
+        Self: 'a;
+    /// Data describing the credential once stored.
+    type StoredCredentialMetadata: Debug
+        + Clone
+        + PartialEq
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync;
+
+    fn create_proposal_attachment_content(
+        input: &Self::CreateProposalInput,
+    ) -> VcxResult<(ProposeCredentialAttachmentFormatType, Vec<u8>)>;
+
+    fn extract_offer_details(offer: &OfferCredentialV2) -> VcxResult<Self::OfferDetails>;
+
+    async fn create_request_attachment_content(
+        offer: &OfferCredentialV2,
+        input: &Self::CreateRequestInput<'_>,
+    ) -> VcxResult<(
+        RequestCredentialAttachmentFormatType,
+        Vec<u8>,
+        Self::CreatedRequestMetadata,
+    )>;
+
+    /// Format the credential is expected to be issued in, given the format it was requested in.
+    /// [None] if `request_format` is not one of the handled formats.
+    fn credential_format(
+        request_format: &RequestCredentialAttachmentFormatType,
+    ) -> Option<IssueCredentialAttachmentFormatType>;
+
+    async fn process_and_store_credential(
+        credential: &IssueCredentialV2,
+        input: &Self::StoreCredentialInput<'_>,
+        request_metadata: &Self::CreatedRequestMetadata,
+    ) -> VcxResult<Self::StoredCredentialMetadata>;
+}
@@ -0,0 +1,218 @@
+use std::{collections::HashMap, fmt, marker::PhantomData, path::Path};
+
+use anoncreds_types::data_types::identifiers::{
+    cred_def_id::CredentialDefinitionId, rev_reg_def_id::RevocationRegistryDefinitionId,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialAttachmentFormatType,
+    offer_credential::{OfferCredentialAttachmentFormatType, OfferCredentialV2},
+    propose_credential::ProposeCredentialV2,
+    request_credential::RequestCredentialV2,
+};
+
+use super::IssuerCredentialIssuanceFormat;
+use crate::{
+    common::{
+        credentials::encoding::encode_attributes,
+        primitives::credential_definition::is_cred_def_retired,
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        common::extract_attachment_data,
+        issuance_v2::formats::{AnoncredsCredentialFilter, AnoncredsFormatFamily},
+    },
+};
+
+/// Issuer side handler of the `anoncreds/...@v1.0` and `hlindy/...@v2.0` attachment formats.
+pub struct AnoncredsIssuerCredentialIssuanceFormat<W, A> {
+    _marker: PhantomData<fn() -> (W, A)>,
+}
+
+// Implemented manually, as derives would require the wallet and anoncreds types to implement
+// these traits as well.
+impl<W, A> fmt::Debug for AnoncredsIssuerCredentialIssuanceFormat<W, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnoncredsIssuerCredentialIssuanceFormat")
+    }
+}
+
+impl<W, A> Clone for AnoncredsIssuerCredentialIssuanceFormat<W, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<W, A> PartialEq for AnoncredsIssuerCredentialIssuanceFormat<W, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct AnoncredsProposalDetails {
+    pub family: AnoncredsFormatFamily,
+    pub filter: AnoncredsCredentialFilter,
+}
+
+pub struct AnoncredsCreateOfferInput<'a, W, A> {
+    pub wallet: &'a W,
+    pub anoncreds: &'a A,
+    pub cred_def_id: &'a CredentialDefinitionId,
+    pub family: AnoncredsFormatFamily,
+}
+
+/// Revocation registry to issue a revocable credential from.
+pub struct AnoncredsRevocationInput<'a> {
+    pub rev_reg_id: &'a RevocationRegistryDefinitionId,
+    pub tails_dir: &'a Path,
+}
+
+/// The issued credential values are taken from the credential preview of the offer.
+pub struct AnoncredsCreateCredentialInput<'a, W, A> {
+    pub wallet: &'a W,
+    pub anoncreds: &'a A,
+    pub revocation: Option<AnoncredsRevocationInput<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnoncredsCreatedCredentialMetadata {
+    pub rev_reg_id: Option<String>,
+    pub cred_rev_id: Option<u32>,
+}
+
+#[async_trait]
+impl<W, A> IssuerCredentialIssuanceFormat for AnoncredsIssuerCredentialIssuanceFormat<W, A>
+where
+    W: BaseWallet,
+    A: BaseAnonCreds,
+{
+    type ProposalDetails = AnoncredsProposalDetails;
+
+    type CreateOfferInput<'a>
+        = AnoncredsCreateOfferInput<'a, W, A>
+    where
+        Self: 'a;
+
+    type CreateCredentialInput<'a>
+        = AnoncredsCreateCredentialInput<'a, W, A>
+    where
+        Self: 'a;
+    type CreatedCredentialMetadata = AnoncredsCreatedCredentialMetadata;
+
+    fn extract_proposal_details(
+        proposal: &ProposeCredentialV2,
+    ) -> VcxResult<AnoncredsProposalDetails> {
+        let proposal_formats = AnoncredsFormatFamily::all().map(|family| family.proposal_format());
+        let (format, data) = extract_attachment_data(
+            &proposal.content.formats,
+            &proposal.content.filters_attach,
+            &proposal_formats,
+        )?;
+        let family = AnoncredsFormatFamily::from_proposal_format(&format).ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Credential proposal is not in an anoncreds format",
+            )
+        })?;
+        Ok(AnoncredsProposalDetails {
+            family,
+            filter: serde_json::from_slice(&data)?,
+        })
+    }
+
+    async fn create_offer_attachment_content(
+        input: &AnoncredsCreateOfferInput<'_, W, A>,
+    ) -> VcxResult<(OfferCredentialAttachmentFormatType, Vec<u8>)> {
+        if is_cred_def_retired(input.wallet, input.cred_def_id).await? {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Credential definition {} has been retired and can no longer be offered",
+                    input.cred_def_id
+                ),
+            ));
+        }
+        let cred_offer = input
+            .anoncreds
+            .issuer_create_credential_offer(input.wallet, input.cred_def_id)
+            .await?;
+        Ok((
+            input.family.offer_format(),
+            serde_json::to_vec(&cred_offer)?,
+        ))
+    }
+
+    async fn create_credential_attachment_content(
+        offer: &OfferCredentialV2,
+        request: &RequestCredentialV2,
+        input: &AnoncredsCreateCredentialInput<'_, W, A>,
+    ) -> VcxResult<(
+        IssueCredentialAttachmentFormatType,
+        Vec<u8>,
+        AnoncredsCreatedCredentialMetadata,
+    )> {
+        let offer_formats = AnoncredsFormatFamily::all().map(|family| family.offer_format());
+        let (_, offer_data) = extract_attachment_data(
+            &offer.content.formats,
+            &offer.content.offers_attach,
+            &offer_formats,
+        )?;
+        let request_formats = AnoncredsFormatFamily::all().map(|family| family.request_format());
+        let (request_format, request_data) = extract_attachment_data(
+            &request.content.formats,
+            &request.content.requests_attach,
+            &request_formats,
+        )?;
+        let family =
+            AnoncredsFormatFamily::from_request_format(&request_format).ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "Credential request is not in an anoncreds format",
+                )
+            })?;
+
+        let attributes: HashMap<&str, &str> = offer
+            .content
+            .credential_preview
+            .attributes
+            .iter()
+            .map(|attr| (attr.name.as_str(), attr.value.as_str()))
+            .collect();
+        let cred_values = encode_attributes(&serde_json::to_string(&attributes)?)?;
+
+        let (credential, cred_rev_id) = input
+            .anoncreds
+            .issuer_create_credential(
+                input.wallet,
+                serde_json::from_slice(&offer_data)?,
+                serde_json::from_slice(&request_data)?,
+                serde_json::from_str(&cred_values)?,
+                input
+                    .revocation
+                    .as_ref()
+                    .map(|revocation| revocation.rev_reg_id),
+                input
+                    .revocation
+                    .as_ref()
+                    .map(|revocation| revocation.tails_dir),
+            )
+            .await?;
+        let metadata = AnoncredsCreatedCredentialMetadata {
+            rev_reg_id: input
+                .revocation
+                .as_ref()
+                .map(|revocation| revocation.rev_reg_id.0.clone()),
+            cred_rev_id,
+        };
+        Ok((
+            family.credential_format(),
+            serde_json::to_vec(&credential)?,
+            metadata,
+        ))
+    }
+}
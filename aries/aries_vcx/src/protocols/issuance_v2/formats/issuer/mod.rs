@@ -0,0 +1,57 @@
+pub mod anoncreds;
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialAttachmentFormatType,
+    offer_credential::{OfferCredentialAttachmentFormatType, OfferCredentialV2},
+    propose_credential::ProposeCredentialV2,
+    request_credential::RequestCredentialV2,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::error::VcxResult;
+
+/// Attachment format handler used by [crate::protocols::issuance_v2::issuer::IssuerV2] to
+/// create and process the format-specific attachments of the protocol messages.
+#[async_trait]
+pub trait IssuerCredentialIssuanceFormat {
+    /// Details of a received credential proposal, as extracted from its attachment.
+    type ProposalDetails;
+
+    /// Input used to create the attachment of a credential offer.
+    type CreateOfferInput<'a>: Send + Sync
+    where
+        Self: 'a;
+
+    /// Input used to create the attachment of the issued credential.
+    type CreateCredentialInput<'a>: Send + Sync
+    where
+        Self: 'a;
+    /// Data describing the issued credential, such as its revocation details.
+    type CreatedCredentialMetadata: Debug
+        + Clone
+        + PartialEq
+        + Serialize
+        + DeserializeOwned
+        + Send
+        + Sync;
+
+    fn extract_proposal_details(proposal: &ProposeCredentialV2)
+        -> VcxResult<Self::ProposalDetails>;
+
+    async fn create_offer_attachment_content(
+        input: &Self::CreateOfferInput<'_>,
+    ) -> VcxResult<(OfferCredentialAttachmentFormatType, Vec<u8>)>;
+
+    async fn create_credential_attachment_content(
+        offer: &OfferCredentialV2,
+        request: &RequestCredentialV2,
+        input: &Self::CreateCredentialInput<'_>,
+    ) -> VcxResult<(
+        IssueCredentialAttachmentFormatType,
+        Vec<u8>,
+        Self::CreatedCredentialMetadata,
+    )>;
+}
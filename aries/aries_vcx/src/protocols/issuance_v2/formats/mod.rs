@@ -0,0 +1,144 @@
+//! Attachment format handlers for the issue credential 2.0 protocol.
+//!
+//! A handler implements [holder::HolderCredentialIssuanceFormat] and/or
+//! [issuer::IssuerCredentialIssuanceFormat] for a family of attachment formats. The anoncreds
+//! handlers cover both the `anoncreds/...@v1.0` formats and the `hlindy/...@v2.0` formats, whose
+//! payloads are identical.
+
+pub mod holder;
+pub mod issuer;
+
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    issue_credential::IssueCredentialAttachmentFormatType,
+    offer_credential::OfferCredentialAttachmentFormatType,
+    propose_credential::ProposeCredentialAttachmentFormatType,
+    request_credential::RequestCredentialAttachmentFormatType,
+};
+
+/// Identifies which of the two interchangeable attachment format identifier sets is used for
+/// an anoncreds credential exchange. Replies are always sent in the family the counterparty used.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnoncredsFormatFamily {
+    /// `anoncreds/credential-offer@v1.0`, `anoncreds/credential@v1.0`, ...
+    Anoncreds,
+    /// `hlindy/cred-abstract@v2.0`, `hlindy/cred@v2.0`, ...
+    #[default]
+    HyperledgerIndy,
+}
+
+impl AnoncredsFormatFamily {
+    pub fn proposal_format(&self) -> ProposeCredentialAttachmentFormatType {
+        match self {
+            Self::Anoncreds => ProposeCredentialAttachmentFormatType::AnoncredCredentialFilter1_0,
+            Self::HyperledgerIndy => {
+                ProposeCredentialAttachmentFormatType::HyperledgerIndyCredentialFilter2_0
+            }
+        }
+    }
+
+    pub fn offer_format(&self) -> OfferCredentialAttachmentFormatType {
+        match self {
+            Self::Anoncreds => OfferCredentialAttachmentFormatType::AnoncredsCredentialOffer1_0,
+            Self::HyperledgerIndy => {
+                OfferCredentialAttachmentFormatType::HyperledgerIndyCredentialAbstract2_0
+            }
+        }
+    }
+
+    pub fn request_format(&self) -> RequestCredentialAttachmentFormatType {
+        match self {
+            Self::Anoncreds => RequestCredentialAttachmentFormatType::AnoncredsCredentialRequest1_0,
+            Self::HyperledgerIndy => {
+                RequestCredentialAttachmentFormatType::HyperledgerIndyCredentialRequest2_0
+            }
+        }
+    }
+
+    pub fn credential_format(&self) -> IssueCredentialAttachmentFormatType {
+        match self {
+            Self::Anoncreds => IssueCredentialAttachmentFormatType::AnoncredsCredential1_0,
+            Self::HyperledgerIndy => {
+                IssueCredentialAttachmentFormatType::HyperledgerIndyCredential2_0
+            }
+        }
+    }
+
+    pub fn from_proposal_format(format: &ProposeCredentialAttachmentFormatType) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.proposal_format() == *format)
+    }
+
+    pub fn from_offer_format(format: &OfferCredentialAttachmentFormatType) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.offer_format() == *format)
+    }
+
+    pub fn from_request_format(format: &RequestCredentialAttachmentFormatType) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.request_format() == *format)
+    }
+
+    pub fn from_credential_format(format: &IssueCredentialAttachmentFormatType) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.credential_format() == *format)
+    }
+
+    pub(crate) fn all() -> [Self; 2] {
+        [Self::Anoncreds, Self::HyperledgerIndy]
+    }
+}
+
+/// Credential filter carried by anoncreds credential proposals, restricting which credential
+/// the holder would like to be offered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnoncredsCredentialFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_issuer_did: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer_did: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cred_def_id: Option<String>,
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_format_family_roundtrip() {
+        for family in AnoncredsFormatFamily::all() {
+            assert_eq!(
+                AnoncredsFormatFamily::from_proposal_format(&family.proposal_format()),
+                Some(family)
+            );
+            assert_eq!(
+                AnoncredsFormatFamily::from_offer_format(&family.offer_format()),
+                Some(family)
+            );
+            assert_eq!(
+                AnoncredsFormatFamily::from_request_format(&family.request_format()),
+                Some(family)
+            );
+            assert_eq!(
+                AnoncredsFormatFamily::from_credential_format(&family.credential_format()),
+                Some(family)
+            );
+        }
+        assert_eq!(
+            AnoncredsFormatFamily::from_credential_format(
+                &IssueCredentialAttachmentFormatType::AriesLdProofVc1_0
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,382 @@
+pub mod states;
+
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::{
+        cred_issuance::v2::{
+            ack::{AckCredentialV2, AckCredentialV2Content},
+            issue_credential::IssueCredentialV2,
+            offer_credential::OfferCredentialV2,
+            problem_report::CredIssuanceProblemReportV2,
+            propose_credential::{
+                ProposeCredentialV2, ProposeCredentialV2Content, ProposeCredentialV2Decorators,
+            },
+            request_credential::{
+                RequestCredentialV2, RequestCredentialV2Content, RequestCredentialV2Decorators,
+            },
+            CredentialPreviewV2,
+        },
+        notification::ack::{AckContent, AckDecorators, AckStatus},
+    },
+};
+use shared::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+use self::states::{
+    complete::Complete, credential_received::CredentialReceived, failed::Failed,
+    offer_received::OfferReceived, proposal_prepared::ProposalPrepared,
+    request_prepared::RequestPrepared,
+};
+use super::formats::holder::HolderCredentialIssuanceFormat;
+use crate::{
+    errors::error::VcxResult,
+    handlers::util::{matches_opt_thread_id, matches_thread_id},
+    protocols::{
+        common::{build_attachment, build_protocol_problem_report, thread_mismatch_error},
+        did_exchange::transition::{
+            transition_error::TransitionError, transition_result::TransitionResult,
+        },
+    },
+};
+
+/// Holder side of the issue credential 2.0 protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HolderV2<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> HolderV2<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+
+    /// Abandons the protocol, producing a problem report to be sent to the issuer.
+    pub fn prepare_problem_report(
+        self,
+        reason: String,
+    ) -> TransitionResult<HolderV2<Failed>, CredIssuanceProblemReportV2> {
+        let problem_report = build_protocol_problem_report(&reason, &self.thread_id);
+        TransitionResult {
+            state: HolderV2 {
+                state: Failed {
+                    failure_message: reason,
+                },
+                thread_id: self.thread_id,
+            },
+            output: problem_report,
+        }
+    }
+
+    pub fn receive_problem_report(
+        self,
+        problem_report: CredIssuanceProblemReportV2,
+    ) -> Result<HolderV2<Failed>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        Ok(HolderV2 {
+            state: Failed {
+                failure_message: problem_report.content.inner.description.code,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+fn build_proposal<T: HolderCredentialIssuanceFormat>(
+    input: &T::CreateProposalInput,
+    preview: Option<CredentialPreviewV2>,
+    thread_id: Option<&str>,
+) -> VcxResult<ProposeCredentialV2> {
+    let (format, data) = T::create_proposal_attachment_content(input)?;
+    let (specifier, attachment) = build_attachment(format, &data);
+    let content = ProposeCredentialV2Content::builder()
+        .credential_preview(preview)
+        .formats(vec![specifier])
+        .filters_attach(vec![attachment])
+        .build();
+    let decorators = ProposeCredentialV2Decorators::builder()
+        .thread(thread_id.map(|thid| Thread::builder().thid(thid.to_owned()).build()))
+        .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+        .build();
+    Ok(ProposeCredentialV2::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+impl<T: HolderCredentialIssuanceFormat> HolderV2<ProposalPrepared<T>> {
+    /// Starts the protocol by proposing a credential to the issuer.
+    pub fn with_proposal(
+        input: &T::CreateProposalInput,
+        preview: Option<CredentialPreviewV2>,
+    ) -> VcxResult<Self> {
+        let proposal = build_proposal::<T>(input, preview, None)?;
+        Ok(Self {
+            thread_id: proposal.id.clone(),
+            state: ProposalPrepared {
+                proposal,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_proposal(&self) -> &ProposeCredentialV2 {
+        &self.state.proposal
+    }
+
+    pub fn receive_offer(
+        self,
+        offer: OfferCredentialV2,
+    ) -> Result<HolderV2<OfferReceived<T>>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(offer, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("credential offer"),
+                state: self,
+            });
+        }
+        Ok(HolderV2 {
+            state: OfferReceived {
+                proposal: Some(self.state.proposal),
+                offer,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: HolderCredentialIssuanceFormat> HolderV2<OfferReceived<T>> {
+    /// Starts the protocol from a credential offer received from the issuer.
+    pub fn from_offer(offer: OfferCredentialV2) -> Self {
+        let thread_id = offer
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| offer.id.clone());
+        Self {
+            state: OfferReceived {
+                proposal: None,
+                offer,
+                _marker: PhantomData,
+            },
+            thread_id,
+        }
+    }
+
+    pub fn get_offer(&self) -> &OfferCredentialV2 {
+        &self.state.offer
+    }
+
+    pub fn get_offer_details(&self) -> VcxResult<T::OfferDetails> {
+        T::extract_offer_details(&self.state.offer)
+    }
+
+    /// Counters the received offer with a new proposal.
+    pub fn prepare_proposal(
+        self,
+        input: &T::CreateProposalInput,
+        preview: Option<CredentialPreviewV2>,
+    ) -> Result<HolderV2<ProposalPrepared<T>>, TransitionError<Self>> {
+        let proposal = match build_proposal::<T>(input, preview, Some(&self.thread_id)) {
+            Ok(proposal) => proposal,
+            Err(error) => return Err(TransitionError { error, state: self }),
+        };
+        Ok(HolderV2 {
+            state: ProposalPrepared {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Accepts the received offer, preparing a credential request to be sent to the issuer.
+    pub async fn prepare_credential_request(
+        self,
+        input: &T::CreateRequestInput<'_>,
+    ) -> Result<HolderV2<RequestPrepared<T>>, TransitionError<Self>> {
+        let (format, data, request_metadata) =
+            match T::create_request_attachment_content(&self.state.offer, input).await {
+                Ok(result) => result,
+                Err(error) => return Err(TransitionError { error, state: self }),
+            };
+        let (specifier, attachment) = build_attachment(format, &data);
+        let content = RequestCredentialV2Content::builder()
+            .formats(vec![specifier])
+            .requests_attach(vec![attachment])
+            .build();
+        let decorators = RequestCredentialV2Decorators::builder()
+            .thread(Some(Thread::builder().thid(self.thread_id.clone()).build()))
+            .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+            .build();
+        let request = RequestCredentialV2::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build();
+        Ok(HolderV2 {
+            state: RequestPrepared {
+                offer: self.state.offer,
+                request,
+                request_metadata,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Declines the received offer, producing a problem report to be sent to the issuer.
+    pub fn decline_offer(
+        self,
+        reason: Option<String>,
+    ) -> TransitionResult<HolderV2<Failed>, CredIssuanceProblemReportV2> {
+        self.prepare_problem_report(reason.unwrap_or_else(|| "Offer declined".to_owned()))
+    }
+}
+
+impl<T: HolderCredentialIssuanceFormat> HolderV2<RequestPrepared<T>> {
+    pub fn get_request(&self) -> &RequestCredentialV2 {
+        &self.state.request
+    }
+
+    /// Processes and stores the credential issued in reply to our request.
+    ///
+    /// A credential which was not issued in the format it was requested in is rejected: the
+    /// protocol is abandoned, with a problem report to be sent to the issuer.
+    pub async fn receive_credential(
+        self,
+        credential: IssueCredentialV2,
+        input: &T::StoreCredentialInput<'_>,
+    ) -> Result<HolderV2<CredentialReceived<T>>, ReceiveCredentialError<T>> {
+        if !matches_thread_id!(credential, self.thread_id.as_str()) {
+            return Err(ReceiveCredentialError::Transition(TransitionError {
+                error: thread_mismatch_error("credential"),
+                state: self,
+            }));
+        }
+        if !is_in_requested_format::<T>(&self.state.request, &credential) {
+            return Err(ReceiveCredentialError::FormatMismatch(
+                self.prepare_problem_report(
+                    "Credential was not issued in the requested format".to_owned(),
+                ),
+            ));
+        }
+        let stored_credential_metadata =
+            match T::process_and_store_credential(&credential, input, &self.state.request_metadata)
+                .await
+            {
+                Ok(metadata) => metadata,
+                Err(error) => {
+                    return Err(ReceiveCredentialError::Transition(TransitionError {
+                        error,
+                        state: self,
+                    }))
+                }
+            };
+        Ok(HolderV2 {
+            state: CredentialReceived {
+                credential,
+                stored_credential_metadata,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+/// Error of [HolderV2::receive_credential].
+#[derive(Debug)]
+pub enum ReceiveCredentialError<T: HolderCredentialIssuanceFormat> {
+    /// The credential could not be processed. The holder keeps waiting for the credential.
+    Transition(TransitionError<HolderV2<RequestPrepared<T>>>),
+    /// The credential was not issued in the requested format. The protocol is abandoned, and the
+    /// problem report is to be sent to the issuer.
+    FormatMismatch(TransitionResult<HolderV2<Failed>, CredIssuanceProblemReportV2>),
+}
+
+/// Whether `credential` is attached in the format matching that of `request`.
+fn is_in_requested_format<T: HolderCredentialIssuanceFormat>(
+    request: &RequestCredentialV2,
+    credential: &IssueCredentialV2,
+) -> bool {
+    request
+        .content
+        .formats
+        .iter()
+        .filter_map(|specifier| match &specifier.format {
+            MaybeKnown::Known(format) => T::credential_format(format),
+            MaybeKnown::Unknown(_) => None,
+        })
+        .any(|expected| {
+            let expected = MaybeKnown::Known(expected);
+            credential
+                .content
+                .formats
+                .iter()
+                .any(|specifier| specifier.format == expected)
+        })
+}
+
+impl<T: HolderCredentialIssuanceFormat> HolderV2<CredentialReceived<T>> {
+    pub fn get_credential(&self) -> &IssueCredentialV2 {
+        &self.state.credential
+    }
+
+    pub fn get_stored_credential_metadata(&self) -> &T::StoredCredentialMetadata {
+        &self.state.stored_credential_metadata
+    }
+
+    /// Completes the protocol, producing an ack to be sent to the issuer if it asked for one.
+    pub fn prepare_ack_if_required(
+        self,
+    ) -> TransitionResult<HolderV2<Complete<T>>, Option<AckCredentialV2>> {
+        let ack_required = self.state.credential.decorators.please_ack.is_some();
+        let ack = ack_required.then(|| {
+            let content: AckCredentialV2Content =
+                AckContent::builder().status(AckStatus::Ok).build();
+            let decorators = AckDecorators::builder()
+                .thread(Thread::builder().thid(self.thread_id.clone()).build())
+                .timing(Timing::builder().out_time(Utc::now()).build())
+                .build();
+            AckCredentialV2::builder()
+                .id(Uuid::new_v4().to_string())
+                .content(content)
+                .decorators(decorators)
+                .build()
+        });
+        TransitionResult {
+            state: HolderV2 {
+                state: Complete {
+                    ack_sent: ack.is_some(),
+                    stored_credential_metadata: self.state.stored_credential_metadata,
+                },
+                thread_id: self.thread_id,
+            },
+            output: ack,
+        }
+    }
+}
+
+impl<T: HolderCredentialIssuanceFormat> HolderV2<Complete<T>> {
+    pub fn get_stored_credential_metadata(&self) -> &T::StoredCredentialMetadata {
+        &self.state.stored_credential_metadata
+    }
+}
+
+impl HolderV2<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
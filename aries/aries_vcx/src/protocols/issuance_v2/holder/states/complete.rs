@@ -0,0 +1,9 @@
+use crate::protocols::issuance_v2::formats::holder::HolderCredentialIssuanceFormat;
+
+/// The credential has been received and stored, and the issuer acknowledged if requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Complete<T: HolderCredentialIssuanceFormat> {
+    pub ack_sent: bool,
+    pub stored_credential_metadata: T::StoredCredentialMetadata,
+}
@@ -0,0 +1,12 @@
+use messages::msg_fields::protocols::cred_issuance::v2::issue_credential::IssueCredentialV2;
+
+use crate::protocols::issuance_v2::formats::holder::HolderCredentialIssuanceFormat;
+
+/// The holder has received and stored the issued credential. An ack has to be sent to the issuer
+/// if it was requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CredentialReceived<T: HolderCredentialIssuanceFormat> {
+    pub credential: IssueCredentialV2,
+    pub stored_credential_metadata: T::StoredCredentialMetadata,
+}
@@ -0,0 +1,6 @@
+pub mod complete;
+pub mod credential_received;
+pub mod failed;
+pub mod offer_received;
+pub mod proposal_prepared;
+pub mod request_prepared;
@@ -0,0 +1,18 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    offer_credential::OfferCredentialV2, propose_credential::ProposeCredentialV2,
+};
+
+use crate::protocols::issuance_v2::formats::holder::HolderCredentialIssuanceFormat;
+
+/// The holder has received a credential offer, which it can accept by preparing a credential
+/// request, counter with a proposal or decline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct OfferReceived<T: HolderCredentialIssuanceFormat> {
+    pub proposal: Option<ProposeCredentialV2>,
+    pub offer: OfferCredentialV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
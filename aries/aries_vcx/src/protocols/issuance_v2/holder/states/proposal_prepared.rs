@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::ProposeCredentialV2;
+
+use crate::protocols::issuance_v2::formats::holder::HolderCredentialIssuanceFormat;
+
+/// The holder has prepared a credential proposal, to be sent to the issuer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProposalPrepared<T: HolderCredentialIssuanceFormat> {
+    pub proposal: ProposeCredentialV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,14 @@
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    offer_credential::OfferCredentialV2, request_credential::RequestCredentialV2,
+};
+
+use crate::protocols::issuance_v2::formats::holder::HolderCredentialIssuanceFormat;
+
+/// The holder has prepared a credential request, to be sent to the issuer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestPrepared<T: HolderCredentialIssuanceFormat> {
+    pub offer: OfferCredentialV2,
+    pub request: RequestCredentialV2,
+    pub request_metadata: T::CreatedRequestMetadata,
+}
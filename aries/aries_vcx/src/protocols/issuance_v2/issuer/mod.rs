@@ -0,0 +1,340 @@
+pub mod states;
+
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use messages::{
+    decorators::{
+        please_ack::{AckOn, PleaseAck},
+        thread::Thread,
+        timing::Timing,
+    },
+    msg_fields::protocols::cred_issuance::v2::{
+        ack::AckCredentialV2,
+        issue_credential::{
+            IssueCredentialV2, IssueCredentialV2Content, IssueCredentialV2Decorators,
+        },
+        offer_credential::{
+            OfferCredentialV2, OfferCredentialV2Content, OfferCredentialV2Decorators,
+        },
+        problem_report::CredIssuanceProblemReportV2,
+        propose_credential::ProposeCredentialV2,
+        request_credential::RequestCredentialV2,
+        CredentialPreviewV2,
+    },
+};
+use uuid::Uuid;
+
+use self::states::{
+    complete::Complete, credential_prepared::CredentialPrepared, failed::Failed,
+    offer_prepared::OfferPrepared, proposal_received::ProposalReceived,
+    request_received::RequestReceived,
+};
+use super::formats::issuer::IssuerCredentialIssuanceFormat;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::util::{matches_opt_thread_id, matches_thread_id},
+    protocols::{
+        common::{build_attachment, build_protocol_problem_report, thread_mismatch_error},
+        did_exchange::transition::{
+            transition_error::TransitionError, transition_result::TransitionResult,
+        },
+    },
+};
+
+/// Issuer side of the issue credential 2.0 protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IssuerV2<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> IssuerV2<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+
+    /// Abandons the protocol, producing a problem report to be sent to the holder.
+    pub fn prepare_problem_report(
+        self,
+        reason: String,
+    ) -> TransitionResult<IssuerV2<Failed>, CredIssuanceProblemReportV2> {
+        let problem_report = build_protocol_problem_report(&reason, &self.thread_id);
+        TransitionResult {
+            state: IssuerV2 {
+                state: Failed {
+                    failure_message: reason,
+                },
+                thread_id: self.thread_id,
+            },
+            output: problem_report,
+        }
+    }
+
+    pub fn receive_problem_report(
+        self,
+        problem_report: CredIssuanceProblemReportV2,
+    ) -> Result<IssuerV2<Failed>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        Ok(IssuerV2 {
+            state: Failed {
+                failure_message: problem_report.content.inner.description.code,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+async fn build_offer<T: IssuerCredentialIssuanceFormat>(
+    input: &T::CreateOfferInput<'_>,
+    preview: CredentialPreviewV2,
+    replacement_id: Option<String>,
+    thread_id: Option<&str>,
+) -> VcxResult<OfferCredentialV2> {
+    let (format, data) = T::create_offer_attachment_content(input).await?;
+    let (specifier, attachment) = build_attachment(format, &data);
+    let content = OfferCredentialV2Content::builder()
+        .replacement_id(replacement_id)
+        .credential_preview(preview)
+        .formats(vec![specifier])
+        .offers_attach(vec![attachment])
+        .build();
+    let decorators = OfferCredentialV2Decorators::builder()
+        .thread(thread_id.map(|thid| Thread::builder().thid(thid.to_owned()).build()))
+        .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+        .build();
+    Ok(OfferCredentialV2::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+impl<T: IssuerCredentialIssuanceFormat> IssuerV2<ProposalReceived<T>> {
+    /// Starts the protocol from a credential proposal received from the holder.
+    pub fn from_proposal(proposal: ProposeCredentialV2) -> Self {
+        let thread_id = proposal
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| proposal.id.clone());
+        Self {
+            state: ProposalReceived {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id,
+        }
+    }
+
+    pub fn get_proposal(&self) -> &ProposeCredentialV2 {
+        &self.state.proposal
+    }
+
+    pub fn get_proposal_details(&self) -> VcxResult<T::ProposalDetails> {
+        T::extract_proposal_details(&self.state.proposal)
+    }
+
+    /// Answers the received proposal with a credential offer.
+    pub async fn prepare_offer(
+        self,
+        input: &T::CreateOfferInput<'_>,
+        preview: CredentialPreviewV2,
+        replacement_id: Option<String>,
+    ) -> Result<IssuerV2<OfferPrepared<T>>, TransitionError<Self>> {
+        let offer =
+            match build_offer::<T>(input, preview, replacement_id, Some(&self.thread_id)).await {
+                Ok(offer) => offer,
+                Err(error) => return Err(TransitionError { error, state: self }),
+            };
+        Ok(IssuerV2 {
+            state: OfferPrepared {
+                offer,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: IssuerCredentialIssuanceFormat> IssuerV2<OfferPrepared<T>> {
+    /// Starts the protocol by offering a credential to the holder.
+    pub async fn with_offer(
+        input: &T::CreateOfferInput<'_>,
+        preview: CredentialPreviewV2,
+        replacement_id: Option<String>,
+    ) -> VcxResult<Self> {
+        let offer = build_offer::<T>(input, preview, replacement_id, None).await?;
+        Ok(Self {
+            thread_id: offer.id.clone(),
+            state: OfferPrepared {
+                offer,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_offer(&self) -> &OfferCredentialV2 {
+        &self.state.offer
+    }
+
+    /// Handles a counter proposal sent by the holder in reply to our offer.
+    pub fn receive_proposal(
+        self,
+        proposal: ProposeCredentialV2,
+    ) -> Result<IssuerV2<ProposalReceived<T>>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(proposal, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("credential proposal"),
+                state: self,
+            });
+        }
+        Ok(IssuerV2 {
+            state: ProposalReceived {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    pub fn receive_request(
+        self,
+        request: RequestCredentialV2,
+    ) -> Result<IssuerV2<RequestReceived<T>>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(request, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("credential request"),
+                state: self,
+            });
+        }
+        Ok(IssuerV2 {
+            state: RequestReceived {
+                offer: self.state.offer,
+                request,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: IssuerCredentialIssuanceFormat> IssuerV2<RequestReceived<T>> {
+    pub fn get_request(&self) -> &RequestCredentialV2 {
+        &self.state.request
+    }
+
+    /// Issues the requested credential. If `please_ack` is set, the holder is asked to
+    /// acknowledge its receipt.
+    pub async fn prepare_credential(
+        self,
+        input: &T::CreateCredentialInput<'_>,
+        please_ack: bool,
+    ) -> Result<IssuerV2<CredentialPrepared<T>>, TransitionError<Self>> {
+        let (format, data, credential_metadata) = match T::create_credential_attachment_content(
+            &self.state.offer,
+            &self.state.request,
+            input,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(error) => return Err(TransitionError { error, state: self }),
+        };
+        let (specifier, attachment) = build_attachment(format, &data);
+        let content = IssueCredentialV2Content::builder()
+            .replacement_id(self.state.offer.content.replacement_id.clone())
+            .formats(vec![specifier])
+            .credentials_attach(vec![attachment])
+            .build();
+        let decorators = IssueCredentialV2Decorators::builder()
+            .thread(Thread::builder().thid(self.thread_id.clone()).build())
+            .please_ack(please_ack.then(|| PleaseAck::builder().on(vec![AckOn::Receipt]).build()))
+            .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+            .build();
+        let credential = IssueCredentialV2::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build();
+        Ok(IssuerV2 {
+            state: CredentialPrepared {
+                credential,
+                credential_metadata,
+                please_ack,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: IssuerCredentialIssuanceFormat> IssuerV2<CredentialPrepared<T>> {
+    pub fn get_credential(&self) -> &IssueCredentialV2 {
+        &self.state.credential
+    }
+
+    pub fn get_credential_metadata(&self) -> &T::CreatedCredentialMetadata {
+        &self.state.credential_metadata
+    }
+
+    pub fn receive_ack(
+        self,
+        ack: AckCredentialV2,
+    ) -> Result<IssuerV2<Complete<T>>, TransitionError<Self>> {
+        if !matches_thread_id!(ack, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("ack"),
+                state: self,
+            });
+        }
+        Ok(IssuerV2 {
+            state: Complete {
+                ack: Some(ack),
+                credential_metadata: self.state.credential_metadata,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Completes the protocol once the credential was sent, if no ack was asked for.
+    pub fn complete_without_ack(self) -> Result<IssuerV2<Complete<T>>, TransitionError<Self>> {
+        if self.state.please_ack {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot complete without an ack, as the holder was asked to send one",
+                ),
+                state: self,
+            });
+        }
+        Ok(IssuerV2 {
+            state: Complete {
+                ack: None,
+                credential_metadata: self.state.credential_metadata,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: IssuerCredentialIssuanceFormat> IssuerV2<Complete<T>> {
+    pub fn get_credential_metadata(&self) -> &T::CreatedCredentialMetadata {
+        &self.state.credential_metadata
+    }
+}
+
+impl IssuerV2<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
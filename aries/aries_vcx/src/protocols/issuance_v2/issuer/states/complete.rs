@@ -0,0 +1,11 @@
+use messages::msg_fields::protocols::cred_issuance::v2::ack::AckCredentialV2;
+
+use crate::protocols::issuance_v2::formats::issuer::IssuerCredentialIssuanceFormat;
+
+/// The credential has been issued, and acknowledged by the holder if requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Complete<T: IssuerCredentialIssuanceFormat> {
+    pub ack: Option<AckCredentialV2>,
+    pub credential_metadata: T::CreatedCredentialMetadata,
+}
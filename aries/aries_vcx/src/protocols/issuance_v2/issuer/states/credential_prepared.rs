@@ -0,0 +1,12 @@
+use messages::msg_fields::protocols::cred_issuance::v2::issue_credential::IssueCredentialV2;
+
+use crate::protocols::issuance_v2::formats::issuer::IssuerCredentialIssuanceFormat;
+
+/// The issuer has prepared the credential, to be sent to the holder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct CredentialPrepared<T: IssuerCredentialIssuanceFormat> {
+    pub credential: IssueCredentialV2,
+    pub credential_metadata: T::CreatedCredentialMetadata,
+    pub please_ack: bool,
+}
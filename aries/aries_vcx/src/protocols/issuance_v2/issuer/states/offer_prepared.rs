@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::cred_issuance::v2::offer_credential::OfferCredentialV2;
+
+use crate::protocols::issuance_v2::formats::issuer::IssuerCredentialIssuanceFormat;
+
+/// The issuer has prepared a credential offer, to be sent to the holder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct OfferPrepared<T: IssuerCredentialIssuanceFormat> {
+    pub offer: OfferCredentialV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
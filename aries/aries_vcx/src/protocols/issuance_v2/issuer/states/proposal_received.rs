@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::cred_issuance::v2::propose_credential::ProposeCredentialV2;
+
+use crate::protocols::issuance_v2::formats::issuer::IssuerCredentialIssuanceFormat;
+
+/// The issuer has received a credential proposal, which it can answer with an offer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProposalReceived<T: IssuerCredentialIssuanceFormat> {
+    pub proposal: ProposeCredentialV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,17 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::cred_issuance::v2::{
+    offer_credential::OfferCredentialV2, request_credential::RequestCredentialV2,
+};
+
+use crate::protocols::issuance_v2::formats::issuer::IssuerCredentialIssuanceFormat;
+
+/// The issuer has received a credential request in reply to its offer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestReceived<T: IssuerCredentialIssuanceFormat> {
+    pub offer: OfferCredentialV2,
+    pub request: RequestCredentialV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,318 @@
+//! State machines for the [issue credential 2.0 protocol](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0453-issue-credential-v2/README.md>).
+//!
+//! Both [holder::HolderV2] and [issuer::IssuerV2] are typestate machines, generic over an
+//! attachment format handler which creates and processes the format-specific attachments
+//! exchanged within the protocol messages. See [formats] for the available handlers.
+
+pub mod formats;
+pub mod holder;
+pub mod issuer;
+
+#[cfg(test)]
+mod unit_tests {
+    use async_trait::async_trait;
+    use messages::msg_fields::protocols::cred_issuance::v2::{
+        issue_credential::{IssueCredentialAttachmentFormatType, IssueCredentialV2},
+        offer_credential::{OfferCredentialAttachmentFormatType, OfferCredentialV2},
+        propose_credential::{ProposeCredentialAttachmentFormatType, ProposeCredentialV2},
+        request_credential::{RequestCredentialAttachmentFormatType, RequestCredentialV2},
+        CredentialPreviewV2,
+    };
+    use shared::maybe_known::MaybeKnown;
+
+    use super::{
+        formats::{
+            holder::HolderCredentialIssuanceFormat, issuer::IssuerCredentialIssuanceFormat,
+            AnoncredsFormatFamily,
+        },
+        holder::{
+            states::{offer_received::OfferReceived, proposal_prepared::ProposalPrepared},
+            HolderV2, ReceiveCredentialError,
+        },
+        issuer::{
+            states::{offer_prepared::OfferPrepared, proposal_received::ProposalReceived},
+            IssuerV2,
+        },
+    };
+    use crate::{errors::error::VcxResult, protocols::common::extract_attachment_data};
+
+    const FAMILY: AnoncredsFormatFamily = AnoncredsFormatFamily::Anoncreds;
+
+    /// Format handler passing fixed payloads around, the credential payload being derived from
+    /// the request payload.
+    #[derive(Debug)]
+    struct MockFormat;
+
+    #[async_trait]
+    impl HolderCredentialIssuanceFormat for MockFormat {
+        type CreateProposalInput = ();
+        type OfferDetails = Vec<u8>;
+        type CreateRequestInput<'a> = ();
+        type CreatedRequestMetadata = String;
+        type StoreCredentialInput<'a> = ();
+        type StoredCredentialMetadata = String;
+
+        fn create_proposal_attachment_content(
+            _input: &(),
+        ) -> VcxResult<(ProposeCredentialAttachmentFormatType, Vec<u8>)> {
+            Ok((FAMILY.proposal_format(), b"proposal".to_vec()))
+        }
+
+        fn extract_offer_details(offer: &OfferCredentialV2) -> VcxResult<Vec<u8>> {
+            let (_, data) = extract_attachment_data(
+                &offer.content.formats,
+                &offer.content.offers_attach,
+                &[FAMILY.offer_format()],
+            )?;
+            Ok(data)
+        }
+
+        async fn create_request_attachment_content(
+            _offer: &OfferCredentialV2,
+            _input: &(),
+        ) -> VcxResult<(RequestCredentialAttachmentFormatType, Vec<u8>, String)> {
+            Ok((
+                FAMILY.request_format(),
+                b"request".to_vec(),
+                "request metadata".to_owned(),
+            ))
+        }
+
+        fn credential_format(
+            request_format: &RequestCredentialAttachmentFormatType,
+        ) -> Option<IssueCredentialAttachmentFormatType> {
+            (*request_format == FAMILY.request_format()).then(|| FAMILY.credential_format())
+        }
+
+        async fn process_and_store_credential(
+            credential: &IssueCredentialV2,
+            _input: &(),
+            request_metadata: &String,
+        ) -> VcxResult<String> {
+            let (_, data) = extract_attachment_data(
+                &credential.content.formats,
+                &credential.content.credentials_attach,
+                &[FAMILY.credential_format()],
+            )?;
+            Ok(format!(
+                "{} stored with {}",
+                String::from_utf8_lossy(&data),
+                request_metadata
+            ))
+        }
+    }
+
+    #[async_trait]
+    impl IssuerCredentialIssuanceFormat for MockFormat {
+        type ProposalDetails = Vec<u8>;
+        type CreateOfferInput<'a> = ();
+        type CreateCredentialInput<'a> = ();
+        type CreatedCredentialMetadata = String;
+
+        fn extract_proposal_details(proposal: &ProposeCredentialV2) -> VcxResult<Vec<u8>> {
+            let (_, data) = extract_attachment_data(
+                &proposal.content.formats,
+                &proposal.content.filters_attach,
+                &[FAMILY.proposal_format()],
+            )?;
+            Ok(data)
+        }
+
+        async fn create_offer_attachment_content(
+            _input: &(),
+        ) -> VcxResult<(OfferCredentialAttachmentFormatType, Vec<u8>)> {
+            Ok((FAMILY.offer_format(), b"offer".to_vec()))
+        }
+
+        async fn create_credential_attachment_content(
+            _offer: &OfferCredentialV2,
+            request: &RequestCredentialV2,
+            _input: &(),
+        ) -> VcxResult<(IssueCredentialAttachmentFormatType, Vec<u8>, String)> {
+            let (_, request_data) = extract_attachment_data(
+                &request.content.formats,
+                &request.content.requests_attach,
+                &[FAMILY.request_format()],
+            )?;
+            let credential = format!("credential for {}", String::from_utf8_lossy(&request_data));
+            Ok((
+                FAMILY.credential_format(),
+                credential.into_bytes(),
+                "credential metadata".to_owned(),
+            ))
+        }
+    }
+
+    fn preview() -> CredentialPreviewV2 {
+        CredentialPreviewV2::new(vec![])
+    }
+
+    #[tokio::test]
+    async fn test_issuance_from_proposal_with_ack() {
+        let holder = HolderV2::<ProposalPrepared<MockFormat>>::with_proposal(&(), None).unwrap();
+        let issuer =
+            IssuerV2::<ProposalReceived<MockFormat>>::from_proposal(holder.get_proposal().clone());
+        assert_eq!(issuer.get_thread_id(), holder.get_thread_id());
+        assert_eq!(issuer.get_proposal_details().unwrap(), b"proposal");
+
+        let issuer = issuer.prepare_offer(&(), preview(), None).await.unwrap();
+        let holder = holder.receive_offer(issuer.get_offer().clone()).unwrap();
+        assert_eq!(holder.get_offer_details().unwrap(), b"offer");
+
+        let holder = holder.prepare_credential_request(&()).await.unwrap();
+        let issuer = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap();
+        let issuer = issuer.prepare_credential(&(), true).await.unwrap();
+        assert_eq!(issuer.get_credential_metadata(), "credential metadata");
+
+        let holder = holder
+            .receive_credential(issuer.get_credential().clone(), &())
+            .await
+            .unwrap();
+        assert_eq!(
+            holder.get_stored_credential_metadata(),
+            "credential for request stored with request metadata"
+        );
+        let result = holder.prepare_ack_if_required();
+        assert!(result.state.get_state().ack_sent);
+        let issuer = issuer.receive_ack(result.output.unwrap()).unwrap();
+        assert_eq!(issuer.get_credential_metadata(), "credential metadata");
+        assert_eq!(issuer.get_thread_id(), result.state.get_thread_id());
+    }
+
+    #[tokio::test]
+    async fn test_issuance_from_offer_without_ack() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder = HolderV2::<OfferReceived<MockFormat>>::from_offer(issuer.get_offer().clone());
+        assert_eq!(holder.get_thread_id(), issuer.get_thread_id());
+
+        let holder = holder.prepare_credential_request(&()).await.unwrap();
+        let issuer = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap();
+        let issuer = issuer.prepare_credential(&(), false).await.unwrap();
+        let holder = holder
+            .receive_credential(issuer.get_credential().clone(), &())
+            .await
+            .unwrap();
+
+        let result = holder.prepare_ack_if_required();
+        assert!(result.output.is_none());
+        assert!(!result.state.get_state().ack_sent);
+        issuer.complete_without_ack().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_issuer_cannot_complete_without_requested_ack() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder = HolderV2::<OfferReceived<MockFormat>>::from_offer(issuer.get_offer().clone())
+            .prepare_credential_request(&())
+            .await
+            .unwrap();
+        let issuer = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap()
+            .prepare_credential(&(), true)
+            .await
+            .unwrap();
+        let error = issuer.complete_without_ack().unwrap_err();
+        assert!(error.state.get_credential().decorators.please_ack.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_holder_declines_offer() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder = HolderV2::<OfferReceived<MockFormat>>::from_offer(issuer.get_offer().clone());
+        let result = holder.decline_offer(Some("Not interested".to_owned()));
+        assert_eq!(result.state.get_failure_message(), "Not interested");
+
+        let issuer = issuer.receive_problem_report(result.output).unwrap();
+        assert_eq!(issuer.get_failure_message(), "Not interested");
+    }
+
+    #[tokio::test]
+    async fn test_issuer_abandons_after_request() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder = HolderV2::<OfferReceived<MockFormat>>::from_offer(issuer.get_offer().clone())
+            .prepare_credential_request(&())
+            .await
+            .unwrap();
+        let result = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap()
+            .prepare_problem_report("Cannot issue".to_owned());
+        assert_eq!(result.state.get_failure_message(), "Cannot issue");
+
+        let holder = holder.receive_problem_report(result.output).unwrap();
+        assert_eq!(holder.get_failure_message(), "Cannot issue");
+    }
+
+    #[tokio::test]
+    async fn test_holder_rejects_credential_in_other_format() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder = HolderV2::<OfferReceived<MockFormat>>::from_offer(issuer.get_offer().clone())
+            .prepare_credential_request(&())
+            .await
+            .unwrap();
+        let issuer = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap()
+            .prepare_credential(&(), false)
+            .await
+            .unwrap();
+        let mut credential = issuer.get_credential().clone();
+        credential.content.formats[0].format =
+            MaybeKnown::Known(AnoncredsFormatFamily::HyperledgerIndy.credential_format());
+
+        let Err(ReceiveCredentialError::FormatMismatch(result)) =
+            holder.receive_credential(credential, &()).await
+        else {
+            panic!("Expected the credential to be rejected");
+        };
+        assert_eq!(
+            result.state.get_failure_message(),
+            "Credential was not issued in the requested format"
+        );
+        let issuer = issuer.receive_problem_report(result.output).unwrap();
+        assert_eq!(
+            issuer.get_failure_message(),
+            "Credential was not issued in the requested format"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_messages_of_other_threads_are_rejected() {
+        let issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let other_issuer = IssuerV2::<OfferPrepared<MockFormat>>::with_offer(&(), preview(), None)
+            .await
+            .unwrap();
+        let holder =
+            HolderV2::<OfferReceived<MockFormat>>::from_offer(other_issuer.get_offer().clone())
+                .prepare_credential_request(&())
+                .await
+                .unwrap();
+
+        let error = issuer
+            .receive_request(holder.get_request().clone())
+            .unwrap_err();
+        let problem_report = error
+            .state
+            .prepare_problem_report("Unexpected request".to_owned())
+            .output;
+        let error = holder.receive_problem_report(problem_report).unwrap_err();
+        assert_eq!(error.state.get_thread_id(), other_issuer.get_thread_id());
+    }
+}
@@ -4,13 +4,18 @@ use messages::AriesMessage;
 
 use crate::errors::error::VcxResult;
 
+pub mod action_menu;
 pub mod common;
 pub mod connection;
 pub mod did_exchange;
+pub mod did_rotate;
 pub mod issuance;
+pub mod issuance_v2;
 pub mod mediated_connection;
 pub mod oob;
 pub mod proof_presentation;
+pub mod proof_presentation_v2;
+pub mod question_answer;
 pub mod revocation_notification;
 pub mod trustping;
 
@@ -97,7 +97,7 @@ pub async fn oob_invitation_to_legacy_did_doc(
     Ok(did_doc)
 }
 
-fn normalize_keys_as_naked(keys_list: &Vec<String>) -> VcxResult<Vec<String>> {
+pub(crate) fn normalize_keys_as_naked(keys_list: &[String]) -> VcxResult<Vec<String>> {
     let mut result = Vec::new();
     for key in keys_list {
         if let Some(stripped_didkey) = key.strip_prefix(DID_KEY_PREFIX) {
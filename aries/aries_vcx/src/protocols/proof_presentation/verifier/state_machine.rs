@@ -25,6 +25,7 @@ use messages::{
 use uuid::Uuid;
 
 use crate::{
+    common::proofs::verifier::verification_report::{VerificationPolicy, VerificationReport},
     errors::error::prelude::*,
     handlers::util::{make_attach_from_str, verify_thread_id, AttachmentId, Status},
     protocols::{
@@ -254,18 +255,32 @@ impl VerifierSM {
         ledger: &'a impl AnoncredsLedgerRead,
         anoncreds: &'a impl BaseAnonCreds,
         presentation: PresentationV1,
+    ) -> VcxResult<Self> {
+        self.verify_presentation_with_policy(
+            ledger,
+            anoncreds,
+            presentation,
+            &VerificationPolicy::default(),
+        )
+        .await
+    }
+
+    pub async fn verify_presentation_with_policy<'a>(
+        self,
+        ledger: &'a impl AnoncredsLedgerRead,
+        anoncreds: &'a impl BaseAnonCreds,
+        presentation: PresentationV1,
+        policy: &'a VerificationPolicy,
     ) -> VcxResult<Self> {
         verify_thread_id(&self.thread_id, &presentation.clone().into())?;
         let state = match self.state {
             VerifierFullState::PresentationRequestSent(state) => {
                 let verification_result = state
-                    .verify_presentation(ledger, anoncreds, &presentation, &self.thread_id)
+                    .verify_presentation(ledger, anoncreds, &presentation, &self.thread_id, policy)
                     .await;
 
                 match verification_result {
-                    Ok(()) => VerifierFullState::Finished(
-                        (state, presentation, PresentationVerificationStatus::Valid).into(),
-                    ),
+                    Ok(report) => VerifierFullState::Finished((state, presentation, report).into()),
                     Err(err) => {
                         let problem_report =
                             build_problem_report_msg(Some(err.to_string()), &self.thread_id);
@@ -420,6 +435,13 @@ impl VerifierSM {
         }
     }
 
+    pub fn get_verification_report(&self) -> Option<&VerificationReport> {
+        match self.state {
+            VerifierFullState::Finished(ref state) => state.verification_report.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn presentation_request_msg(&self) -> VcxResult<RequestPresentationV1> {
         match self.state {
             VerifierFullState::Initial(_) => Err(AriesVcxError::from_msg(
@@ -6,7 +6,10 @@ use messages::msg_fields::protocols::{
 };
 
 use crate::{
-    common::proofs::verifier::validate_indy_proof,
+    common::proofs::verifier::{
+        verification_report::{VerificationPolicy, VerificationReport},
+        verify_indy_proof_with_report,
+    },
     errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
     handlers::util::{get_attach_as_string, matches_thread_id, Status},
     protocols::proof_presentation::verifier::{
@@ -26,7 +29,8 @@ impl PresentationRequestSentState {
         anoncreds: &impl BaseAnonCreds,
         presentation: &PresentationV1,
         thread_id: &str,
-    ) -> VcxResult<()> {
+        policy: &VerificationPolicy,
+    ) -> VcxResult<VerificationReport> {
         if !matches_thread_id!(presentation, thread_id) {
             return Err(AriesVcxError::from_msg(
                 AriesVcxErrorKind::InvalidJson,
@@ -45,16 +49,15 @@ impl PresentationRequestSentState {
                 .request_presentations_attach
         );
 
-        let valid = validate_indy_proof(ledger, anoncreds, &proof_json, &proof_req_json).await?;
+        let report =
+            verify_indy_proof_with_report(ledger, anoncreds, &proof_json, &proof_req_json, policy)
+                .await?;
 
-        if !valid {
-            return Err(AriesVcxError::from_msg(
-                AriesVcxErrorKind::InvalidProof,
-                "Presentation verification failed",
-            ));
+        if !report.is_valid() {
+            warn!("Presentation verification failed, report: {:?}", report);
         }
 
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -78,6 +81,32 @@ impl
             presentation: Some(presentation),
             status: Status::Success,
             verification_status,
+            verification_report: None,
+        }
+    }
+}
+
+impl
+    From<(
+        PresentationRequestSentState,
+        PresentationV1,
+        VerificationReport,
+    )> for FinishedState
+{
+    fn from(
+        (state, presentation, verification_report): (
+            PresentationRequestSentState,
+            PresentationV1,
+            VerificationReport,
+        ),
+    ) -> Self {
+        trace!("transit state from PresentationRequestSentState to FinishedState");
+        FinishedState {
+            presentation_request: Some(state.presentation_request),
+            presentation: Some(presentation),
+            status: Status::Success,
+            verification_status: verification_report.verification_status(),
+            verification_report: Some(verification_report),
         }
     }
 }
@@ -94,6 +123,7 @@ impl From<(PresentationRequestSentState, ProblemReport)> for FinishedState {
             presentation: None,
             status: Status::Failed(problem_report),
             verification_status: PresentationVerificationStatus::Unavailable,
+            verification_report: None,
         }
     }
 }
@@ -0,0 +1,179 @@
+//! Attachment format handlers for the present proof 2.0 protocol.
+//!
+//! A handler implements [prover::ProverPresentationFormat] and/or
+//! [verifier::VerifierPresentationFormat] for a family of attachment formats. The anoncreds
+//! handlers cover both the `anoncreds/...@v1.0` formats and the `hlindy/...@v2.0` formats, and
+//! reuse the anoncreds proof generation and verification of [crate::common::proofs]. The
+//! presentation exchange handlers cover the `dif/presentation-exchange/...@v1.0` formats, with
+//! submissions backed by anoncreds proofs as built by
+//! [crate::common::proofs::presentation_exchange::anoncreds]. Other DIF submissions, e.g. of
+//! JSON-LD or JWT credentials, are not supported.
+
+pub mod prover;
+pub mod verifier;
+
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationAttachmentFormatType, propose::ProposePresentationAttachmentFormatType,
+    request::PresentationRequestAttachmentFormatType,
+};
+
+use crate::{
+    common::proofs::presentation_exchange::definition::PresentationExchangeRequest,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::issuance_v2::formats::AnoncredsFormatFamily,
+};
+
+impl AnoncredsFormatFamily {
+    pub fn proof_proposal_format(&self) -> ProposePresentationAttachmentFormatType {
+        match self {
+            Self::Anoncreds => ProposePresentationAttachmentFormatType::AnoncredsProofRequest1_0,
+            Self::HyperledgerIndy => {
+                ProposePresentationAttachmentFormatType::HyperledgerIndyProofRequest2_0
+            }
+        }
+    }
+
+    pub fn proof_request_format(&self) -> PresentationRequestAttachmentFormatType {
+        match self {
+            Self::Anoncreds => PresentationRequestAttachmentFormatType::AnoncredsProofRequest1_0,
+            Self::HyperledgerIndy => {
+                PresentationRequestAttachmentFormatType::HyperledgerIndyProofRequest2_0
+            }
+        }
+    }
+
+    pub fn proof_format(&self) -> PresentationAttachmentFormatType {
+        match self {
+            Self::Anoncreds => PresentationAttachmentFormatType::AnoncredsProof1_0,
+            Self::HyperledgerIndy => PresentationAttachmentFormatType::HyperledgerIndyProof2_0,
+        }
+    }
+
+    pub fn from_proof_proposal_format(
+        format: &ProposePresentationAttachmentFormatType,
+    ) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.proof_proposal_format() == *format)
+    }
+
+    pub fn from_proof_request_format(
+        format: &PresentationRequestAttachmentFormatType,
+    ) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.proof_request_format() == *format)
+    }
+
+    pub fn from_proof_format(format: &PresentationAttachmentFormatType) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|family| family.proof_format() == *format)
+    }
+}
+
+/// Returns the challenge submissions to `request` are bound to.
+fn presentation_exchange_challenge(request: &PresentationExchangeRequest) -> VcxResult<&str> {
+    request
+        .options
+        .as_ref()
+        .and_then(|options| options.challenge.as_deref())
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Presentation exchange request has no challenge",
+            )
+        })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+    use shared::maybe_known::MaybeKnown;
+    use test_utils::{
+        mock_wallet::MockWallet,
+        mockdata::{mock_anoncreds::MockAnoncreds, mock_ledger::MockLedger},
+    };
+
+    use super::{
+        prover::presentation_exchange::PresentationExchangeProverPresentationFormat,
+        verifier::{
+            presentation_exchange::PresentationExchangeVerifierPresentationFormat,
+            VerifierPresentationFormat,
+        },
+        *,
+    };
+    use crate::protocols::proof_presentation_v2::{
+        prover::{states::request_received::RequestReceived, ProverV2},
+        verifier::{states::request_prepared::RequestPrepared, VerifierV2},
+    };
+
+    type DifProver =
+        PresentationExchangeProverPresentationFormat<MockWallet, MockLedger, MockAnoncreds>;
+    type DifVerifier = PresentationExchangeVerifierPresentationFormat<MockLedger, MockAnoncreds>;
+
+    fn presentation_exchange_request(challenge: Option<&str>) -> PresentationExchangeRequest {
+        serde_json::from_value(json!({
+            "options": {"challenge": challenge},
+            "presentation_definition": {
+                "id": "adult",
+                "input_descriptors": [{
+                    "id": "identity",
+                    "constraints": {"fields": [{"path": ["$.credentialSubject.name"]}]}
+                }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_presentation_exchange_request() {
+        let request = presentation_exchange_request(Some("challenge"));
+        let verifier =
+            VerifierV2::<RequestPrepared<DifVerifier>>::with_request(&request, false).unwrap();
+        assert_eq!(
+            verifier.get_request().content.formats[0].format,
+            MaybeKnown::Known(
+                PresentationRequestAttachmentFormatType::DifPresentationExchangeDefinitions1_0
+            )
+        );
+        let prover =
+            ProverV2::<RequestReceived<DifProver>>::from_request(verifier.get_request().clone());
+        assert_eq!(prover.get_request_details().unwrap(), request);
+    }
+
+    #[test]
+    fn test_presentation_exchange_request_without_challenge_fails() {
+        let request = presentation_exchange_request(None);
+        assert_eq!(
+            DifVerifier::create_request_attachment_content(&request)
+                .unwrap_err()
+                .kind(),
+            AriesVcxErrorKind::InvalidInput
+        );
+    }
+
+    #[test]
+    fn test_proof_format_family_roundtrip() {
+        for family in AnoncredsFormatFamily::all() {
+            assert_eq!(
+                AnoncredsFormatFamily::from_proof_proposal_format(&family.proof_proposal_format()),
+                Some(family)
+            );
+            assert_eq!(
+                AnoncredsFormatFamily::from_proof_request_format(&family.proof_request_format()),
+                Some(family)
+            );
+            assert_eq!(
+                AnoncredsFormatFamily::from_proof_format(&family.proof_format()),
+                Some(family)
+            );
+        }
+        assert_eq!(
+            AnoncredsFormatFamily::from_proof_request_format(
+                &PresentationRequestAttachmentFormatType::DifPresentationExchangeDefinitions1_0
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use anoncreds_types::data_types::messages::{
+    cred_selection::SelectedCredentials, pres_request::PresentationRequest,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationAttachmentFormatType, propose::ProposePresentationAttachmentFormatType,
+    request::RequestPresentationV2,
+};
+
+use super::ProverPresentationFormat;
+use crate::{
+    common::proofs::prover::generate_indy_proof,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{common::extract_attachment_data, issuance_v2::formats::AnoncredsFormatFamily},
+};
+
+/// Prover side handler of the `anoncreds/...@v1.0` and `hlindy/...@v2.0` attachment formats.
+pub struct AnoncredsProverPresentationFormat<W, R, A> {
+    _marker: PhantomData<fn() -> (W, R, A)>,
+}
+
+// Implemented manually, as derives would require the wallet, ledger and anoncreds types to
+// implement these traits as well.
+impl<W, R, A> fmt::Debug for AnoncredsProverPresentationFormat<W, R, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnoncredsProverPresentationFormat")
+    }
+}
+
+impl<W, R, A> Clone for AnoncredsProverPresentationFormat<W, R, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<W, R, A> PartialEq for AnoncredsProverPresentationFormat<W, R, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Anoncreds presentation proposals take the shape of the presentation request the prover
+/// would like to receive.
+pub struct AnoncredsCreatePresentationProposalInput {
+    pub family: AnoncredsFormatFamily,
+    pub proposal: PresentationRequest,
+}
+
+#[derive(Debug)]
+pub struct AnoncredsPresentationRequestDetails {
+    pub family: AnoncredsFormatFamily,
+    pub presentation_request: PresentationRequest,
+}
+
+pub struct AnoncredsCreatePresentationInput<'a, W, R, A> {
+    pub wallet: &'a W,
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+    pub credentials: &'a SelectedCredentials,
+    pub self_attested_attrs: HashMap<String, String>,
+}
+
+#[async_trait]
+impl<W, R, A> ProverPresentationFormat for AnoncredsProverPresentationFormat<W, R, A>
+where
+    W: BaseWallet,
+    R: AnoncredsLedgerRead,
+    A: BaseAnonCreds,
+{
+    type CreateProposalInput = AnoncredsCreatePresentationProposalInput;
+    type RequestDetails = AnoncredsPresentationRequestDetails;
+
+    type CreatePresentationInput<'a>
+        = AnoncredsCreatePresentationInput<'a, W, R, A>
+    where
+        Self: 'a;
+
+    fn create_proposal_attachment_content(
+        input: &AnoncredsCreatePresentationProposalInput,
+    ) -> VcxResult<(ProposePresentationAttachmentFormatType, Vec<u8>)> {
+        Ok((
+            input.family.proof_proposal_format(),
+            serde_json::to_vec(&input.proposal)?,
+        ))
+    }
+
+    fn extract_request_details(
+        request: &RequestPresentationV2,
+    ) -> VcxResult<AnoncredsPresentationRequestDetails> {
+        let (family, data) = extract_request_data(request)?;
+        Ok(AnoncredsPresentationRequestDetails {
+            family,
+            presentation_request: serde_json::from_slice(&data)?,
+        })
+    }
+
+    async fn create_presentation_attachment_content(
+        request: &RequestPresentationV2,
+        input: &AnoncredsCreatePresentationInput<'_, W, R, A>,
+    ) -> VcxResult<(PresentationAttachmentFormatType, Vec<u8>)> {
+        let (family, data) = extract_request_data(request)?;
+        let presentation = generate_indy_proof(
+            input.wallet,
+            input.ledger,
+            input.anoncreds,
+            input.credentials,
+            input.self_attested_attrs.clone(),
+            serde_json::from_slice(&data)?,
+        )
+        .await?;
+        Ok((family.proof_format(), serde_json::to_vec(&presentation)?))
+    }
+}
+
+fn extract_request_data(
+    request: &RequestPresentationV2,
+) -> VcxResult<(AnoncredsFormatFamily, Vec<u8>)> {
+    let request_formats = AnoncredsFormatFamily::all().map(|family| family.proof_request_format());
+    let (format, data) = extract_attachment_data(
+        &request.content.formats,
+        &request.content.request_presentations_attach,
+        &request_formats,
+    )?;
+    let family = AnoncredsFormatFamily::from_proof_request_format(&format).ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Presentation request is not in an anoncreds format",
+        )
+    })?;
+    Ok((family, data))
+}
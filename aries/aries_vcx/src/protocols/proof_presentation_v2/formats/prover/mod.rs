@@ -0,0 +1,36 @@
+pub mod anoncreds;
+pub mod presentation_exchange;
+
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationAttachmentFormatType, propose::ProposePresentationAttachmentFormatType,
+    request::RequestPresentationV2,
+};
+
+use crate::errors::error::VcxResult;
+
+/// Attachment format handler used by [crate::protocols::proof_presentation_v2::prover::ProverV2]
+/// to create and process the format-specific attachments of the protocol messages.
+#[async_trait]
+pub trait ProverPresentationFormat {
+    /// Input used to create the attachment of a presentation proposal.
+    type CreateProposalInput: Send + Sync;
+    /// Details of a received presentation request, as extracted from its attachment.
+    type RequestDetails;
+
+    /// Input used to create the attachment of the presentation.
+    type CreatePresentationInput<'a>: Send + Sync
+    where
+        Self: 'a;
+
+    fn create_proposal_attachment_content(
+        input: &Self::CreateProposalInput,
+    ) -> VcxResult<(ProposePresentationAttachmentFormatType, Vec<u8>)>;
+
+    fn extract_request_details(request: &RequestPresentationV2) -> VcxResult<Self::RequestDetails>;
+
+    async fn create_presentation_attachment_content(
+        request: &RequestPresentationV2,
+        input: &Self::CreatePresentationInput<'_>,
+    ) -> VcxResult<(PresentationAttachmentFormatType, Vec<u8>)>;
+}
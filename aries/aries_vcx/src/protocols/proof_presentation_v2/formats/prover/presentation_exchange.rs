@@ -0,0 +1,118 @@
+use std::{fmt, marker::PhantomData};
+
+use anoncreds_types::data_types::messages::cred_selection::RetrievedCredentialInfo;
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationAttachmentFormatType,
+    propose::ProposePresentationAttachmentFormatType,
+    request::{PresentationRequestAttachmentFormatType, RequestPresentationV2},
+};
+
+use super::ProverPresentationFormat;
+use crate::{
+    common::proofs::presentation_exchange::{
+        anoncreds::build_anoncreds_submission, definition::PresentationExchangeRequest,
+    },
+    errors::error::VcxResult,
+    protocols::{
+        common::extract_attachment_data,
+        proof_presentation_v2::formats::presentation_exchange_challenge,
+    },
+};
+
+/// Prover side handler of the `dif/presentation-exchange/...@v1.0` attachment formats. The
+/// submitted presentations are backed by anoncreds proofs, see
+/// [crate::common::proofs::presentation_exchange::anoncreds].
+pub struct PresentationExchangeProverPresentationFormat<W, R, A> {
+    _marker: PhantomData<fn() -> (W, R, A)>,
+}
+
+// Implemented manually, as derives would require the wallet, ledger and anoncreds types to
+// implement these traits as well.
+impl<W, R, A> fmt::Debug for PresentationExchangeProverPresentationFormat<W, R, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PresentationExchangeProverPresentationFormat")
+    }
+}
+
+impl<W, R, A> Clone for PresentationExchangeProverPresentationFormat<W, R, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<W, R, A> PartialEq for PresentationExchangeProverPresentationFormat<W, R, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+pub struct PresentationExchangeCreatePresentationInput<'a, W, R, A> {
+    pub wallet: &'a W,
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+    /// Stored credentials to select the submitted ones from, e.g. the candidates found by
+    /// [crate::common::proofs::presentation_exchange::anoncreds::match_stored_credentials].
+    pub credentials: &'a [RetrievedCredentialInfo],
+}
+
+#[async_trait]
+impl<W, R, A> ProverPresentationFormat for PresentationExchangeProverPresentationFormat<W, R, A>
+where
+    W: BaseWallet,
+    R: AnoncredsLedgerRead,
+    A: BaseAnonCreds,
+{
+    type CreateProposalInput = PresentationExchangeRequest;
+    type RequestDetails = PresentationExchangeRequest;
+
+    type CreatePresentationInput<'a>
+        = PresentationExchangeCreatePresentationInput<'a, W, R, A>
+    where
+        Self: 'a;
+
+    fn create_proposal_attachment_content(
+        input: &PresentationExchangeRequest,
+    ) -> VcxResult<(ProposePresentationAttachmentFormatType, Vec<u8>)> {
+        Ok((
+            ProposePresentationAttachmentFormatType::DifPresentationExchangeDefinitions1_0,
+            serde_json::to_vec(input)?,
+        ))
+    }
+
+    fn extract_request_details(
+        request: &RequestPresentationV2,
+    ) -> VcxResult<PresentationExchangeRequest> {
+        let (_, data) = extract_attachment_data(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            &[PresentationRequestAttachmentFormatType::DifPresentationExchangeDefinitions1_0],
+        )?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    async fn create_presentation_attachment_content(
+        request: &RequestPresentationV2,
+        input: &PresentationExchangeCreatePresentationInput<'_, W, R, A>,
+    ) -> VcxResult<(PresentationAttachmentFormatType, Vec<u8>)> {
+        let request = Self::extract_request_details(request)?;
+        let presentation = build_anoncreds_submission(
+            input.wallet,
+            input.ledger,
+            input.anoncreds,
+            &request.presentation_definition,
+            presentation_exchange_challenge(&request)?,
+            input.credentials,
+        )
+        .await?;
+        Ok((
+            PresentationAttachmentFormatType::DifPresentationExchangeSubmission1_0,
+            serde_json::to_vec(&presentation)?,
+        ))
+    }
+}
@@ -0,0 +1,159 @@
+use std::{fmt, marker::PhantomData};
+
+use anoncreds_types::data_types::messages::pres_request::PresentationRequest;
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2,
+    propose::ProposePresentationV2,
+    request::{PresentationRequestAttachmentFormatType, RequestPresentationV2},
+};
+
+use super::VerifierPresentationFormat;
+use crate::{
+    common::proofs::verifier::{
+        verification_report::{VerificationPolicy, VerificationReport},
+        verify_indy_proof_with_report,
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        common::{extract_attachment_data, extract_optional_id_attachment_data},
+        issuance_v2::formats::AnoncredsFormatFamily,
+    },
+};
+
+/// Verifier side handler of the `anoncreds/...@v1.0` and `hlindy/...@v2.0` attachment formats.
+pub struct AnoncredsVerifierPresentationFormat<R, A> {
+    _marker: PhantomData<fn() -> (R, A)>,
+}
+
+// Implemented manually, as derives would require the ledger and anoncreds types to implement
+// these traits as well.
+impl<R, A> fmt::Debug for AnoncredsVerifierPresentationFormat<R, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AnoncredsVerifierPresentationFormat")
+    }
+}
+
+impl<R, A> Clone for AnoncredsVerifierPresentationFormat<R, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R, A> PartialEq for AnoncredsVerifierPresentationFormat<R, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct AnoncredsPresentationProposalDetails {
+    pub family: AnoncredsFormatFamily,
+    pub proposal: PresentationRequest,
+}
+
+pub struct AnoncredsCreatePresentationRequestInput {
+    pub family: AnoncredsFormatFamily,
+    pub presentation_request: PresentationRequest,
+}
+
+pub struct AnoncredsVerifyPresentationInput<'a, R, A> {
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+    pub policy: &'a VerificationPolicy,
+}
+
+#[async_trait]
+impl<R, A> VerifierPresentationFormat for AnoncredsVerifierPresentationFormat<R, A>
+where
+    R: AnoncredsLedgerRead,
+    A: BaseAnonCreds,
+{
+    type ProposalDetails = AnoncredsPresentationProposalDetails;
+    type CreateRequestInput = AnoncredsCreatePresentationRequestInput;
+
+    type VerifyPresentationInput<'a>
+        = AnoncredsVerifyPresentationInput<'a, R, A>
+    where
+        Self: 'a;
+    type VerificationDetails = VerificationReport;
+
+    fn extract_proposal_details(
+        proposal: &ProposePresentationV2,
+    ) -> VcxResult<AnoncredsPresentationProposalDetails> {
+        let proposal_formats =
+            AnoncredsFormatFamily::all().map(|family| family.proof_proposal_format());
+        let (format, data) = extract_optional_id_attachment_data(
+            &proposal.content.formats,
+            proposal
+                .content
+                .proposals_attach
+                .as_deref()
+                .unwrap_or_default(),
+            &proposal_formats,
+        )?;
+        let family =
+            AnoncredsFormatFamily::from_proof_proposal_format(&format).ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessageFormat,
+                    "Presentation proposal is not in an anoncreds format",
+                )
+            })?;
+        Ok(AnoncredsPresentationProposalDetails {
+            family,
+            proposal: serde_json::from_slice(&data)?,
+        })
+    }
+
+    fn create_request_attachment_content(
+        input: &AnoncredsCreatePresentationRequestInput,
+    ) -> VcxResult<(PresentationRequestAttachmentFormatType, Vec<u8>)> {
+        Ok((
+            input.family.proof_request_format(),
+            serde_json::to_vec(&input.presentation_request)?,
+        ))
+    }
+
+    async fn verify_presentation(
+        request: &RequestPresentationV2,
+        presentation: &PresentationV2,
+        input: &AnoncredsVerifyPresentationInput<'_, R, A>,
+    ) -> VcxResult<(bool, VerificationReport)> {
+        let request_formats =
+            AnoncredsFormatFamily::all().map(|family| family.proof_request_format());
+        let (_, request_data) = extract_attachment_data(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            &request_formats,
+        )?;
+        let presentation_formats = AnoncredsFormatFamily::all().map(|family| family.proof_format());
+        let (_, presentation_data) = extract_attachment_data(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            &presentation_formats,
+        )?;
+
+        let report = verify_indy_proof_with_report(
+            input.ledger,
+            input.anoncreds,
+            &utf8_attachment(presentation_data)?,
+            &utf8_attachment(request_data)?,
+            input.policy,
+        )
+        .await?;
+        Ok((report.is_valid(), report))
+    }
+}
+
+fn utf8_attachment(data: Vec<u8>) -> VcxResult<String> {
+    String::from_utf8(data).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::EncodeError,
+            format!("Attachment is not valid UTF-8: {err}"),
+        )
+    })
+}
@@ -0,0 +1,48 @@
+pub mod anoncreds;
+pub mod presentation_exchange;
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2,
+    propose::ProposePresentationV2,
+    request::{PresentationRequestAttachmentFormatType, RequestPresentationV2},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::error::VcxResult;
+
+/// Attachment format handler used by
+/// [crate::protocols::proof_presentation_v2::verifier::VerifierV2] to create and process the
+/// format-specific attachments of the protocol messages.
+#[async_trait]
+pub trait VerifierPresentationFormat {
+    /// Details of a received presentation proposal, as extracted from its attachment.
+    type ProposalDetails;
+    /// Input used to create the attachment of a presentation request.
+    type CreateRequestInput: Send + Sync;
+
+    /// Input used to verify the received presentation.
+    type VerifyPresentationInput<'a>: Send + Sync
+    where
+        Self: 'a;
+    /// Format-specific outcome of the presentation verification.
+    type VerificationDetails: Debug + Clone + PartialEq + Serialize + DeserializeOwned + Send + Sync;
+
+    fn extract_proposal_details(
+        proposal: &ProposePresentationV2,
+    ) -> VcxResult<Self::ProposalDetails>;
+
+    fn create_request_attachment_content(
+        input: &Self::CreateRequestInput,
+    ) -> VcxResult<(PresentationRequestAttachmentFormatType, Vec<u8>)>;
+
+    /// Verifies `presentation` against `request`, returning whether it is valid along with the
+    /// details of the verification.
+    async fn verify_presentation(
+        request: &RequestPresentationV2,
+        presentation: &PresentationV2,
+        input: &Self::VerifyPresentationInput<'_>,
+    ) -> VcxResult<(bool, Self::VerificationDetails)>;
+}
@@ -0,0 +1,133 @@
+use std::{fmt, marker::PhantomData};
+
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use async_trait::async_trait;
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::{PresentationAttachmentFormatType, PresentationV2},
+    propose::{ProposePresentationAttachmentFormatType, ProposePresentationV2},
+    request::{PresentationRequestAttachmentFormatType, RequestPresentationV2},
+};
+use serde_json::Value;
+
+use super::VerifierPresentationFormat;
+use crate::{
+    common::proofs::{
+        presentation_exchange::{
+            anoncreds::verify_anoncreds_submission, definition::PresentationExchangeRequest,
+        },
+        verifier::verification_report::{VerificationPolicy, VerificationReport},
+    },
+    errors::error::VcxResult,
+    protocols::{
+        common::{extract_attachment_data, extract_optional_id_attachment_data},
+        proof_presentation_v2::formats::presentation_exchange_challenge,
+    },
+};
+
+/// Verifier side handler of the `dif/presentation-exchange/...@v1.0` attachment formats. The
+/// received presentations have to be backed by anoncreds proofs, see
+/// [crate::common::proofs::presentation_exchange::anoncreds].
+pub struct PresentationExchangeVerifierPresentationFormat<R, A> {
+    _marker: PhantomData<fn() -> (R, A)>,
+}
+
+// Implemented manually, as derives would require the ledger and anoncreds types to implement
+// these traits as well.
+impl<R, A> fmt::Debug for PresentationExchangeVerifierPresentationFormat<R, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PresentationExchangeVerifierPresentationFormat")
+    }
+}
+
+impl<R, A> Clone for PresentationExchangeVerifierPresentationFormat<R, A> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R, A> PartialEq for PresentationExchangeVerifierPresentationFormat<R, A> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+pub struct PresentationExchangeVerifyPresentationInput<'a, R, A> {
+    pub ledger: &'a R,
+    pub anoncreds: &'a A,
+    pub policy: &'a VerificationPolicy,
+}
+
+#[async_trait]
+impl<R, A> VerifierPresentationFormat for PresentationExchangeVerifierPresentationFormat<R, A>
+where
+    R: AnoncredsLedgerRead,
+    A: BaseAnonCreds,
+{
+    type ProposalDetails = PresentationExchangeRequest;
+    type CreateRequestInput = PresentationExchangeRequest;
+
+    type VerifyPresentationInput<'a>
+        = PresentationExchangeVerifyPresentationInput<'a, R, A>
+    where
+        Self: 'a;
+    type VerificationDetails = VerificationReport;
+
+    fn extract_proposal_details(
+        proposal: &ProposePresentationV2,
+    ) -> VcxResult<PresentationExchangeRequest> {
+        let (_, data) = extract_optional_id_attachment_data(
+            &proposal.content.formats,
+            proposal
+                .content
+                .proposals_attach
+                .as_deref()
+                .unwrap_or_default(),
+            &[ProposePresentationAttachmentFormatType::DifPresentationExchangeDefinitions1_0],
+        )?;
+        Ok(serde_json::from_slice(&data)?)
+    }
+
+    /// Fails if `input` has no challenge, as submissions are bound to the exchange through it.
+    fn create_request_attachment_content(
+        input: &PresentationExchangeRequest,
+    ) -> VcxResult<(PresentationRequestAttachmentFormatType, Vec<u8>)> {
+        presentation_exchange_challenge(input)?;
+        Ok((
+            PresentationRequestAttachmentFormatType::DifPresentationExchangeDefinitions1_0,
+            serde_json::to_vec(input)?,
+        ))
+    }
+
+    async fn verify_presentation(
+        request: &RequestPresentationV2,
+        presentation: &PresentationV2,
+        input: &PresentationExchangeVerifyPresentationInput<'_, R, A>,
+    ) -> VcxResult<(bool, VerificationReport)> {
+        let (_, request_data) = extract_attachment_data(
+            &request.content.formats,
+            &request.content.request_presentations_attach,
+            &[PresentationRequestAttachmentFormatType::DifPresentationExchangeDefinitions1_0],
+        )?;
+        let (_, presentation_data) = extract_attachment_data(
+            &presentation.content.formats,
+            &presentation.content.presentations_attach,
+            &[PresentationAttachmentFormatType::DifPresentationExchangeSubmission1_0],
+        )?;
+        let request: PresentationExchangeRequest = serde_json::from_slice(&request_data)?;
+        let presentation: Value = serde_json::from_slice(&presentation_data)?;
+
+        let report = verify_anoncreds_submission(
+            input.ledger,
+            input.anoncreds,
+            &request.presentation_definition,
+            presentation_exchange_challenge(&request)?,
+            &presentation,
+            input.policy,
+        )
+        .await?;
+        Ok((report.is_valid(), report))
+    }
+}
@@ -0,0 +1,252 @@
+//! State machines for the [present proof 2.0 protocol](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0454-present-proof-v2/README.md>).
+//!
+//! Both [prover::ProverV2] and [verifier::VerifierV2] are typestate machines, generic over an
+//! attachment format handler which creates and processes the format-specific attachments
+//! exchanged within the protocol messages. The format of each message is negotiated through its
+//! attachment format specifiers: the first format listed by the sender which the handler
+//! supports is used. See [formats] for the available handlers.
+
+pub mod formats;
+pub mod prover;
+pub mod verifier;
+
+#[cfg(test)]
+mod unit_tests {
+    use async_trait::async_trait;
+    use messages::msg_fields::protocols::present_proof::v2::{
+        present::{PresentationAttachmentFormatType, PresentationV2},
+        propose::{ProposePresentationAttachmentFormatType, ProposePresentationV2},
+        request::{PresentationRequestAttachmentFormatType, RequestPresentationV2},
+    };
+
+    use super::{
+        formats::{prover::ProverPresentationFormat, verifier::VerifierPresentationFormat},
+        prover::{
+            states::{proposal_prepared::ProposalPrepared, request_received::RequestReceived},
+            ProverV2,
+        },
+        verifier::{
+            states::{proposal_received::ProposalReceived, request_prepared::RequestPrepared},
+            VerifierV2,
+        },
+    };
+    use crate::{
+        errors::error::VcxResult,
+        protocols::{
+            common::{extract_attachment_data, extract_optional_id_attachment_data},
+            issuance_v2::formats::AnoncredsFormatFamily,
+        },
+    };
+
+    const FAMILY: AnoncredsFormatFamily = AnoncredsFormatFamily::Anoncreds;
+    const VALID_PRESENTATION: &[u8] = b"valid presentation";
+
+    /// Format handler passing fixed payloads around, presentations being valid if they carry
+    /// [VALID_PRESENTATION].
+    #[derive(Debug)]
+    struct MockFormat;
+
+    #[async_trait]
+    impl ProverPresentationFormat for MockFormat {
+        type CreateProposalInput = ();
+        type RequestDetails = Vec<u8>;
+        type CreatePresentationInput<'a> = Vec<u8>;
+
+        fn create_proposal_attachment_content(
+            _input: &(),
+        ) -> VcxResult<(ProposePresentationAttachmentFormatType, Vec<u8>)> {
+            Ok((FAMILY.proof_proposal_format(), b"proposal".to_vec()))
+        }
+
+        fn extract_request_details(request: &RequestPresentationV2) -> VcxResult<Vec<u8>> {
+            let (_, data) = extract_attachment_data(
+                &request.content.formats,
+                &request.content.request_presentations_attach,
+                &[FAMILY.proof_request_format()],
+            )?;
+            Ok(data)
+        }
+
+        async fn create_presentation_attachment_content(
+            _request: &RequestPresentationV2,
+            input: &Vec<u8>,
+        ) -> VcxResult<(PresentationAttachmentFormatType, Vec<u8>)> {
+            Ok((FAMILY.proof_format(), input.clone()))
+        }
+    }
+
+    #[async_trait]
+    impl VerifierPresentationFormat for MockFormat {
+        type ProposalDetails = Vec<u8>;
+        type CreateRequestInput = ();
+        type VerifyPresentationInput<'a> = ();
+        type VerificationDetails = String;
+
+        fn extract_proposal_details(proposal: &ProposePresentationV2) -> VcxResult<Vec<u8>> {
+            let (_, data) = extract_optional_id_attachment_data(
+                &proposal.content.formats,
+                proposal
+                    .content
+                    .proposals_attach
+                    .as_deref()
+                    .unwrap_or_default(),
+                &[FAMILY.proof_proposal_format()],
+            )?;
+            Ok(data)
+        }
+
+        fn create_request_attachment_content(
+            _input: &(),
+        ) -> VcxResult<(PresentationRequestAttachmentFormatType, Vec<u8>)> {
+            Ok((FAMILY.proof_request_format(), b"request".to_vec()))
+        }
+
+        async fn verify_presentation(
+            _request: &RequestPresentationV2,
+            presentation: &PresentationV2,
+            _input: &(),
+        ) -> VcxResult<(bool, String)> {
+            let (_, data) = extract_attachment_data(
+                &presentation.content.formats,
+                &presentation.content.presentations_attach,
+                &[FAMILY.proof_format()],
+            )?;
+            Ok((
+                data == VALID_PRESENTATION,
+                String::from_utf8_lossy(&data).into_owned(),
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_presentation_from_proposal_with_ack() {
+        let prover = ProverV2::<ProposalPrepared<MockFormat>>::with_proposal(&()).unwrap();
+        let verifier = VerifierV2::<ProposalReceived<MockFormat>>::from_proposal(
+            prover.get_proposal().clone(),
+        );
+        assert_eq!(verifier.get_thread_id(), prover.get_thread_id());
+        assert_eq!(verifier.get_proposal_details().unwrap(), b"proposal");
+
+        let verifier = verifier.prepare_request(&(), false).unwrap();
+        let prover = prover
+            .receive_request(verifier.get_request().clone())
+            .unwrap();
+        assert_eq!(prover.get_request_details().unwrap(), b"request");
+
+        let prover = prover
+            .prepare_presentation(&VALID_PRESENTATION.to_vec(), true)
+            .await
+            .unwrap();
+        let verifier = verifier
+            .receive_presentation(prover.get_presentation().clone(), &())
+            .await
+            .unwrap();
+        assert!(verifier.is_presentation_valid());
+        assert_eq!(verifier.get_verification_details(), "valid presentation");
+
+        let result = verifier.prepare_ack_if_required();
+        assert!(result.state.get_state().ack_sent);
+        let prover = prover.receive_ack(result.output.unwrap()).unwrap();
+        assert!(prover.get_state().ack.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_presentation_from_request_without_ack() {
+        let verifier = VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), false).unwrap();
+        let prover =
+            ProverV2::<RequestReceived<MockFormat>>::from_request(verifier.get_request().clone());
+        assert_eq!(prover.get_thread_id(), verifier.get_thread_id());
+
+        let prover = prover
+            .prepare_presentation(&VALID_PRESENTATION.to_vec(), false)
+            .await
+            .unwrap();
+        let verifier = verifier
+            .receive_presentation(prover.get_presentation().clone(), &())
+            .await
+            .unwrap();
+        let result = verifier.prepare_ack_if_required();
+        assert!(result.output.is_none());
+        assert!(result.state.is_presentation_valid());
+        let prover = prover.complete_without_ack().unwrap();
+        assert!(prover.get_state().ack.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prover_cannot_complete_without_confirmation() {
+        let verifier = VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), true).unwrap();
+        let prover =
+            ProverV2::<RequestReceived<MockFormat>>::from_request(verifier.get_request().clone())
+                .prepare_presentation(&VALID_PRESENTATION.to_vec(), false)
+                .await
+                .unwrap();
+        assert!(prover.complete_without_ack().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_presentation_is_reported() {
+        let verifier = VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), true).unwrap();
+        let prover =
+            ProverV2::<RequestReceived<MockFormat>>::from_request(verifier.get_request().clone())
+                .prepare_presentation(&b"forged presentation".to_vec(), false)
+                .await
+                .unwrap();
+        let verifier = verifier
+            .receive_presentation(prover.get_presentation().clone(), &())
+            .await
+            .unwrap();
+        assert!(!verifier.is_presentation_valid());
+
+        let result = verifier.prepare_problem_report("Invalid presentation".to_owned());
+        assert_eq!(result.state.get_failure_message(), "Invalid presentation");
+        let prover = prover.receive_problem_report(result.output).unwrap();
+        assert_eq!(prover.get_failure_message(), "Invalid presentation");
+    }
+
+    #[tokio::test]
+    async fn test_prover_declines_request() {
+        let verifier = VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), false).unwrap();
+        let prover =
+            ProverV2::<RequestReceived<MockFormat>>::from_request(verifier.get_request().clone());
+        let result = prover.decline_request(None);
+        assert_eq!(
+            result.state.get_failure_message(),
+            "Presentation request declined"
+        );
+
+        let verifier = verifier.receive_problem_report(result.output).unwrap();
+        assert_eq!(
+            verifier.get_failure_message(),
+            "Presentation request declined"
+        );
+    }
+
+    #[test]
+    fn test_proposal_without_attachment_id() {
+        let prover = ProverV2::<ProposalPrepared<MockFormat>>::with_proposal(&()).unwrap();
+        let mut proposal = prover.get_proposal().clone();
+        proposal.content.formats[0].attach_id = None;
+        let verifier = VerifierV2::<ProposalReceived<MockFormat>>::from_proposal(proposal);
+        assert_eq!(verifier.get_proposal_details().unwrap(), b"proposal");
+    }
+
+    #[tokio::test]
+    async fn test_messages_of_other_threads_are_rejected() {
+        let verifier = VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), false).unwrap();
+        let thread_id = verifier.get_thread_id().to_owned();
+        let other_verifier =
+            VerifierV2::<RequestPrepared<MockFormat>>::with_request(&(), false).unwrap();
+        let prover = ProverV2::<RequestReceived<MockFormat>>::from_request(
+            other_verifier.get_request().clone(),
+        )
+        .prepare_presentation(&VALID_PRESENTATION.to_vec(), false)
+        .await
+        .unwrap();
+
+        let error = verifier
+            .receive_presentation(prover.get_presentation().clone(), &())
+            .await
+            .unwrap_err();
+        assert_eq!(error.state.get_thread_id(), thread_id);
+    }
+}
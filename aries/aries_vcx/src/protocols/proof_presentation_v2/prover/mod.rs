@@ -0,0 +1,300 @@
+pub mod states;
+
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use messages::{
+    decorators::{
+        please_ack::{AckOn, PleaseAck},
+        thread::Thread,
+        timing::Timing,
+    },
+    msg_fields::protocols::{
+        common::attachment_format_specifier::OptionalIdAttachmentFormatSpecifier,
+        present_proof::v2::{
+            ack::AckPresentationV2,
+            present::{PresentationV2, PresentationV2Content, PresentationV2Decorators},
+            problem_report::PresentProofV2ProblemReport,
+            propose::{
+                ProposePresentationV2, ProposePresentationV2Content,
+                ProposePresentationV2Decorators,
+            },
+            request::RequestPresentationV2,
+        },
+    },
+};
+use uuid::Uuid;
+
+use self::states::{
+    complete::Complete, failed::Failed, presentation_prepared::PresentationPrepared,
+    proposal_prepared::ProposalPrepared, request_received::RequestReceived,
+};
+use super::formats::prover::ProverPresentationFormat;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::util::{matches_opt_thread_id, matches_thread_id},
+    protocols::{
+        common::{build_attachment, build_protocol_problem_report, thread_mismatch_error},
+        did_exchange::transition::{
+            transition_error::TransitionError, transition_result::TransitionResult,
+        },
+    },
+};
+
+/// Prover side of the present proof 2.0 protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProverV2<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> ProverV2<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+
+    /// Abandons the protocol, producing a problem report to be sent to the verifier.
+    pub fn prepare_problem_report(
+        self,
+        reason: String,
+    ) -> TransitionResult<ProverV2<Failed>, PresentProofV2ProblemReport> {
+        let problem_report = build_protocol_problem_report(&reason, &self.thread_id);
+        TransitionResult {
+            state: ProverV2 {
+                state: Failed {
+                    failure_message: reason,
+                },
+                thread_id: self.thread_id,
+            },
+            output: problem_report,
+        }
+    }
+
+    pub fn receive_problem_report(
+        self,
+        problem_report: PresentProofV2ProblemReport,
+    ) -> Result<ProverV2<Failed>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        Ok(ProverV2 {
+            state: Failed {
+                failure_message: problem_report.content.inner.description.code,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+fn build_proposal<T: ProverPresentationFormat>(
+    input: &T::CreateProposalInput,
+    thread_id: Option<&str>,
+) -> VcxResult<ProposePresentationV2> {
+    let (format, data) = T::create_proposal_attachment_content(input)?;
+    let (specifier, attachment) = build_attachment(format, &data);
+    let specifier = OptionalIdAttachmentFormatSpecifier::builder()
+        .attach_id(Some(specifier.attach_id))
+        .format(specifier.format)
+        .build();
+    let content = ProposePresentationV2Content::builder()
+        .formats(vec![specifier])
+        .proposals_attach(Some(vec![attachment]))
+        .build();
+    let decorators = ProposePresentationV2Decorators::builder()
+        .thread(thread_id.map(|thid| Thread::builder().thid(thid.to_owned()).build()))
+        .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+        .build();
+    Ok(ProposePresentationV2::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+impl<T: ProverPresentationFormat> ProverV2<ProposalPrepared<T>> {
+    /// Starts the protocol by proposing a presentation to the verifier.
+    pub fn with_proposal(input: &T::CreateProposalInput) -> VcxResult<Self> {
+        let proposal = build_proposal::<T>(input, None)?;
+        Ok(Self {
+            thread_id: proposal.id.clone(),
+            state: ProposalPrepared {
+                proposal,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_proposal(&self) -> &ProposePresentationV2 {
+        &self.state.proposal
+    }
+
+    pub fn receive_request(
+        self,
+        request: RequestPresentationV2,
+    ) -> Result<ProverV2<RequestReceived<T>>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(request, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("presentation request"),
+                state: self,
+            });
+        }
+        Ok(ProverV2 {
+            state: RequestReceived {
+                request,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: ProverPresentationFormat> ProverV2<RequestReceived<T>> {
+    /// Starts the protocol from a presentation request received from the verifier.
+    pub fn from_request(request: RequestPresentationV2) -> Self {
+        let thread_id = request
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| request.id.clone());
+        Self {
+            state: RequestReceived {
+                request,
+                _marker: PhantomData,
+            },
+            thread_id,
+        }
+    }
+
+    pub fn get_request(&self) -> &RequestPresentationV2 {
+        &self.state.request
+    }
+
+    pub fn get_request_details(&self) -> VcxResult<T::RequestDetails> {
+        T::extract_request_details(&self.state.request)
+    }
+
+    /// Counters the received request with a new proposal.
+    pub fn prepare_proposal(
+        self,
+        input: &T::CreateProposalInput,
+    ) -> Result<ProverV2<ProposalPrepared<T>>, TransitionError<Self>> {
+        let proposal = match build_proposal::<T>(input, Some(&self.thread_id)) {
+            Ok(proposal) => proposal,
+            Err(error) => return Err(TransitionError { error, state: self }),
+        };
+        Ok(ProverV2 {
+            state: ProposalPrepared {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Accepts the received request, preparing a presentation to be sent to the verifier. If
+    /// `please_ack` is set, the verifier is asked to acknowledge the presentation.
+    pub async fn prepare_presentation(
+        self,
+        input: &T::CreatePresentationInput<'_>,
+        please_ack: bool,
+    ) -> Result<ProverV2<PresentationPrepared<T>>, TransitionError<Self>> {
+        let (format, data) =
+            match T::create_presentation_attachment_content(&self.state.request, input).await {
+                Ok(result) => result,
+                Err(error) => return Err(TransitionError { error, state: self }),
+            };
+        let (specifier, attachment) = build_attachment(format, &data);
+        let content = PresentationV2Content::builder()
+            .formats(vec![specifier])
+            .presentations_attach(vec![attachment])
+            .build();
+        let mut decorators = PresentationV2Decorators::builder()
+            .thread(Thread::builder().thid(self.thread_id.clone()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        decorators.please_ack =
+            please_ack.then(|| PleaseAck::builder().on(vec![AckOn::Receipt]).build());
+        let presentation = PresentationV2::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build();
+        Ok(ProverV2 {
+            state: PresentationPrepared {
+                request: self.state.request,
+                presentation,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Declines the received request, producing a problem report to be sent to the verifier.
+    pub fn decline_request(
+        self,
+        reason: Option<String>,
+    ) -> TransitionResult<ProverV2<Failed>, PresentProofV2ProblemReport> {
+        self.prepare_problem_report(
+            reason.unwrap_or_else(|| "Presentation request declined".to_owned()),
+        )
+    }
+}
+
+impl<T: ProverPresentationFormat> ProverV2<PresentationPrepared<T>> {
+    pub fn get_presentation(&self) -> &PresentationV2 {
+        &self.state.presentation
+    }
+
+    fn ack_expected(&self) -> bool {
+        self.state.presentation.decorators.please_ack.is_some()
+            || self.state.request.content.will_confirm == Some(true)
+    }
+
+    pub fn receive_ack(
+        self,
+        ack: AckPresentationV2,
+    ) -> Result<ProverV2<Complete>, TransitionError<Self>> {
+        if !matches_thread_id!(ack, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("ack"),
+                state: self,
+            });
+        }
+        Ok(ProverV2 {
+            state: Complete { ack: Some(ack) },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Completes the protocol once the presentation was sent, if the verifier is not expected to
+    /// acknowledge it.
+    pub fn complete_without_ack(self) -> Result<ProverV2<Complete>, TransitionError<Self>> {
+        if self.ack_expected() {
+            return Err(TransitionError {
+                error: AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Cannot complete without an ack, as the verifier is expected to send one",
+                ),
+                state: self,
+            });
+        }
+        Ok(ProverV2 {
+            state: Complete { ack: None },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl ProverV2<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
@@ -0,0 +1,7 @@
+use messages::msg_fields::protocols::present_proof::v2::ack::AckPresentationV2;
+
+/// The presentation has been sent, and acknowledged by the verifier if requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Complete {
+    pub ack: Option<AckPresentationV2>,
+}
@@ -0,0 +1,5 @@
+pub mod complete;
+pub mod failed;
+pub mod presentation_prepared;
+pub mod proposal_prepared;
+pub mod request_received;
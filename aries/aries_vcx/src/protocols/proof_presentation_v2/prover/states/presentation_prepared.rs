@@ -0,0 +1,17 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2, request::RequestPresentationV2,
+};
+
+use crate::protocols::proof_presentation_v2::formats::prover::ProverPresentationFormat;
+
+/// The prover has prepared the presentation, to be sent to the verifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PresentationPrepared<T: ProverPresentationFormat> {
+    pub request: RequestPresentationV2,
+    pub presentation: PresentationV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+
+use crate::protocols::proof_presentation_v2::formats::prover::ProverPresentationFormat;
+
+/// The prover has prepared a presentation proposal, to be sent to the verifier.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProposalPrepared<T: ProverPresentationFormat> {
+    pub proposal: ProposePresentationV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,15 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+
+use crate::protocols::proof_presentation_v2::formats::prover::ProverPresentationFormat;
+
+/// The prover has received a presentation request, which it can accept by preparing a
+/// presentation, counter with a proposal or decline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestReceived<T: ProverPresentationFormat> {
+    pub request: RequestPresentationV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
@@ -0,0 +1,291 @@
+pub mod states;
+
+use std::marker::PhantomData;
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::{
+        notification::ack::{AckContent, AckDecorators, AckStatus},
+        present_proof::v2::{
+            ack::{AckPresentationV2, AckPresentationV2Content},
+            present::PresentationV2,
+            problem_report::PresentProofV2ProblemReport,
+            propose::ProposePresentationV2,
+            request::{
+                RequestPresentationV2, RequestPresentationV2Content,
+                RequestPresentationV2Decorators,
+            },
+        },
+    },
+};
+use uuid::Uuid;
+
+use self::states::{
+    complete::Complete, failed::Failed, presentation_verified::PresentationVerified,
+    proposal_received::ProposalReceived, request_prepared::RequestPrepared,
+};
+use super::formats::verifier::VerifierPresentationFormat;
+use crate::{
+    errors::error::VcxResult,
+    handlers::util::{matches_opt_thread_id, matches_thread_id},
+    protocols::{
+        common::{build_attachment, build_protocol_problem_report, thread_mismatch_error},
+        did_exchange::transition::{
+            transition_error::TransitionError, transition_result::TransitionResult,
+        },
+    },
+};
+
+/// Verifier side of the present proof 2.0 protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerifierV2<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> VerifierV2<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+
+    /// Abandons the protocol, producing a problem report to be sent to the prover.
+    pub fn prepare_problem_report(
+        self,
+        reason: String,
+    ) -> TransitionResult<VerifierV2<Failed>, PresentProofV2ProblemReport> {
+        let problem_report = build_protocol_problem_report(&reason, &self.thread_id);
+        TransitionResult {
+            state: VerifierV2 {
+                state: Failed {
+                    failure_message: reason,
+                },
+                thread_id: self.thread_id,
+            },
+            output: problem_report,
+        }
+    }
+
+    pub fn receive_problem_report(
+        self,
+        problem_report: PresentProofV2ProblemReport,
+    ) -> Result<VerifierV2<Failed>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(problem_report, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("problem report"),
+                state: self,
+            });
+        }
+        Ok(VerifierV2 {
+            state: Failed {
+                failure_message: problem_report.content.inner.description.code,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+fn build_request<T: VerifierPresentationFormat>(
+    input: &T::CreateRequestInput,
+    will_confirm: bool,
+    thread_id: Option<&str>,
+) -> VcxResult<RequestPresentationV2> {
+    let (format, data) = T::create_request_attachment_content(input)?;
+    let (specifier, attachment) = build_attachment(format, &data);
+    let content = RequestPresentationV2Content::builder()
+        .will_confirm(Some(will_confirm))
+        .formats(vec![specifier])
+        .request_presentations_attach(vec![attachment])
+        .build();
+    let decorators = RequestPresentationV2Decorators::builder()
+        .thread(thread_id.map(|thid| Thread::builder().thid(thid.to_owned()).build()))
+        .timing(Some(Timing::builder().out_time(Utc::now()).build()))
+        .build();
+    Ok(RequestPresentationV2::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+impl<T: VerifierPresentationFormat> VerifierV2<ProposalReceived<T>> {
+    /// Starts the protocol from a presentation proposal received from the prover.
+    pub fn from_proposal(proposal: ProposePresentationV2) -> Self {
+        let thread_id = proposal
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| proposal.id.clone());
+        Self {
+            state: ProposalReceived {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id,
+        }
+    }
+
+    pub fn get_proposal(&self) -> &ProposePresentationV2 {
+        &self.state.proposal
+    }
+
+    pub fn get_proposal_details(&self) -> VcxResult<T::ProposalDetails> {
+        T::extract_proposal_details(&self.state.proposal)
+    }
+
+    /// Answers the received proposal with a presentation request.
+    pub fn prepare_request(
+        self,
+        input: &T::CreateRequestInput,
+        will_confirm: bool,
+    ) -> Result<VerifierV2<RequestPrepared<T>>, TransitionError<Self>> {
+        let request = match build_request::<T>(input, will_confirm, Some(&self.thread_id)) {
+            Ok(request) => request,
+            Err(error) => return Err(TransitionError { error, state: self }),
+        };
+        Ok(VerifierV2 {
+            state: RequestPrepared {
+                request,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: VerifierPresentationFormat> VerifierV2<RequestPrepared<T>> {
+    /// Starts the protocol by requesting a presentation from the prover. If `will_confirm` is
+    /// set, the prover is told to expect an ack once the presentation is verified.
+    pub fn with_request(input: &T::CreateRequestInput, will_confirm: bool) -> VcxResult<Self> {
+        let request = build_request::<T>(input, will_confirm, None)?;
+        Ok(Self {
+            thread_id: request.id.clone(),
+            state: RequestPrepared {
+                request,
+                _marker: PhantomData,
+            },
+        })
+    }
+
+    pub fn get_request(&self) -> &RequestPresentationV2 {
+        &self.state.request
+    }
+
+    /// Handles a counter proposal sent by the prover in reply to our request.
+    pub fn receive_proposal(
+        self,
+        proposal: ProposePresentationV2,
+    ) -> Result<VerifierV2<ProposalReceived<T>>, TransitionError<Self>> {
+        if !matches_opt_thread_id!(proposal, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("presentation proposal"),
+                state: self,
+            });
+        }
+        Ok(VerifierV2 {
+            state: ProposalReceived {
+                proposal,
+                _marker: PhantomData,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    /// Verifies the presentation received in reply to our request. An invalid presentation
+    /// does not fail the transition; its outcome is recorded in the resulting state instead.
+    pub async fn receive_presentation(
+        self,
+        presentation: PresentationV2,
+        input: &T::VerifyPresentationInput<'_>,
+    ) -> Result<VerifierV2<PresentationVerified<T>>, TransitionError<Self>> {
+        if !matches_thread_id!(presentation, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("presentation"),
+                state: self,
+            });
+        }
+        let (valid, verification_details) =
+            match T::verify_presentation(&self.state.request, &presentation, input).await {
+                Ok(result) => result,
+                Err(error) => return Err(TransitionError { error, state: self }),
+            };
+        Ok(VerifierV2 {
+            state: PresentationVerified {
+                request: self.state.request,
+                presentation,
+                valid,
+                verification_details,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+}
+
+impl<T: VerifierPresentationFormat> VerifierV2<PresentationVerified<T>> {
+    pub fn get_presentation(&self) -> &PresentationV2 {
+        &self.state.presentation
+    }
+
+    pub fn is_presentation_valid(&self) -> bool {
+        self.state.valid
+    }
+
+    pub fn get_verification_details(&self) -> &T::VerificationDetails {
+        &self.state.verification_details
+    }
+
+    /// Completes the protocol, producing an ack to be sent to the prover if the presentation is
+    /// valid and the prover asked for one or was promised one. Invalid presentations should be
+    /// answered with [VerifierV2::prepare_problem_report] instead.
+    pub fn prepare_ack_if_required(
+        self,
+    ) -> TransitionResult<VerifierV2<Complete<T>>, Option<AckPresentationV2>> {
+        let ack_required = self.state.presentation.decorators.please_ack.is_some()
+            || self.state.request.content.will_confirm == Some(true);
+        let ack = (self.state.valid && ack_required).then(|| {
+            let content: AckPresentationV2Content =
+                AckContent::builder().status(AckStatus::Ok).build();
+            let decorators = AckDecorators::builder()
+                .thread(Thread::builder().thid(self.thread_id.clone()).build())
+                .timing(Timing::builder().out_time(Utc::now()).build())
+                .build();
+            AckPresentationV2::builder()
+                .id(Uuid::new_v4().to_string())
+                .content(content)
+                .decorators(decorators)
+                .build()
+        });
+        TransitionResult {
+            state: VerifierV2 {
+                state: Complete {
+                    valid: self.state.valid,
+                    verification_details: self.state.verification_details,
+                    ack_sent: ack.is_some(),
+                },
+                thread_id: self.thread_id,
+            },
+            output: ack,
+        }
+    }
+}
+
+impl<T: VerifierPresentationFormat> VerifierV2<Complete<T>> {
+    pub fn is_presentation_valid(&self) -> bool {
+        self.state.valid
+    }
+
+    pub fn get_verification_details(&self) -> &T::VerificationDetails {
+        &self.state.verification_details
+    }
+}
+
+impl VerifierV2<Failed> {
+    pub fn get_failure_message(&self) -> &str {
+        &self.state.failure_message
+    }
+}
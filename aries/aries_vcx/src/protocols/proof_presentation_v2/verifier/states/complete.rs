@@ -0,0 +1,10 @@
+use crate::protocols::proof_presentation_v2::formats::verifier::VerifierPresentationFormat;
+
+/// The presentation has been verified, and the prover acknowledged if requested.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Complete<T: VerifierPresentationFormat> {
+    pub valid: bool,
+    pub verification_details: T::VerificationDetails,
+    pub ack_sent: bool,
+}
@@ -0,0 +1,5 @@
+/// The protocol was abandoned, either by us or by the prover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Failed {
+    pub failure_message: String,
+}
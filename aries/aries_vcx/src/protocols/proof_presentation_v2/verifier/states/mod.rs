@@ -0,0 +1,5 @@
+pub mod complete;
+pub mod failed;
+pub mod presentation_verified;
+pub mod proposal_received;
+pub mod request_prepared;
@@ -0,0 +1,16 @@
+use messages::msg_fields::protocols::present_proof::v2::{
+    present::PresentationV2, request::RequestPresentationV2,
+};
+
+use crate::protocols::proof_presentation_v2::formats::verifier::VerifierPresentationFormat;
+
+/// The verifier has received and verified the presentation. An ack has to be sent to the prover
+/// if it was requested and the presentation is valid.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PresentationVerified<T: VerifierPresentationFormat> {
+    pub request: RequestPresentationV2,
+    pub presentation: PresentationV2,
+    pub valid: bool,
+    pub verification_details: T::VerificationDetails,
+}
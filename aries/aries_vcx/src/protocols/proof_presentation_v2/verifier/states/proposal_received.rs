@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::present_proof::v2::propose::ProposePresentationV2;
+
+use crate::protocols::proof_presentation_v2::formats::verifier::VerifierPresentationFormat;
+
+/// The verifier has received a presentation proposal, which it can answer with a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProposalReceived<T: VerifierPresentationFormat> {
+    pub proposal: ProposePresentationV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
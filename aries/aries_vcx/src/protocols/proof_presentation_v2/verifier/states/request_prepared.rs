@@ -0,0 +1,14 @@
+use std::marker::PhantomData;
+
+use messages::msg_fields::protocols::present_proof::v2::request::RequestPresentationV2;
+
+use crate::protocols::proof_presentation_v2::formats::verifier::VerifierPresentationFormat;
+
+/// The verifier has prepared a presentation request, to be sent to the prover.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RequestPrepared<T: VerifierPresentationFormat> {
+    pub request: RequestPresentationV2,
+    #[serde(skip)]
+    pub _marker: PhantomData<T>,
+}
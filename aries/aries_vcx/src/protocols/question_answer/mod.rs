@@ -0,0 +1,208 @@
+//! State machines for the [question answer 1.0 protocol](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0113-question-answer/README.md>),
+//! e.g. for step-up confirmations such as "Did you initiate this login?".
+//!
+//! The [questioner::Questioner] asks a question with a fixed set of valid responses. The
+//! [responder::Responder] picks one of them and, if the question requires so, signs the
+//! response along with the question text and nonce with the key agreement key of its DID
+//! document. The questioner verifies the signature against the DID document of the connection.
+
+pub mod questioner;
+pub mod responder;
+pub mod states;
+
+use chrono::Utc;
+use messages::msg_fields::protocols::question_answer::question::Question;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Checks that `response` is one of the valid responses of `question`.
+fn validate_response(question: &Question, response: &str) -> VcxResult<()> {
+    if !question
+        .content
+        .valid_responses
+        .iter()
+        .any(|valid_response| valid_response.text == response)
+    {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("Response {response} is not a valid response to the question"),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that `question` has not expired, according to the `expires_time` of its `~timing`
+/// decorator.
+fn validate_not_expired(question: &Question) -> VcxResult<()> {
+    let expires_time = question
+        .decorators
+        .timing
+        .as_ref()
+        .and_then(|timing| timing.expires_time);
+    if matches!(expires_time, Some(expires_time) if expires_time < Utc::now()) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("Question {} has expired", question.id),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use chrono::{Duration, Utc};
+    use test_utils::devsetup::build_setup_profile;
+    use url::Url;
+
+    use super::{questioner::Questioner, responder::Responder};
+    use crate::{
+        handlers::discover_features::FeatureRegistry,
+        protocols::did_exchange::state_machine::helpers::create_peer_did_4,
+    };
+
+    fn valid_responses() -> Vec<String> {
+        vec!["Yes, it's me".to_owned(), "No, that's not me!".to_owned()]
+    }
+
+    #[tokio::test]
+    async fn test_signed_answer() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let endpoint = Url::parse("http://dummy.lol").unwrap();
+        let (responder_did, _) = create_peer_did_4(wallet, endpoint.clone(), vec![])
+            .await
+            .unwrap();
+        let responder_did_document = responder_did.resolve_did_doc().unwrap();
+        let (other_did, _) = create_peer_did_4(wallet, endpoint, vec![]).await.unwrap();
+        let other_did_document = other_did.resolve_did_doc().unwrap();
+
+        let questioner = Questioner::send_question(
+            "Did you initiate this login?".to_owned(),
+            None,
+            valid_responses(),
+            true,
+            Some(Utc::now() + Duration::minutes(5)),
+        );
+        let responder = Responder::receive_question(questioner.get_question().clone());
+        assert_eq!(responder.get_thread_id(), questioner.get_thread_id());
+
+        assert!(responder
+            .clone()
+            .answer(wallet, &responder_did_document, "Maybe")
+            .await
+            .is_err());
+        let answer = responder
+            .answer(wallet, &responder_did_document, "Yes, it's me")
+            .await
+            .unwrap();
+        assert!(answer.output.content.response_sig.is_some());
+
+        let questioner = questioner
+            .receive_answer(wallet, &other_did_document, answer.output.clone())
+            .await
+            .unwrap_err()
+            .state;
+        let mut tampered = answer.output.clone();
+        tampered.content.response = "No, that's not me!".to_owned();
+        let questioner = questioner
+            .receive_answer(wallet, &responder_did_document, tampered)
+            .await
+            .unwrap_err()
+            .state;
+
+        let questioner = questioner
+            .receive_answer(wallet, &responder_did_document, answer.output)
+            .await
+            .unwrap();
+        assert_eq!(questioner.get_response(), "Yes, it's me");
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_answer_rejected_when_signature_required() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let (did, _) = create_peer_did_4(wallet, Url::parse("http://dummy.lol").unwrap(), vec![])
+            .await
+            .unwrap();
+        let did_document = did.resolve_did_doc().unwrap();
+
+        let questioner = Questioner::send_question(
+            "Did you initiate this login?".to_owned(),
+            None,
+            valid_responses(),
+            true,
+            None,
+        );
+        let mut answer = Responder::receive_question(questioner.get_question().clone())
+            .answer(wallet, &did_document, "Yes, it's me")
+            .await
+            .unwrap()
+            .output;
+        answer.content.response_sig = None;
+        assert!(questioner
+            .receive_answer(wallet, &did_document, answer)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_question_can_not_be_answered() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let (did, _) = create_peer_did_4(wallet, Url::parse("http://dummy.lol").unwrap(), vec![])
+            .await
+            .unwrap();
+        let did_document = did.resolve_did_doc().unwrap();
+
+        let questioner = Questioner::send_question(
+            "Did you initiate this login?".to_owned(),
+            None,
+            valid_responses(),
+            true,
+            Some(Utc::now() - Duration::minutes(1)),
+        );
+        let responder = Responder::receive_question(questioner.get_question().clone());
+        assert!(responder
+            .answer(wallet, &did_document, "Yes, it's me")
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_answer_to_expired_question_rejected() {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let (did, _) = create_peer_did_4(wallet, Url::parse("http://dummy.lol").unwrap(), vec![])
+            .await
+            .unwrap();
+        let did_document = did.resolve_did_doc().unwrap();
+
+        let questioner = Questioner::send_question(
+            "Did you initiate this login?".to_owned(),
+            None,
+            valid_responses(),
+            false,
+            Some(Utc::now() - Duration::minutes(1)),
+        );
+        // The responder did not see the question expire
+        let mut question = questioner.get_question().clone();
+        question.decorators.timing = None;
+        let answer = Responder::receive_question(question)
+            .answer(wallet, &did_document, "Yes, it's me")
+            .await
+            .unwrap()
+            .output;
+        assert!(questioner
+            .receive_answer(wallet, &did_document, answer)
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_question_answer_is_discoverable() {
+        let registry = FeatureRegistry::from_protocol_registry();
+        assert!(!registry
+            .query_protocols("https://didcomm.org/questionanswer/*")
+            .is_empty());
+    }
+}
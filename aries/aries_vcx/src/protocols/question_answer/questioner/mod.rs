@@ -0,0 +1,154 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::{DateTime, Utc};
+use did_doc::schema::did_doc::DidDocument;
+use messages::{
+    decorators::timing::Timing,
+    msg_fields::protocols::question_answer::{
+        answer::Answer,
+        question::{Question, QuestionContent, QuestionDecorators, ValidResponse},
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    states::{AnswerReceived, QuestionSent},
+    validate_not_expired, validate_response,
+};
+use crate::{
+    common::signing::verify_answer_response,
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    handlers::util::matches_thread_id,
+    protocols::{
+        common::thread_mismatch_error, did_exchange::transition::transition_error::TransitionError,
+    },
+    utils::didcomm_utils::resolve_ed25519_key_agreement,
+};
+
+/// Questioner of the question answer protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Questioner<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> Questioner<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl Questioner<QuestionSent> {
+    /// Asks `question_text`, to be answered with one of `valid_responses`. If
+    /// `signature_required` is set, the answer is only accepted if signed by the responder.
+    pub fn send_question(
+        question_text: String,
+        question_detail: Option<String>,
+        valid_responses: Vec<String>,
+        signature_required: bool,
+        expires_time: Option<DateTime<Utc>>,
+    ) -> Self {
+        let content = QuestionContent {
+            question_text,
+            question_detail,
+            nonce: Uuid::new_v4().to_string(),
+            signature_required,
+            valid_responses: valid_responses
+                .into_iter()
+                .map(ValidResponse::new)
+                .collect(),
+        };
+        let timing = match expires_time {
+            Some(expires_time) => Timing::builder()
+                .out_time(Utc::now())
+                .expires_time(expires_time)
+                .build(),
+            None => Timing::builder().out_time(Utc::now()).build(),
+        };
+        let question = Question::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(QuestionDecorators::builder().timing(timing).build())
+            .build();
+        Self {
+            thread_id: question.id.clone(),
+            state: QuestionSent { question },
+        }
+    }
+
+    pub fn get_question(&self) -> &Question {
+        &self.state.question
+    }
+
+    /// Receives the answer to the question. The question must not have expired, the response
+    /// must be one of the valid responses and, if the question required a signature, be signed
+    /// with the key agreement key of `their_did_document`.
+    pub async fn receive_answer(
+        self,
+        wallet: &impl BaseWallet,
+        their_did_document: &DidDocument,
+        answer: Answer,
+    ) -> Result<Questioner<AnswerReceived>, TransitionError<Self>> {
+        if !matches_thread_id!(answer, self.thread_id.as_str()) {
+            return Err(TransitionError {
+                error: thread_mismatch_error("answer"),
+                state: self,
+            });
+        }
+        if let Err(error) = self
+            .verify_answer(wallet, their_did_document, &answer)
+            .await
+        {
+            return Err(TransitionError { error, state: self });
+        }
+        Ok(Questioner {
+            state: AnswerReceived {
+                question: self.state.question,
+                answer,
+            },
+            thread_id: self.thread_id,
+        })
+    }
+
+    async fn verify_answer(
+        &self,
+        wallet: &impl BaseWallet,
+        their_did_document: &DidDocument,
+        answer: &Answer,
+    ) -> VcxResult<()> {
+        let question = &self.state.question;
+        validate_not_expired(question)?;
+        validate_response(question, &answer.content.response)?;
+        if !question.content.signature_required {
+            return Ok(());
+        }
+        let response_sig = answer.content.response_sig.as_ref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Question requires a signed answer, but the answer is not signed",
+            )
+        })?;
+        let their_key = resolve_ed25519_key_agreement(their_did_document)?;
+        verify_answer_response(
+            wallet,
+            response_sig,
+            &question.content,
+            &answer.content.response,
+            &their_key.base58(),
+        )
+        .await
+    }
+}
+
+impl Questioner<AnswerReceived> {
+    pub fn get_answer(&self) -> &Answer {
+        &self.state.answer
+    }
+
+    pub fn get_response(&self) -> &str {
+        &self.state.answer.content.response
+    }
+}
@@ -0,0 +1,126 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
+use did_doc::schema::did_doc::DidDocument;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::question_answer::{
+        answer::{Answer, AnswerContent, AnswerDecorators},
+        question::Question,
+    },
+};
+use uuid::Uuid;
+
+use super::{
+    states::{AnswerSent, QuestionReceived},
+    validate_not_expired, validate_response,
+};
+use crate::{
+    common::signing::sign_answer_response,
+    errors::error::VcxResult,
+    protocols::did_exchange::transition::{
+        transition_error::TransitionError, transition_result::TransitionResult,
+    },
+    utils::didcomm_utils::resolve_ed25519_key_agreement,
+};
+
+/// Responder of the question answer protocol, in state `S`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Responder<S> {
+    state: S,
+    thread_id: String,
+}
+
+impl<S> Responder<S> {
+    pub fn get_thread_id(&self) -> &str {
+        &self.thread_id
+    }
+
+    pub fn get_state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl Responder<QuestionReceived> {
+    pub fn receive_question(question: Question) -> Self {
+        let thread_id = question
+            .decorators
+            .thread
+            .as_ref()
+            .map_or(question.id.as_str(), |thread| thread.thid.as_str())
+            .to_owned();
+        Self {
+            state: QuestionReceived { question },
+            thread_id,
+        }
+    }
+
+    pub fn get_question(&self) -> &Question {
+        &self.state.question
+    }
+
+    /// Answers the question with `response`, which must be one of its valid responses. If the
+    /// question requires a signature, the response is signed with the key agreement key of
+    /// `our_did_document`.
+    pub async fn answer(
+        self,
+        wallet: &impl BaseWallet,
+        our_did_document: &DidDocument,
+        response: &str,
+    ) -> Result<TransitionResult<Responder<AnswerSent>, Answer>, TransitionError<Self>> {
+        let answer = match self.build_answer(wallet, our_did_document, response).await {
+            Ok(answer) => answer,
+            Err(error) => return Err(TransitionError { error, state: self }),
+        };
+        Ok(TransitionResult {
+            state: Responder {
+                state: AnswerSent {
+                    question: self.state.question,
+                    answer: answer.clone(),
+                },
+                thread_id: self.thread_id,
+            },
+            output: answer,
+        })
+    }
+
+    async fn build_answer(
+        &self,
+        wallet: &impl BaseWallet,
+        our_did_document: &DidDocument,
+        response: &str,
+    ) -> VcxResult<Answer> {
+        let question = &self.state.question;
+        validate_not_expired(question)?;
+        validate_response(question, response)?;
+
+        let content = if question.content.signature_required {
+            let our_key = resolve_ed25519_key_agreement(our_did_document)?;
+            let response_sig =
+                sign_answer_response(wallet, &our_key.base58(), &question.content, response)
+                    .await?;
+            AnswerContent::builder()
+                .response(response.to_owned())
+                .response_sig(response_sig)
+                .build()
+        } else {
+            AnswerContent::builder()
+                .response(response.to_owned())
+                .build()
+        };
+        let decorators = AnswerDecorators::builder()
+            .thread(Thread::builder().thid(self.thread_id.clone()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        Ok(Answer::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build())
+    }
+}
+
+impl Responder<AnswerSent> {
+    pub fn get_answer(&self) -> &Answer {
+        &self.state.answer
+    }
+}
@@ -0,0 +1,27 @@
+use messages::msg_fields::protocols::question_answer::{answer::Answer, question::Question};
+
+/// A question was sent by the questioner, awaiting its answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionSent {
+    pub question: Question,
+}
+
+/// A valid answer was received by the questioner, its signature verified if one was required.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerReceived {
+    pub question: Question,
+    pub answer: Answer,
+}
+
+/// A question was received by the responder, awaiting a response to be picked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionReceived {
+    pub question: Question,
+}
+
+/// The question was answered by the responder.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnswerSent {
+    pub question: Question,
+    pub answer: Answer,
+}
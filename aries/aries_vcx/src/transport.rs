@@ -1,13 +1,34 @@
+use std::future::Future;
+
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use async_trait::async_trait;
+use messages::{decorators::transport::ReturnRoute, AriesMessage};
+use public_key::Key;
+use serde_json::Value;
 use url::Url;
 
-use crate::errors::error::VcxResult;
+use crate::{errors::error::prelude::*, utils::encryption_envelope::EncryptionEnvelope};
 
 /// Trait used for implementing a mechanism to send a message, used by
 /// [`crate::protocols::connection::Connection`].
 #[async_trait]
 pub trait Transport: Send + Sync {
     async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()>;
+
+    /// Sends the message and returns the packed message the recipient replied with over the same
+    /// connection, if any. Recipients only reply this way to messages asking for it through
+    /// `~transport.return_route`, see [`serialize_with_return_route`].
+    ///
+    /// Transports unable to receive replies can rely on the default implementation, which sends
+    /// the message and returns `None`.
+    async fn send_message_and_receive(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        self.send_message(msg, service_endpoint).await?;
+        Ok(None)
+    }
 }
 
 // While in many cases the auto-dereferencing does the trick,
@@ -19,6 +40,275 @@ where
     T: Transport + ?Sized,
 {
     async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
-        self.send_message(msg, service_endpoint).await
+        (*self).send_message(msg, service_endpoint).await
+    }
+
+    async fn send_message_and_receive(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        (*self)
+            .send_message_and_receive(msg, service_endpoint)
+            .await
+    }
+}
+
+/// Trait implemented by established connections messages can be sent over, regardless of the
+/// protocol the connection was established with. Implemented by
+/// [`crate::protocols::connection::GenericConnection`] and
+/// [`crate::protocols::did_exchange::state_machine::generic::GenericDidExchange`].
+#[async_trait]
+pub trait MessageConnection: Send + Sync {
+    /// Encrypts `message` for the counterparty and sends it to their service endpoint.
+    async fn send_message<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        transport: &T,
+    ) -> VcxResult<()>
+    where
+        W: BaseWallet,
+        T: Transport;
+
+    /// Encrypts `message` for the counterparty, with `~transport.return_route` set to
+    /// `return_route`, and sends it to their service endpoint. Returns the reply received over
+    /// the same connection, if the counterparty and the transport support it.
+    ///
+    /// Connections unable to receive replies can rely on the default implementation, which sends
+    /// the message without the decorator and returns `None`.
+    async fn send_message_with_return_route<W, T>(
+        &self,
+        wallet: &W,
+        message: &AriesMessage,
+        _return_route: ReturnRoute,
+        transport: &T,
+    ) -> VcxResult<Option<AriesMessage>>
+    where
+        W: BaseWallet,
+        T: Transport,
+    {
+        self.send_message(wallet, message, transport).await?;
+        Ok(None)
+    }
+}
+
+/// Serializes `message` with the `~transport` decorator, asking the recipient to send replies
+/// over the connection the message is sent on.
+pub fn serialize_with_return_route(
+    message: &AriesMessage,
+    return_route: ReturnRoute,
+) -> VcxResult<Vec<u8>> {
+    let mut message = serde_json::to_value(message)?;
+    let Value::Object(fields) = &mut message else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::SerializationError,
+            "Aries message is not serialized as JSON object",
+        ));
+    };
+    fields.insert(
+        "~transport".to_owned(),
+        json!({ "return_route": return_route }),
+    );
+    Ok(serde_json::to_vec(&message)?)
+}
+
+/// Sends a message to each of `services` in turn, until one of them accepts it. Counterparties
+/// list alternate services to be tried when their preferred one, listed first, is unreachable.
+/// Returns the result of the first successful `send`. Errors other than transport failures, such
+/// as failures to encrypt the message, are returned right away, since other services would fail
+/// in the same way.
+pub(crate) async fn send_to_first_available_service<S, R, F, Fut>(
+    services: impl IntoIterator<Item = S>,
+    mut send: F,
+) -> VcxResult<R>
+where
+    F: FnMut(S) -> Fut,
+    Fut: Future<Output = VcxResult<R>>,
+{
+    let mut last_err = None;
+    for service in services {
+        match send(service).await {
+            Ok(result) => return Ok(result),
+            Err(err) if is_transport_error(&err) => {
+                warn!(
+                    "Failed to send message to service, trying next one: {}",
+                    err
+                );
+                last_err = Some(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Err(last_err.unwrap_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidUrl,
+            "No service to send message to",
+        )
+    }))
+}
+
+/// Whether the error is a failure to reach the service, rather than to prepare the message.
+fn is_transport_error(err: &AriesVcxError) -> bool {
+    matches!(
+        err.kind(),
+        AriesVcxErrorKind::PostMessageFailed | AriesVcxErrorKind::InvalidUrl
+    )
+}
+
+/// Sends a packed message to `service_endpoint`. If `receive_reply` is set, returns the reply
+/// received over the same connection, see [`Transport::send_message_and_receive`].
+pub(crate) async fn send_packed_message(
+    transport: &impl Transport,
+    msg: Vec<u8>,
+    service_endpoint: &Url,
+    receive_reply: bool,
+) -> VcxResult<Option<Vec<u8>>> {
+    if receive_reply {
+        transport
+            .send_message_and_receive(msg, service_endpoint)
+            .await
+    } else {
+        transport.send_message(msg, service_endpoint).await?;
+        Ok(None)
+    }
+}
+
+/// Unpacks the reply returned by [`Transport::send_message_and_receive`], checking it was sent by
+/// `expected_sender_vk`. Replies which are not encrypted envelopes, such as acknowledgements of
+/// the transport itself, are ignored.
+pub(crate) async fn unpack_returned_message(
+    wallet: &impl BaseWallet,
+    reply: Option<Vec<u8>>,
+    expected_sender_vk: Key,
+) -> VcxResult<Option<AriesMessage>> {
+    let Some(reply) = reply else {
+        return Ok(None);
+    };
+    let is_envelope =
+        serde_json::from_slice::<Value>(&reply).is_ok_and(|reply| reply.get("protected").is_some());
+    if !is_envelope {
+        trace!("unpack_returned_message >>> ignoring reply which is not an envelope");
+        return Ok(None);
+    }
+    let (message, _, _) =
+        EncryptionEnvelope::unpack_aries_msg(wallet, &reply, &Some(expected_sender_vk)).await?;
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use messages::msg_fields::protocols::trust_ping::ping::{Ping, PingContent};
+    use test_utils::mock_wallet::MockWallet;
+
+    use super::*;
+
+    /// Connection only able to send messages, recording them
+    #[derive(Default)]
+    struct SendOnlyConnection {
+        sent: Mutex<Vec<AriesMessage>>,
+    }
+
+    #[async_trait]
+    impl MessageConnection for SendOnlyConnection {
+        async fn send_message<W, T>(
+            &self,
+            _wallet: &W,
+            message: &AriesMessage,
+            _transport: &T,
+        ) -> VcxResult<()>
+        where
+            W: BaseWallet,
+            T: Transport,
+        {
+            self.sent.lock().unwrap().push(message.clone());
+            Ok(())
+        }
+    }
+
+    struct NoopTransport;
+
+    #[async_trait]
+    impl Transport for NoopTransport {
+        async fn send_message(&self, _msg: Vec<u8>, _service_endpoint: &Url) -> VcxResult<()> {
+            Ok(())
+        }
+    }
+
+    fn build_ping() -> AriesMessage {
+        Ping::builder()
+            .id("1".to_owned())
+            .content(PingContent::builder().response_requested(true).build())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_send_message_with_return_route_defaults_to_send_message() {
+        let connection = SendOnlyConnection::default();
+        let reply = connection
+            .send_message_with_return_route(
+                &MockWallet,
+                &build_ping(),
+                ReturnRoute::All,
+                &NoopTransport,
+            )
+            .await
+            .unwrap();
+        assert!(reply.is_none());
+        assert_eq!(*connection.sent.lock().unwrap(), vec![build_ping()]);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_first_available_service_falls_back_on_transport_error() {
+        let tried = Mutex::new(Vec::new());
+        let sent = send_to_first_available_service(["first", "second"], |service| {
+            tried.lock().unwrap().push(service);
+            async move {
+                match service {
+                    "first" => Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::PostMessageFailed,
+                        "Connection refused",
+                    )),
+                    _ => Ok(service),
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(sent, "second");
+        assert_eq!(*tried.lock().unwrap(), vec!["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_first_available_service_stops_on_other_errors() {
+        let tried = Mutex::new(Vec::new());
+        let err = send_to_first_available_service(["first", "second"], |service| {
+            tried.lock().unwrap().push(service);
+            async move {
+                Err::<(), _>(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    "Cannot serialize message",
+                ))
+            }
+        })
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidJson);
+        assert_eq!(*tried.lock().unwrap(), vec!["first"]);
+    }
+
+    #[test]
+    fn test_serialize_with_return_route() {
+        let serialized = serialize_with_return_route(&build_ping(), ReturnRoute::All).unwrap();
+        let serialized: Value = serde_json::from_slice(&serialized).unwrap();
+        assert_eq!(serialized["~transport"], json!({ "return_route": "all" }));
+        assert_eq!(serialized["@id"], "1");
+
+        let deserialized: AriesMessage = serde_json::from_value(serialized).unwrap();
+        let AriesMessage::TrustPing(_) = deserialized else {
+            panic!("Expected trust ping, got {deserialized:?}")
+        };
     }
 }
@@ -2,10 +2,24 @@ use did_doc::schema::{
     did_doc::DidDocument, service::service_key_kind::ServiceKeyKind, types::uri::Uri,
     verification_method::VerificationMethodType,
 };
+use did_key::DidKey;
 use public_key::{Key, KeyType};
 
 use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
 
+/// Parses an ed25519 key given either as base58 verkey, as `did:key` or as `did:key` URL, such as
+/// `did:key:z6Mk...#z6Mk...`, all of which services may list their keys as.
+pub fn parse_ed25519_key(key: &str) -> VcxResult<Key> {
+    let key = match key.starts_with("did:key:") {
+        true => {
+            let did = key.split_once('#').map_or(key, |(did, _fragment)| did);
+            DidKey::parse(did)?.key().clone()
+        }
+        false => Key::from_base58(key, KeyType::Ed25519)?,
+    };
+    Ok(key.validate_key_type(KeyType::Ed25519)?.to_owned())
+}
+
 pub(crate) fn resolve_service_key_to_typed_key(
     key: &ServiceKeyKind,
     did_document: &DidDocument,
@@ -13,12 +27,17 @@ pub(crate) fn resolve_service_key_to_typed_key(
     match key {
         ServiceKeyKind::DidKey(did_key) => Ok(did_key.key().clone()),
         ServiceKeyKind::Reference(reference) => {
-            let verification_method = did_document.dereference_key(reference).ok_or_else(|| {
-                AriesVcxError::from_msg(
+            let Some(verification_method) = did_document.dereference_key(reference) else {
+                // Keys of other DIDs, such as routing keys of mediators, are referenced by their
+                // did:key URL
+                if reference.to_string().starts_with("did:key:") {
+                    return parse_ed25519_key(&reference.to_string());
+                }
+                return Err(AriesVcxError::from_msg(
                     AriesVcxErrorKind::InvalidState,
                     format!("Unable to dereference key: {}", reference),
-                )
-            })?;
+                ));
+            };
             let key = verification_method.public_key().map_err(|err| {
                 AriesVcxError::from_msg(
                     AriesVcxErrorKind::InvalidState,
@@ -27,10 +46,7 @@ pub(crate) fn resolve_service_key_to_typed_key(
             })?;
             Ok(key)
         }
-        ServiceKeyKind::Value(value) => Ok(Key::new(
-            value.as_bytes().to_vec(),
-            public_key::KeyType::Ed25519,
-        )?),
+        ServiceKeyKind::Value(value) => parse_ed25519_key(value),
     }
 }
 
@@ -105,3 +121,20 @@ pub fn get_ed25519_recipient_keys(
 
     Ok(ed25519_recipient_keys)
 }
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ed25519_key() {
+        let did_key = "did:key:z6MkmjY8GnV5i9YTDtPETC2uUAW6ejw3nk5mXF5yci5ab7th";
+        let key = parse_ed25519_key(did_key).unwrap();
+        assert_eq!(
+            parse_ed25519_key(&format!("{did_key}#{}", key.fingerprint())).unwrap(),
+            key
+        );
+        assert_eq!(parse_ed25519_key(&key.base58()).unwrap(), key);
+        assert!(parse_ed25519_key("did:key:invalid").is_err());
+    }
+}
@@ -11,7 +11,8 @@ use uuid::Uuid;
 use crate::{
     errors::error::prelude::*,
     utils::didcomm_utils::{
-        get_ed25519_recipient_keys, get_ed25519_routing_keys, resolve_ed25519_key_agreement,
+        get_ed25519_recipient_keys, get_ed25519_routing_keys, parse_ed25519_key,
+        resolve_ed25519_key_agreement,
     },
 };
 
@@ -32,26 +33,27 @@ impl EncryptionEnvelope {
             did_doc
         );
 
-        let recipient_key_base58 =
-            did_doc
-                .recipient_keys()?
-                .first()
-                .cloned()
-                .ok_or(AriesVcxError::from_msg(
-                    AriesVcxErrorKind::InvalidState,
-                    format!("No recipient key found in DIDDoc: {:?}", did_doc),
-                ))?;
-
-        let recipient_key = Key::from_base58(&recipient_key_base58, KeyType::Ed25519)?;
+        let recipient_keys = did_doc
+            .recipient_keys()?
+            .iter()
+            .map(|recipient_key| parse_ed25519_key(recipient_key))
+            .collect::<VcxResult<Vec<_>>>()?;
+        if recipient_keys.is_empty() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("No recipient key found in DIDDoc: {:?}", did_doc),
+            ));
+        }
         let routing_keys = did_doc
             .routing_keys()
             .iter()
-            .map(|routing_key| Key::from_base58(routing_key, KeyType::Ed25519))
-            .collect::<Result<Vec<_>, _>>()?;
+            .map(|routing_key| parse_ed25519_key(routing_key))
+            .collect::<VcxResult<Vec<_>>>()?;
         let sender_key = sender_vk
             .map(|key| Key::from_base58(key, KeyType::Ed25519))
             .transpose()?;
-        Self::create_from_keys(wallet, data, sender_key, recipient_key, routing_keys).await
+        Self::create_from_recipient_keys(wallet, data, sender_key, recipient_keys, routing_keys)
+            .await
     }
 
     /// Create encrypted message based on key agreement keys of our did document, counterparties
@@ -75,21 +77,18 @@ impl EncryptionEnvelope {
     ) -> VcxResult<EncryptionEnvelope> {
         let sender_vk = resolve_ed25519_key_agreement(our_did_doc)?;
 
-        let recipient_key = {
-            let service_keys = get_ed25519_recipient_keys(their_did_doc, their_service_id)?;
-            match service_keys.into_iter().next() {
-                Some(key) => key,
-                // as a backup, use the first key agreement key, or none
-                None => resolve_ed25519_key_agreement(their_did_doc)?,
-            }
-        };
+        let mut recipient_keys = get_ed25519_recipient_keys(their_did_doc, their_service_id)?;
+        if recipient_keys.is_empty() {
+            // as a backup, use the first key agreement key, or none
+            recipient_keys.push(resolve_ed25519_key_agreement(their_did_doc)?);
+        }
         let routing_keys = get_ed25519_routing_keys(their_did_doc, their_service_id)?;
 
-        EncryptionEnvelope::create_from_keys(
+        EncryptionEnvelope::create_from_recipient_keys(
             wallet,
             data,
             Some(sender_vk),
-            recipient_key,
+            recipient_keys,
             routing_keys,
         )
         .await
@@ -101,41 +100,62 @@ impl EncryptionEnvelope {
         sender_vk: Option<Key>,
         recipient_key: Key,
         routing_keys: Vec<Key>,
+    ) -> VcxResult<EncryptionEnvelope> {
+        Self::create_from_recipient_keys(wallet, data, sender_vk, vec![recipient_key], routing_keys)
+            .await
+    }
+
+    /// Create message encrypted for each of `recipient_keys`, wrapped into a forward message for
+    /// each of `routing_keys`. The first routing key is the one of the mediator closest to the
+    /// recipient, the message being wrapped for it first, and the last routing key is the one of
+    /// the mediator the message is to be sent to.
+    pub async fn create_from_recipient_keys(
+        wallet: &impl BaseWallet,
+        data: &[u8],
+        sender_vk: Option<Key>,
+        recipient_keys: Vec<Key>,
+        routing_keys: Vec<Key>,
     ) -> VcxResult<EncryptionEnvelope> {
         // Validate keys are Ed25519
         sender_vk
             .as_ref()
             .map(|key| key.validate_key_type(KeyType::Ed25519))
             .transpose()?;
-        for key in routing_keys.iter().as_ref() {
+        for key in recipient_keys.iter().chain(routing_keys.iter()) {
             key.validate_key_type(KeyType::Ed25519)?;
         }
+        // Mediators know the recipient by any of its keys, forwards name the first one
+        let forward_to_key = recipient_keys.first().cloned().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Cannot encrypt message without recipient key",
+            )
+        })?;
 
-        let message = EncryptionEnvelope::encrypt_for_pairwise(
+        let message =
+            EncryptionEnvelope::encrypt_for_pairwise(wallet, data, sender_vk, recipient_keys)
+                .await?;
+        EncryptionEnvelope::wrap_into_forward_messages(
             wallet,
-            data,
-            sender_vk,
-            recipient_key.validate_key_type(KeyType::Ed25519)?.clone(),
+            message,
+            forward_to_key,
+            routing_keys,
         )
-        .await?;
-        EncryptionEnvelope::wrap_into_forward_messages(wallet, message, recipient_key, routing_keys)
-            .await
-            .map(EncryptionEnvelope)
+        .await
+        .map(EncryptionEnvelope)
     }
 
     async fn encrypt_for_pairwise(
         wallet: &impl BaseWallet,
         data: &[u8],
         sender_vk: Option<Key>,
-        recipient_key: Key,
+        recipient_keys: Vec<Key>,
     ) -> VcxResult<Vec<u8>> {
         debug!(
-            "Encrypting for pairwise; sender_vk: {:?}, recipient_key: {}",
-            sender_vk, recipient_key
+            "Encrypting for pairwise; sender_vk: {:?}, recipient_keys: {:?}",
+            sender_vk, recipient_keys
         );
 
-        let recipient_keys = vec![recipient_key];
-
         wallet
             .pack_message(sender_vk, recipient_keys, data)
             .await
@@ -279,10 +299,12 @@ impl EncryptionEnvelope {
 #[cfg(test)]
 pub mod unit_tests {
     use aries_vcx_wallet::wallet::base_wallet::did_wallet::DidWallet;
+    use base64::Engine;
     use serde_json::Value;
     use test_utils::devsetup::build_setup_profile;
 
     use super::*;
+    use crate::utils::base64::URL_SAFE_LENIENT;
 
     #[tokio::test]
     async fn test_pack_unpack_anon() {
@@ -398,6 +420,100 @@ pub mod unit_tests {
         assert_eq!(data_original, core_payload);
     }
 
+    #[tokio::test]
+    async fn test_pack_unpack_with_two_hop_routing() {
+        let setup = build_setup_profile().await;
+        let mut keys = Vec::new();
+        for _ in 0..3 {
+            let did_data = setup
+                .wallet
+                .create_and_store_my_did(None, None)
+                .await
+                .unwrap();
+            keys.push(did_data.verkey().clone());
+        }
+        let [recipient_key, inner_routing_key, outer_routing_key] = keys.try_into().unwrap();
+
+        let data_original = "foobar";
+
+        let envelope = EncryptionEnvelope::create_from_keys(
+            &setup.wallet,
+            data_original.as_bytes(),
+            None,
+            recipient_key.clone(),
+            vec![inner_routing_key.clone(), outer_routing_key.clone()],
+        )
+        .await
+        .unwrap();
+
+        // The mediator of the last routing key unwraps the outermost forward
+        let (outer_fwd, _, unpacked_by) =
+            EncryptionEnvelope::unpack(&setup.wallet, &envelope.0, &None)
+                .await
+                .unwrap();
+        assert_eq!(unpacked_by, outer_routing_key);
+        let outer_fwd = serde_json::from_str::<Value>(&outer_fwd).unwrap();
+        assert_eq!(outer_fwd["to"], inner_routing_key.base58());
+
+        let (inner_fwd, _, unpacked_by) = EncryptionEnvelope::unpack(
+            &setup.wallet,
+            outer_fwd["msg"].to_string().as_bytes(),
+            &None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(unpacked_by, inner_routing_key);
+        let inner_fwd = serde_json::from_str::<Value>(&inner_fwd).unwrap();
+        assert_eq!(inner_fwd["to"], recipient_key.base58());
+
+        let (core_payload, _, unpacked_by) = EncryptionEnvelope::unpack(
+            &setup.wallet,
+            inner_fwd["msg"].to_string().as_bytes(),
+            &None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(unpacked_by, recipient_key);
+        assert_eq!(data_original, core_payload);
+    }
+
+    #[tokio::test]
+    async fn test_pack_for_multiple_recipients() {
+        let setup = build_setup_profile().await;
+        let mut keys = Vec::new();
+        for _ in 0..2 {
+            let did_data = setup
+                .wallet
+                .create_and_store_my_did(None, None)
+                .await
+                .unwrap();
+            keys.push(did_data.verkey().clone());
+        }
+
+        let envelope = EncryptionEnvelope::create_from_recipient_keys(
+            &setup.wallet,
+            "foobar".as_bytes(),
+            None,
+            keys.clone(),
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let envelope = serde_json::from_slice::<Value>(&envelope.0).unwrap();
+        let protected = URL_SAFE_LENIENT
+            .decode(envelope["protected"].as_str().unwrap())
+            .unwrap();
+        let protected = serde_json::from_slice::<Value>(&protected).unwrap();
+        let kids: Vec<_> = protected["recipients"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|recipient| recipient["header"]["kid"].as_str().unwrap().to_owned())
+            .collect();
+        assert_eq!(kids, vec![keys[0].base58(), keys[1].base58()]);
+    }
+
     #[tokio::test]
     async fn test_pack_unpack_unexpected_key_detection() {
         let setup = build_setup_profile().await;
@@ -1,10 +1,30 @@
 use std::error::Error;
 
-use aries_vcx::common::credentials::get_cred_rev_id;
+use aries_vcx::{
+    common::credentials::{
+        get_cred_rev_id,
+        link_secret::{
+            export_link_secret, get_default_link_secret_id, import_link_secret, list_link_secrets,
+            rotate_link_secret, set_default_link_secret, DEFAULT_LINK_SECRET_TAG,
+        },
+    },
+    global::settings::DEFAULT_LINK_SECRET_ALIAS,
+};
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::{
+    base_wallet::{
+        did_wallet::DidWallet, record::Record, record_category::RecordCategory,
+        record_wallet::RecordWallet, BaseWallet,
+    },
+    record_tags::{RecordTag, RecordTags},
+};
 use serde_json::json;
-use test_utils::{constants::DEFAULT_SCHEMA_ATTRS, devsetup::build_setup_profile};
+use test_utils::{
+    constants::DEFAULT_SCHEMA_ATTRS,
+    devsetup::{build_setup_profile, dev_build_featured_anoncreds, dev_build_featured_wallet},
+    random::generate_random_seed,
+};
 
 use crate::utils::{
     create_and_publish_test_rev_reg, create_and_write_credential, create_and_write_test_cred_def,
@@ -167,3 +187,89 @@ async fn test_pool_revoke_credential() -> Result<(), Box<dyn Error>> {
     assert_ne!(first_rev_reg_delta, second_rev_reg_delta);
     Ok(())
 }
+
+#[tokio::test]
+async fn test_link_secret_rotation_and_recovery() -> Result<(), Box<dyn Error>> {
+    let seed = generate_random_seed();
+    let (did, wallet) = dev_build_featured_wallet(&seed).await;
+    let anoncreds = dev_build_featured_anoncreds().await;
+    anoncreds
+        .prover_create_link_secret(&wallet, &DEFAULT_LINK_SECRET_ALIAS.to_string())
+        .await?;
+    assert_eq!(
+        get_default_link_secret_id(&wallet).await?,
+        DEFAULT_LINK_SECRET_ALIAS
+    );
+
+    let rotated_id = "rotated".to_string();
+    rotate_link_secret(&wallet, &anoncreds, &rotated_id).await?;
+    assert_eq!(get_default_link_secret_id(&wallet).await?, rotated_id);
+    let mut link_secrets = list_link_secrets(&wallet).await?;
+    link_secrets.sort();
+    assert_eq!(link_secrets, vec![DEFAULT_LINK_SECRET_ALIAS, "rotated"]);
+
+    let key = wallet.key_for_did(&did).await?;
+    let exported = export_link_secret(&wallet, &rotated_id, key).await?;
+
+    // a wallet on another device, restored from the same seed
+    let (_, recovered_wallet) = dev_build_featured_wallet(&seed).await;
+    let imported_id = import_link_secret(&recovered_wallet, &exported).await?;
+    assert_eq!(imported_id, rotated_id);
+    assert_eq!(
+        list_link_secrets(&recovered_wallet).await?,
+        vec![rotated_id]
+    );
+    assert!(import_link_secret(&recovered_wallet, &exported)
+        .await
+        .is_err());
+    Ok(())
+}
+
+async fn link_secret_tags(
+    wallet: &impl BaseWallet,
+    link_secret_id: &str,
+) -> Result<RecordTags, Box<dyn Error>> {
+    let record = wallet
+        .get_record(RecordCategory::LinkSecret, link_secret_id)
+        .await?;
+    Ok(record.tags().clone())
+}
+
+#[tokio::test]
+async fn test_set_default_link_secret_keeps_other_tags() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet(&generate_random_seed()).await;
+    for id in ["first", "second"] {
+        let record = Record::builder()
+            .name(id.to_owned())
+            .category(RecordCategory::LinkSecret)
+            .value("link secret value".to_owned())
+            .tags(RecordTags::new(vec![RecordTag::new("device", id)]))
+            .build();
+        wallet.add_record(record).await?;
+    }
+
+    set_default_link_secret(&wallet, &"first".to_owned()).await?;
+    assert_eq!(get_default_link_secret_id(&wallet).await?, "first");
+    assert_eq!(
+        link_secret_tags(&wallet, "first").await?,
+        RecordTags::new(vec![
+            RecordTag::new("device", "first"),
+            RecordTag::new(DEFAULT_LINK_SECRET_TAG, "true"),
+        ])
+    );
+
+    set_default_link_secret(&wallet, &"second".to_owned()).await?;
+    assert_eq!(get_default_link_secret_id(&wallet).await?, "second");
+    assert_eq!(
+        link_secret_tags(&wallet, "first").await?,
+        RecordTags::new(vec![RecordTag::new("device", "first")])
+    );
+    assert_eq!(
+        link_secret_tags(&wallet, "second").await?,
+        RecordTags::new(vec![
+            RecordTag::new("device", "second"),
+            RecordTag::new(DEFAULT_LINK_SECRET_TAG, "true"),
+        ])
+    );
+    Ok(())
+}
@@ -3,14 +3,23 @@ use std::error::Error;
 use anoncreds_types::{
     data_types::ledger::cred_def::CredentialDefinition, utils::validation::Validatable,
 };
-use aries_vcx::common::primitives::{
-    credential_definition::generate_cred_def, revocation_registry::generate_rev_reg,
+use aries_vcx::{
+    common::primitives::{
+        credential_definition::{generate_cred_def, get_active_cred_def_ids, CredentialDef},
+        revocation_registry::{generate_rev_reg, RevocationRegistry},
+    },
+    did_parser_nom::Did,
 };
 use aries_vcx_ledger::ledger::{
     base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite},
     indy::pool::test_utils::get_temp_dir_path,
 };
-use test_utils::{constants::DEFAULT_SCHEMA_ATTRS, devsetup::build_setup_profile};
+use test_utils::{
+    constants::{schema_id, DEFAULT_SCHEMA_ATTRS},
+    devsetup::{build_setup_profile, dev_build_featured_anoncreds, dev_build_featured_wallet},
+    mockdata::mock_ledger::MockLedger,
+    random::generate_random_seed,
+};
 
 use crate::utils::create_and_write_test_schema;
 
@@ -130,3 +139,131 @@ async fn test_pool_create_rev_reg_def() -> Result<(), Box<dyn Error>> {
         .await?;
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_pool_rotate_cred_def() -> Result<(), Box<dyn Error>> {
+    let setup = build_setup_profile().await;
+    let schema = create_and_write_test_schema(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        &setup.institution_did,
+        DEFAULT_SCHEMA_ATTRS,
+    )
+    .await;
+
+    let cred_def = CredentialDef::create(
+        &setup.wallet,
+        &setup.ledger_read,
+        &setup.anoncreds,
+        "1".to_string(),
+        setup.institution_did.clone(),
+        schema.schema_id.clone(),
+        "tag_1".to_string(),
+        false,
+    )
+    .await?;
+    assert!(!cred_def.is_retired(&setup.wallet).await?);
+
+    let (rotated, rotated_rev_reg) = cred_def
+        .rotate(
+            &setup.wallet,
+            &setup.ledger_read,
+            &setup.anoncreds,
+            "tag_2".to_string(),
+            None,
+        )
+        .await?;
+    assert!(rotated_rev_reg.is_none());
+    assert_eq!(rotated.get_tag(), "tag_2");
+    assert_eq!(rotated.get_schema_id(), cred_def.get_schema_id());
+    assert_ne!(rotated.get_cred_def_id(), cred_def.get_cred_def_id());
+    assert!(cred_def.is_retired(&setup.wallet).await?);
+    assert!(!rotated.is_retired(&setup.wallet).await?);
+
+    let active = get_active_cred_def_ids(&setup.wallet).await?;
+    assert!(active.contains(rotated.get_cred_def_id()));
+    assert!(!active.contains(cred_def.get_cred_def_id()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rotate_revocable_cred_def_replaces_rev_reg() -> Result<(), Box<dyn Error>> {
+    let (issuer_did, wallet) = dev_build_featured_wallet(&generate_random_seed()).await;
+    let issuer_did = Did::parse(issuer_did)?;
+    let anoncreds = dev_build_featured_anoncreds().await;
+    let tails_dir = get_temp_dir_path().to_str().unwrap().to_string();
+
+    let cred_def = CredentialDef::create(
+        &wallet,
+        &MockLedger,
+        &anoncreds,
+        "1".to_string(),
+        issuer_did.clone(),
+        schema_id(),
+        "tag_1".to_string(),
+        true,
+    )
+    .await?;
+    let rev_reg = RevocationRegistry::create(
+        &wallet,
+        &anoncreds,
+        &issuer_did,
+        cred_def.get_cred_def_id(),
+        &tails_dir,
+        5,
+        1,
+    )
+    .await?;
+
+    let rotation = cred_def
+        .rotate(&wallet, &MockLedger, &anoncreds, "tag_2".to_string(), None)
+        .await;
+    assert!(rotation.is_err());
+    assert!(!cred_def.is_retired(&wallet).await?);
+
+    // the new registry can not be created, so the rotated credential definition is discarded
+    let mut broken_rev_reg = rev_reg.clone();
+    broken_rev_reg.tails_dir = "/dev/null/tails".to_string();
+    let rotation = cred_def
+        .rotate(
+            &wallet,
+            &MockLedger,
+            &anoncreds,
+            "tag_2".to_string(),
+            Some(&broken_rev_reg),
+        )
+        .await;
+    assert!(rotation.is_err());
+    assert!(!cred_def.is_retired(&wallet).await?);
+    assert_eq!(
+        get_active_cred_def_ids(&wallet).await?,
+        vec![cred_def.get_cred_def_id().clone()]
+    );
+
+    let (rotated, rotated_rev_reg) = cred_def
+        .rotate(
+            &wallet,
+            &MockLedger,
+            &anoncreds,
+            "tag_2".to_string(),
+            Some(&rev_reg),
+        )
+        .await?;
+    assert!(rotated.get_support_revocation());
+    let rotated_rev_reg = rotated_rev_reg.expect("rotated revocation registry");
+    assert_eq!(
+        rotated_rev_reg.get_cred_def_id(),
+        rotated.get_cred_def_id().to_string()
+    );
+    assert_ne!(rotated_rev_reg.get_rev_reg_id(), rev_reg.get_rev_reg_id());
+    assert_eq!(rotated_rev_reg.get_tails_dir(), tails_dir);
+    assert_eq!(
+        rotated_rev_reg.get_rev_reg_def().value.max_cred_num,
+        rev_reg.get_rev_reg_def().value.max_cred_num
+    );
+    assert!(cred_def.is_retired(&wallet).await?);
+    assert!(!rotated.is_retired(&wallet).await?);
+    Ok(())
+}
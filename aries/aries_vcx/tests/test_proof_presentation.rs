@@ -4,6 +4,7 @@ use std::error::Error;
 
 use anoncreds_types::data_types::messages::pres_request::PresentationRequest;
 use aries_vcx::{
+    common::proofs::verifier::verification_report::{ReferentKind, RevocationCheck},
     handlers::proof_presentation::{prover::Prover, verifier::Verifier},
     protocols::proof_presentation::{
         prover::state_machine::ProverState,
@@ -164,6 +165,24 @@ async fn test_agency_pool_generate_proof_with_predicates() -> Result<(), Box<dyn
             verifier.get_verification_status(),
             PresentationVerificationStatus::Valid
         );
+        let report = verifier.get_verification_report()?;
+        assert!(report.proof_valid);
+        assert_eq!(report.referents.len(), 4);
+        let self_attested = report
+            .referents
+            .iter()
+            .find(|referent| referent.referent == "zip_self_attested_3")
+            .unwrap();
+        assert_eq!(self_attested.kind, ReferentKind::SelfAttestedAttribute);
+        let predicate = report
+            .referents
+            .iter()
+            .find(|referent| referent.predicate.is_some())
+            .unwrap();
+        assert!(matches!(
+            predicate.revocation,
+            RevocationCheck::Passed { .. }
+        ));
     } else {
         panic!("Unexpected message type {:?}", final_message);
     }
@@ -1,9 +1,16 @@
 use std::{error::Error, thread, time::Duration};
 
 use anoncreds_types::data_types::messages::pres_request::NonRevokedInterval;
-use aries_vcx::protocols::proof_presentation::verifier::{
-    state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+use aries_vcx::{
+    common::credentials::revocation_status::{
+        check_credential_revocation_status, refresh_credentials_revocation_status, RevocationStatus,
+    },
+    protocols::proof_presentation::verifier::{
+        state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+    },
 };
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use serde_json::json;
 use test_utils::devsetup::*;
 
 use crate::utils::{
@@ -191,6 +198,59 @@ async fn test_agency_pool_local_revocation() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_agency_pool_holder_revocation_status() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+
+    let (_schema, _cred_def, rev_reg, issuer) =
+        issue_address_credential(&mut consumer, &mut institution).await;
+    let cred_id = consumer
+        .anoncreds
+        .prover_get_credentials(&consumer.wallet, None)
+        .await?
+        .pop()
+        .unwrap()
+        .referent;
+
+    let status = check_credential_revocation_status(
+        &consumer.wallet,
+        &consumer.ledger_read,
+        &consumer.anoncreds,
+        &cred_id,
+    )
+    .await?;
+    assert_eq!(status.status, RevocationStatus::Active);
+
+    revoke_credential_and_publish_accumulator(&mut institution, &issuer, &rev_reg).await;
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    let refresh = refresh_credentials_revocation_status(
+        &consumer.wallet,
+        &consumer.ledger_read,
+        &consumer.anoncreds,
+    )
+    .await?;
+    assert!(refresh.failures.is_empty());
+    let statuses = refresh.statuses;
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].cred_id, cred_id);
+    assert!(statuses[0].is_revoked());
+    assert!(statuses[0].timestamp >= status.timestamp);
+
+    let revoked_creds = consumer
+        .anoncreds
+        .prover_get_credentials(
+            &consumer.wallet,
+            Some(&json!({ "revocation_status": "revoked" }).to_string()),
+        )
+        .await?;
+    assert_eq!(revoked_creds.len(), 1);
+    Ok(())
+}
+
 // TODO - re-enable after https://github.com/hyperledger/aries-vcx/issues/1309
 // #[tokio::test]
 // #[ignore]
@@ -1009,6 +1009,7 @@ impl BaseAnonCreds for Anoncreds {
             RecordTag::new("schema_version", &schema_version),
             RecordTag::new("issuer_did", &issuer_did.0),
             RecordTag::new("cred_def_id", &cred_def_id.0),
+            RecordTag::new("link_secret_id", link_secret_id),
         ]);
 
         if let Some(rev_reg_id) = &credential.rev_reg_id {
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+use url::Url;
+
+/// Struct representing the `~service` decorator from its [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0056-service-decorator/README.md>).
+///
+/// Used on messages exchanged without a connection, telling the recipient where and for which
+/// keys to pack its reply.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub recipient_keys: Vec<String>,
+    #[builder(default)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub routing_keys: Vec<String>,
+    pub service_endpoint: Url,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+pub mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    pub fn make_minimal_service() -> Service {
+        Service::builder()
+            .recipient_keys(vec![
+                "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K".to_owned()
+            ])
+            .service_endpoint("https://example.com/endpoint".parse().unwrap())
+            .build()
+    }
+
+    pub fn make_extended_service() -> Service {
+        Service::builder()
+            .recipient_keys(vec![
+                "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K".to_owned()
+            ])
+            .routing_keys(vec![
+                "3zUsbVAPr4Xcdfy1Y2WZhL6fxFgQWM9ipeD5ruAGTJdb".to_owned()
+            ])
+            .service_endpoint("https://example.com/endpoint".parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_minimal_service() {
+        let service = make_minimal_service();
+        let expected = json!({
+            "recipientKeys": service.recipient_keys,
+            "serviceEndpoint": service.service_endpoint
+        });
+
+        test_utils::test_serde(service, expected);
+    }
+
+    #[test]
+    fn test_extended_service() {
+        let service = make_extended_service();
+        let expected = json!({
+            "recipientKeys": service.recipient_keys,
+            "routingKeys": service.routing_keys,
+            "serviceEndpoint": service.service_endpoint
+        });
+
+        test_utils::test_serde(service, expected);
+    }
+}
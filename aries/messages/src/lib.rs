@@ -16,15 +16,24 @@ use derive_more::From;
 use display_as_json::Display;
 use misc::utils;
 use msg_fields::protocols::{
+    action_menu::ActionMenu,
     cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
     did_exchange::{v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, DidExchange},
-    pickup::Pickup,
+    did_rotate::DidRotate,
+    pickup::{v2::PickupV2, v3::PickupV3, Pickup},
     present_proof::{v2::PresentProofV2, PresentProof},
+    question_answer::QuestionAnswer,
 };
 use msg_types::{
     cred_issuance::CredentialIssuanceType,
     present_proof::PresentProofType,
-    protocols::did_exchange::{DidExchangeType, DidExchangeTypeV1},
+    protocols::{
+        coordinate_mediation::{
+            CoordinateMediationType, CoordinateMediationTypeV1, CoordinateMediationTypeV2,
+        },
+        did_exchange::{DidExchangeType, DidExchangeTypeV1},
+        pickup::{PickupType, PickupTypeV2, PickupTypeV3},
+    },
     report_problem::ReportProblemTypeV1_0,
     routing::RoutingTypeV1_0,
     MsgWithType,
@@ -34,10 +43,18 @@ use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use crate::{
     msg_fields::{
         protocols::{
-            basic_message::BasicMessage, connection::Connection,
-            coordinate_mediation::CoordinateMediation, discover_features::DiscoverFeatures,
-            notification::Notification, out_of_band::OutOfBand, present_proof::v1::PresentProofV1,
-            report_problem::ProblemReport, revocation::Revocation, routing::Forward,
+            basic_message::BasicMessage,
+            connection::Connection,
+            coordinate_mediation::{
+                v1::CoordinateMediationV1, v2::CoordinateMediationV2, CoordinateMediation,
+            },
+            discover_features::DiscoverFeatures,
+            notification::Notification,
+            out_of_band::OutOfBand,
+            present_proof::v1::PresentProofV1,
+            report_problem::ProblemReport,
+            revocation::Revocation,
+            routing::Forward,
             trust_ping::TrustPing,
         },
         traits::DelayedSerde,
@@ -76,6 +93,9 @@ pub enum AriesMessage {
     Pickup(Pickup),
     CoordinateMediation(CoordinateMediation),
     DidExchange(DidExchange),
+    DidRotate(DidRotate),
+    ActionMenu(ActionMenu),
+    QuestionAnswer(QuestionAnswer),
 }
 
 impl DelayedSerde for AriesMessage {
@@ -185,13 +205,22 @@ impl DelayedSerde for AriesMessage {
                 Notification::delayed_deserialize((msg_type, kind_str), deserializer)
                     .map(From::from)
             }
-            Protocol::PickupType(msg_type) => {
-                Pickup::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            Protocol::PickupType(PickupType::V2(PickupTypeV2::V2_0(msg_type))) => {
+                PickupV2::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(|x| AriesMessage::from(Pickup::V2(x)))
             }
-            Protocol::CoordinateMediationType(msg_type) => {
-                CoordinateMediation::delayed_deserialize((msg_type, kind_str), deserializer)
-                    .map(From::from)
+            Protocol::PickupType(PickupType::V3(PickupTypeV3::V3_0(msg_type))) => {
+                PickupV3::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(|x| AriesMessage::from(Pickup::V3(x)))
             }
+            Protocol::CoordinateMediationType(CoordinateMediationType::V1(
+                CoordinateMediationTypeV1::V1_0(msg_type),
+            )) => CoordinateMediationV1::delayed_deserialize((msg_type, kind_str), deserializer)
+                .map(|x| AriesMessage::from(CoordinateMediation::V1(x))),
+            Protocol::CoordinateMediationType(CoordinateMediationType::V2(
+                CoordinateMediationTypeV2::V2_0(msg_type),
+            )) => CoordinateMediationV2::delayed_deserialize((msg_type, kind_str), deserializer)
+                .map(|x| AriesMessage::from(CoordinateMediation::V2(x))),
             Protocol::DidExchangeType(DidExchangeType::V1(DidExchangeTypeV1::V1_0(msg_type))) => {
                 DidExchangeV1_0::delayed_deserialize((msg_type, kind_str), deserializer)
                     .map(|x| AriesMessage::from(DidExchange::V1_0(x)))
@@ -200,6 +229,16 @@ impl DelayedSerde for AriesMessage {
                 DidExchangeV1_1::delayed_deserialize((msg_type, kind_str), deserializer)
                     .map(|x| AriesMessage::from(DidExchange::V1_1(x)))
             }
+            Protocol::DidRotateType(msg_type) => {
+                DidRotate::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            Protocol::ActionMenuType(msg_type) => {
+                ActionMenu::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            Protocol::QuestionAnswerType(msg_type) => {
+                QuestionAnswer::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(From::from)
+            }
         }
     }
 
@@ -221,10 +260,19 @@ impl DelayedSerde for AriesMessage {
             Self::BasicMessage(v) => MsgWithType::from(v).serialize(serializer),
             Self::OutOfBand(v) => v.delayed_serialize(serializer),
             Self::Notification(v) => v.delayed_serialize(serializer),
-            Self::Pickup(v) => v.delayed_serialize(serializer),
-            Self::CoordinateMediation(v) => v.delayed_serialize(serializer),
+            Self::Pickup(Pickup::V2(v)) => v.delayed_serialize(serializer),
+            Self::Pickup(Pickup::V3(v)) => v.delayed_serialize(serializer),
+            Self::CoordinateMediation(CoordinateMediation::V1(v)) => {
+                v.delayed_serialize(serializer)
+            }
+            Self::CoordinateMediation(CoordinateMediation::V2(v)) => {
+                v.delayed_serialize(serializer)
+            }
             Self::DidExchange(DidExchange::V1_0(v)) => v.delayed_serialize(serializer),
             Self::DidExchange(DidExchange::V1_1(v)) => v.delayed_serialize(serializer),
+            Self::DidRotate(v) => v.delayed_serialize(serializer),
+            Self::ActionMenu(v) => v.delayed_serialize(serializer),
+            Self::QuestionAnswer(v) => v.delayed_serialize(serializer),
         }
     }
 }
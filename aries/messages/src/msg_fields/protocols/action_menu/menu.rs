@@ -0,0 +1,186 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+/// Presents the options the responder offers to the requester.
+pub type Menu = MsgParts<MenuContent, MenuDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuContent {
+    pub title: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errormsg: Option<String>,
+    pub options: Vec<MenuOption>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuOption {
+    pub name: String,
+    pub title: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form: Option<MenuForm>,
+}
+
+/// Parameters the requester is asked for before performing a [`MenuOption`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuForm {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub params: Vec<MenuFormParam>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "submit-label")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit_label: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuFormParam {
+    pub name: String,
+    pub title: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MenuDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::protocols::action_menu::ActionMenuTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_menu() {
+        let option = MenuOption::builder()
+            .name("obtain-license".to_owned())
+            .title("Obtain a license".to_owned())
+            .build();
+        let content = MenuContent::builder()
+            .title("Welcome to IIWBook".to_owned())
+            .options(vec![option])
+            .build();
+        let decorators = MenuDecorators::default();
+
+        let expected = json!({
+            "title": content.title,
+            "options": [{
+                "name": "obtain-license",
+                "title": "Obtain a license"
+            }]
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Menu, expected);
+    }
+
+    #[test]
+    fn test_extended_menu() {
+        let param = MenuFormParam::builder()
+            .name("query".to_owned())
+            .title("Attendee name".to_owned())
+            .default("".to_owned())
+            .description("".to_owned())
+            .required(true)
+            .param_type("text".to_owned())
+            .build();
+        let form = MenuForm::builder()
+            .description("Enter a participant name below to perform a search.".to_owned())
+            .params(vec![param])
+            .submit_label("Search".to_owned())
+            .build();
+        let option = MenuOption::builder()
+            .name("search-introductions".to_owned())
+            .title("Search introductions".to_owned())
+            .description("Filter attendee records to make a connection".to_owned())
+            .disabled(true)
+            .form(form)
+            .build();
+        let content = MenuContent::builder()
+            .title("Welcome to IIWBook".to_owned())
+            .description("IIWBook facilitates connections between attendees".to_owned())
+            .errormsg("No IIWBook names were found.".to_owned())
+            .options(vec![option])
+            .build();
+        let decorators = MenuDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "title": content.title,
+            "description": content.description,
+            "errormsg": content.errormsg,
+            "options": [{
+                "name": "search-introductions",
+                "title": "Search introductions",
+                "description": "Filter attendee records to make a connection",
+                "disabled": true,
+                "form": {
+                    "description": "Enter a participant name below to perform a search.",
+                    "params": [{
+                        "name": "query",
+                        "title": "Attendee name",
+                        "default": "",
+                        "description": "",
+                        "required": true,
+                        "type": "text"
+                    }],
+                    "submit-label": "Search"
+                }
+            }],
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Menu, expected);
+    }
+}
@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+/// Asks the responder to send its current menu.
+pub type MenuRequest = MsgParts<MenuRequestContent, MenuRequestDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MenuRequestContent {}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MenuRequestDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::protocols::action_menu::ActionMenuTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_menu_request() {
+        let content = MenuRequestContent::default();
+        let decorators = MenuRequestDecorators::default();
+
+        let expected = json!({});
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            ActionMenuTypeV1_0::MenuRequest,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_menu_request() {
+        let content = MenuRequestContent::default();
+        let decorators = MenuRequestDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            ActionMenuTypeV1_0::MenuRequest,
+            expected,
+        );
+    }
+}
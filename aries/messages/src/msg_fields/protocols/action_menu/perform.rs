@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+/// Selects one of the options of the menu it is threaded to, along with the parameters of its
+/// form.
+pub type Perform = MsgParts<PerformContent, PerformDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct PerformContent {
+    pub name: String,
+    #[builder(default)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct PerformDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::protocols::action_menu::ActionMenuTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_perform() {
+        let content = PerformContent::builder()
+            .name("obtain-license".to_owned())
+            .build();
+        let decorators = PerformDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "name": content.name,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Perform, expected);
+    }
+
+    #[test]
+    fn test_extended_perform() {
+        let content = PerformContent::builder()
+            .name("search-introductions".to_owned())
+            .params(HashMap::from([("query".to_owned(), "Alice".to_owned())]))
+            .build();
+        let decorators = PerformDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "name": content.name,
+            "params": content.params,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Perform, expected);
+    }
+}
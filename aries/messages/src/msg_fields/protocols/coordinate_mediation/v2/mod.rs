@@ -0,0 +1,145 @@
+//! Module containing the `coordinate-mediation/2.0` protocol messages, as defined in the [spec](<https://didcomm.org/coordinate-mediation/2.0/>).
+//!
+//! Unlike 1.0, recipients are registered as DIDs rather than keys, and the mediator grants
+//! mediation with its routing DIDs.
+
+pub mod mediate_deny;
+pub mod mediate_grant;
+pub mod mediate_request;
+pub mod recipient;
+pub mod recipient_query;
+pub mod recipient_update;
+pub mod recipient_update_response;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+pub use self::{
+    mediate_deny::{MediateDeny, MediateDenyContent, MediateDenyDecorators},
+    mediate_grant::{MediateGrant, MediateGrantContent, MediateGrantDecorators},
+    mediate_request::{MediateRequest, MediateRequestContent, MediateRequestDecorators},
+    recipient::{Recipient, RecipientContent, RecipientDecorators},
+    recipient_query::{RecipientQuery, RecipientQueryContent, RecipientQueryDecorators},
+    recipient_update::{RecipientUpdate, RecipientUpdateContent, RecipientUpdateDecorators},
+    recipient_update_response::{
+        RecipientUpdateResponse, RecipientUpdateResponseContent, RecipientUpdateResponseDecorators,
+    },
+};
+use super::CoordinateMediation;
+use crate::{
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::coordinate_mediation::CoordinateMediationTypeV2_0, MsgKindType, MsgWithType,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum CoordinateMediationV2 {
+    MediateRequest(MediateRequest),
+    MediateDeny(MediateDeny),
+    MediateGrant(MediateGrant),
+    RecipientUpdate(RecipientUpdate),
+    RecipientUpdateResponse(RecipientUpdateResponse),
+    RecipientQuery(RecipientQuery),
+    Recipient(Recipient),
+}
+
+impl DelayedSerde for CoordinateMediationV2 {
+    type MsgType<'a> = (MsgKindType<CoordinateMediationTypeV2_0>, &'a str);
+
+    fn delayed_deserialize<'de, D>(
+        msg_type: Self::MsgType<'de>,
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+        let kind = protocol.kind_from_str(kind_str);
+
+        match kind.map_err(D::Error::custom)? {
+            CoordinateMediationTypeV2_0::MediateRequest => {
+                MediateRequest::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::MediateDeny => {
+                MediateDeny::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::MediateGrant => {
+                MediateGrant::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientUpdate => {
+                RecipientUpdate::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientUpdateResponse => {
+                RecipientUpdateResponse::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientQuery => {
+                RecipientQuery::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::Recipient => {
+                Recipient::deserialize(deserializer).map(From::from)
+            }
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::MediateRequest(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateDeny(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateGrant(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientUpdate(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientUpdateResponse(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientQuery(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Recipient(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+transit_to_aries_msg!(
+    MediateRequestContent: MediateRequestDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    MediateDenyContent: MediateDenyDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    MediateGrantContent: MediateGrantDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    RecipientUpdateContent: RecipientUpdateDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    RecipientUpdateResponseContent: RecipientUpdateResponseDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    RecipientQueryContent: RecipientQueryDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+transit_to_aries_msg!(
+    RecipientContent: RecipientDecorators,
+    CoordinateMediationV2, CoordinateMediation
+);
+
+into_msg_with_type!(MediateRequest, CoordinateMediationTypeV2_0, MediateRequest);
+into_msg_with_type!(MediateDeny, CoordinateMediationTypeV2_0, MediateDeny);
+into_msg_with_type!(MediateGrant, CoordinateMediationTypeV2_0, MediateGrant);
+into_msg_with_type!(
+    RecipientUpdate,
+    CoordinateMediationTypeV2_0,
+    RecipientUpdate
+);
+into_msg_with_type!(
+    RecipientUpdateResponse,
+    CoordinateMediationTypeV2_0,
+    RecipientUpdateResponse
+);
+into_msg_with_type!(RecipientQuery, CoordinateMediationTypeV2_0, RecipientQuery);
+into_msg_with_type!(Recipient, CoordinateMediationTypeV2_0, Recipient);
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use super::recipient_update::RecipientUpdateItemAction;
+use crate::{decorators::thread::Thread, msg_parts::MsgParts};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#recipient-update-response
+pub type RecipientUpdateResponse =
+    MsgParts<RecipientUpdateResponseContent, RecipientUpdateResponseDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseContent {
+    pub updated: Vec<RecipientUpdateResponseItem>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseItem {
+    pub recipient_did: String,
+    pub action: RecipientUpdateItemAction,
+    pub result: RecipientUpdateItemResult,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum RecipientUpdateItemResult {
+    #[serde(rename = "client_error")]
+    ClientError,
+    #[serde(rename = "server_error")]
+    ServerError,
+    #[serde(rename = "no_change")]
+    NoChange,
+    #[serde(rename = "success")]
+    Success,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_recipient_update_response() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/recipient-update-response",
+                "updated": [
+                    {
+                        "recipient_did": "did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc",
+                        "action": "add",
+                        "result": "success"
+                    }
+                ]
+            }
+        );
+        let update_item = RecipientUpdateResponseItem::builder()
+            .recipient_did(
+                "did:peer:2.Ez6LSbysY2xFMRpGMhb7tFTLMpeuPRaqaWM1yECx2AtzE3KCc".to_owned(),
+            )
+            .action(RecipientUpdateItemAction::Add)
+            .result(RecipientUpdateItemResult::Success)
+            .build();
+        let content = RecipientUpdateResponseContent::builder()
+            .updated(vec![update_item])
+            .build();
+        let decorators = RecipientUpdateResponseDecorators::builder().build();
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV2_0::RecipientUpdateResponse,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{attachment::Attachment, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Rotate = MsgParts<RotateContent, RotateDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RotateContent {
+    pub to_did: String,
+    /// The new DID, signed with a key of the DID being rotated away from.
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "to_did~attach")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_did_attach: Option<Attachment>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RotateDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, timing::tests::make_extended_timing,
+        },
+        misc::test_utils,
+        msg_types::protocols::did_rotate::DidRotateTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_rotate() {
+        let content = RotateContent::builder()
+            .to_did("did:peer:4zQmNew".to_owned())
+            .build();
+
+        let decorators = RotateDecorators::default();
+
+        let expected = json!({
+            "to_did": content.to_did,
+        });
+
+        test_utils::test_msg(content, decorators, DidRotateTypeV1_0::Rotate, expected);
+    }
+
+    #[test]
+    fn test_extended_rotate() {
+        let content = RotateContent::builder()
+            .to_did("did:peer:4zQmNew".to_owned())
+            .to_did_attach(make_extended_attachment())
+            .build();
+
+        let decorators = RotateDecorators::builder()
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "to_did": content.to_did,
+            "to_did~attach": content.to_did_attach,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, DidRotateTypeV1_0::Rotate, expected);
+    }
+}
@@ -1,7 +1,9 @@
 //! Module containing the `discover features` protocol messages, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0031-discover-features/README.md>).
+//! The 2.0 messages are found in [`v2`].
 
 pub mod disclose;
 pub mod query;
+pub mod v2;
 
 use derive_more::From;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
@@ -11,6 +13,10 @@ use typed_builder::TypedBuilder;
 use self::{
     disclose::{Disclose, DiscloseContent, DiscloseDecorators},
     query::{Query, QueryContent, QueryDecorators},
+    v2::{
+        disclosures::{Disclosures, DisclosuresContent, DisclosuresDecorators},
+        queries::{Queries, QueriesContent, QueriesDecorators},
+    },
 };
 use crate::{
     misc::utils::{into_msg_with_type, transit_to_aries_msg},
@@ -18,7 +24,7 @@ use crate::{
     msg_types::{
         protocols::discover_features::{
             DiscoverFeaturesType as DiscoverFeaturesKind, DiscoverFeaturesTypeV1,
-            DiscoverFeaturesTypeV1_0,
+            DiscoverFeaturesTypeV1_0, DiscoverFeaturesTypeV2, DiscoverFeaturesTypeV2_0,
         },
         MsgWithType, Protocol, Role,
     },
@@ -28,6 +34,8 @@ use crate::{
 pub enum DiscoverFeatures {
     Query(Query),
     Disclose(Disclose),
+    Queries(Queries),
+    Disclosures(Disclosures),
 }
 
 impl DelayedSerde for DiscoverFeatures {
@@ -42,16 +50,26 @@ impl DelayedSerde for DiscoverFeatures {
     {
         let (protocol, kind_str) = msg_type;
 
-        let kind = match protocol {
+        match protocol {
             DiscoverFeaturesKind::V1(DiscoverFeaturesTypeV1::V1_0(kind)) => {
-                kind.kind_from_str(kind_str)
+                match kind.kind_from_str(kind_str).map_err(D::Error::custom)? {
+                    DiscoverFeaturesTypeV1_0::Query => {
+                        Query::deserialize(deserializer).map(From::from)
+                    }
+                    DiscoverFeaturesTypeV1_0::Disclose => {
+                        Disclose::deserialize(deserializer).map(From::from)
+                    }
+                }
             }
-        };
-
-        match kind.map_err(D::Error::custom)? {
-            DiscoverFeaturesTypeV1_0::Query => Query::deserialize(deserializer).map(From::from),
-            DiscoverFeaturesTypeV1_0::Disclose => {
-                Disclose::deserialize(deserializer).map(From::from)
+            DiscoverFeaturesKind::V2(DiscoverFeaturesTypeV2::V2_0(kind)) => {
+                match kind.kind_from_str(kind_str).map_err(D::Error::custom)? {
+                    DiscoverFeaturesTypeV2_0::Queries => {
+                        Queries::deserialize(deserializer).map(From::from)
+                    }
+                    DiscoverFeaturesTypeV2_0::Disclosures => {
+                        Disclosures::deserialize(deserializer).map(From::from)
+                    }
+                }
             }
         }
     }
@@ -63,6 +81,8 @@ impl DelayedSerde for DiscoverFeatures {
         match self {
             Self::Query(v) => MsgWithType::from(v).serialize(serializer),
             Self::Disclose(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Queries(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Disclosures(v) => MsgWithType::from(v).serialize(serializer),
         }
     }
 }
@@ -77,6 +97,10 @@ pub struct ProtocolDescriptor {
 
 transit_to_aries_msg!(QueryContent: QueryDecorators, DiscoverFeatures);
 transit_to_aries_msg!(DiscloseContent: DiscloseDecorators, DiscoverFeatures);
+transit_to_aries_msg!(QueriesContent: QueriesDecorators, DiscoverFeatures);
+transit_to_aries_msg!(DisclosuresContent: DisclosuresDecorators, DiscoverFeatures);
 
 into_msg_with_type!(Query, DiscoverFeaturesTypeV1_0, Query);
 into_msg_with_type!(Disclose, DiscoverFeaturesTypeV1_0, Disclose);
+into_msg_with_type!(Queries, DiscoverFeaturesTypeV2_0, Queries);
+into_msg_with_type!(Disclosures, DiscoverFeaturesTypeV2_0, Disclosures);
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use super::FeatureDisclosure;
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Disclosures = MsgParts<DisclosuresContent, DisclosuresDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct DisclosuresContent {
+    pub disclosures: Vec<FeatureDisclosure>,
+}
+
+/// Disclosures can be sent without being queried, in which case they are not threaded.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct DisclosuresDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared::maybe_known::MaybeKnown;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_fields::protocols::discover_features::v2::FeatureType,
+        msg_types::{discover_features::DiscoverFeaturesTypeV2_0, Role},
+    };
+
+    #[test]
+    fn test_minimal_disclosures() {
+        let content = DisclosuresContent::builder().disclosures(vec![]).build();
+
+        let decorators = DisclosuresDecorators::default();
+
+        let expected = json!({
+            "disclosures": []
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            DiscoverFeaturesTypeV2_0::Disclosures,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_disclosures() {
+        let disclosures = vec![
+            FeatureDisclosure::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::Protocol))
+                .id("https://didcomm.org/discover-features/2.0".to_owned())
+                .roles(vec![
+                    MaybeKnown::Known(Role::Requester),
+                    MaybeKnown::Known(Role::Responder),
+                ])
+                .build(),
+            FeatureDisclosure::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::GoalCode))
+                .id("org.didcomm.sell.goods.consumer".to_owned())
+                .build(),
+        ];
+        let content = DisclosuresContent::builder()
+            .disclosures(disclosures)
+            .build();
+
+        let decorators = DisclosuresDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "disclosures": [
+                {
+                    "feature-type": "protocol",
+                    "id": "https://didcomm.org/discover-features/2.0",
+                    "roles": ["requester", "responder"]
+                },
+                { "feature-type": "goal-code", "id": "org.didcomm.sell.goods.consumer" }
+            ],
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            DiscoverFeaturesTypeV2_0::Disclosures,
+            expected,
+        );
+    }
+}
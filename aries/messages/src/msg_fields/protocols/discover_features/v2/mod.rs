@@ -0,0 +1,37 @@
+//! Module containing the `discover features` 2.0 protocol messages, as defined in the [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0557-discover-features-v2/README.md>).
+
+pub mod disclosures;
+pub mod queries;
+
+use serde::{Deserialize, Serialize};
+use shared::maybe_known::MaybeKnown;
+use typed_builder::TypedBuilder;
+
+use crate::msg_types::Role;
+
+/// The kinds of features that can be queried and disclosed.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum FeatureType {
+    Protocol,
+    GoalCode,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct FeatureQuery {
+    #[serde(rename = "feature-type")]
+    pub feature_type: MaybeKnown<FeatureType>,
+    /// The identifiers to match, where `*` matches any sequence of characters.
+    #[serde(rename = "match")]
+    pub pattern: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct FeatureDisclosure {
+    #[serde(rename = "feature-type")]
+    pub feature_type: MaybeKnown<FeatureType>,
+    pub id: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<MaybeKnown<Role>>>,
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use super::FeatureQuery;
+use crate::{decorators::timing::Timing, msg_parts::MsgParts};
+
+pub type Queries = MsgParts<QueriesContent, QueriesDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct QueriesContent {
+    pub queries: Vec<FeatureQuery>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct QueriesDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared::maybe_known::MaybeKnown;
+
+    use super::*;
+    use crate::{
+        decorators::timing::tests::make_extended_timing, misc::test_utils,
+        msg_fields::protocols::discover_features::v2::FeatureType,
+        msg_types::discover_features::DiscoverFeaturesTypeV2_0,
+    };
+
+    #[test]
+    fn test_minimal_queries() {
+        let query = FeatureQuery::builder()
+            .feature_type(MaybeKnown::Known(FeatureType::Protocol))
+            .pattern("https://didcomm.org/tictactoe/1.*".to_owned())
+            .build();
+        let content = QueriesContent::builder().queries(vec![query]).build();
+
+        let decorators = QueriesDecorators::default();
+
+        let expected = json!({
+            "queries": [
+                { "feature-type": "protocol", "match": "https://didcomm.org/tictactoe/1.*" }
+            ]
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            DiscoverFeaturesTypeV2_0::Queries,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_queries() {
+        let queries = vec![
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Known(FeatureType::GoalCode))
+                .pattern("org.didcomm.*".to_owned())
+                .build(),
+            FeatureQuery::builder()
+                .feature_type(MaybeKnown::Unknown("gov-fw".to_owned()))
+                .pattern("*".to_owned())
+                .build(),
+        ];
+        let content = QueriesContent::builder().queries(queries).build();
+
+        let decorators = QueriesDecorators::builder()
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "queries": [
+                { "feature-type": "goal-code", "match": "org.didcomm.*" },
+                { "feature-type": "gov-fw", "match": "*" }
+            ],
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            DiscoverFeaturesTypeV2_0::Queries,
+            expected,
+        );
+    }
+}
@@ -1,14 +1,17 @@
+pub mod action_menu;
 pub mod basic_message;
 pub mod common;
 pub mod connection;
 pub mod coordinate_mediation;
 pub mod cred_issuance;
 pub mod did_exchange;
+pub mod did_rotate;
 pub mod discover_features;
 pub mod notification;
 pub mod out_of_band;
 pub mod pickup;
 pub mod present_proof;
+pub mod question_answer;
 pub mod report_problem;
 pub mod revocation;
 pub mod routing;
@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 use crate::{
-    decorators::{attachment::Attachment, please_ack::PleaseAck, thread::Thread, timing::Timing},
+    decorators::{
+        attachment::Attachment, please_ack::PleaseAck, service::Service, thread::Thread,
+        timing::Timing,
+    },
     msg_parts::MsgParts,
 };
 
@@ -29,6 +32,10 @@ pub struct PresentationV1Decorators {
     #[serde(rename = "~timing")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Timing>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~service")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<Service>,
 }
 
 #[cfg(test)]
@@ -40,8 +47,8 @@ mod tests {
     use crate::{
         decorators::{
             attachment::tests::make_extended_attachment,
-            please_ack::tests::make_minimal_please_ack, thread::tests::make_extended_thread,
-            timing::tests::make_extended_timing,
+            please_ack::tests::make_minimal_please_ack, service::tests::make_extended_service,
+            thread::tests::make_extended_thread, timing::tests::make_extended_timing,
         },
         misc::test_utils,
         msg_types::present_proof::PresentProofTypeV1_0,
@@ -81,6 +88,7 @@ mod tests {
             .thread(make_extended_thread())
             .timing(make_extended_timing())
             .please_ack(make_minimal_please_ack())
+            .service(make_extended_service())
             .build();
 
         let expected = json!({
@@ -88,7 +96,8 @@ mod tests {
             "presentations~attach": content.presentations_attach,
             "~thread": decorators.thread,
             "~timing": decorators.timing,
-            "~please_ack": decorators.please_ack
+            "~please_ack": decorators.please_ack,
+            "~service": decorators.service
         });
 
         test_utils::test_msg(
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::connection::response::ConnectionSignature,
+    msg_parts::MsgParts,
+};
+
+/// Answers the question it is threaded to with one of its valid responses. The response is
+/// signed if the question required so.
+pub type Answer = MsgParts<AnswerContent, AnswerDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct AnswerContent {
+    pub response: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "response~sig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_sig: Option<ConnectionSignature>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct AnswerDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::protocols::question_answer::QuestionAnswerTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_answer() {
+        let content = AnswerContent::builder().response("Yes".to_owned()).build();
+        let decorators = AnswerDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "response": content.response,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Answer,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_answer() {
+        let response_sig = ConnectionSignature::new(
+            "test_signature".to_owned(),
+            "test_sig_data".to_owned(),
+            "test_signer".to_owned(),
+        );
+        let content = AnswerContent::builder()
+            .response("Yes, it's me".to_owned())
+            .response_sig(response_sig)
+            .build();
+        let decorators = AnswerDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "response": content.response,
+            "response~sig": content.response_sig,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Answer,
+            expected,
+        );
+    }
+}
@@ -10,6 +10,7 @@ use crate::msg_types::{role::Role, MsgKindType};
 #[msg_type(protocol = "discover-features")]
 pub enum DiscoverFeaturesType {
     V1(DiscoverFeaturesTypeV1),
+    V2(DiscoverFeaturesTypeV2),
 }
 
 #[derive(Copy, Clone, Debug, From, PartialEq, Transitive, MessageType)]
@@ -20,6 +21,14 @@ pub enum DiscoverFeaturesTypeV1 {
     V1_0(MsgKindType<DiscoverFeaturesTypeV1_0>),
 }
 
+#[derive(Copy, Clone, Debug, From, PartialEq, Transitive, MessageType)]
+#[transitive(into(DiscoverFeaturesType, Protocol))]
+#[msg_type(major = 2)]
+pub enum DiscoverFeaturesTypeV2 {
+    #[msg_type(minor = 0, roles = "Role::Requester, Role::Responder")]
+    V2_0(MsgKindType<DiscoverFeaturesTypeV2_0>),
+}
+
 #[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
 #[strum(serialize_all = "kebab-case")]
 pub enum DiscoverFeaturesTypeV1_0 {
@@ -27,6 +36,13 @@ pub enum DiscoverFeaturesTypeV1_0 {
     Disclose,
 }
 
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DiscoverFeaturesTypeV2_0 {
+    Queries,
+    Disclosures,
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -50,12 +66,28 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_protocol_discover_features_v2() {
+        test_utils::test_serde(
+            Protocol::from(DiscoverFeaturesTypeV2::new_v2_0()),
+            json!("https://didcomm.org/discover-features/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_discover_features_v2() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/discover-features/2.255",
+            DiscoverFeaturesTypeV2::new_v2_0(),
+        )
+    }
+
     #[test]
     #[should_panic]
     fn test_unsupported_version_discover_features() {
         test_utils::test_serde(
             Protocol::from(DiscoverFeaturesTypeV1::new_v1_0()),
-            json!("https://didcomm.org/discover-features/2.0"),
+            json!("https://didcomm.org/discover-features/3.0"),
         )
     }
 
@@ -76,4 +108,22 @@ mod tests {
             DiscoverFeaturesTypeV1::new_v1_0(),
         )
     }
+
+    #[test]
+    fn test_msg_type_queries() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/discover-features/2.0",
+            "queries",
+            DiscoverFeaturesTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_disclosures() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/discover-features/2.0",
+            "disclosures",
+            DiscoverFeaturesTypeV2::new_v2_0(),
+        )
+    }
 }
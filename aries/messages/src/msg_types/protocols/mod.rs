@@ -5,28 +5,33 @@ use serde::{Deserialize, Serialize};
 use shared::misc::utils::CowStr;
 
 use self::{
-    basic_message::BasicMessageType, connection::ConnectionType,
+    action_menu::ActionMenuType, basic_message::BasicMessageType, connection::ConnectionType,
     coordinate_mediation::CoordinateMediationType, cred_issuance::CredentialIssuanceType,
-    did_exchange::DidExchangeType, discover_features::DiscoverFeaturesType,
-    notification::NotificationType, out_of_band::OutOfBandType, pickup::PickupType,
-    present_proof::PresentProofType, report_problem::ReportProblemType, revocation::RevocationType,
-    routing::RoutingType, signature::SignatureType, trust_ping::TrustPingType,
+    did_exchange::DidExchangeType, did_rotate::DidRotateType,
+    discover_features::DiscoverFeaturesType, notification::NotificationType,
+    out_of_band::OutOfBandType, pickup::PickupType, present_proof::PresentProofType,
+    question_answer::QuestionAnswerType, report_problem::ReportProblemType,
+    revocation::RevocationType, routing::RoutingType, signature::SignatureType,
+    trust_ping::TrustPingType,
 };
 use crate::{
     error::{MsgTypeError, MsgTypeResult},
     msg_types::traits::ProtocolName,
 };
 
+pub mod action_menu;
 pub mod basic_message;
 pub mod connection;
 pub mod coordinate_mediation;
 pub mod cred_issuance;
 pub mod did_exchange;
+pub mod did_rotate;
 pub mod discover_features;
 pub mod notification;
 pub mod out_of_band;
 pub mod pickup;
 pub mod present_proof;
+pub mod question_answer;
 pub mod report_problem;
 pub mod revocation;
 pub mod routing;
@@ -65,6 +70,9 @@ pub enum Protocol {
     PickupType(PickupType),
     CoordinateMediationType(CoordinateMediationType),
     DidExchangeType(DidExchangeType),
+    DidRotateType(DidRotateType),
+    ActionMenuType(ActionMenuType),
+    QuestionAnswerType(QuestionAnswerType),
 }
 
 /// Utility macro to avoid harder to read and error prone calling
@@ -103,6 +111,9 @@ impl Protocol {
         match_protocol!(PickupType, protocol, major, minor);
         match_protocol!(CoordinateMediationType, protocol, major, minor);
         match_protocol!(DidExchangeType, protocol, major, minor);
+        match_protocol!(DidRotateType, protocol, major, minor);
+        match_protocol!(ActionMenuType, protocol, major, minor);
+        match_protocol!(QuestionAnswerType, protocol, major, minor);
 
         Err(MsgTypeError::unknown_protocol(protocol.to_owned()))
     }
@@ -125,6 +136,9 @@ impl Protocol {
             Self::PickupType(v) => v.as_protocol_parts(),
             Self::CoordinateMediationType(v) => v.as_protocol_parts(),
             Self::DidExchangeType(v) => v.as_protocol_parts(),
+            Self::DidRotateType(v) => v.as_protocol_parts(),
+            Self::ActionMenuType(v) => v.as_protocol_parts(),
+            Self::QuestionAnswerType(v) => v.as_protocol_parts(),
         }
     }
 
@@ -10,6 +10,7 @@ use crate::msg_types::{MsgKindType, Role};
 #[msg_type(protocol = "messagepickup")]
 pub enum PickupType {
     V2(PickupTypeV2),
+    V3(PickupTypeV3),
 }
 
 #[derive(Copy, Clone, Debug, From, TryInto, PartialEq, Transitive, MessageType)]
@@ -20,6 +21,14 @@ pub enum PickupTypeV2 {
     V2_0(MsgKindType<PickupTypeV2_0>),
 }
 
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, Transitive, MessageType)]
+#[transitive(into(PickupType, Protocol))]
+#[msg_type(major = 3)]
+pub enum PickupTypeV3 {
+    #[msg_type(minor = 0, roles = "Role::Mediator, Role::Recipient")]
+    V3_0(MsgKindType<PickupTypeV3_0>),
+}
+
 #[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
 #[strum(serialize_all = "kebab-case")]
 pub enum PickupTypeV2_0 {
@@ -30,3 +39,99 @@ pub enum PickupTypeV2_0 {
     MessagesReceived,
     LiveDeliveryChange,
 }
+
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum PickupTypeV3_0 {
+    Status,
+    StatusRequest,
+    DeliveryRequest,
+    Delivery,
+    MessagesReceived,
+    LiveDeliveryChange,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    #[test]
+    fn test_protocol_pickup_v2() {
+        test_utils::test_serde(
+            Protocol::from(PickupTypeV2::new_v2_0()),
+            json!("https://didcomm.org/messagepickup/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_pickup_v2() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/messagepickup/2.255",
+            PickupTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_status_request_v2() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/messagepickup/2.0",
+            "status-request",
+            PickupTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_protocol_pickup_v3() {
+        test_utils::test_serde(
+            Protocol::from(PickupTypeV3::new_v3_0()),
+            json!("https://didcomm.org/messagepickup/3.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_pickup_v3() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/messagepickup/3.255",
+            PickupTypeV3::new_v3_0(),
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_version_pickup_v3() {
+        test_utils::test_serde(
+            Protocol::from(PickupTypeV3::new_v3_0()),
+            json!("https://didcomm.org/messagepickup/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_status_request_v3() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/messagepickup/3.0",
+            "status-request",
+            PickupTypeV3::new_v3_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_delivery_v3() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/messagepickup/3.0",
+            "delivery",
+            PickupTypeV3::new_v3_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_live_delivery_change_v3() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/messagepickup/3.0",
+            "live-delivery-change",
+            PickupTypeV3::new_v3_0(),
+        )
+    }
+}
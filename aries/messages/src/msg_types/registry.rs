@@ -7,16 +7,19 @@ use super::{role::Role, Protocol};
 use crate::msg_types::{
     present_proof::PresentProofTypeV2,
     protocols::{
+        action_menu::ActionMenuTypeV1,
         basic_message::BasicMessageTypeV1,
         connection::ConnectionTypeV1,
-        coordinate_mediation::CoordinateMediationTypeV1,
+        coordinate_mediation::{CoordinateMediationTypeV1, CoordinateMediationTypeV2},
         cred_issuance::{CredentialIssuanceTypeV1, CredentialIssuanceTypeV2},
         did_exchange::DidExchangeTypeV1,
-        discover_features::DiscoverFeaturesTypeV1,
+        did_rotate::DidRotateTypeV1,
+        discover_features::{DiscoverFeaturesTypeV1, DiscoverFeaturesTypeV2},
         notification::NotificationTypeV1,
         out_of_band::OutOfBandTypeV1,
-        pickup::PickupTypeV2,
+        pickup::{PickupTypeV2, PickupTypeV3},
         present_proof::PresentProofTypeV1,
+        question_answer::QuestionAnswerTypeV1,
         report_problem::ReportProblemTypeV1,
         revocation::RevocationTypeV2,
         routing::RoutingTypeV1,
@@ -87,6 +90,7 @@ lazy_static! {
         map_insert(&mut m, extract_parts!(CredentialIssuanceTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(CredentialIssuanceTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(DiscoverFeaturesTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(DiscoverFeaturesTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(NotificationTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(OutOfBandTypeV1::new_v1_1()));
         map_insert(&mut m, extract_parts!(PresentProofTypeV1::new_v1_0()));
@@ -95,9 +99,14 @@ lazy_static! {
         map_insert(&mut m, extract_parts!(RevocationTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(TrustPingTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(PickupTypeV2::new_v2_0()));
+        map_insert(&mut m, extract_parts!(PickupTypeV3::new_v3_0()));
         map_insert(&mut m, extract_parts!(CoordinateMediationTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(CoordinateMediationTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_1()));
+        map_insert(&mut m, extract_parts!(DidRotateTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(ActionMenuTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(QuestionAnswerTypeV1::new_v1_0()));
         m
     };
 }
@@ -19,4 +19,9 @@ pub enum Role {
     Notifier,
     Mediator,
     Recipient,
+    Questioner,
+    #[serde(rename = "rotating_party")]
+    RotatingParty,
+    #[serde(rename = "observing_party")]
+    ObservingParty,
 }
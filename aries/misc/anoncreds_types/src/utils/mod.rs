@@ -1,4 +1,5 @@
 /// VCX utility additon
 pub mod conversions;
+pub mod pres_request_evaluation;
 pub mod query;
 pub mod validation;
@@ -0,0 +1,565 @@
+//! Pure-Rust evaluation of presentation request referents against credential metadata.
+//!
+//! Unlike the matching performed internally by the anoncreds library, the evaluation here
+//! reports *why* a credential does or does not satisfy a referent, which allows verifiers to
+//! pre-validate their requests and holders to explain why a request can't be fulfilled.
+
+use std::collections::HashMap;
+
+use crate::{
+    data_types::messages::{
+        cred_selection::RetrievedCredentialInfo,
+        pres_request::{
+            AttributeInfo, NonRevokedInterval, PredicateInfo, PredicateTypes, PredicateValue,
+            PresentationRequestPayload,
+        },
+    },
+    utils::{
+        query::Query,
+        validation::{LEGACY_CRED_DEF_IDENTIFIER, LEGACY_SCHEMA_IDENTIFIER},
+    },
+};
+
+/// Reason why a credential does not satisfy a presentation request referent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum EvaluationFailure {
+    /// The credential does not contain the requested attribute.
+    MissingAttribute { name: String },
+    /// The credential does not match the given restriction clause of the referent.
+    RestrictionMismatch { restriction: Query },
+    /// The value of the credential attribute can not be used in a predicate, as it is not an
+    /// integer.
+    PredicateValueNotNumeric { name: String, value: String },
+    /// The value of the credential attribute does not satisfy the predicate.
+    PredicateNotSatisfied {
+        name: String,
+        value: PredicateValue,
+        p_type: PredicateTypes,
+        p_value: PredicateValue,
+    },
+    /// The credential was revoked before non-revocation could be proven within the requested
+    /// interval.
+    RevokedWithinInterval {
+        revoked_at: u64,
+        interval: NonRevokedInterval,
+    },
+}
+
+/// Evaluation of a single credential against a single referent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialEvaluation {
+    /// The unique identifier of the credential in the wallet
+    pub referent: String,
+    /// Every reason why the credential does not satisfy the referent. Empty if it does.
+    pub failures: Vec<EvaluationFailure>,
+}
+
+impl CredentialEvaluation {
+    pub fn is_satisfied(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Evaluation of all the given credentials against a single referent.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ReferentEvaluation {
+    pub credentials: Vec<CredentialEvaluation>,
+}
+
+impl ReferentEvaluation {
+    pub fn is_satisfied(&self) -> bool {
+        self.credentials
+            .iter()
+            .any(CredentialEvaluation::is_satisfied)
+    }
+
+    /// Identifiers of the credentials which can be used to satisfy the referent.
+    pub fn satisfying_credentials(&self) -> impl Iterator<Item = &str> {
+        self.credentials
+            .iter()
+            .filter(|cred| cred.is_satisfied())
+            .map(|cred| cred.referent.as_str())
+    }
+}
+
+/// Evaluation of all the given credentials against every referent of a presentation request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct PresentationRequestEvaluation {
+    pub requested_attributes: HashMap<String, ReferentEvaluation>,
+    pub requested_predicates: HashMap<String, ReferentEvaluation>,
+}
+
+impl PresentationRequestEvaluation {
+    /// Whether every referent of the presentation request is satisfied by at least one
+    /// credential.
+    pub fn is_satisfied(&self) -> bool {
+        self.requested_attributes
+            .values()
+            .chain(self.requested_predicates.values())
+            .all(ReferentEvaluation::is_satisfied)
+    }
+
+    /// Referents (attribute and predicate) which no credential satisfies.
+    pub fn unsatisfied_referents(&self) -> impl Iterator<Item = &str> {
+        self.requested_attributes
+            .iter()
+            .chain(self.requested_predicates.iter())
+            .filter(|(_, evaluation)| !evaluation.is_satisfied())
+            .map(|(referent, _)| referent.as_str())
+    }
+}
+
+/// Evaluates every credential in `credentials` against every referent of the presentation
+/// `request`.
+///
+/// `revoked_at` maps credential referents (wallet ids) to the epoch second timestamp at which the
+/// credential is known to have been revoked. Credentials missing from the map are considered not
+/// revoked.
+pub fn evaluate_presentation_request(
+    request: &PresentationRequestPayload,
+    credentials: &[RetrievedCredentialInfo],
+    revoked_at: &HashMap<String, u64>,
+) -> PresentationRequestEvaluation {
+    let global_interval = request.non_revoked.as_ref();
+
+    let requested_attributes = request
+        .requested_attributes
+        .iter()
+        .map(|(referent, attr_info)| {
+            let evaluation = ReferentEvaluation {
+                credentials: credentials
+                    .iter()
+                    .map(|cred| evaluate_attribute(attr_info, global_interval, cred, revoked_at))
+                    .collect(),
+            };
+            (referent.clone(), evaluation)
+        })
+        .collect();
+
+    let requested_predicates = request
+        .requested_predicates
+        .iter()
+        .map(|(referent, predicate_info)| {
+            let evaluation = ReferentEvaluation {
+                credentials: credentials
+                    .iter()
+                    .map(|cred| {
+                        evaluate_predicate(predicate_info, global_interval, cred, revoked_at)
+                    })
+                    .collect(),
+            };
+            (referent.clone(), evaluation)
+        })
+        .collect();
+
+    PresentationRequestEvaluation {
+        requested_attributes,
+        requested_predicates,
+    }
+}
+
+/// Evaluates a single credential against a requested attribute referent. `global_interval` is
+/// the request level non-revoked interval, which is used if the referent does not specify its
+/// own.
+pub fn evaluate_attribute(
+    attr_info: &AttributeInfo,
+    global_interval: Option<&NonRevokedInterval>,
+    cred: &RetrievedCredentialInfo,
+    revoked_at: &HashMap<String, u64>,
+) -> CredentialEvaluation {
+    let mut failures = Vec::new();
+
+    let names = attr_info
+        .name
+        .iter()
+        .chain(attr_info.names.iter().flatten());
+    for name in names {
+        if attribute_value(cred, name).is_none() {
+            failures.push(EvaluationFailure::MissingAttribute { name: name.clone() });
+        }
+    }
+    if let Some(restrictions) = &attr_info.restrictions {
+        failures.extend(restriction_failures(restrictions, cred));
+    }
+    let interval = attr_info.non_revoked.as_ref().or(global_interval);
+    failures.extend(revocation_failure(interval, cred, revoked_at));
+
+    CredentialEvaluation {
+        referent: cred.referent.clone(),
+        failures,
+    }
+}
+
+/// Evaluates a single credential against a requested predicate referent. `global_interval` is
+/// the request level non-revoked interval, which is used if the referent does not specify its
+/// own.
+pub fn evaluate_predicate(
+    predicate_info: &PredicateInfo,
+    global_interval: Option<&NonRevokedInterval>,
+    cred: &RetrievedCredentialInfo,
+    revoked_at: &HashMap<String, u64>,
+) -> CredentialEvaluation {
+    let mut failures = Vec::new();
+
+    let name = &predicate_info.name;
+    match attribute_value(cred, name) {
+        None => failures.push(EvaluationFailure::MissingAttribute { name: name.clone() }),
+        Some(raw_value) => match raw_value.parse::<PredicateValue>() {
+            Err(_) => failures.push(EvaluationFailure::PredicateValueNotNumeric {
+                name: name.clone(),
+                value: raw_value.to_owned(),
+            }),
+            Ok(value)
+                if !predicate_holds(&predicate_info.p_type, value, predicate_info.p_value) =>
+            {
+                failures.push(EvaluationFailure::PredicateNotSatisfied {
+                    name: name.clone(),
+                    value,
+                    p_type: predicate_info.p_type.clone(),
+                    p_value: predicate_info.p_value,
+                })
+            }
+            Ok(_) => {}
+        },
+    }
+    if let Some(restrictions) = &predicate_info.restrictions {
+        failures.extend(restriction_failures(restrictions, cred));
+    }
+    let interval = predicate_info.non_revoked.as_ref().or(global_interval);
+    failures.extend(revocation_failure(interval, cred, revoked_at));
+
+    CredentialEvaluation {
+        referent: cred.referent.clone(),
+        failures,
+    }
+}
+
+/// Whether the credential matches the `restrictions` WQL query, in the same way as the wallet
+/// would match the tags of the stored credential.
+pub fn matches_restrictions(restrictions: &Query, cred: &RetrievedCredentialInfo) -> bool {
+    match restrictions {
+        Query::And(subqueries) => subqueries.iter().all(|q| matches_restrictions(q, cred)),
+        Query::Or(subqueries) => subqueries.iter().any(|q| matches_restrictions(q, cred)),
+        Query::Not(subquery) => !matches_restrictions(subquery, cred),
+        Query::Eq(name, value) => tag_value(cred, name).is_some_and(|tag| tag == *value),
+        Query::Neq(name, value) => tag_value(cred, name).is_some_and(|tag| tag != *value),
+        Query::Gt(name, value) => tag_value(cred, name).is_some_and(|tag| tag > *value),
+        Query::Gte(name, value) => tag_value(cred, name).is_some_and(|tag| tag >= *value),
+        Query::Lt(name, value) => tag_value(cred, name).is_some_and(|tag| tag < *value),
+        Query::Lte(name, value) => tag_value(cred, name).is_some_and(|tag| tag <= *value),
+        Query::Like(name, pattern) => {
+            tag_value(cred, name).is_some_and(|tag| like_matches(pattern, &tag))
+        }
+        Query::In(name, values) => tag_value(cred, name).is_some_and(|tag| values.contains(&tag)),
+        Query::Exist(names) => names.iter().all(|name| tag_value(cred, name).is_some()),
+    }
+}
+
+// Top level conjunctions are reported clause by clause, any other restriction is reported as a
+// whole.
+fn restriction_failures(
+    restrictions: &Query,
+    cred: &RetrievedCredentialInfo,
+) -> Vec<EvaluationFailure> {
+    let clauses = match restrictions {
+        Query::And(subqueries) => subqueries.iter().collect(),
+        other => vec![other],
+    };
+    clauses
+        .into_iter()
+        .filter(|clause| !matches_restrictions(clause, cred))
+        .map(|clause| EvaluationFailure::RestrictionMismatch {
+            restriction: clause.clone(),
+        })
+        .collect()
+}
+
+// Non-revocation has to be proven at some point within the interval, so a credential can only
+// satisfy the interval if it was revoked after the earliest acceptable timestamp.
+fn revocation_failure(
+    interval: Option<&NonRevokedInterval>,
+    cred: &RetrievedCredentialInfo,
+    revoked_at: &HashMap<String, u64>,
+) -> Option<EvaluationFailure> {
+    let interval = interval?;
+    cred.rev_reg_id.as_ref()?;
+    let revoked_at = *revoked_at.get(&cred.referent)?;
+    let earliest = interval.from.or(interval.to).unwrap_or(u64::MAX);
+    (revoked_at <= earliest).then(|| EvaluationFailure::RevokedWithinInterval {
+        revoked_at,
+        interval: interval.clone(),
+    })
+}
+
+fn predicate_holds(
+    p_type: &PredicateTypes,
+    value: PredicateValue,
+    p_value: PredicateValue,
+) -> bool {
+    match p_type {
+        PredicateTypes::GE => value >= p_value,
+        PredicateTypes::GT => value > p_value,
+        PredicateTypes::LE => value <= p_value,
+        PredicateTypes::LT => value < p_value,
+    }
+}
+
+// attribute name, (case insensitive and ignore spaces)
+fn normalize_attr_name(name: &str) -> String {
+    name.replace(' ', "").to_lowercase()
+}
+
+fn attribute_value<'a>(cred: &'a RetrievedCredentialInfo, name: &str) -> Option<&'a str> {
+    let name = normalize_attr_name(name);
+    cred.attributes
+        .iter()
+        .find(|(attr_name, _)| normalize_attr_name(attr_name) == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Resolves the wallet tag with the given name, as it would be stored alongside the credential.
+fn tag_value(cred: &RetrievedCredentialInfo, name: &str) -> Option<String> {
+    if let Some(attr) = name.strip_prefix("attr::") {
+        if let Some(attr_name) = attr.strip_suffix("::value") {
+            return attribute_value(cred, attr_name).map(ToOwned::to_owned);
+        }
+        if let Some(attr_name) = attr.strip_suffix("::marker") {
+            return attribute_value(cred, attr_name).map(|_| "1".to_owned());
+        }
+        return None;
+    }
+    match name {
+        "schema_id" => Some(cred.schema_id.0.clone()),
+        "cred_def_id" => Some(cred.cred_def_id.0.clone()),
+        "rev_reg_id" => cred.rev_reg_id.clone(),
+        "issuer_did" | "issuer_id" => legacy_cred_def_issuer(&cred.cred_def_id.0),
+        "schema_issuer_did" | "schema_issuer_id" => legacy_schema_id_part(&cred.schema_id.0, 0),
+        "schema_name" => legacy_schema_id_part(&cred.schema_id.0, 2),
+        "schema_version" => legacy_schema_id_part(&cred.schema_id.0, 3),
+        _ => None,
+    }
+}
+
+// Legacy schema ids are formatted as `<issuer did>:2:<name>:<version>`, where the name may
+// itself contain colons.
+fn legacy_schema_id_part(schema_id: &str, index: usize) -> Option<String> {
+    if !LEGACY_SCHEMA_IDENTIFIER.is_match(schema_id) {
+        return None;
+    }
+    let (issuer_did, rest) = schema_id.split_once(":2:")?;
+    let (name, version) = rest.rsplit_once(':')?;
+    match index {
+        0 => Some(issuer_did.to_owned()),
+        2 => Some(name.to_owned()),
+        3 => Some(version.to_owned()),
+        _ => None,
+    }
+}
+
+// Legacy credential definition ids are formatted as `<issuer did>:3:CL:<schema ref>:<tag>`.
+fn legacy_cred_def_issuer(cred_def_id: &str) -> Option<String> {
+    if !LEGACY_CRED_DEF_IDENTIFIER.is_match(cred_def_id) {
+        return None;
+    }
+    cred_def_id.split(':').next().map(ToOwned::to_owned)
+}
+
+// SQL 'LIKE' matching, where `%` matches any sequence of characters and `_` matches any single
+// character.
+fn like_matches(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    // matches[j] - whether the pattern processed so far matches value[..j]
+    let mut matches = vec![false; value.len() + 1];
+    matches[0] = true;
+    for p in pattern {
+        let mut next = vec![false; value.len() + 1];
+        for j in 0..=value.len() {
+            next[j] = match p {
+                '%' => matches[j] || (j > 0 && next[j - 1]),
+                '_' => j > 0 && matches[j - 1],
+                c => j > 0 && matches[j - 1] && value[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+    matches[value.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::identifiers::{
+        cred_def_id::CredentialDefinitionId, schema_id::SchemaId,
+    };
+
+    const SCHEMA_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:2:address:1.0";
+    const CRED_DEF_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:3:CL:12:tag1";
+    const REV_REG_ID: &str = "V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7PBUe6f:3:CL:12:tag1:\
+                              CL_ACCUM:tag1";
+
+    fn credential(referent: &str, attrs: &[(&str, &str)]) -> RetrievedCredentialInfo {
+        RetrievedCredentialInfo {
+            referent: referent.to_owned(),
+            attributes: attrs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            schema_id: SchemaId::new_unchecked(SCHEMA_ID),
+            cred_def_id: CredentialDefinitionId::new_unchecked(CRED_DEF_ID),
+            rev_reg_id: Some(REV_REG_ID.to_owned()),
+            cred_rev_id: Some(1),
+        }
+    }
+
+    fn request(value: serde_json::Value) -> PresentationRequestPayload {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_attribute_satisfied() {
+        let req = request(json!({
+            "nonce": "123",
+            "name": "req",
+            "requested_attributes": {
+                "address": {
+                    "names": ["Street", "city"],
+                    "restrictions": {
+                        "schema_name": "address",
+                        "issuer_did": "V4SGRU86Z58d6TV7PBUe6f"
+                    }
+                }
+            }
+        }));
+        let cred = credential("cred1", &[("street", "Main"), ("city", "Prague")]);
+
+        let evaluation = evaluate_presentation_request(&req, &[cred], &HashMap::new());
+
+        assert!(evaluation.is_satisfied());
+        let address = &evaluation.requested_attributes["address"];
+        assert_eq!(
+            address.satisfying_credentials().collect::<Vec<_>>(),
+            ["cred1"]
+        );
+    }
+
+    #[test]
+    fn test_attribute_missing_and_restriction_mismatch() {
+        let req = request(json!({
+            "nonce": "123",
+            "name": "req",
+            "requested_attributes": {
+                "zip": {
+                    "name": "zip",
+                    "restrictions": { "schema_version": "2.0", "cred_def_id": CRED_DEF_ID }
+                }
+            }
+        }));
+        let cred = credential("cred1", &[("street", "Main")]);
+
+        let evaluation = evaluate_presentation_request(&req, &[cred], &HashMap::new());
+
+        assert!(!evaluation.is_satisfied());
+        assert_eq!(
+            evaluation.unsatisfied_referents().collect::<Vec<_>>(),
+            ["zip"]
+        );
+        assert_eq!(
+            evaluation.requested_attributes["zip"].credentials[0].failures,
+            vec![
+                EvaluationFailure::MissingAttribute {
+                    name: "zip".to_owned()
+                },
+                EvaluationFailure::RestrictionMismatch {
+                    restriction: Query::Eq("schema_version".to_owned(), "2.0".to_owned())
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_predicate_evaluation() {
+        let req = request(json!({
+            "nonce": "123",
+            "name": "req",
+            "requested_predicates": {
+                "adult": { "name": "age", "p_type": ">=", "p_value": 18 }
+            }
+        }));
+        let creds = [
+            credential("adult", &[("age", "30")]),
+            credential("minor", &[("age", "12")]),
+            credential("unknown", &[("age", "thirty")]),
+        ];
+
+        let evaluation = evaluate_presentation_request(&req, &creds, &HashMap::new());
+
+        let adult = &evaluation.requested_predicates["adult"];
+        assert!(adult.credentials[0].is_satisfied());
+        assert_eq!(
+            adult.credentials[1].failures,
+            vec![EvaluationFailure::PredicateNotSatisfied {
+                name: "age".to_owned(),
+                value: 12,
+                p_type: PredicateTypes::GE,
+                p_value: 18,
+            }]
+        );
+        assert_eq!(
+            adult.credentials[2].failures,
+            vec![EvaluationFailure::PredicateValueNotNumeric {
+                name: "age".to_owned(),
+                value: "thirty".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_revoked_within_interval() {
+        let req = request(json!({
+            "nonce": "123",
+            "name": "req",
+            "requested_attributes": {
+                "street": { "name": "street" },
+                "city": { "name": "city", "non_revoked": { "from": 50, "to": 200 } }
+            },
+            "non_revoked": { "from": 150, "to": 200 }
+        }));
+        let cred = credential("cred1", &[("street", "Main"), ("city", "Prague")]);
+        let revoked_at = HashMap::from([("cred1".to_owned(), 100)]);
+
+        let evaluation = evaluate_presentation_request(&req, &[cred], &revoked_at);
+
+        assert!(evaluation.requested_attributes["city"].is_satisfied());
+        assert_eq!(
+            evaluation.requested_attributes["street"].credentials[0].failures,
+            vec![EvaluationFailure::RevokedWithinInterval {
+                revoked_at: 100,
+                interval: NonRevokedInterval::new(Some(150), Some(200)),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_matches_restrictions_operators() {
+        let cred = credential("cred1", &[("Street Name", "Main")]);
+        let matches = |restrictions: serde_json::Value| {
+            matches_restrictions(&serde_json::from_value(restrictions).unwrap(), &cred)
+        };
+
+        assert!(matches(json!({ "attr::streetname::value": "Main" })));
+        assert!(matches(json!({ "attr::Street Name::marker": "1" })));
+        assert!(matches(json!({ "schema_name": { "$like": "add%s" } })));
+        assert!(matches(
+            json!({ "schema_version": { "$in": ["1.0", "2.0"] } })
+        ));
+        assert!(matches(json!({ "$not": { "schema_id": "other" } })));
+        assert!(matches(
+            json!([{ "cred_def_id": "other" }, { "schema_id": SCHEMA_ID }])
+        ));
+        assert!(!matches(json!({ "rev_reg_id": { "$neq": REV_REG_ID } })));
+        assert!(!matches(json!({ "$exist": ["attr::zip::value"] })));
+    }
+}